@@ -0,0 +1,451 @@
+//! Aggregation pipelines built one stage at a time, and the code that runs them
+//! from the mongo shell or the Python and Rust drivers
+
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Documents shown in the preview of each stage
+pub const PREVIEW_LIMIT: u32 = 10;
+
+/// Generated code is wrapped onto several lines past this width
+const LINE_WIDTH: usize = 80;
+
+/// Connection string written into generated driver code. The real one may hold
+/// credentials, so it is never copied out.
+const PLACEHOLDER_URI: &str = "mongodb://localhost:27017";
+
+/// Stages offered by the pipeline builder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageKind {
+    Match,
+    Group,
+    Project,
+    Lookup,
+    Unwind,
+    Sort,
+    Limit,
+}
+
+impl StageKind {
+    pub const ALL: [StageKind; 7] = [
+        StageKind::Match,
+        StageKind::Group,
+        StageKind::Project,
+        StageKind::Lookup,
+        StageKind::Unwind,
+        StageKind::Sort,
+        StageKind::Limit,
+    ];
+
+    pub fn operator(&self) -> &'static str {
+        match self {
+            StageKind::Match => "$match",
+            StageKind::Group => "$group",
+            StageKind::Project => "$project",
+            StageKind::Lookup => "$lookup",
+            StageKind::Unwind => "$unwind",
+            StageKind::Sort => "$sort",
+            StageKind::Limit => "$limit",
+        }
+    }
+
+    /// Body a new stage starts with
+    pub fn template(&self) -> &'static str {
+        match self {
+            StageKind::Match => "{\n  \n}",
+            StageKind::Group => "{\n  _id: \"$field\",\n  count: { $sum: 1 }\n}",
+            StageKind::Project => "{\n  _id: 0,\n  field: 1\n}",
+            StageKind::Lookup => {
+                "{\n  from: \"collection\",\n  localField: \"field\",\n  foreignField: \"_id\",\n  as: \"joined\"\n}"
+            }
+            StageKind::Unwind => "\"$field\"",
+            StageKind::Sort => "{\n  field: -1\n}",
+            StageKind::Limit => "10",
+        }
+    }
+}
+
+/// One stage as written in the builder. The body is mongo shell syntax, so
+/// unquoted keys, `ObjectId(...)`, `ISODate(...)` and regexes can be used.
+#[derive(Debug, Clone)]
+pub struct Stage {
+    pub kind: StageKind,
+    pub body: String,
+    pub enabled: bool,
+}
+
+impl Stage {
+    pub fn new(kind: StageKind) -> Self {
+        Self {
+            kind,
+            body: kind.template().to_string(),
+            enabled: true,
+        }
+    }
+}
+
+/// Parse a stage body into the Extended JSON stage `{ "$operator": body }`
+#[cfg(feature = "mongodb")]
+pub fn parse_stage(kind: StageKind, body: &str) -> Result<Value, String> {
+    let value = crate::db::drivers::mongo_shell::parse_shell_value(body)?;
+    let value = crate::db::drivers::mongo::bson_to_json(value).map_err(|e| e.to_string())?;
+    let mut stage = serde_json::Map::new();
+    stage.insert(kind.operator().to_string(), value);
+    Ok(Value::Object(stage))
+}
+
+#[cfg(not(feature = "mongodb"))]
+pub fn parse_stage(kind: StageKind, body: &str) -> Result<Value, String> {
+    let _ = (kind, body);
+    Err("MongoDB support not compiled. Rebuild with --features mongodb".to_string())
+}
+
+/// Languages the finished pipeline can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeLanguage {
+    Shell,
+    Python,
+    Rust,
+}
+
+impl CodeLanguage {
+    pub const ALL: [CodeLanguage; 3] = [
+        CodeLanguage::Shell,
+        CodeLanguage::Python,
+        CodeLanguage::Rust,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CodeLanguage::Shell => "Shell",
+            CodeLanguage::Python => "Python",
+            CodeLanguage::Rust => "Rust",
+        }
+    }
+}
+
+/// Code that runs `pipeline` (Extended JSON stages) against a collection
+pub fn generate_code(
+    language: CodeLanguage,
+    database: &str,
+    collection: &str,
+    pipeline: &[Value],
+) -> String {
+    let mut printer = Printer {
+        language,
+        imports: BTreeSet::new(),
+    };
+    match language {
+        CodeLanguage::Shell => {
+            let stages = printer.stages(pipeline, "", "  ");
+            format!(
+                "db.getSiblingDB({}).getCollection({}).aggregate({})\n",
+                json_string(database),
+                json_string(collection),
+                stages
+            )
+        }
+        CodeLanguage::Python => {
+            let stages = printer.stages(pipeline, "", "    ");
+            let mut code = String::from("from pymongo import MongoClient\n");
+            let bson_imports: Vec<&str> = printer
+                .imports
+                .iter()
+                .copied()
+                .filter(|name| *name != "datetime")
+                .collect();
+            if !bson_imports.is_empty() {
+                code.push_str(&format!("from bson import {}\n", bson_imports.join(", ")));
+            }
+            if printer.imports.contains("datetime") {
+                code.push_str("from datetime import datetime\n");
+            }
+            code.push_str(&format!(
+                "\nclient = MongoClient({})\ncollection = client[{}][{}]\n\npipeline = {}\n\n\
+                 for document in collection.aggregate(pipeline):\n    print(document)\n",
+                json_string(PLACEHOLDER_URI),
+                json_string(database),
+                json_string(collection),
+                stages
+            ));
+            code
+        }
+        CodeLanguage::Rust => {
+            let stages = printer.stages(pipeline, "    ", "    ");
+            let mut bson_imports: Vec<&str> = vec!["doc", "Document"];
+            bson_imports.extend(printer.imports.iter().copied());
+            format!(
+                "use futures::TryStreamExt;\nuse mongodb::{{\n    bson::{{{}}},\n    Client,\n}};\n\n\
+                 #[tokio::main]\nasync fn main() -> Result<(), Box<dyn std::error::Error>> {{\n    \
+                 let client = Client::with_uri_str({}).await?;\n    \
+                 let collection = client.database({}).collection::<Document>({});\n\n    \
+                 let pipeline = vec!{};\n\n    \
+                 let mut cursor = collection.aggregate(pipeline).await?;\n    \
+                 while let Some(document) = cursor.try_next().await? {{\n        \
+                 println!(\"{{}}\", document);\n    }}\n    Ok(())\n}}\n",
+                bson_imports.join(", "),
+                rust_string(PLACEHOLDER_URI),
+                rust_string(database),
+                rust_string(collection),
+                stages
+            )
+        }
+    }
+}
+
+/// Writes Extended JSON values as literals of one language, collecting the
+/// names that have to be imported for them
+struct Printer {
+    language: CodeLanguage,
+    imports: BTreeSet<&'static str>,
+}
+
+impl Printer {
+    /// The pipeline array, one stage per line
+    fn stages(&mut self, pipeline: &[Value], indent: &str, step: &str) -> String {
+        if pipeline.is_empty() {
+            return "[]".to_string();
+        }
+        let inner = format!("{}{}", indent, step);
+        let stages: Vec<String> = pipeline
+            .iter()
+            .map(|stage| {
+                let prefix = if self.language == CodeLanguage::Rust {
+                    "doc! "
+                } else {
+                    ""
+                };
+                format!("{}{}{}", inner, prefix, self.value(stage, &inner, step))
+            })
+            .collect();
+        format!("[\n{},\n{}]", stages.join(",\n"), indent)
+    }
+
+    /// A value on one line when it fits, otherwise with one entry per line
+    fn value(&mut self, value: &Value, indent: &str, step: &str) -> String {
+        let inline = self.inline(value);
+        if indent.len() + inline.len() <= LINE_WIDTH || self.special(value).is_some() {
+            return inline;
+        }
+        let inner = format!("{}{}", indent, step);
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                let entries: Vec<String> = map
+                    .iter()
+                    .map(|(key, value)| {
+                        format!(
+                            "{}{}: {}",
+                            inner,
+                            self.key(key),
+                            self.value(value, &inner, step)
+                        )
+                    })
+                    .collect();
+                format!("{{\n{},\n{}}}", entries.join(",\n"), indent)
+            }
+            Value::Array(items) if !items.is_empty() => {
+                let items: Vec<String> = items
+                    .iter()
+                    .map(|item| format!("{}{}", inner, self.value(item, &inner, step)))
+                    .collect();
+                format!("[\n{},\n{}]", items.join(",\n"), indent)
+            }
+            _ => inline,
+        }
+    }
+
+    fn inline(&mut self, value: &Value) -> String {
+        if let Some(special) = self.special(value) {
+            return special;
+        }
+        match value {
+            Value::Null => match self.language {
+                CodeLanguage::Python => "None".to_string(),
+                CodeLanguage::Shell | CodeLanguage::Rust => "null".to_string(),
+            },
+            Value::Bool(b) => match (self.language, b) {
+                (CodeLanguage::Python, true) => "True".to_string(),
+                (CodeLanguage::Python, false) => "False".to_string(),
+                _ => b.to_string(),
+            },
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => self.string(s),
+            Value::Array(items) => {
+                let items: Vec<String> = items.iter().map(|item| self.inline(item)).collect();
+                format!("[{}]", items.join(", "))
+            }
+            Value::Object(map) if map.is_empty() => "{}".to_string(),
+            Value::Object(map) => {
+                let entries: Vec<String> = map
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", self.key(key), self.inline(value)))
+                    .collect();
+                match self.language {
+                    CodeLanguage::Python => format!("{{{}}}", entries.join(", ")),
+                    CodeLanguage::Shell | CodeLanguage::Rust => {
+                        format!("{{ {} }}", entries.join(", "))
+                    }
+                }
+            }
+        }
+    }
+
+    fn key(&self, key: &str) -> String {
+        let bare = self.language == CodeLanguage::Shell
+            && key
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+        if bare {
+            key.to_string()
+        } else {
+            self.string(key)
+        }
+    }
+
+    fn string(&self, s: &str) -> String {
+        match self.language {
+            CodeLanguage::Rust => rust_string(s),
+            CodeLanguage::Shell | CodeLanguage::Python => json_string(s),
+        }
+    }
+
+    /// Extended JSON wrappers written with the language's own BSON types
+    fn special(&mut self, value: &Value) -> Option<String> {
+        let map = value.as_object()?;
+        let (key, inner) = map.iter().next()?;
+        if map.len() != 1 {
+            return None;
+        }
+        let text = inner.as_str();
+        let code = match (key.as_str(), self.language) {
+            ("$oid", CodeLanguage::Shell) => format!("ObjectId({})", self.string(text?)),
+            ("$oid", CodeLanguage::Python) => {
+                self.imports.insert("ObjectId");
+                format!("ObjectId({})", self.string(text?))
+            }
+            ("$oid", CodeLanguage::Rust) => {
+                self.imports.insert("oid::ObjectId");
+                format!("ObjectId::parse_str({})?", self.string(text?))
+            }
+            ("$numberLong", CodeLanguage::Shell) => format!("NumberLong({})", text?),
+            ("$numberLong", CodeLanguage::Python) => {
+                self.imports.insert("Int64");
+                format!("Int64({})", text?)
+            }
+            ("$numberLong", CodeLanguage::Rust) => format!("{}_i64", text?),
+            ("$numberInt", _) => text?.to_string(),
+            ("$numberDouble", language) => match (text?, language) {
+                ("Infinity", CodeLanguage::Shell) => "Infinity".to_string(),
+                ("-Infinity", CodeLanguage::Shell) => "-Infinity".to_string(),
+                ("NaN", CodeLanguage::Shell) => "NaN".to_string(),
+                ("Infinity", CodeLanguage::Python) => "float(\"inf\")".to_string(),
+                ("-Infinity", CodeLanguage::Python) => "float(\"-inf\")".to_string(),
+                ("NaN", CodeLanguage::Python) => "float(\"nan\")".to_string(),
+                ("Infinity", CodeLanguage::Rust) => "f64::INFINITY".to_string(),
+                ("-Infinity", CodeLanguage::Rust) => "f64::NEG_INFINITY".to_string(),
+                ("NaN", CodeLanguage::Rust) => "f64::NAN".to_string(),
+                (number, CodeLanguage::Rust) if !number.contains(['.', 'e', 'E']) => {
+                    format!("{}.0", number)
+                }
+                (number, _) => number.to_string(),
+            },
+            ("$numberDecimal", CodeLanguage::Shell) => {
+                format!("NumberDecimal({})", self.string(text?))
+            }
+            ("$numberDecimal", CodeLanguage::Python) => {
+                self.imports.insert("Decimal128");
+                format!("Decimal128({})", self.string(text?))
+            }
+            ("$numberDecimal", CodeLanguage::Rust) => {
+                self.imports.insert("Decimal128");
+                format!("{}.parse::<Decimal128>()?", self.string(text?))
+            }
+            ("$date", language) => self.date(inner, language)?,
+            ("$regularExpression", language) => {
+                let pattern = inner.get("pattern")?.as_str()?;
+                let options = inner.get("options")?.as_str()?;
+                match language {
+                    CodeLanguage::Shell => format!("/{}/{}", escape_slashes(pattern), options),
+                    CodeLanguage::Python => {
+                        self.imports.insert("Regex");
+                        format!("Regex({}, {})", self.string(pattern), self.string(options))
+                    }
+                    CodeLanguage::Rust => {
+                        self.imports.insert("Regex");
+                        format!(
+                            "Regex {{ pattern: {}.to_string(), options: {}.to_string() }}",
+                            self.string(pattern),
+                            self.string(options)
+                        )
+                    }
+                }
+            }
+            _ => return None,
+        };
+        Some(code)
+    }
+
+    /// `{"$date": "<ISO-8601>"}` or `{"$date": {"$numberLong": "<millis>"}}`
+    fn date(&mut self, inner: &Value, language: CodeLanguage) -> Option<String> {
+        let iso = match inner {
+            Value::String(iso) => iso.clone(),
+            _ => {
+                let millis: i64 = inner.get("$numberLong")?.as_str()?.parse().ok()?;
+                match chrono::DateTime::from_timestamp_millis(millis) {
+                    Some(date) => date.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                    None => {
+                        return Some(match language {
+                            CodeLanguage::Shell => format!("new Date({})", millis),
+                            CodeLanguage::Python => {
+                                self.imports.insert("DatetimeMS");
+                                format!("DatetimeMS({})", millis)
+                            }
+                            CodeLanguage::Rust => {
+                                self.imports.insert("DateTime");
+                                format!("DateTime::from_millis({})", millis)
+                            }
+                        })
+                    }
+                }
+            }
+        };
+        Some(match language {
+            CodeLanguage::Shell => format!("ISODate({})", self.string(&iso)),
+            CodeLanguage::Python => {
+                self.imports.insert("datetime");
+                format!("datetime.fromisoformat({})", self.string(&iso))
+            }
+            CodeLanguage::Rust => {
+                self.imports.insert("DateTime");
+                format!("DateTime::parse_rfc3339_str({})?", self.string(&iso))
+            }
+        })
+    }
+}
+
+/// A double-quoted string literal valid in JavaScript and Python
+fn json_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_else(|_| format!("\"{}\"", s))
+}
+
+fn rust_string(s: &str) -> String {
+    format!("{:?}", s)
+}
+
+/// Escape the `/` characters that would end a regex literal early
+fn escape_slashes(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut escaped = false;
+    for c in pattern.chars() {
+        if c == '/' && !escaped {
+            out.push('\\');
+        }
+        escaped = c == '\\' && !escaped;
+        out.push(c);
+    }
+    out
+}
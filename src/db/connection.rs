@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::driver::ConnectionConfig;
+
 /// Supported database types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DatabaseType {
@@ -74,6 +76,19 @@ impl DatabaseType {
         }
     }
 
+    /// Whether this is a SQL database (rows instead of documents)
+    pub fn is_sql(&self) -> bool {
+        matches!(
+            self,
+            DatabaseType::PostgreSQL | DatabaseType::MySQL | DatabaseType::SQLite
+        )
+    }
+
+    /// Whether the driver honours [`TlsConfig`]
+    pub fn supports_tls(&self) -> bool {
+        matches!(self, DatabaseType::PostgreSQL | DatabaseType::MySQL)
+    }
+
     /// All database types (for UI listing)
     pub fn all() -> &'static [DatabaseType] {
         &[
@@ -96,6 +111,145 @@ impl DatabaseType {
     }
 }
 
+/// How TLS is negotiated with the server, following libpq's `sslmode`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SslMode {
+    /// Never use TLS
+    Disable,
+    /// Use TLS if the server supports it, without verifying the certificate
+    #[default]
+    Prefer,
+    /// Always use TLS; the certificate is only checked if a CA is configured
+    Require,
+    /// Always use TLS and check the certificate chain, but not the host name
+    VerifyCa,
+    /// Always use TLS and check both the certificate chain and the host name
+    VerifyFull,
+}
+
+impl SslMode {
+    /// The libpq spelling, also used for storage
+    pub fn name(&self) -> &'static str {
+        match self {
+            SslMode::Disable => "disable",
+            SslMode::Prefer => "prefer",
+            SslMode::Require => "require",
+            SslMode::VerifyCa => "verify-ca",
+            SslMode::VerifyFull => "verify-full",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|mode| mode.name() == value)
+    }
+
+    pub fn all() -> &'static [SslMode] {
+        &[
+            SslMode::Disable,
+            SslMode::Prefer,
+            SslMode::Require,
+            SslMode::VerifyCa,
+            SslMode::VerifyFull,
+        ]
+    }
+
+    /// Whether the server certificate is checked against trusted roots
+    pub fn verifies_certificate(&self) -> bool {
+        matches!(self, SslMode::VerifyCa | SslMode::VerifyFull)
+    }
+}
+
+/// TLS settings for PostgreSQL and MySQL connections
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub ssl_mode: SslMode,
+    /// PEM file with the CA certificate(s) to trust instead of the system roots
+    pub ca_cert_path: Option<String>,
+    /// Client certificate (PEM for PostgreSQL, PKCS#12 bundle for MySQL)
+    pub client_cert_path: Option<String>,
+    /// Client private key in PKCS#8 PEM (PostgreSQL only)
+    pub client_key_path: Option<String>,
+}
+
+/// How the SSH tunnel authenticates against the jump host
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SshAuth {
+    #[default]
+    Password,
+    /// Private key file, optionally protected by a passphrase
+    PrivateKey,
+}
+
+impl SshAuth {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SshAuth::Password => "Password",
+            SshAuth::PrivateKey => "Private key",
+        }
+    }
+
+    pub fn all() -> &'static [SshAuth] {
+        &[SshAuth::Password, SshAuth::PrivateKey]
+    }
+}
+
+/// What to do with the jump host's key, like OpenSSH's `StrictHostKeyChecking`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HostKeyCheck {
+    /// The key must already be in known_hosts
+    #[default]
+    Strict,
+    /// Unknown hosts are added to known_hosts; changed keys are still rejected
+    AcceptNew,
+    /// Don't verify the host key
+    Off,
+}
+
+impl HostKeyCheck {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HostKeyCheck::Strict => "Strict",
+            HostKeyCheck::AcceptNew => "Accept new",
+            HostKeyCheck::Off => "Don't verify",
+        }
+    }
+
+    pub fn all() -> &'static [HostKeyCheck] {
+        &[HostKeyCheck::Strict, HostKeyCheck::AcceptNew, HostKeyCheck::Off]
+    }
+}
+
+/// SSH jump host the database is reached through (all network drivers)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SshTunnelConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub auth: SshAuth,
+    /// Private key file, for `SshAuth::PrivateKey`
+    pub private_key_path: Option<String>,
+    /// Password, or the private key's passphrase. Kept in the secret store.
+    pub password: Option<String>,
+    pub host_key_check: HostKeyCheck,
+    /// known_hosts file to check the host key against, `~/.ssh/known_hosts` if unset
+    pub known_hosts_path: Option<String>,
+}
+
+impl Default for SshTunnelConfig {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: 22,
+            username: String::new(),
+            auth: SshAuth::default(),
+            private_key_path: None,
+            password: None,
+            host_key_check: HostKeyCheck::default(),
+            known_hosts_path: None,
+        }
+    }
+}
+
 /// A database connection configuration (stored in app database)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Connection {
@@ -114,6 +268,15 @@ pub struct Connection {
     pub visible_databases: Option<Vec<String>>,
     /// Whether "Show All" is enabled in the database picker
     pub show_all_databases: Option<bool>,
+    /// Don't save the password; ask for it when connecting instead
+    #[serde(default)]
+    pub ask_password: bool,
+    /// TLS settings (PostgreSQL and MySQL)
+    #[serde(default)]
+    pub tls: TlsConfig,
+    /// Reach the database through this SSH jump host
+    #[serde(default)]
+    pub ssh: Option<SshTunnelConfig>,
 }
 
 impl Connection {
@@ -130,9 +293,28 @@ impl Connection {
             connection_string: None,
             visible_databases: None,
             show_all_databases: None,
+            ask_password: false,
+            tls: TlsConfig::default(),
+            ssh: None,
         }
     }
 
+    /// Driver configuration for connecting to this database
+    pub fn config(&self) -> ConnectionConfig {
+        ConnectionConfig::new(self.db_type, self.get_connection_string())
+            .with_tls(self.tls.clone())
+            .with_ssh(self.ssh.clone())
+    }
+
+    /// Whether the password has to be asked for before connecting
+    pub fn needs_password(&self) -> bool {
+        let has_connection_string = self
+            .connection_string
+            .as_ref()
+            .is_some_and(|s| !s.is_empty());
+        self.ask_password && self.password.is_none() && !has_connection_string
+    }
+
     /// Build connection string from fields, or return custom one if set
     pub fn get_connection_string(&self) -> String {
         // If custom connection string is set, use it
@@ -0,0 +1,698 @@
+//! Entity-relationship diagrams of a SQL database, built from the tables the
+//! catalog lists and the foreign keys of their structure
+//!
+//! Tables are laid out in layers from left to right: a table sits one layer to
+//! the right of every table it references, and tables within a layer are ordered
+//! to keep edges short. Tables without foreign keys either way go into a grid
+//! below. Coordinates are unscaled pixels; the view applies zoom and pan.
+
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::driver::{DatabaseConnection, TableStructure};
+
+/// Tables whose structure is read at the same time
+const CONCURRENT_LOADS: usize = 4;
+
+/// Height of a table's name bar
+pub const HEADER_HEIGHT: f32 = 28.0;
+/// Height of one column row
+pub const ROW_HEIGHT: f32 = 20.0;
+/// Font size of names and types
+pub const FONT_SIZE: f32 = 12.0;
+/// Width of one character at `FONT_SIZE` in Fira Code
+const CHAR_WIDTH: f32 = 7.2;
+/// Horizontal padding inside a box
+pub const BOX_PADDING: f32 = 10.0;
+const MIN_BOX_WIDTH: f32 = 140.0;
+const MAX_BOX_WIDTH: f32 = 360.0;
+/// Space between layers
+const LAYER_GAP: f32 = 96.0;
+/// Space between boxes of a layer or of the grid
+const BOX_GAP: f32 = 32.0;
+/// Space around the whole diagram
+const MARGIN: f32 = 40.0;
+/// Barycenter passes used to order the tables of each layer
+const ORDERING_SWEEPS: usize = 4;
+/// PNG exports are rendered at twice the diagram size
+const PNG_SCALE: f32 = 2.0;
+
+/// Column of a table, as the diagram shows it
+#[derive(Debug, Clone)]
+pub struct DiagramColumn {
+    pub name: String,
+    pub data_type: String,
+    pub primary_key: bool,
+    pub foreign_key: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiagramTable {
+    /// Name as the catalog lists it, e.g. `public.orders`
+    pub name: String,
+    /// Name shown on the box: without the schema the diagram is drawn for
+    pub label: String,
+    pub columns: Vec<DiagramColumn>,
+}
+
+/// A foreign key from `from` to `to`, both indexes into `Diagram::tables`
+#[derive(Debug, Clone)]
+pub struct DiagramEdge {
+    pub from: usize,
+    pub columns: Vec<String>,
+    pub to: usize,
+    pub referenced_columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Diagram {
+    pub tables: Vec<DiagramTable>,
+    pub edges: Vec<DiagramEdge>,
+    /// Foreign keys to tables outside the diagram, e.g. in another schema
+    pub external_keys: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    pub fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+}
+
+/// Where each visible table is drawn
+#[derive(Debug, Clone, Default)]
+pub struct Layout {
+    pub boxes: HashMap<usize, Rect>,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Layout {
+    /// Grow the bounds to hold every box, e.g. after one was dragged
+    pub fn fit_bounds(&mut self) {
+        self.width = self
+            .boxes
+            .values()
+            .map(|rect| rect.right() + MARGIN)
+            .fold(0.0, f32::max);
+        self.height = self
+            .boxes
+            .values()
+            .map(|rect| rect.bottom() + MARGIN)
+            .fold(0.0, f32::max);
+    }
+}
+
+/// Cubic Bézier curve of an edge, from the referencing column to the referenced one
+#[derive(Debug, Clone, Copy)]
+pub struct EdgeCurve {
+    pub start: (f32, f32),
+    pub control_a: (f32, f32),
+    pub control_b: (f32, f32),
+    pub end: (f32, f32),
+}
+
+impl EdgeCurve {
+    /// Triangle pointing into the referenced table at the end of the curve
+    pub fn arrow_head(&self) -> [(f32, f32); 3] {
+        const LENGTH: f32 = 8.0;
+        const HALF_WIDTH: f32 = 4.0;
+        let (dx, dy) = (self.end.0 - self.control_b.0, self.end.1 - self.control_b.1);
+        let length = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+        let (ux, uy) = (dx / length, dy / length);
+        let base = (self.end.0 - ux * LENGTH, self.end.1 - uy * LENGTH);
+        [
+            self.end,
+            (base.0 - uy * HALF_WIDTH, base.1 + ux * HALF_WIDTH),
+            (base.0 + uy * HALF_WIDTH, base.1 - ux * HALF_WIDTH),
+        ]
+    }
+}
+
+/// File formats a diagram can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramFormat {
+    Svg,
+    Png,
+}
+
+impl DiagramFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            DiagramFormat::Svg => "SVG",
+            DiagramFormat::Png => "PNG",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            DiagramFormat::Svg => "svg",
+            DiagramFormat::Png => "png",
+        }
+    }
+}
+
+/// Read the structure of `tables` and build their diagram. `schema` is the
+/// PostgreSQL schema the tables were listed from, left out of their labels.
+pub async fn load(
+    connection: Arc<dyn DatabaseConnection>,
+    database_name: &str,
+    tables: Vec<String>,
+    schema: Option<&str>,
+) -> super::error::Result<Diagram> {
+    let structures: Vec<(String, TableStructure)> = stream::iter(tables)
+        .map(|table| {
+            let connection = connection.clone();
+            async move {
+                let structure = connection.table_structure(database_name, &table).await?;
+                Ok::<_, super::error::ConnectionError>((table, structure))
+            }
+        })
+        .buffered(CONCURRENT_LOADS)
+        .try_collect()
+        .await?;
+    Ok(Diagram::build(structures, schema))
+}
+
+impl Diagram {
+    pub fn build(structures: Vec<(String, TableStructure)>, schema: Option<&str>) -> Self {
+        let prefix = schema.map(|schema| format!("{}.", schema));
+        let index: HashMap<&str, usize> = structures
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| (name.as_str(), i))
+            .collect();
+
+        let mut edges = Vec::new();
+        let mut external_keys = 0;
+        for (from, (name, structure)) in structures.iter().enumerate() {
+            for key in &structure.foreign_keys {
+                let Some(to) = resolve(&index, name, &key.referenced_table) else {
+                    external_keys += 1;
+                    continue;
+                };
+                // Keys that name no columns reference the primary key
+                let referenced_columns = if key.referenced_columns.is_empty() {
+                    structures[to]
+                        .1
+                        .primary_key
+                        .as_ref()
+                        .map(|key| key.columns.clone())
+                        .unwrap_or_default()
+                } else {
+                    key.referenced_columns.clone()
+                };
+                edges.push(DiagramEdge {
+                    from,
+                    columns: key.columns.clone(),
+                    to,
+                    referenced_columns,
+                });
+            }
+        }
+
+        let tables = structures
+            .into_iter()
+            .map(|(name, structure)| {
+                let primary_key: Vec<&String> = structure
+                    .primary_key
+                    .iter()
+                    .flat_map(|key| &key.columns)
+                    .collect();
+                let columns = structure
+                    .columns
+                    .iter()
+                    .map(|column| DiagramColumn {
+                        name: column.name.clone(),
+                        data_type: column.data_type.clone(),
+                        primary_key: primary_key.contains(&&column.name),
+                        foreign_key: structure
+                            .foreign_keys
+                            .iter()
+                            .any(|key| key.columns.contains(&column.name)),
+                    })
+                    .collect();
+                let label = match &prefix {
+                    Some(prefix) => name.strip_prefix(prefix.as_str()).unwrap_or(&name),
+                    None => &name,
+                }
+                .to_string();
+                DiagramTable {
+                    name,
+                    label,
+                    columns,
+                }
+            })
+            .collect();
+
+        Self {
+            tables,
+            edges,
+            external_keys,
+        }
+    }
+
+    /// Every table
+    pub fn all(&self) -> Vec<usize> {
+        (0..self.tables.len()).collect()
+    }
+
+    /// Tables at most `depth` foreign keys away from `center`, either way
+    pub fn neighbourhood(&self, center: usize, depth: usize) -> Vec<usize> {
+        let mut distance = HashMap::from([(center, 0)]);
+        let mut queue = VecDeque::from([center]);
+        while let Some(table) = queue.pop_front() {
+            let next = distance[&table] + 1;
+            if next > depth {
+                continue;
+            }
+            for edge in &self.edges {
+                let neighbour = if edge.from == table {
+                    edge.to
+                } else if edge.to == table {
+                    edge.from
+                } else {
+                    continue;
+                };
+                if let Entry::Vacant(slot) = distance.entry(neighbour) {
+                    slot.insert(next);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        let mut tables: Vec<usize> = distance.into_keys().collect();
+        tables.sort_unstable();
+        tables
+    }
+
+    /// Size of a table's box: wide enough for its name and its longest column
+    pub fn box_size(&self, table: usize) -> (f32, f32) {
+        let table = &self.tables[table];
+        let header = table.label.chars().count() as f32 * CHAR_WIDTH;
+        // Key marker, name, a gap of two characters and the type
+        let widest_row = table
+            .columns
+            .iter()
+            .map(|column| {
+                (column.name.chars().count() + column.data_type.chars().count() + 5) as f32
+                    * CHAR_WIDTH
+            })
+            .fold(0.0, f32::max);
+        let width =
+            (header.max(widest_row) + 2.0 * BOX_PADDING).clamp(MIN_BOX_WIDTH, MAX_BOX_WIDTH);
+        let height = HEADER_HEIGHT + table.columns.len().max(1) as f32 * ROW_HEIGHT;
+        (width, height)
+    }
+
+    /// Lay out the `visible` tables
+    pub fn layout(&self, visible: &[usize]) -> Layout {
+        let shown: HashSet<usize> = visible.iter().copied().collect();
+        let links: Vec<(usize, usize)> = self
+            .edges
+            .iter()
+            .filter(|edge| edge.from != edge.to)
+            .filter(|edge| shown.contains(&edge.from) && shown.contains(&edge.to))
+            .map(|edge| (edge.from, edge.to))
+            .collect();
+        let linked: HashSet<usize> = links.iter().flat_map(|(a, b)| [*a, *b]).collect();
+
+        let mut tables: Vec<usize> = visible.to_vec();
+        tables.sort_by(|a, b| self.tables[*a].label.cmp(&self.tables[*b].label));
+        let (connected, isolated): (Vec<usize>, Vec<usize>) =
+            tables.into_iter().partition(|table| linked.contains(table));
+
+        let mut layout = Layout::default();
+        let mut top = MARGIN;
+
+        if !connected.is_empty() {
+            let layers = self.order_layers(assign_layers(&connected, &links), &links);
+            let heights: Vec<f32> = layers
+                .iter()
+                .map(|layer| {
+                    layer.iter().map(|t| self.box_size(*t).1).sum::<f32>()
+                        + BOX_GAP * (layer.len() - 1) as f32
+                })
+                .collect();
+            let tallest = heights.iter().copied().fold(0.0, f32::max);
+
+            let mut x = MARGIN;
+            for (layer, height) in layers.iter().zip(&heights) {
+                // Layers are centred on the tallest one
+                let mut y = top + (tallest - height) / 2.0;
+                let mut layer_width: f32 = 0.0;
+                for &table in layer {
+                    let (width, height) = self.box_size(table);
+                    layout.boxes.insert(
+                        table,
+                        Rect {
+                            x,
+                            y,
+                            width,
+                            height,
+                        },
+                    );
+                    y += height + BOX_GAP;
+                    layer_width = layer_width.max(width);
+                }
+                x += layer_width + LAYER_GAP;
+            }
+            top += tallest + LAYER_GAP;
+        }
+
+        if !isolated.is_empty() {
+            let per_row = (isolated.len() as f32).sqrt().ceil() as usize;
+            for row in isolated.chunks(per_row) {
+                let mut x = MARGIN;
+                let mut row_height: f32 = 0.0;
+                for &table in row {
+                    let (width, height) = self.box_size(table);
+                    layout.boxes.insert(
+                        table,
+                        Rect {
+                            x,
+                            y: top,
+                            width,
+                            height,
+                        },
+                    );
+                    x += width + BOX_GAP;
+                    row_height = row_height.max(height);
+                }
+                top += row_height + BOX_GAP;
+            }
+        }
+
+        layout.fit_bounds();
+        layout
+    }
+
+    /// Order the tables of each layer by the mean position of their neighbours
+    /// in the layer before, then in the layer after
+    fn order_layers(
+        &self,
+        mut layers: Vec<Vec<usize>>,
+        links: &[(usize, usize)],
+    ) -> Vec<Vec<usize>> {
+        let neighbours = |table: usize| {
+            links.iter().filter_map(move |&(a, b)| {
+                if a == table {
+                    Some(b)
+                } else if b == table {
+                    Some(a)
+                } else {
+                    None
+                }
+            })
+        };
+        let reorder = |layer: &mut Vec<usize>, adjacent: &[usize]| {
+            let position: HashMap<usize, usize> =
+                adjacent.iter().enumerate().map(|(i, t)| (*t, i)).collect();
+            let mut keyed: Vec<(f32, usize)> = layer
+                .iter()
+                .enumerate()
+                .map(|(i, &table)| {
+                    let positions: Vec<f32> = neighbours(table)
+                        .filter_map(|n| position.get(&n).map(|p| *p as f32))
+                        .collect();
+                    let key = if positions.is_empty() {
+                        i as f32
+                    } else {
+                        positions.iter().sum::<f32>() / positions.len() as f32
+                    };
+                    (key, table)
+                })
+                .collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            *layer = keyed.into_iter().map(|(_, table)| table).collect();
+        };
+
+        for _ in 0..ORDERING_SWEEPS {
+            for i in 1..layers.len() {
+                let adjacent = layers[i - 1].clone();
+                reorder(&mut layers[i], &adjacent);
+            }
+            for i in (0..layers.len().saturating_sub(1)).rev() {
+                let adjacent = layers[i + 1].clone();
+                reorder(&mut layers[i], &adjacent);
+            }
+        }
+        layers
+    }
+
+    /// Curve of an edge between two laid out tables, None if either is hidden
+    pub fn edge_curve(&self, edge: &DiagramEdge, layout: &Layout) -> Option<EdgeCurve> {
+        let from = layout.boxes.get(&edge.from)?;
+        let to = layout.boxes.get(&edge.to)?;
+        let start_y = from.y + self.row_offset(edge.from, edge.columns.first());
+        let end_y = to.y + self.row_offset(edge.to, edge.referenced_columns.first());
+
+        // Leave from the sides facing each other; boxes stacked in the same
+        // layer (and keys referencing their own table) loop out to the right
+        let curve = if from.right() < to.x {
+            let bend = ((to.x - from.right()) / 2.0).max(LAYER_GAP / 2.0);
+            EdgeCurve {
+                start: (from.right(), start_y),
+                control_a: (from.right() + bend, start_y),
+                control_b: (to.x - bend, end_y),
+                end: (to.x, end_y),
+            }
+        } else if to.right() < from.x {
+            let bend = ((from.x - to.right()) / 2.0).max(LAYER_GAP / 2.0);
+            EdgeCurve {
+                start: (from.x, start_y),
+                control_a: (from.x - bend, start_y),
+                control_b: (to.right() + bend, end_y),
+                end: (to.right(), end_y),
+            }
+        } else {
+            let out = from.right().max(to.right()) + LAYER_GAP / 2.0;
+            EdgeCurve {
+                start: (from.right(), start_y),
+                control_a: (out, start_y),
+                control_b: (out, end_y),
+                end: (to.right(), end_y),
+            }
+        };
+        Some(curve)
+    }
+
+    /// Vertical offset of a column's row centre in its box, or of the name bar
+    /// when the column is unknown
+    fn row_offset(&self, table: usize, column: Option<&String>) -> f32 {
+        let row = column.and_then(|column| {
+            self.tables[table]
+                .columns
+                .iter()
+                .position(|c| &c.name == column)
+        });
+        match row {
+            Some(row) => HEADER_HEIGHT + (row as f32 + 0.5) * ROW_HEIGHT,
+            None => HEADER_HEIGHT / 2.0,
+        }
+    }
+
+    /// The laid out tables and their edges as a standalone SVG document, in the
+    /// app's dark colours
+    pub fn to_svg(&self, layout: &Layout) -> String {
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="Fira Code, monospace" font-size="{FONT_SIZE}">"##,
+            w = layout.width.ceil(),
+            h = layout.height.ceil()
+        );
+        let _ = writeln!(
+            svg,
+            r##"<rect width="100%" height="100%" fill="#1a1a1a"/>"##
+        );
+
+        for edge in &self.edges {
+            let Some(curve) = self.edge_curve(edge, layout) else {
+                continue;
+            };
+            let _ = writeln!(
+                svg,
+                r##"<path d="M{:.1} {:.1} C{:.1} {:.1} {:.1} {:.1} {:.1} {:.1}" fill="none" stroke="#4e94ce" stroke-width="1.5"/>"##,
+                curve.start.0,
+                curve.start.1,
+                curve.control_a.0,
+                curve.control_a.1,
+                curve.control_b.0,
+                curve.control_b.1,
+                curve.end.0,
+                curve.end.1
+            );
+            let [a, b, c] = curve.arrow_head();
+            let _ = writeln!(
+                svg,
+                r##"<polygon points="{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}" fill="#4e94ce"/>"##,
+                a.0, a.1, b.0, b.1, c.0, c.1
+            );
+        }
+
+        let mut tables: Vec<(&usize, &Rect)> = layout.boxes.iter().collect();
+        tables.sort_by_key(|(table, _)| **table);
+        for (&table, rect) in tables {
+            let table = &self.tables[table];
+            let _ = writeln!(
+                svg,
+                r##"<g transform="translate({:.1} {:.1})">"##,
+                rect.x, rect.y
+            );
+            let _ = writeln!(
+                svg,
+                r##"<rect width="{:.1}" height="{:.1}" rx="4" fill="#252526" stroke="#3c3c3c"/>"##,
+                rect.width, rect.height
+            );
+            let _ = writeln!(
+                svg,
+                r##"<path d="M0 {h} H{w}" stroke="#3c3c3c"/><text x="{BOX_PADDING}" y="{y:.1}" fill="#e0e0e0" font-weight="bold">{}</text>"##,
+                escape_xml(&table.label),
+                h = HEADER_HEIGHT,
+                w = rect.width,
+                y = HEADER_HEIGHT / 2.0 + FONT_SIZE / 3.0
+            );
+            for (row, column) in table.columns.iter().enumerate() {
+                let y = HEADER_HEIGHT + (row as f32 + 0.5) * ROW_HEIGHT + FONT_SIZE / 3.0;
+                let (marker, color) = match (column.primary_key, column.foreign_key) {
+                    (true, _) => (Some("PK"), "#dcb67a"),
+                    (false, true) => (Some("FK"), "#4e94ce"),
+                    _ => (None, "#cccccc"),
+                };
+                if let Some(marker) = marker {
+                    let _ = write!(
+                        svg,
+                        r##"<text x="{BOX_PADDING}" y="{y:.1}" font-size="9" fill="{color}">{marker}</text>"##
+                    );
+                }
+                let _ = writeln!(
+                    svg,
+                    r##"<text x="{:.1}" y="{y:.1}" fill="{color}">{}</text><text x="{:.1}" y="{y:.1}" fill="#808080" text-anchor="end">{}</text>"##,
+                    BOX_PADDING + 3.0 * CHAR_WIDTH,
+                    escape_xml(&column.name),
+                    rect.width - BOX_PADDING,
+                    escape_xml(&column.data_type)
+                );
+            }
+            let _ = writeln!(svg, "</g>");
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Write the laid out tables to `path`
+    pub fn export(&self, layout: &Layout, format: DiagramFormat, path: &Path) -> Result<()> {
+        let svg = self.to_svg(layout);
+        match format {
+            DiagramFormat::Svg => std::fs::write(path, svg)
+                .with_context(|| format!("Failed to write {}", path.display())),
+            DiagramFormat::Png => write_png(&svg, path),
+        }
+    }
+}
+
+/// Index of the table a foreign key references. PostgreSQL names tables of the
+/// search path without their schema, so the referencing table's schema and
+/// `public` are tried too; SQLite matches table names case-insensitively.
+fn resolve(index: &HashMap<&str, usize>, from: &str, referenced: &str) -> Option<usize> {
+    let referenced = referenced.replace('"', "");
+    let schema = from.split_once('.').map(|(schema, _)| schema);
+    [
+        Some(referenced.clone()),
+        schema.map(|schema| format!("{}.{}", schema, referenced)),
+        Some(format!("public.{}", referenced)),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|name| index.get(name.as_str()).copied())
+    .or_else(|| {
+        index
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&referenced))
+            .map(|(_, table)| *table)
+    })
+}
+
+/// Layer of each connected table: one more than the deepest table it
+/// references. Keys closing a cycle are ignored.
+fn assign_layers(tables: &[usize], links: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    fn visit(
+        table: usize,
+        links: &[(usize, usize)],
+        layer: &mut HashMap<usize, usize>,
+        on_path: &mut HashSet<usize>,
+    ) -> usize {
+        if let Some(depth) = layer.get(&table) {
+            return *depth;
+        }
+        on_path.insert(table);
+        let mut depth = 0;
+        for &(from, to) in links {
+            if from == table && !on_path.contains(&to) {
+                depth = depth.max(visit(to, links, layer, on_path) + 1);
+            }
+        }
+        on_path.remove(&table);
+        layer.insert(table, depth);
+        depth
+    }
+
+    let mut layer = HashMap::new();
+    for &table in tables {
+        visit(table, links, &mut layer, &mut HashSet::new());
+    }
+    let count = layer.values().max().map_or(0, |max| max + 1);
+    let mut layers = vec![Vec::new(); count];
+    for &table in tables {
+        layers[layer[&table]].push(table);
+    }
+    layers.retain(|layer| !layer.is_empty());
+    layers
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render an SVG document to a PNG file
+fn write_png(svg: &str, path: &Path) -> Result<()> {
+    use resvg::{tiny_skia, usvg};
+
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_str(svg, &options).context("Failed to render the diagram")?;
+    let size = tree.size();
+    let mut pixmap = tiny_skia::Pixmap::new(
+        (size.width() * PNG_SCALE).ceil() as u32,
+        (size.height() * PNG_SCALE).ceil() as u32,
+    )
+    .context("The diagram is too large to export as PNG")?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(PNG_SCALE, PNG_SCALE),
+        &mut pixmap.as_mut(),
+    );
+    pixmap
+        .save_png(path)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
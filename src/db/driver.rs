@@ -2,7 +2,8 @@ use async_trait::async_trait;
 use std::time::Duration;
 
 use super::error::{ConnectionError, Result};
-use super::connection::DatabaseType;
+use super::connection::{DatabaseType, SshTunnelConfig, TlsConfig};
+use super::explain::QueryPlan;
 
 /// Information returned from a successful connection test
 #[derive(Debug, Clone)]
@@ -25,6 +26,278 @@ pub struct CollectionInfo {
     pub document_count: Option<u64>,
 }
 
+/// Column of a SQL table
+#[derive(Debug, Clone)]
+pub struct ColumnInfo {
+    pub name: String,
+    pub nullable: bool,
+    /// Part of the primary key
+    pub primary_key: bool,
+}
+
+/// How an index orders or interprets one of its keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexOrder {
+    Ascending,
+    Descending,
+    /// MongoDB index type such as `text`, `hashed` or `2dsphere`
+    Special(String),
+}
+
+impl IndexOrder {
+    pub fn label(&self) -> &str {
+        match self {
+            IndexOrder::Ascending => "ASC",
+            IndexOrder::Descending => "DESC",
+            IndexOrder::Special(kind) => kind,
+        }
+    }
+}
+
+/// Index of a collection or table
+#[derive(Debug, Clone)]
+pub struct IndexInfo {
+    pub name: String,
+    /// Indexed fields or columns (expressions for expression indexes)
+    pub keys: Vec<(String, IndexOrder)>,
+    /// Access method when it is not the default, e.g. `gin` or `FULLTEXT`
+    pub method: Option<String>,
+    pub unique: bool,
+    pub primary: bool,
+    /// Filter of a partial index: a query document or a `WHERE` predicate
+    pub partial_filter: Option<String>,
+    /// Seconds after which documents expire (MongoDB TTL indexes)
+    pub expire_after_seconds: Option<i64>,
+    pub size_bytes: Option<u64>,
+    /// Number of times queries used the index since the server's statistics
+    /// were last reset
+    pub usage_count: Option<u64>,
+}
+
+/// Index to create
+#[derive(Debug, Clone, Default)]
+pub struct IndexSpec {
+    /// Name chosen by the server (MongoDB) or from the table and keys when `None`
+    pub name: Option<String>,
+    pub keys: Vec<(String, IndexOrder)>,
+    pub unique: bool,
+    /// A query document (MongoDB) or `WHERE` predicate (PostgreSQL, SQLite)
+    pub partial_filter: Option<String>,
+    /// MongoDB only
+    pub expire_after_seconds: Option<i64>,
+}
+
+/// Progress of an index build, as far as the server reports it
+#[derive(Debug, Clone)]
+pub struct IndexBuildProgress {
+    /// Phase as the server names it, e.g. `building index: scanning table`
+    pub phase: Option<String>,
+    pub done: u64,
+    pub total: u64,
+}
+
+/// Column of a SQL table with the rest of its definition
+#[derive(Debug, Clone)]
+pub struct ColumnDefinition {
+    pub name: String,
+    /// Type as the server spells it, e.g. `character varying(64)`
+    pub data_type: String,
+    pub nullable: bool,
+    /// Default expression, as written in the table definition
+    pub default: Option<String>,
+    /// How the value is generated instead of stored, e.g. `auto_increment`,
+    /// an identity or a generation expression
+    pub generated: Option<String>,
+    /// Only set when it differs from the type's default collation
+    pub collation: Option<String>,
+    pub comment: Option<String>,
+}
+
+/// Primary key or unique constraint
+#[derive(Debug, Clone)]
+pub struct KeyConstraint {
+    /// None when the server named it itself (SQLite)
+    pub name: Option<String>,
+    pub columns: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckConstraint {
+    pub name: Option<String>,
+    pub expression: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ForeignKey {
+    pub name: Option<String>,
+    pub columns: Vec<String>,
+    /// Referenced table, qualified with its schema when it lives in another one
+    pub referenced_table: String,
+    /// Empty when the key references the other table's primary key implicitly (SQLite)
+    pub referenced_columns: Vec<String>,
+    /// Referential actions such as `CASCADE`, None for the default `NO ACTION`
+    pub on_update: Option<String>,
+    pub on_delete: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TriggerInfo {
+    pub name: String,
+    /// `BEFORE`, `AFTER` or `INSTEAD OF`
+    pub timing: String,
+    /// Statements firing the trigger, e.g. `INSERT OR UPDATE`
+    pub events: String,
+    /// Body, or the whole `CREATE TRIGGER` statement where the server keeps only that
+    pub definition: String,
+}
+
+/// What the catalog knows about a SQL table besides its rows
+#[derive(Debug, Clone, Default)]
+pub struct TableStructure {
+    pub columns: Vec<ColumnDefinition>,
+    pub primary_key: Option<KeyConstraint>,
+    pub unique_constraints: Vec<KeyConstraint>,
+    pub check_constraints: Vec<CheckConstraint>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub triggers: Vec<TriggerInfo>,
+}
+
+/// Storage statistics of a database, or of one collection or table, as far as
+/// the server keeps them. Sizes are in bytes.
+#[derive(Debug, Clone, Default)]
+pub struct StorageStats {
+    /// Documents or rows; an estimate where the server keeps only that
+    pub document_count: Option<u64>,
+    /// Collections or tables (database statistics only)
+    pub collection_count: Option<u64>,
+    /// Size of the documents or rows themselves
+    pub data_size: Option<u64>,
+    /// Space the documents or rows take on disk, indexes left out
+    pub storage_size: Option<u64>,
+    /// Space allocated but unused, given back by a compaction or `VACUUM`
+    pub free_size: Option<u64>,
+    pub index_count: Option<u64>,
+    pub index_size: Option<u64>,
+    /// Space taken on disk, indexes included
+    pub total_size: Option<u64>,
+    pub avg_object_size: Option<u64>,
+    /// Size limit of a capped collection
+    pub max_size: Option<u64>,
+    /// Document limit of a capped collection
+    pub max_documents: Option<u64>,
+    /// Size of each index (collection statistics only)
+    pub index_sizes: Vec<(String, u64)>,
+    /// Everything else worth knowing, e.g. time-series options, storage engine
+    /// or encoding
+    pub properties: Vec<(String, String)>,
+}
+
+/// One reading of a server's activity, taken by polling. Counters only grow
+/// while the server runs; rates come from the difference between two readings.
+#[derive(Debug, Clone, Default)]
+pub struct ServerStatus {
+    /// Commands, queries or transactions served since the server started
+    pub operations: Option<u64>,
+    /// Open client connections
+    pub connections: Option<u64>,
+    /// Reads served from the cache since the server started
+    pub cache_hits: Option<u64>,
+    /// Reads that missed the cache since the server started
+    pub cache_misses: Option<u64>,
+    /// Memory in use by the server, in bytes
+    pub memory_bytes: Option<u64>,
+    /// Seconds this server trails its primary; `None` unless it is a replica
+    pub replication_lag: Option<f64>,
+    /// Seconds since the server started
+    pub uptime: Option<u64>,
+    /// Other figures shown next to the charts, e.g. version or role
+    pub properties: Vec<(String, String)>,
+}
+
+/// Result of running an ad-hoc query from the console.
+/// When a script contains several statements, this describes the last one.
+#[derive(Debug, Clone, Default)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    /// Rows changed by a data-modifying statement
+    pub affected_rows: Option<u64>,
+    /// Raw documents returned by document databases. When set, `rows` is empty
+    /// and `columns` is the union of the documents' top-level fields.
+    pub documents: Option<Vec<serde_json::Value>>,
+    pub elapsed: Duration,
+}
+
+impl QueryResult {
+    /// Number of rows or documents returned
+    pub fn row_count(&self) -> usize {
+        self.documents.as_ref().map_or(self.rows.len(), |docs| docs.len())
+    }
+}
+
+/// Outcome of inserting a batch of documents
+#[derive(Debug, Clone, Default)]
+pub struct BatchInsertResult {
+    pub inserted: usize,
+    /// Rejected documents, by their index in the batch, with the reason
+    pub rejected: Vec<(usize, String)>,
+}
+
+/// Reply to a raw command, as sent by the server (a RESP value tree)
+#[derive(Debug, Clone, PartialEq)]
+pub enum RespValue {
+    Nil,
+    Integer(i64),
+    Double(f64),
+    Boolean(bool),
+    /// Simple string such as `OK` or `PONG`
+    Status(String),
+    BulkString(String),
+    /// Error reply, e.g. `WRONGTYPE Operation against a key holding the wrong kind of value`
+    Error(String),
+    Array(Vec<RespValue>),
+    Set(Vec<RespValue>),
+    Map(Vec<(RespValue, RespValue)>),
+}
+
+/// Collection name under which Redis lists the keys of a logical database
+pub const REDIS_KEYSPACE: &str = "*";
+
+/// Filtered Redis key counts stop at this many matches rather than walk the whole keyspace
+pub const REDIS_COUNT_LIMIT: usize = 10_000;
+
+/// Where a paged SCAN of a Redis keyspace continues
+#[derive(Debug, Clone, Default)]
+pub struct KeyspaceCursor {
+    /// Server cursor to resume from (0 before the first page)
+    pub cursor: u64,
+    /// Keys the server already returned beyond the previous page
+    pub pending: Vec<Vec<u8>>,
+    /// The server cursor wrapped around; only `pending` is left
+    pub scanned: bool,
+}
+
+/// One page of a Redis keyspace listing
+#[derive(Debug, Clone)]
+pub struct KeyspacePage {
+    /// Key, type and TTL rows, as returned by `query_documents`
+    pub rows: Vec<serde_json::Value>,
+    /// Where the next page starts, `None` after the last page
+    pub next: Option<KeyspaceCursor>,
+}
+
+/// Escape glob characters so a Redis key can be used as an exact MATCH filter
+pub fn escape_redis_pattern(key: &str) -> String {
+    let mut escaped = String::with_capacity(key.len());
+    for c in key.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 /// Core trait for database connections
 #[async_trait]
 pub trait DatabaseConnection: Send + Sync {
@@ -73,6 +346,225 @@ pub trait DatabaseConnection: Send + Sync {
         Ok(0)
     }
 
+    /// One page of the keys of a Redis database matching `filter`, continuing the
+    /// SCAN where the previous page stopped instead of rescanning from the start
+    async fn scan_keyspace(
+        &self,
+        database_name: &str,
+        filter: Option<&str>,
+        from: &KeyspaceCursor,
+        limit: u32,
+    ) -> Result<KeyspacePage> {
+        let _ = (database_name, filter, from, limit);
+        Err(ConnectionError::Failed("Key scanning not supported for this driver".into()))
+    }
+
+    /// Columns of a SQL table in definition order.
+    /// Returns empty list for databases that don't support this operation
+    async fn table_columns(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<Vec<ColumnInfo>> {
+        let _ = (database_name, collection_name);
+        Ok(Vec::new())
+    }
+
+    /// Run statements in a single transaction, rolling back if any of them fails.
+    /// Returns the number of rows each statement affected.
+    async fn execute_transaction(
+        &self,
+        database_name: &str,
+        statements: &[String],
+    ) -> Result<Vec<u64>> {
+        let _ = (database_name, statements);
+        Err(ConnectionError::Failed("Transactions not supported for this driver".into()))
+    }
+
+    /// Run every statement in one transaction, each behind its own savepoint: a
+    /// statement the server rejects is rolled back alone and the rest are committed.
+    /// With `stop_on_error`, the statements after the first rejected one are skipped.
+    async fn execute_each(
+        &self,
+        database_name: &str,
+        statements: &[String],
+        stop_on_error: bool,
+    ) -> Result<BatchInsertResult> {
+        let _ = (database_name, statements, stop_on_error);
+        Err(ConnectionError::Failed("Transactions not supported for this driver".into()))
+    }
+
+    /// Execute arbitrary query text (one or more statements) against a database.
+    /// An empty database name uses the connection's default database.
+    async fn execute_query(&self, database_name: &str, query: &str) -> Result<QueryResult> {
+        let _ = (database_name, query);
+        Err(ConnectionError::Failed("Query console not supported for this driver".into()))
+    }
+
+    /// Plan of the page `query_documents` loads with this filter and sort
+    async fn explain_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        limit: u32,
+        skip: u32,
+        filter: Option<&str>,
+        sort: Option<&str>,
+    ) -> Result<QueryPlan> {
+        let _ = (database_name, collection_name, limit, skip, filter, sort);
+        Err(ConnectionError::Failed("Explain not supported for this driver".into()))
+    }
+
+    /// Plan of one console statement or shell command, with execution figures
+    /// where the server collects them. Statements that have to run to be timed
+    /// are rolled back.
+    async fn explain_query(&self, database_name: &str, query: &str) -> Result<QueryPlan> {
+        let _ = (database_name, query);
+        Err(ConnectionError::Failed("Explain not supported for this driver".into()))
+    }
+
+    /// Send a raw command (e.g. `HGETALL user:1`) and return the reply tree.
+    /// Error replies from the server are returned as `RespValue::Error`.
+    async fn execute_command(&self, database_name: &str, command: &str) -> Result<RespValue> {
+        let _ = (database_name, command);
+        Err(ConnectionError::Failed("Command console not supported for this driver".into()))
+    }
+
+    /// Set fields on the document with the given `_id` (`updateOne` with `$set`).
+    /// `id` and the new values are Extended JSON, so BSON types survive the round trip.
+    /// Fails if no document matches.
+    async fn update_document(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        id: &serde_json::Value,
+        changes: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<()> {
+        let _ = (database_name, collection_name, id, changes);
+        Err(ConnectionError::Failed("Document editing not supported for this driver".into()))
+    }
+
+    /// Insert an Extended JSON document and return its `_id`
+    async fn insert_document(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        document: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let _ = (database_name, collection_name, document);
+        Err(ConnectionError::Failed("Document editing not supported for this driver".into()))
+    }
+
+    /// Insert a batch of Extended JSON documents (`insertMany`). Documents that
+    /// cannot be inserted are reported in the result instead of failing the call;
+    /// with `ordered`, nothing after the first of them is inserted.
+    async fn insert_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        documents: &[serde_json::Value],
+        ordered: bool,
+    ) -> Result<BatchInsertResult> {
+        let _ = (database_name, collection_name, documents, ordered);
+        Err(ConnectionError::Failed("Document editing not supported for this driver".into()))
+    }
+
+    /// Replace the whole document with the given `_id` (`replaceOne`). The new
+    /// document may omit `_id`, but cannot change it. Fails if no document matches.
+    async fn replace_document(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        id: &serde_json::Value,
+        document: &serde_json::Value,
+    ) -> Result<()> {
+        let _ = (database_name, collection_name, id, document);
+        Err(ConnectionError::Failed("Document editing not supported for this driver".into()))
+    }
+
+    /// Delete the documents with the given `_id`s, returning how many were removed
+    async fn delete_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        ids: &[serde_json::Value],
+    ) -> Result<u64> {
+        let _ = (database_name, collection_name, ids);
+        Err(ConnectionError::Failed("Document editing not supported for this driver".into()))
+    }
+
+    /// Run an aggregation pipeline of Extended JSON stages and return at most
+    /// `limit` of the documents it outputs
+    async fn aggregate(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        pipeline: &[serde_json::Value],
+        limit: u32,
+    ) -> Result<Vec<serde_json::Value>> {
+        let _ = (database_name, collection_name, pipeline, limit);
+        Err(ConnectionError::Failed("Aggregation not supported for this driver".into()))
+    }
+
+    /// List the indexes of a collection or table, with size and usage where
+    /// the server keeps them
+    async fn list_indexes(&self, database_name: &str, collection_name: &str) -> Result<Vec<IndexInfo>> {
+        let _ = (database_name, collection_name);
+        Err(ConnectionError::Failed("Indexes not supported for this driver".into()))
+    }
+
+    /// Build an index and wait until it is ready. Builds are not bounded by the
+    /// timeout, as they can take a long time on large collections.
+    async fn create_index(&self, database_name: &str, collection_name: &str, spec: &IndexSpec) -> Result<()> {
+        let _ = (database_name, collection_name, spec);
+        Err(ConnectionError::Failed("Indexes not supported for this driver".into()))
+    }
+
+    async fn drop_index(&self, database_name: &str, collection_name: &str, index_name: &str) -> Result<()> {
+        let _ = (database_name, collection_name, index_name);
+        Err(ConnectionError::Failed("Indexes not supported for this driver".into()))
+    }
+
+    /// Progress of an index build running on the collection or table, for
+    /// servers that report it
+    async fn index_build_progress(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<Option<IndexBuildProgress>> {
+        let _ = (database_name, collection_name);
+        Ok(None)
+    }
+
+    /// Columns, constraints and triggers of a SQL table, read from the catalog
+    async fn table_structure(&self, database_name: &str, collection_name: &str) -> Result<TableStructure> {
+        let _ = (database_name, collection_name);
+        Err(ConnectionError::Failed("Table structure not supported for this driver".into()))
+    }
+
+    /// Statements recreating a SQL table: `CREATE TABLE`, then its indexes and triggers
+    async fn table_ddl(&self, database_name: &str, collection_name: &str) -> Result<String> {
+        let _ = (database_name, collection_name);
+        Err(ConnectionError::Failed("Table structure not supported for this driver".into()))
+    }
+
+    /// Size and object counts of a database, e.g. `dbStats`
+    async fn database_stats(&self, database_name: &str) -> Result<StorageStats> {
+        let _ = database_name;
+        Err(ConnectionError::Failed("Statistics not supported for this driver".into()))
+    }
+
+    /// Size, row count and options of a collection or table, e.g. `$collStats`
+    async fn collection_stats(&self, database_name: &str, collection_name: &str) -> Result<StorageStats> {
+        let _ = (database_name, collection_name);
+        Err(ConnectionError::Failed("Statistics not supported for this driver".into()))
+    }
+
+    /// Activity counters of the whole server, e.g. `serverStatus` or `INFO`
+    async fn server_status(&self) -> Result<ServerStatus> {
+        Err(ConnectionError::Failed("Server status not supported for this driver".into()))
+    }
+
     /// Drop (delete) a database
     async fn drop_database(&self, database_name: &str) -> Result<()> {
         let _ = database_name;
@@ -84,6 +576,9 @@ pub trait DatabaseConnection: Send + Sync {
         let _ = (database_name, collection_name);
         Err(ConnectionError::Failed("Drop collection not supported for this driver".into()))
     }
+
+    /// Gracefully shut down pooled clients before the connection is dropped
+    async fn close(&self) {}
 }
 
 /// Configuration for creating a database connection
@@ -92,6 +587,10 @@ pub struct ConnectionConfig {
     pub driver: DatabaseType,
     pub connection_string: String,
     pub timeout: Duration,
+    /// Only used by the PostgreSQL and MySQL drivers
+    pub tls: TlsConfig,
+    /// Forward the connection through an SSH jump host
+    pub ssh: Option<SshTunnelConfig>,
 }
 
 impl ConnectionConfig {
@@ -100,16 +599,62 @@ impl ConnectionConfig {
             driver,
             connection_string,
             timeout: Duration::from_secs(10),
+            tls: TlsConfig::default(),
+            ssh: None,
         }
     }
+
+    pub fn with_tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub fn with_ssh(mut self, ssh: Option<SshTunnelConfig>) -> Self {
+        self.ssh = ssh;
+        self
+    }
 }
 
-/// Factory function - creates the right connection type based on driver
-pub fn create_connection(config: ConnectionConfig) -> Result<Box<dyn DatabaseConnection>> {
+/// Factory function - creates the right connection type based on driver.
+/// With an SSH tunnel configured, the tunnel is opened first on a blocking
+/// thread (the handshake is synchronous) and the driver connects through its
+/// local end.
+pub async fn create_connection(config: ConnectionConfig) -> Result<Box<dyn DatabaseConnection>> {
     if !config.driver.is_available() {
         return Err(ConnectionError::DriverNotAvailable(config.driver.feature_name()));
     }
-    
+
+    #[cfg(feature = "ssh")]
+    if let Some(ssh) = config.ssh.clone() {
+        use super::tunnel::{SshTunnel, TunnelTarget, TunneledConnection};
+
+        let target = TunnelTarget::parse(config.driver, &config.connection_string)?;
+        let timeout = config.timeout;
+        let (target, tunnel) = tokio::task::spawn_blocking(move || {
+            let tunnel = SshTunnel::open(&ssh, &target, timeout)?;
+            Ok::<_, ConnectionError>((target, tunnel))
+        })
+        .await
+        .map_err(|e| ConnectionError::Ssh(format!("Tunnel setup was interrupted: {}", e)))??;
+        let inner = create_driver(ConnectionConfig {
+            connection_string: target.connection_string(tunnel.local_port()),
+            ssh: None,
+            ..config
+        })?;
+        return Ok(Box::new(TunneledConnection::new(inner, tunnel)));
+    }
+
+    #[cfg(not(feature = "ssh"))]
+    if config.ssh.is_some() {
+        return Err(ConnectionError::Ssh(
+            "SSH tunnel support not compiled. Rebuild with --features ssh".into(),
+        ));
+    }
+
+    create_driver(config)
+}
+
+fn create_driver(config: ConnectionConfig) -> Result<Box<dyn DatabaseConnection>> {
     match config.driver {
         #[cfg(feature = "postgres")]
         DatabaseType::PostgreSQL => {
@@ -7,6 +7,9 @@ pub mod postgres;
 #[cfg(feature = "mongodb")]
 pub mod mongo;
 
+#[cfg(feature = "mongodb")]
+pub mod mongo_shell;
+
 #[cfg(feature = "redis")]
 pub mod redis_driver;
 
@@ -15,3 +18,6 @@ pub mod mysql;
 
 #[cfg(feature = "sqlite-driver")]
 pub mod sqlite;
+
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite-driver"))]
+pub mod sql;
@@ -2,30 +2,111 @@
 
 use async_trait::async_trait;
 use futures::TryStreamExt;
-use mongodb::{bson::Document, options::ClientOptions, Client};
-use std::time::Instant;
+use mongodb::{
+    bson::{doc, Bson, Document},
+    error::{ErrorKind, InsertManyError},
+    options::ClientOptions,
+    Client,
+};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-use crate::db::driver::{CollectionInfo, ConnectionConfig, ConnectionInfo, DatabaseConnection, DatabaseInfo};
+use crate::db::driver::{
+    BatchInsertResult, CollectionInfo, ConnectionConfig, ConnectionInfo, DatabaseConnection, DatabaseInfo,
+    IndexBuildProgress, IndexInfo, IndexOrder, IndexSpec, QueryResult, ServerStatus, StorageStats,
+};
+use crate::db::drivers::mongo_shell::{parse_shell_command, ShellCommand};
 use crate::db::error::{ConnectionError, Result};
+use crate::db::explain::{self, QueryPlan};
 
-/// Parse a JSON string into a BSON Document. Returns empty document on failure.
-fn parse_json_to_document(json_str: Option<&str>) -> Document {
-    match json_str {
-        Some(s) if !s.is_empty() && s != "{}" => {
-            serde_json::from_str::<serde_json::Value>(s)
-                .ok()
-                .and_then(|v| {
-                    let bson = mongodb::bson::to_bson(&v).ok()?;
-                    bson.as_document().cloned()
-                })
-                .unwrap_or_else(Document::new)
+/// Maximum number of documents a console command returns
+const CONSOLE_DOCUMENT_LIMIT: usize = 1000;
+
+/// How long a replication lag reading is reused; `replSetGetStatus` reports on
+/// every member, which is too heavy to run on each status poll
+const REPLICATION_LAG_TTL: Duration = Duration::from_secs(15);
+
+/// Parse a filter or sort given as (Extended) JSON into a BSON Document. Empty
+/// input is an empty document; invalid input is an error, not a match-all filter.
+fn parse_json_to_document(json_str: Option<&str>, what: &str) -> Result<Document> {
+    match json_str.map(str::trim) {
+        Some(s) if !s.is_empty() => {
+            let value: serde_json::Value = serde_json::from_str(s)
+                .map_err(|e| ConnectionError::Failed(format!("Invalid {}: {}", what, e)))?;
+            match json_to_bson(&value)? {
+                Bson::Document(document) => Ok(document),
+                _ => Err(ConnectionError::Failed(format!("Invalid {}: expected a JSON object", what))),
+            }
         }
-        _ => Document::new(),
+        _ => Ok(Document::new()),
+    }
+}
+
+/// Convert a BSON document to a JSON value (extended JSON for BSON-only types)
+fn document_to_json(doc: Document) -> Result<serde_json::Value> {
+    bson_to_json(Bson::Document(doc))
+}
+
+/// Convert a BSON value to JSON. Int64 stays tagged as `$numberLong` (plain JSON
+/// numbers read back as Int32 when they fit), so edited values keep their type.
+pub fn bson_to_json(value: Bson) -> Result<serde_json::Value> {
+    match value {
+        Bson::Int64(n) => Ok(serde_json::json!({ "$numberLong": n.to_string() })),
+        Bson::Document(doc) => doc
+            .into_iter()
+            .map(|(key, value)| Ok((key, bson_to_json(value)?)))
+            .collect::<Result<serde_json::Map<_, _>>>()
+            .map(serde_json::Value::Object),
+        Bson::Array(items) => items
+            .into_iter()
+            .map(bson_to_json)
+            .collect::<Result<Vec<_>>>()
+            .map(serde_json::Value::Array),
+        other => serde_json::to_value(other).map_err(|e| ConnectionError::Failed(e.to_string())),
     }
 }
 
+/// Parse an Extended JSON value (canonical or relaxed) back into BSON
+fn json_to_bson(value: &serde_json::Value) -> Result<Bson> {
+    Bson::try_from(value.clone())
+        .map_err(|e| ConnectionError::Failed(format!("Invalid Extended JSON: {}", e)))
+}
+
+/// Integer value of a numeric BSON field, whichever width the server used
+fn bson_integer(value: &Bson) -> Option<i64> {
+    match value {
+        Bson::Int32(n) => Some(*n as i64),
+        Bson::Int64(n) => Some(*n),
+        Bson::Double(n) => Some(*n as i64),
+        _ => None,
+    }
+}
+
+/// Drain a cursor into JSON documents, stopping after `max` documents
+async fn collect_documents(
+    mut cursor: mongodb::Cursor<Document>,
+    max: usize,
+) -> Result<Vec<serde_json::Value>> {
+    let mut documents = Vec::new();
+    while documents.len() < max {
+        match cursor
+            .try_next()
+            .await
+            .map_err(|e: mongodb::error::Error| ConnectionError::Failed(e.to_string()))?
+        {
+            Some(doc) => documents.push(document_to_json(doc)?),
+            None => break,
+        }
+    }
+    Ok(documents)
+}
+
 pub struct MongoConnection {
     config: ConnectionConfig,
+    /// Created on first use; the driver pools server connections internally
+    client: Mutex<Option<Client>>,
+    /// Last replication lag of a secondary and when it was read
+    replication_lag: Mutex<Option<(Instant, Option<f64>)>>,
 }
 
 impl MongoConnection {
@@ -36,16 +117,21 @@ impl MongoConnection {
                 "MongoDB connection string must start with mongodb:// or mongodb+srv://".into(),
             ));
         }
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            client: Mutex::new(None),
+            replication_lag: Mutex::new(None),
+        })
     }
-}
 
-#[async_trait]
-impl DatabaseConnection for MongoConnection {
-    async fn test_connection(&self) -> Result<ConnectionInfo> {
-        let start = Instant::now();
+    /// Get the shared client, building it with the configured connect and server
+    /// selection timeouts on first use
+    async fn client(&self) -> Result<Client> {
+        let cached = self.client.lock().unwrap().clone();
+        if let Some(client) = cached {
+            return Ok(client);
+        }
 
-        // Parse connection string
         let mut client_options = tokio::time::timeout(
             self.config.timeout,
             ClientOptions::parse(&self.config.connection_string),
@@ -54,13 +140,71 @@ impl DatabaseConnection for MongoConnection {
         .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
         .map_err(|e| ConnectionError::InvalidConnectionString(e.to_string()))?;
 
-        // Set connection timeout
         client_options.connect_timeout = Some(self.config.timeout);
         client_options.server_selection_timeout = Some(self.config.timeout);
 
-        // Create client
         let client =
             Client::with_options(client_options).map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        *self.client.lock().unwrap() = Some(client.clone());
+        Ok(client)
+    }
+
+    /// Run `explain` on a find, count, distinct or aggregate command. Like the
+    /// console, this is not bounded by the timeout, as the query is executed.
+    async fn explain_command(&self, database_name: &str, command: Document) -> Result<QueryPlan> {
+        let client = self.client().await?;
+        let db = if database_name.is_empty() {
+            client.default_database().unwrap_or_else(|| client.database("test"))
+        } else {
+            client.database(database_name)
+        };
+
+        let reply = db
+            .run_command(doc! { "explain": command, "verbosity": "executionStats" })
+            .await
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        Ok(explain::from_mongo(document_to_json(reply)?))
+    }
+
+    /// Seconds a secondary trails the primary by: the age of its last applied
+    /// operation relative to the primary's. Read at most every `REPLICATION_LAG_TTL`.
+    async fn replication_lag(&self, client: &Client) -> Option<f64> {
+        if let Some((read_at, lag)) = *self.replication_lag.lock().unwrap() {
+            if read_at.elapsed() < REPLICATION_LAG_TTL {
+                return lag;
+            }
+        }
+
+        let status = tokio::time::timeout(
+            self.config.timeout,
+            client.database("admin").run_command(doc! { "replSetGetStatus": 1 }),
+        )
+        .await
+        .ok()
+        .and_then(|reply| reply.ok());
+        let members = status
+            .as_ref()
+            .and_then(|status| status.get_array("members").ok())
+            .map(|members| members.iter().filter_map(Bson::as_document).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let optime = |member: Option<&&Document>| {
+            member.and_then(|member| member.get_datetime("optimeDate").ok()).map(|date| date.timestamp_millis())
+        };
+        let primary = optime(members.iter().find(|member| member.get_str("stateStr").ok() == Some("PRIMARY")));
+        let own = optime(members.iter().find(|member| member.get_bool("self").unwrap_or(false)));
+        let lag = primary.zip(own).map(|(primary, own)| (primary - own).max(0) as f64 / 1000.0);
+
+        *self.replication_lag.lock().unwrap() = Some((Instant::now(), lag));
+        lag
+    }
+}
+
+#[async_trait]
+impl DatabaseConnection for MongoConnection {
+    async fn test_connection(&self) -> Result<ConnectionInfo> {
+        let start = Instant::now();
+
+        let client = self.client().await?;
 
         // Ping the server
         let db = client.database("admin");
@@ -95,20 +239,7 @@ impl DatabaseConnection for MongoConnection {
     }
 
     async fn list_databases(&self) -> Result<Vec<DatabaseInfo>> {
-        // Parse connection string
-        let mut client_options = tokio::time::timeout(
-            self.config.timeout,
-            ClientOptions::parse(&self.config.connection_string),
-        )
-        .await
-        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
-        .map_err(|e| ConnectionError::InvalidConnectionString(e.to_string()))?;
-
-        client_options.connect_timeout = Some(self.config.timeout);
-        client_options.server_selection_timeout = Some(self.config.timeout);
-
-        let client =
-            Client::with_options(client_options).map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let client = self.client().await?;
 
         let databases = tokio::time::timeout(
             self.config.timeout,
@@ -130,20 +261,7 @@ impl DatabaseConnection for MongoConnection {
     }
 
     async fn list_collections(&self, database_name: &str) -> Result<Vec<CollectionInfo>> {
-        // Parse connection string
-        let mut client_options = tokio::time::timeout(
-            self.config.timeout,
-            ClientOptions::parse(&self.config.connection_string),
-        )
-        .await
-        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
-        .map_err(|e| ConnectionError::InvalidConnectionString(e.to_string()))?;
-
-        client_options.connect_timeout = Some(self.config.timeout);
-        client_options.server_selection_timeout = Some(self.config.timeout);
-
-        let client =
-            Client::with_options(client_options).map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let client = self.client().await?;
 
         let db = client.database(database_name);
 
@@ -175,27 +293,14 @@ impl DatabaseConnection for MongoConnection {
         filter: Option<&str>,
         sort: Option<&str>,
     ) -> Result<Vec<serde_json::Value>> {
-        // Parse connection string
-        let mut client_options = tokio::time::timeout(
-            self.config.timeout,
-            ClientOptions::parse(&self.config.connection_string),
-        )
-        .await
-        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
-        .map_err(|e| ConnectionError::InvalidConnectionString(e.to_string()))?;
-
-        client_options.connect_timeout = Some(self.config.timeout);
-        client_options.server_selection_timeout = Some(self.config.timeout);
-
-        let client =
-            Client::with_options(client_options).map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let client = self.client().await?;
 
         let db = client.database(database_name);
         let collection = db.collection::<Document>(collection_name);
 
         // Parse filter and sort
-        let filter_doc = parse_json_to_document(filter);
-        let sort_doc = parse_json_to_document(sort);
+        let filter_doc = parse_json_to_document(filter, "filter")?;
+        let sort_doc = parse_json_to_document(sort, "sort")?;
 
         // Build find options
         let find_options = mongodb::options::FindOptions::builder()
@@ -205,7 +310,7 @@ impl DatabaseConnection for MongoConnection {
             .build();
 
         // Execute query
-        let mut cursor = tokio::time::timeout(
+        let cursor = tokio::time::timeout(
             self.config.timeout,
             collection.find(filter_doc).with_options(find_options),
         )
@@ -213,22 +318,7 @@ impl DatabaseConnection for MongoConnection {
         .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
         .map_err(|e| ConnectionError::Failed(e.to_string()))?;
 
-        // Collect results
-        let mut documents = Vec::new();
-        while let Some(doc) = cursor
-            .try_next()
-            .await
-            .map_err(|e: mongodb::error::Error| ConnectionError::Failed(e.to_string()))?
-        {
-            // Convert BSON Document to JSON Value
-            let json = mongodb::bson::to_bson(&doc)
-                .map_err(|e| ConnectionError::Failed(e.to_string()))?;
-            let value = serde_json::to_value(&json)
-                .map_err(|e| ConnectionError::Failed(e.to_string()))?;
-            documents.push(value);
-        }
-
-        Ok(documents)
+        collect_documents(cursor, limit as usize).await
     }
 
     async fn count_documents(
@@ -237,78 +327,845 @@ impl DatabaseConnection for MongoConnection {
         collection_name: &str,
         filter: Option<&str>,
     ) -> Result<usize> {
-        // Parse connection string
-        let mut client_options = tokio::time::timeout(
+        let client = self.client().await?;
+
+        let db = client.database(database_name);
+        let collection = db.collection::<Document>(collection_name);
+
+        // Parse filter
+        let filter_doc = parse_json_to_document(filter, "filter")?;
+
+        let count = tokio::time::timeout(
             self.config.timeout,
-            ClientOptions::parse(&self.config.connection_string),
+            collection.count_documents(filter_doc),
         )
         .await
         .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
-        .map_err(|e| ConnectionError::InvalidConnectionString(e.to_string()))?;
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
 
-        client_options.connect_timeout = Some(self.config.timeout);
-        client_options.server_selection_timeout = Some(self.config.timeout);
+        Ok(count as usize)
+    }
 
-        let client =
-            Client::with_options(client_options).map_err(|e| ConnectionError::Failed(e.to_string()))?;
+    async fn execute_query(&self, database_name: &str, query: &str) -> Result<QueryResult> {
+        let command = parse_shell_command(query).map_err(ConnectionError::Failed)?;
+
+        let client = self.client().await?;
+        // Like the shell, fall back to the connection string's database, then "test"
+        let db = if database_name.is_empty() {
+            client.default_database().unwrap_or_else(|| client.database("test"))
+        } else {
+            client.database(database_name)
+        };
+
+        let start = Instant::now();
+        let documents = match command {
+            ShellCommand::Find { collection, filter, projection, sort, skip, limit } => {
+                let find_options = mongodb::options::FindOptions::builder()
+                    .projection(projection)
+                    .sort(sort)
+                    .skip(skip)
+                    .limit(Some(limit.unwrap_or(CONSOLE_DOCUMENT_LIMIT as i64)))
+                    .build();
+                let cursor = db
+                    .collection::<Document>(&collection)
+                    .find(filter)
+                    .with_options(find_options)
+                    .await
+                    .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+                collect_documents(cursor, CONSOLE_DOCUMENT_LIMIT).await?
+            }
+            ShellCommand::FindOne { collection, filter, projection } => {
+                let find_options = mongodb::options::FindOneOptions::builder()
+                    .projection(projection)
+                    .build();
+                db.collection::<Document>(&collection)
+                    .find_one(filter)
+                    .with_options(find_options)
+                    .await
+                    .map_err(|e| ConnectionError::Failed(e.to_string()))?
+                    .map(document_to_json)
+                    .transpose()?
+                    .into_iter()
+                    .collect()
+            }
+            ShellCommand::Aggregate { collection, pipeline } => {
+                let cursor = db
+                    .collection::<Document>(&collection)
+                    .aggregate(pipeline)
+                    .await
+                    .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+                collect_documents(cursor, CONSOLE_DOCUMENT_LIMIT).await?
+            }
+            ShellCommand::CountDocuments { collection, filter } => {
+                let count = db
+                    .collection::<Document>(&collection)
+                    .count_documents(filter)
+                    .await
+                    .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+                vec![serde_json::json!({ "count": count })]
+            }
+            ShellCommand::Distinct { collection, field, filter } => db
+                .collection::<Document>(&collection)
+                .distinct(&field, filter)
+                .await
+                .map_err(|e| ConnectionError::Failed(e.to_string()))?
+                .into_iter()
+                .map(|value| document_to_json(doc! { field.as_str(): value }))
+                .collect::<Result<Vec<_>>>()?,
+            ShellCommand::RunCommand(command) => {
+                let mut reply = db
+                    .run_command(command)
+                    .await
+                    .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+                // Commands that open a cursor (find, aggregate, listCollections...)
+                // are more useful shown as their first batch of documents
+                let first_batch = reply
+                    .get_document_mut("cursor")
+                    .ok()
+                    .and_then(|cursor| cursor.remove("firstBatch"));
+                match first_batch {
+                    Some(Bson::Array(batch)) => batch
+                        .into_iter()
+                        .map(|item| match item {
+                            Bson::Document(doc) => document_to_json(doc),
+                            other => document_to_json(doc! { "value": other }),
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                    _ => vec![document_to_json(reply)?],
+                }
+            }
+        };
+
+        // Table columns are the union of top-level fields, in first-seen order
+        let mut columns: Vec<String> = Vec::new();
+        for doc in &documents {
+            if let Some(object) = doc.as_object() {
+                for key in object.keys() {
+                    if !columns.contains(key) {
+                        columns.push(key.clone());
+                    }
+                }
+            }
+        }
+
+        Ok(QueryResult {
+            columns,
+            documents: Some(documents),
+            elapsed: start.elapsed(),
+            ..Default::default()
+        })
+    }
+
+    async fn explain_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        limit: u32,
+        skip: u32,
+        filter: Option<&str>,
+        sort: Option<&str>,
+    ) -> Result<QueryPlan> {
+        let mut find = doc! {
+            "find": collection_name,
+            "filter": parse_json_to_document(filter, "filter")?,
+            "limit": limit as i64,
+            "skip": skip as i64,
+        };
+        let sort_doc = parse_json_to_document(sort, "sort")?;
+        if !sort_doc.is_empty() {
+            find.insert("sort", sort_doc);
+        }
+        self.explain_command(database_name, find).await
+    }
+
+    async fn explain_query(&self, database_name: &str, query: &str) -> Result<QueryPlan> {
+        let command = match parse_shell_command(query).map_err(ConnectionError::Failed)? {
+            ShellCommand::Find { collection, filter, projection, sort, skip, limit } => {
+                let mut find = doc! { "find": collection, "filter": filter };
+                if let Some(projection) = projection {
+                    find.insert("projection", projection);
+                }
+                if let Some(sort) = sort {
+                    find.insert("sort", sort);
+                }
+                if let Some(skip) = skip {
+                    find.insert("skip", skip as i64);
+                }
+                find.insert("limit", limit.unwrap_or(CONSOLE_DOCUMENT_LIMIT as i64));
+                find
+            }
+            ShellCommand::FindOne { collection, filter, projection } => {
+                let mut find = doc! { "find": collection, "filter": filter, "limit": 1_i64, "singleBatch": true };
+                if let Some(projection) = projection {
+                    find.insert("projection", projection);
+                }
+                find
+            }
+            ShellCommand::Aggregate { collection, pipeline } => {
+                doc! { "aggregate": collection, "pipeline": pipeline, "cursor": {} }
+            }
+            ShellCommand::CountDocuments { collection, filter } => {
+                doc! { "count": collection, "query": filter }
+            }
+            ShellCommand::Distinct { collection, field, filter } => {
+                doc! { "distinct": collection, "key": field, "query": filter }
+            }
+            ShellCommand::RunCommand(_) => {
+                return Err(ConnectionError::Failed(
+                    "Only find, findOne, aggregate, countDocuments and distinct can be explained".into(),
+                ))
+            }
+        };
+        self.explain_command(database_name, command).await
+    }
+
+    async fn update_document(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        id: &serde_json::Value,
+        changes: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<()> {
+        let client = self.client().await?;
+        let collection = client.database(database_name).collection::<Document>(collection_name);
+
+        let mut set = Document::new();
+        for (field, value) in changes {
+            set.insert(field.clone(), json_to_bson(value)?);
+        }
+
+        let result = tokio::time::timeout(
+            self.config.timeout,
+            collection.update_one(doc! { "_id": json_to_bson(id)? }, doc! { "$set": set }),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        if result.matched_count == 0 {
+            return Err(ConnectionError::Failed(format!(
+                "No document with _id {} (it may have been deleted)",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn insert_document(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        document: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let client = self.client().await?;
+        let collection = client.database(database_name).collection::<Document>(collection_name);
+
+        let Bson::Document(document) = json_to_bson(document)? else {
+            return Err(ConnectionError::Failed("A document must be a JSON object".into()));
+        };
+
+        let result = tokio::time::timeout(self.config.timeout, collection.insert_one(document))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        bson_to_json(result.inserted_id)
+    }
+
+    async fn insert_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        documents: &[serde_json::Value],
+        ordered: bool,
+    ) -> Result<BatchInsertResult> {
+        let client = self.client().await?;
+        let collection = client.database(database_name).collection::<Document>(collection_name);
 
+        // Documents that are not valid Extended JSON are rejected without being sent;
+        // `positions` maps the batch sent to the server back to `documents`
+        let mut rejected = Vec::new();
+        let mut batch = Vec::new();
+        let mut positions = Vec::new();
+        for (index, document) in documents.iter().enumerate() {
+            match json_to_bson(document) {
+                Ok(Bson::Document(document)) => {
+                    batch.push(document);
+                    positions.push(index);
+                }
+                Ok(_) => rejected.push((index, "A document must be a JSON object".to_string())),
+                Err(ConnectionError::Failed(message)) => rejected.push((index, message)),
+                Err(e) => rejected.push((index, e.to_string())),
+            }
+            if ordered && !rejected.is_empty() {
+                break;
+            }
+        }
+
+        let mut inserted = 0;
+        if !batch.is_empty() {
+            let sent = batch.len();
+            let result = tokio::time::timeout(self.config.timeout, collection.insert_many(batch).ordered(ordered))
+                .await
+                .map_err(|_| ConnectionError::Timeout(self.config.timeout))?;
+            match result {
+                Ok(result) => inserted = result.inserted_ids.len(),
+                Err(e) => match e.kind.as_ref() {
+                    ErrorKind::InsertMany(InsertManyError {
+                        write_errors: Some(errors),
+                        write_concern_error: None,
+                        ..
+                    }) => {
+                        inserted = if ordered {
+                            errors.iter().map(|error| error.index).min().unwrap_or(sent)
+                        } else {
+                            sent - errors.len()
+                        };
+                        rejected.extend(errors.iter().map(|error| (positions[error.index], error.message.clone())));
+                    }
+                    _ => return Err(ConnectionError::Failed(e.to_string())),
+                },
+            }
+        }
+
+        rejected.sort_by_key(|(index, _)| *index);
+        if ordered {
+            // Nothing after the first rejected document was tried
+            rejected.truncate(1);
+        }
+        Ok(BatchInsertResult { inserted, rejected })
+    }
+
+    async fn replace_document(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        id: &serde_json::Value,
+        document: &serde_json::Value,
+    ) -> Result<()> {
+        let client = self.client().await?;
+        let collection = client.database(database_name).collection::<Document>(collection_name);
+
+        let Bson::Document(document) = json_to_bson(document)? else {
+            return Err(ConnectionError::Failed("A document must be a JSON object".into()));
+        };
+
+        let result = tokio::time::timeout(
+            self.config.timeout,
+            collection.replace_one(doc! { "_id": json_to_bson(id)? }, document),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        if result.matched_count == 0 {
+            return Err(ConnectionError::Failed(format!(
+                "No document with _id {} (it may have been deleted)",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn delete_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        ids: &[serde_json::Value],
+    ) -> Result<u64> {
+        let client = self.client().await?;
+        let collection = client.database(database_name).collection::<Document>(collection_name);
+
+        let ids = ids.iter().map(json_to_bson).collect::<Result<Vec<_>>>()?;
+        let result = tokio::time::timeout(
+            self.config.timeout,
+            collection.delete_many(doc! { "_id": { "$in": ids } }),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        Ok(result.deleted_count)
+    }
+
+    async fn aggregate(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        pipeline: &[serde_json::Value],
+        limit: u32,
+    ) -> Result<Vec<serde_json::Value>> {
+        let client = self.client().await?;
+        let collection = client.database(database_name).collection::<Document>(collection_name);
+
+        let mut stages = Vec::with_capacity(pipeline.len() + 1);
+        for stage in pipeline {
+            let Bson::Document(stage) = json_to_bson(stage)? else {
+                return Err(ConnectionError::Failed("A pipeline stage must be a JSON object".into()));
+            };
+            stages.push(stage);
+        }
+        // Stop the server after the documents we show
+        stages.push(doc! { "$limit": limit as i64 });
+
+        let cursor = tokio::time::timeout(self.config.timeout, collection.aggregate(stages))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        collect_documents(cursor, limit as usize).await
+    }
+
+    async fn list_indexes(&self, database_name: &str, collection_name: &str) -> Result<Vec<IndexInfo>> {
+        let client = self.client().await?;
         let db = client.database(database_name);
+
+        let reply = tokio::time::timeout(
+            self.config.timeout,
+            db.run_command(doc! { "listIndexes": collection_name }),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        // A collection has at most 64 indexes, so they all fit in the first batch
+        let specs = reply
+            .get_document("cursor")
+            .and_then(|cursor| cursor.get_array("firstBatch"))
+            .cloned()
+            .unwrap_or_default();
+
+        // Usage and sizes need extra privileges and are not kept for views, so
+        // they are left out when the server refuses
         let collection = db.collection::<Document>(collection_name);
+        let mut usage = std::collections::HashMap::new();
+        if let Ok(Ok(cursor)) = tokio::time::timeout(
+            self.config.timeout,
+            collection.aggregate([doc! { "$indexStats": {} }]),
+        )
+        .await
+        {
+            let stats: Vec<Document> = cursor.try_collect().await.unwrap_or_default();
+            // Sharded collections report one entry per shard for each index
+            for stats in stats {
+                let name = stats.get_str("name").map(String::from);
+                let ops = stats
+                    .get_document("accesses")
+                    .ok()
+                    .and_then(|accesses| accesses.get("ops"))
+                    .and_then(bson_integer);
+                if let (Ok(name), Some(ops)) = (name, ops) {
+                    *usage.entry(name).or_insert(0) += ops as u64;
+                }
+            }
+        }
+        let sizes = tokio::time::timeout(
+            self.config.timeout,
+            db.run_command(doc! { "collStats": collection_name }),
+        )
+        .await
+        .ok()
+        .and_then(|reply| reply.ok())
+        .and_then(|reply| reply.get_document("indexSizes").ok().cloned())
+        .unwrap_or_default();
 
-        // Parse filter
-        let filter_doc = parse_json_to_document(filter);
+        let mut indexes = Vec::with_capacity(specs.len());
+        for spec in specs {
+            let Bson::Document(spec) = spec else {
+                continue;
+            };
+            let name = spec.get_str("name").unwrap_or_default().to_string();
+            let keys = spec
+                .get_document("key")
+                .map(|key| {
+                    key.iter()
+                        .map(|(field, value)| {
+                            let order = match value {
+                                Bson::String(kind) => IndexOrder::Special(kind.clone()),
+                                value if bson_integer(value).is_some_and(|n| n < 0) => IndexOrder::Descending,
+                                _ => IndexOrder::Ascending,
+                            };
+                            (field.clone(), order)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let partial_filter = match spec.get_document("partialFilterExpression") {
+                Ok(filter) => Some(document_to_json(filter.clone())?.to_string()),
+                Err(_) => None,
+            };
 
-        let count = tokio::time::timeout(
+            indexes.push(IndexInfo {
+                keys,
+                method: None,
+                // The _id index is unique without saying so
+                unique: name == "_id_" || spec.get_bool("unique").unwrap_or(false),
+                primary: name == "_id_",
+                partial_filter,
+                expire_after_seconds: spec.get("expireAfterSeconds").and_then(bson_integer),
+                size_bytes: sizes.get(&name).and_then(bson_integer).map(|n| n as u64),
+                usage_count: usage.get(&name).copied(),
+                name,
+            });
+        }
+        Ok(indexes)
+    }
+
+    async fn create_index(&self, database_name: &str, collection_name: &str, spec: &IndexSpec) -> Result<()> {
+        if spec.keys.is_empty() {
+            return Err(ConnectionError::Failed("An index needs at least one field".into()));
+        }
+        let client = self.client().await?;
+
+        let mut keys = Document::new();
+        for (field, order) in &spec.keys {
+            let value = match order {
+                IndexOrder::Ascending => Bson::Int32(1),
+                IndexOrder::Descending => Bson::Int32(-1),
+                IndexOrder::Special(kind) => Bson::String(kind.clone()),
+            };
+            keys.insert(field.clone(), value);
+        }
+        let mut index = doc! { "key": keys.clone() };
+        // createIndexes requires a name; this is the one the shell would choose
+        let name = spec.name.clone().unwrap_or_else(|| {
+            keys.iter()
+                .map(|(field, value)| match value {
+                    Bson::String(kind) => format!("{}_{}", field, kind),
+                    other => format!("{}_{}", field, other),
+                })
+                .collect::<Vec<_>>()
+                .join("_")
+        });
+        index.insert("name", name);
+        if spec.unique {
+            index.insert("unique", true);
+        }
+        if let Some(filter) = spec.partial_filter.as_deref().filter(|f| !f.trim().is_empty()) {
+            let filter: serde_json::Value = serde_json::from_str(filter)
+                .map_err(|e| ConnectionError::Failed(format!("Invalid partial filter: {}", e)))?;
+            let Bson::Document(filter) = json_to_bson(&filter)? else {
+                return Err(ConnectionError::Failed("The partial filter must be a JSON object".into()));
+            };
+            index.insert("partialFilterExpression", filter);
+        }
+        if let Some(seconds) = spec.expire_after_seconds {
+            index.insert("expireAfterSeconds", seconds);
+        }
+
+        client
+            .database(database_name)
+            .run_command(doc! { "createIndexes": collection_name, "indexes": [index] })
+            .await
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn drop_index(&self, database_name: &str, collection_name: &str, index_name: &str) -> Result<()> {
+        let client = self.client().await?;
+        let collection = client.database(database_name).collection::<Document>(collection_name);
+
+        tokio::time::timeout(self.config.timeout, collection.drop_index(index_name))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(e.to_string()))
+    }
+
+    async fn index_build_progress(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<Option<IndexBuildProgress>> {
+        let client = self.client().await?;
+
+        // Index builds report `progress: { done, total }` while scanning the collection
+        let reply = tokio::time::timeout(
             self.config.timeout,
-            collection.count_documents(filter_doc),
+            client.database("admin").run_command(doc! {
+                "currentOp": true,
+                "command.createIndexes": collection_name,
+            }),
         )
         .await
         .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
         .map_err(|e| ConnectionError::Failed(e.to_string()))?;
 
-        Ok(count as usize)
+        let prefix = format!("{}.", database_name);
+        let operations = reply.get_array("inprog").cloned().unwrap_or_default();
+        let mut building = None;
+        for operation in operations {
+            let Bson::Document(operation) = operation else {
+                continue;
+            };
+            if !operation.get_str("ns").is_ok_and(|ns| ns.starts_with(&prefix)) {
+                continue;
+            }
+            let (done, total) = operation
+                .get_document("progress")
+                .map(|progress| {
+                    let done = progress.get("done").and_then(bson_integer).unwrap_or(0);
+                    let total = progress.get("total").and_then(bson_integer).unwrap_or(0);
+                    (done as u64, total as u64)
+                })
+                .unwrap_or((0, 0));
+            let progress = IndexBuildProgress {
+                phase: operation.get_str("msg").ok().map(String::from),
+                done,
+                total,
+            };
+            // Prefer the operation doing the build over the one waiting for it
+            if total > 0 {
+                return Ok(Some(progress));
+            }
+            building.get_or_insert(progress);
+        }
+        Ok(building)
     }
 
-    async fn drop_database(&self, database_name: &str) -> Result<()> {
-        let mut client_options = tokio::time::timeout(
+    async fn database_stats(&self, database_name: &str) -> Result<StorageStats> {
+        let client = self.client().await?;
+
+        let reply = tokio::time::timeout(
             self.config.timeout,
-            ClientOptions::parse(&self.config.connection_string),
+            client.database(database_name).run_command(doc! { "dbStats": 1 }),
         )
         .await
         .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
-        .map_err(|e| ConnectionError::InvalidConnectionString(e.to_string()))?;
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
 
-        client_options.connect_timeout = Some(self.config.timeout);
-        client_options.server_selection_timeout = Some(self.config.timeout);
+        let number = |key: &str| reply.get(key).and_then(bson_integer).map(|n| n as u64);
+        let mut properties = Vec::new();
+        if let Some(views) = number("views") {
+            properties.push(("Views".to_string(), views.to_string()));
+        }
+        Ok(StorageStats {
+            document_count: number("objects"),
+            collection_count: number("collections"),
+            data_size: number("dataSize"),
+            storage_size: number("storageSize"),
+            free_size: number("freeStorageSize"),
+            index_count: number("indexes"),
+            index_size: number("indexSize"),
+            // totalSize is only reported since MongoDB 4.4
+            total_size: number("totalSize").or_else(|| Some(number("storageSize")? + number("indexSize")?)),
+            avg_object_size: number("avgObjSize"),
+            properties,
+            ..Default::default()
+        })
+    }
 
-        let client =
-            Client::with_options(client_options).map_err(|e| ConnectionError::Failed(e.to_string()))?;
+    async fn collection_stats(&self, database_name: &str, collection_name: &str) -> Result<StorageStats> {
+        let client = self.client().await?;
+        let db = client.database(database_name);
 
-        tokio::time::timeout(
+        // The collection's type and creation options
+        let listing = tokio::time::timeout(
             self.config.timeout,
-            client.database(database_name).drop(),
+            db.run_command(doc! { "listCollections": 1, "filter": { "name": collection_name } }),
         )
         .await
         .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
         .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let spec = listing
+            .get_document("cursor")
+            .and_then(|cursor| cursor.get_array("firstBatch"))
+            .ok()
+            .and_then(|batch| batch.first())
+            .and_then(Bson::as_document)
+            .cloned()
+            .unwrap_or_default();
+        let options = spec.get_document("options").cloned().unwrap_or_default();
 
-        Ok(())
+        let mut stats = StorageStats::default();
+        let kind = spec.get_str("type").unwrap_or("collection");
+        stats.properties.push(("Type".to_string(), kind.to_string()));
+        if let Ok(view_on) = options.get_str("viewOn") {
+            stats.properties.push(("View on".to_string(), view_on.to_string()));
+        }
+        if options.get_bool("capped").unwrap_or(false) {
+            stats.max_size = options.get("size").and_then(bson_integer).map(|n| n as u64);
+            stats.max_documents = options
+                .get("max")
+                .and_then(bson_integer)
+                .filter(|n| *n > 0)
+                .map(|n| n as u64);
+        }
+        if let Ok(timeseries) = options.get_document("timeseries") {
+            for (key, label) in [
+                ("timeField", "Time field"),
+                ("metaField", "Meta field"),
+                ("granularity", "Granularity"),
+            ] {
+                if let Ok(value) = timeseries.get_str(key) {
+                    stats.properties.push((label.to_string(), value.to_string()));
+                }
+            }
+        }
+        if let Some(seconds) = options.get("expireAfterSeconds").and_then(bson_integer) {
+            stats.properties.push(("Expire after".to_string(), format!("{} s", seconds)));
+        }
+        if let Ok(collation) = options.get_document("collation") {
+            if let Ok(locale) = collation.get_str("locale") {
+                stats.properties.push(("Collation".to_string(), locale.to_string()));
+            }
+        }
+        if options.contains_key("validator") {
+            let level = options.get_str("validationLevel").unwrap_or("strict");
+            stats.properties.push(("Validation".to_string(), level.to_string()));
+        }
+        // Views have no storage of their own
+        if kind == "view" {
+            return Ok(stats);
+        }
+
+        let cursor = tokio::time::timeout(
+            self.config.timeout,
+            db.collection::<Document>(collection_name)
+                .aggregate([doc! { "$collStats": { "storageStats": {} } }]),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let replies: Vec<Document> = cursor
+            .try_collect()
+            .await
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        // Sharded collections report one entry per shard; their figures add up
+        let mut index_sizes: Vec<(String, u64)> = Vec::new();
+        for reply in &replies {
+            let Ok(storage) = reply.get_document("storageStats") else {
+                continue;
+            };
+            let number = |key: &str| storage.get(key).and_then(bson_integer).map(|n| n as u64);
+            let add = |total: &mut Option<u64>, value: Option<u64>| {
+                if let Some(value) = value {
+                    *total = Some(total.unwrap_or(0) + value);
+                }
+            };
+            add(&mut stats.document_count, number("count"));
+            add(&mut stats.data_size, number("size"));
+            add(&mut stats.storage_size, number("storageSize"));
+            add(&mut stats.free_size, number("freeStorageSize"));
+            add(&mut stats.index_size, number("totalIndexSize"));
+            add(&mut stats.total_size, number("totalSize"));
+            stats.index_count = stats.index_count.max(number("nindexes"));
+            if let Ok(sizes) = storage.get_document("indexSizes") {
+                for (name, size) in sizes {
+                    let size = bson_integer(size).unwrap_or(0) as u64;
+                    match index_sizes.iter_mut().find(|(n, _)| n == name) {
+                        Some((_, total)) => *total += size,
+                        None => index_sizes.push((name.clone(), size)),
+                    }
+                }
+            }
+        }
+        // totalSize is only reported since MongoDB 4.4
+        if let (None, Some(storage), Some(indexes)) = (stats.total_size, stats.storage_size, stats.index_size) {
+            stats.total_size = Some(storage + indexes);
+        }
+        stats.avg_object_size = match (stats.data_size, stats.document_count) {
+            (Some(size), Some(count)) if count > 0 => Some(size / count),
+            _ => None,
+        };
+        stats.index_sizes = index_sizes;
+        Ok(stats)
     }
 
-    async fn drop_collection(&self, database_name: &str, collection_name: &str) -> Result<()> {
-        let mut client_options = tokio::time::timeout(
+    async fn server_status(&self) -> Result<ServerStatus> {
+        let client = self.client().await?;
+        let admin = client.database("admin");
+
+        let reply = tokio::time::timeout(self.config.timeout, admin.run_command(doc! { "serverStatus": 1 }))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        let number = |section: &str, key: &str| {
+            reply
+                .get_document(section)
+                .ok()
+                .and_then(|section| section.get(key))
+                .and_then(bson_integer)
+                .map(|n| n as u64)
+        };
+        let operations = ["insert", "query", "update", "delete", "getmore", "command"]
+            .iter()
+            .map(|key| number("opcounters", key))
+            .sum::<Option<u64>>();
+
+        // WiredTiger counts every page request; those it had to read came from disk
+        let cache = reply
+            .get_document("wiredTiger")
+            .and_then(|wired_tiger| wired_tiger.get_document("cache"))
+            .ok();
+        let cache_number = |key: &str| cache.and_then(|cache| cache.get(key)).and_then(bson_integer).map(|n| n as u64);
+        let requested = cache_number("pages requested from the cache");
+        let read = cache_number("pages read into cache");
+
+        let mut properties = Vec::new();
+        if let Ok(version) = reply.get_str("version") {
+            properties.push(("Version".to_string(), format!("MongoDB {}", version)));
+        }
+        if let Ok(host) = reply.get_str("host") {
+            properties.push(("Host".to_string(), host.to_string()));
+        }
+        let secondary = reply
+            .get_document("repl")
+            .ok()
+            .and_then(|repl| repl.get_bool("secondary").ok())
+            .unwrap_or(false);
+        if let Ok(repl) = reply.get_document("repl") {
+            let role = if repl.get_bool("ismaster").or_else(|_| repl.get_bool("isWritablePrimary")).unwrap_or(false) {
+                "Primary"
+            } else if secondary {
+                "Secondary"
+            } else {
+                "Other"
+            };
+            properties.push(("Replica set".to_string(), format!("{} ({})", repl.get_str("setName").unwrap_or(""), role)));
+        }
+        if let Some(available) = number("connections", "available") {
+            properties.push(("Available connections".to_string(), available.to_string()));
+        }
+        if let Ok(engine) = reply.get_document("storageEngine").and_then(|engine| engine.get_str("name")) {
+            properties.push(("Storage engine".to_string(), engine.to_string()));
+        }
+
+        let replication_lag = if secondary {
+            self.replication_lag(&client).await
+        } else {
+            None
+        };
+
+        Ok(ServerStatus {
+            operations,
+            connections: number("connections", "current"),
+            cache_hits: requested.zip(read).map(|(requested, read)| requested.saturating_sub(read)),
+            cache_misses: read,
+            // Resident memory is reported in megabytes
+            memory_bytes: number("mem", "resident").map(|mb| mb * 1024 * 1024),
+            replication_lag,
+            uptime: reply.get("uptime").and_then(bson_integer).map(|n| n as u64),
+            properties,
+        })
+    }
+
+    async fn drop_database(&self, database_name: &str) -> Result<()> {
+        let client = self.client().await?;
+
+        tokio::time::timeout(
             self.config.timeout,
-            ClientOptions::parse(&self.config.connection_string),
+            client.database(database_name).drop(),
         )
         .await
         .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
-        .map_err(|e| ConnectionError::InvalidConnectionString(e.to_string()))?;
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
 
-        client_options.connect_timeout = Some(self.config.timeout);
-        client_options.server_selection_timeout = Some(self.config.timeout);
+        Ok(())
+    }
 
-        let client =
-            Client::with_options(client_options).map_err(|e| ConnectionError::Failed(e.to_string()))?;
+    async fn drop_collection(&self, database_name: &str, collection_name: &str) -> Result<()> {
+        let client = self.client().await?;
 
         tokio::time::timeout(
             self.config.timeout,
@@ -320,4 +1177,11 @@ impl DatabaseConnection for MongoConnection {
 
         Ok(())
     }
+
+    async fn close(&self) {
+        let client = self.client.lock().unwrap().take();
+        if let Some(client) = client {
+            client.shutdown().await;
+        }
+    }
 }
@@ -0,0 +1,606 @@
+//! Parser for mongo shell style console input such as
+//! `db.orders.find({ status: "A" }).sort({ total: -1 }).limit(50)`.
+//!
+//! Arguments are JavaScript object literals: keys may be unquoted, strings may
+//! use single quotes, and the usual shell helpers (`ObjectId`, `ISODate`,
+//! `NumberLong`, `NumberInt`, `NumberDecimal`, `/regex/flags`) are understood.
+
+use mongodb::bson::{oid::ObjectId, Bson, DateTime, Decimal128, Document, Regex};
+
+/// A console command, ready to be turned into a driver call
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShellCommand {
+    Find {
+        collection: String,
+        filter: Document,
+        projection: Option<Document>,
+        sort: Option<Document>,
+        skip: Option<u64>,
+        limit: Option<i64>,
+    },
+    FindOne {
+        collection: String,
+        filter: Document,
+        projection: Option<Document>,
+    },
+    Aggregate {
+        collection: String,
+        pipeline: Vec<Document>,
+    },
+    CountDocuments {
+        collection: String,
+        filter: Document,
+    },
+    Distinct {
+        collection: String,
+        field: String,
+        filter: Document,
+    },
+    RunCommand(Document),
+}
+
+/// One step of a `db.a.b(...).c(...)` chain
+enum Segment {
+    Name(String),
+    Call(Vec<Bson>),
+}
+
+/// Parse a single shell command. Errors are user-facing messages.
+pub fn parse_shell_command(input: &str) -> Result<ShellCommand, String> {
+    let mut parser = Parser::new(input);
+    let segments = parser.parse_chain()?;
+    parser.skip_whitespace();
+    while parser.eat(';') {
+        parser.skip_whitespace();
+    }
+    if !parser.at_end() {
+        return Err(format!(
+            "Unexpected input at position {}; only one command can be run at a time",
+            parser.pos
+        ));
+    }
+    build_command(segments)
+}
+
+/// Parse a single value in shell syntax, such as the body of one aggregation stage
+pub fn parse_shell_value(input: &str) -> Result<Bson, String> {
+    let mut parser = Parser::new(input);
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if !parser.at_end() {
+        return Err(parser.error("unexpected input after the value"));
+    }
+    Ok(value)
+}
+
+fn build_command(segments: Vec<Segment>) -> Result<ShellCommand, String> {
+    let mut segments = segments.into_iter().peekable();
+
+    // db.runCommand({...}) and db.getCollection("name")
+    let collection = match segments.next() {
+        Some(Segment::Name(name)) if name == "runCommand" => {
+            let args = expect_call(segments.next(), "runCommand")?;
+            let command = document_arg(&args, 0, "runCommand")?
+                .ok_or("runCommand expects a command document")?;
+            if segments.next().is_some() {
+                return Err("runCommand cannot be chained".to_string());
+            }
+            return Ok(ShellCommand::RunCommand(command));
+        }
+        Some(Segment::Name(name)) if name == "getCollection" => {
+            let args = expect_call(segments.next(), "getCollection")?;
+            match args.first() {
+                Some(Bson::String(name)) => name.clone(),
+                _ => return Err("getCollection expects a collection name".to_string()),
+            }
+        }
+        Some(Segment::Name(name)) => {
+            // Collection names may contain dots (db.system.users.find()); every
+            // name up to the one that is called belongs to the collection
+            let mut parts = vec![name];
+            while let Some(Segment::Name(next)) =
+                segments.next_if(|segment| matches!(segment, Segment::Name(_)))
+            {
+                parts.push(next);
+            }
+            if parts.len() < 2 {
+                return Err("Expected db.<collection>.<method>(...)".to_string());
+            }
+            let method = parts.pop().unwrap_or_default();
+            return build_collection_command(parts.join("."), method, segments);
+        }
+        _ => {
+            return Err("Expected db.<collection>.<method>(...) or db.runCommand(...)".to_string())
+        }
+    };
+
+    match segments.next() {
+        Some(Segment::Name(method)) => build_collection_command(collection, method, segments),
+        _ => Err("Expected a method call after getCollection(...)".to_string()),
+    }
+}
+
+fn build_collection_command(
+    collection: String,
+    method: String,
+    mut segments: impl Iterator<Item = Segment>,
+) -> Result<ShellCommand, String> {
+    let args = expect_call(segments.next(), &method)?;
+
+    let command = match method.as_str() {
+        "find" => {
+            let mut sort = None;
+            let mut skip = None;
+            let mut limit = None;
+            // Cursor modifiers: .sort({...}).skip(n).limit(n)
+            while let Some(segment) = segments.next() {
+                let Segment::Name(modifier) = segment else {
+                    return Err("Expected a cursor method such as .sort() or .limit()".to_string());
+                };
+                let modifier_args = expect_call(segments.next(), &modifier)?;
+                match modifier.as_str() {
+                    "sort" => sort = document_arg(&modifier_args, 0, "sort")?,
+                    "skip" => skip = Some(integer_arg(&modifier_args, "skip")?.max(0) as u64),
+                    "limit" => limit = Some(integer_arg(&modifier_args, "limit")?),
+                    "pretty" | "toArray" => {}
+                    other => return Err(format!("Unsupported cursor method: {}", other)),
+                }
+            }
+            return Ok(ShellCommand::Find {
+                collection,
+                filter: document_arg(&args, 0, "find")?.unwrap_or_default(),
+                projection: document_arg(&args, 1, "find")?,
+                sort,
+                skip,
+                limit,
+            });
+        }
+        "findOne" => ShellCommand::FindOne {
+            collection,
+            filter: document_arg(&args, 0, "findOne")?.unwrap_or_default(),
+            projection: document_arg(&args, 1, "findOne")?,
+        },
+        "aggregate" => {
+            let stages = match args.first() {
+                Some(Bson::Array(stages)) => stages.clone(),
+                Some(Bson::Document(stage)) => vec![Bson::Document(stage.clone())],
+                None => Vec::new(),
+                _ => return Err("aggregate expects an array of pipeline stages".to_string()),
+            };
+            let pipeline = stages
+                .into_iter()
+                .map(|stage| match stage {
+                    Bson::Document(doc) => Ok(doc),
+                    _ => Err("Every pipeline stage must be a document".to_string()),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            ShellCommand::Aggregate {
+                collection,
+                pipeline,
+            }
+        }
+        "countDocuments" | "count" => ShellCommand::CountDocuments {
+            collection,
+            filter: document_arg(&args, 0, &method)?.unwrap_or_default(),
+        },
+        "distinct" => ShellCommand::Distinct {
+            collection,
+            field: match args.first() {
+                Some(Bson::String(field)) => field.clone(),
+                _ => return Err("distinct expects a field name".to_string()),
+            },
+            filter: document_arg(&args, 1, "distinct")?.unwrap_or_default(),
+        },
+        other => return Err(format!("Unsupported collection method: {}", other)),
+    };
+
+    if segments.next().is_some() {
+        return Err(format!("{} cannot be chained", method));
+    }
+    Ok(command)
+}
+
+fn expect_call(segment: Option<Segment>, name: &str) -> Result<Vec<Bson>, String> {
+    match segment {
+        Some(Segment::Call(args)) => Ok(args),
+        _ => Err(format!("Expected ( after {}", name)),
+    }
+}
+
+/// Optional document argument at `index`; `null`/missing yields `None`
+fn document_arg(args: &[Bson], index: usize, method: &str) -> Result<Option<Document>, String> {
+    match args.get(index) {
+        None | Some(Bson::Null) => Ok(None),
+        Some(Bson::Document(doc)) => Ok(Some(doc.clone())),
+        Some(_) => Err(format!(
+            "Argument {} of {} must be a document",
+            index + 1,
+            method
+        )),
+    }
+}
+
+fn integer_arg(args: &[Bson], method: &str) -> Result<i64, String> {
+    match args.first() {
+        Some(Bson::Int32(n)) => Ok(*n as i64),
+        Some(Bson::Int64(n)) => Ok(*n),
+        Some(Bson::Double(n)) if n.fract() == 0.0 => Ok(*n as i64),
+        _ => Err(format!("{} expects a number", method)),
+    }
+}
+
+/// Recursive-descent parser over the input characters
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.chars.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        match self.peek() {
+            Some(c) => format!(
+                "Syntax error at position {}: {} (found '{}')",
+                self.pos, message, c
+            ),
+            None => format!("Syntax error: {} (found end of input)", message),
+        }
+    }
+
+    /// Skip whitespace and `//` / `/* */` comments
+    fn skip_whitespace(&mut self) {
+        loop {
+            match (self.peek(), self.chars.get(self.pos + 1)) {
+                (Some(c), _) if c.is_whitespace() => self.pos += 1,
+                (Some('/'), Some('/')) => {
+                    while !self.at_end() && self.peek() != Some('\n') {
+                        self.pos += 1;
+                    }
+                }
+                (Some('/'), Some('*')) => {
+                    self.pos += 2;
+                    while !self.at_end()
+                        && (self.peek() != Some('*') || self.chars.get(self.pos + 1) != Some(&'/'))
+                    {
+                        self.pos += 1;
+                    }
+                    self.pos = (self.pos + 2).min(self.chars.len());
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Option<String> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            let valid = c.is_alphanumeric() || c == '_' || c == '$';
+            if !valid || (self.pos == start && c.is_ascii_digit()) {
+                break;
+            }
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| self.chars[start..self.pos].iter().collect())
+    }
+
+    /// `db` followed by `.name`, `["name"]` and `(args)` segments
+    fn parse_chain(&mut self) -> Result<Vec<Segment>, String> {
+        self.skip_whitespace();
+        if self.parse_identifier().as_deref() != Some("db") {
+            return Err("Commands must start with db.".to_string());
+        }
+
+        let mut segments = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat('.') {
+                self.skip_whitespace();
+                let name = self
+                    .parse_identifier()
+                    .ok_or_else(|| self.error("expected a name after '.'"))?;
+                segments.push(Segment::Name(name));
+            } else if self.eat('[') {
+                self.skip_whitespace();
+                let name = self.parse_string()?;
+                self.expect(']')?;
+                segments.push(Segment::Name(name));
+            } else if self.eat('(') {
+                segments.push(Segment::Call(self.parse_list(')')?));
+            } else {
+                break;
+            }
+        }
+        Ok(segments)
+    }
+
+    /// Comma-separated values up to `close`; trailing commas are allowed
+    fn parse_list(&mut self, close: char) -> Result<Vec<Bson>, String> {
+        let mut values = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat(close) {
+                return Ok(values);
+            }
+            values.push(self.parse_value()?);
+            self.skip_whitespace();
+            if !self.eat(',') {
+                self.expect(close)?;
+                return Ok(values);
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Bson, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                self.parse_object().map(Bson::Document)
+            }
+            Some('[') => {
+                self.pos += 1;
+                self.parse_list(']').map(Bson::Array)
+            }
+            Some('"') | Some('\'') => self.parse_string().map(Bson::String),
+            Some('/') => self.parse_regex(),
+            Some(c) if c == '-' || c == '+' || c == '.' || c.is_ascii_digit() => {
+                self.parse_number()
+            }
+            Some(_) => {
+                let ident = self
+                    .parse_identifier()
+                    .ok_or_else(|| self.error("expected a value"))?;
+                self.parse_keyword(ident)
+            }
+            None => Err(self.error("expected a value")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Document, String> {
+        let mut doc = Document::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat('}') {
+                return Ok(doc);
+            }
+            let key = match self.peek() {
+                Some('"') | Some('\'') => self.parse_string()?,
+                Some(c) if c.is_ascii_digit() => match self.parse_number()? {
+                    Bson::Int32(n) => n.to_string(),
+                    Bson::Int64(n) => n.to_string(),
+                    other => other.to_string(),
+                },
+                _ => self
+                    .parse_identifier()
+                    .ok_or_else(|| self.error("expected a field name"))?,
+            };
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            doc.insert(key, value);
+            self.skip_whitespace();
+            if !self.eat(',') {
+                self.expect('}')?;
+                return Ok(doc);
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        let quote = match self.peek() {
+            Some(q @ ('"' | '\'')) => q,
+            _ => return Err(self.error("expected a string")),
+        };
+        self.pos += 1;
+
+        let mut out = String::new();
+        loop {
+            let c = self
+                .peek()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            self.pos += 1;
+            match c {
+                c if c == quote => return Ok(out),
+                '\\' => {
+                    let escaped = self
+                        .peek()
+                        .ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    match escaped {
+                        'n' => out.push('\n'),
+                        't' => out.push('\t'),
+                        'r' => out.push('\r'),
+                        'b' => out.push('\u{8}'),
+                        'f' => out.push('\u{c}'),
+                        '0' => out.push('\0'),
+                        'u' => {
+                            let hex: String = self.chars.iter().skip(self.pos).take(4).collect();
+                            let code = u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .filter(|_| hex.len() == 4)
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("invalid \\u escape"))?;
+                            self.pos += 4;
+                            out.push(code);
+                        }
+                        other => out.push(other),
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn parse_regex(&mut self) -> Result<Bson, String> {
+        self.pos += 1; // opening '/'
+        let mut pattern = String::new();
+        loop {
+            let c = self
+                .peek()
+                .ok_or_else(|| self.error("unterminated regular expression"))?;
+            self.pos += 1;
+            match c {
+                '/' => break,
+                '\\' => {
+                    // Keep escapes as-is; they belong to the regex syntax
+                    pattern.push(c);
+                    if let Some(next) = self.peek() {
+                        pattern.push(next);
+                        self.pos += 1;
+                    }
+                }
+                c => pattern.push(c),
+            }
+        }
+
+        let mut options: Vec<char> = Vec::new();
+        while let Some(c) = self.peek().filter(|c| c.is_ascii_alphabetic()) {
+            options.push(c);
+            self.pos += 1;
+        }
+        // BSON requires regex options in alphabetical order
+        options.sort_unstable();
+
+        Ok(Bson::RegularExpression(Regex {
+            pattern,
+            options: options.into_iter().collect(),
+        }))
+    }
+
+    fn parse_number(&mut self) -> Result<Bson, String> {
+        let start = self.pos;
+        if matches!(self.peek(), Some('-' | '+')) {
+            self.pos += 1;
+        }
+        let mut is_float = false;
+        while let Some(c) = self.peek() {
+            match c {
+                '0'..='9' => {}
+                '.' | 'e' | 'E' => is_float = true,
+                '-' | '+' if matches!(self.chars.get(self.pos - 1), Some('e' | 'E')) => {}
+                _ => break,
+            }
+            self.pos += 1;
+        }
+
+        let text: String = self.chars[start..self.pos].iter().collect();
+        let text = text.trim_start_matches('+');
+        if !is_float {
+            if let Ok(n) = text.parse::<i64>() {
+                return Ok(i32::try_from(n).map(Bson::Int32).unwrap_or(Bson::Int64(n)));
+            }
+        }
+        text.parse::<f64>().map(Bson::Double).map_err(|_| {
+            format!(
+                "Syntax error at position {}: invalid number '{}'",
+                start, text
+            )
+        })
+    }
+
+    /// Literals (`true`, `null`, ...) and shell helpers such as `ObjectId("...")`
+    fn parse_keyword(&mut self, ident: String) -> Result<Bson, String> {
+        match ident.as_str() {
+            "true" => return Ok(Bson::Boolean(true)),
+            "false" => return Ok(Bson::Boolean(false)),
+            "null" | "undefined" => return Ok(Bson::Null),
+            "new" => {
+                self.skip_whitespace();
+                let ident = self
+                    .parse_identifier()
+                    .ok_or_else(|| self.error("expected a constructor after 'new'"))?;
+                return self.parse_keyword(ident);
+            }
+            _ => {}
+        }
+
+        self.expect('(')?;
+        let args = self.parse_list(')')?;
+        let string_arg = || match args.first() {
+            Some(Bson::String(s)) => Ok(s.clone()),
+            _ => Err(format!("{} expects a string argument", ident)),
+        };
+
+        match ident.as_str() {
+            "ObjectId" => ObjectId::parse_str(string_arg()?)
+                .map(Bson::ObjectId)
+                .map_err(|e| format!("Invalid ObjectId: {}", e)),
+            "ISODate" | "Date" => {
+                if args.is_empty() {
+                    return Ok(Bson::DateTime(DateTime::now()));
+                }
+                let text = string_arg()?;
+                // The shell accepts bare dates such as ISODate("2024-01-31")
+                let text = if text.len() == 10 {
+                    format!("{}T00:00:00Z", text)
+                } else {
+                    text
+                };
+                DateTime::parse_rfc3339_str(&text)
+                    .map(Bson::DateTime)
+                    .map_err(|e| format!("Invalid date '{}': {}", text, e))
+            }
+            "NumberLong" | "NumberInt" => {
+                let n = match args.first() {
+                    Some(Bson::String(s)) => s
+                        .trim()
+                        .parse::<i64>()
+                        .map_err(|_| format!("Invalid {} value '{}'", ident, s))?,
+                    Some(Bson::Int32(n)) => *n as i64,
+                    Some(Bson::Int64(n)) => *n,
+                    Some(Bson::Double(n)) => *n as i64,
+                    _ => return Err(format!("{} expects a number", ident)),
+                };
+                if ident == "NumberInt" {
+                    i32::try_from(n)
+                        .map(Bson::Int32)
+                        .map_err(|_| format!("NumberInt value out of range: {}", n))
+                } else {
+                    Ok(Bson::Int64(n))
+                }
+            }
+            "NumberDecimal" => {
+                let text = match args.first() {
+                    Some(Bson::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                    None => return Err("NumberDecimal expects a value".to_string()),
+                };
+                text.parse::<Decimal128>()
+                    .map(Bson::Decimal128)
+                    .map_err(|e| format!("Invalid NumberDecimal '{}': {}", text, e))
+            }
+            other => Err(format!("Unknown function: {}", other)),
+        }
+    }
+}
@@ -1,14 +1,64 @@
 //! MySQL driver implementation
 
 use async_trait::async_trait;
+use base64::Engine;
+use mysql_async::consts::{ColumnFlags, ColumnType};
 use mysql_async::prelude::*;
+use mysql_async::{Column, Row, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
-use crate::db::driver::{ConnectionConfig, ConnectionInfo, DatabaseConnection};
+use super::sql::{create_index_statement, default_index_name, explain_statement, page_query, where_clause};
+use crate::db::driver::{
+    BatchInsertResult, CheckConstraint, CollectionInfo, ColumnDefinition, ColumnInfo, ConnectionConfig, ConnectionInfo,
+    DatabaseConnection, DatabaseInfo, ForeignKey, IndexBuildProgress, IndexInfo, IndexOrder, IndexSpec,
+    KeyConstraint, QueryResult, ServerStatus, StorageStats, TableStructure, TriggerInfo,
+};
+use crate::db::connection::{SslMode, TlsConfig};
 use crate::db::error::{ConnectionError, Result};
+use crate::db::explain::{self, QueryPlan};
+
+/// Maximum number of open connections per database pool
+const POOL_SIZE: usize = 4;
+
+/// Name, type, nullability, default, extra, generation expression, collation and comment
+type ColumnRow = (String, String, String, Option<String>, String, Option<String>, Option<String>, String);
+
+/// Type, engine, row format, rows, average row length, data, index and free
+/// lengths, next auto-increment value, collation, create options and comment
+type TableStatsRow = (
+    String,
+    Option<String>,
+    Option<String>,
+    Option<u64>,
+    Option<u64>,
+    Option<u64>,
+    Option<u64>,
+    Option<u64>,
+    Option<u64>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+/// Constraint name and type, then the column and what it references (foreign keys)
+type ConstraintRow = (
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
 
 pub struct MySqlConnection {
     config: ConnectionConfig,
+    /// Connection pools keyed by database name ("" = connection string default)
+    pools: Mutex<HashMap<String, Arc<mysql_async::Pool>>>,
 }
 
 impl MySqlConnection {
@@ -19,27 +69,197 @@ impl MySqlConnection {
                 "MySQL connection string must start with mysql://".into(),
             ));
         }
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            pools: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Take a pooled connection, optionally for the given database.
+    /// Dropping the connection returns it to the pool.
+    async fn connect(&self, database_name: Option<&str>) -> Result<mysql_async::Conn> {
+        let key = database_name.unwrap_or_default().to_string();
+        // Building a pool opens nothing yet, so it is done under the lock and two
+        // callers never both create one
+        let pool = {
+            let mut pools = self.pools.lock().unwrap();
+            match pools.get(&key) {
+                Some(pool) => pool.clone(),
+                None => {
+                    let pool = Arc::new(self.new_pool(database_name, ssl_opts(&self.config.tls)?)?);
+                    pools.insert(key.clone(), pool.clone());
+                    pool
+                }
+            }
+        };
+
+        let result = tokio::time::timeout(self.config.timeout, pool.get_conn())
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?;
+
+        match result {
+            // `prefer` falls back to plain text when the server has no TLS
+            Err(mysql_async::Error::Driver(mysql_async::DriverError::NoClientSslFlagFromServer))
+                if self.config.tls.ssl_mode == SslMode::Prefer =>
+            {
+                // Another caller may have switched to plain text already. The TLS pool
+                // never got a connection, so dropping it closes nothing.
+                let pool = {
+                    let mut pools = self.pools.lock().unwrap();
+                    match pools.get(&key) {
+                        Some(current) if !Arc::ptr_eq(current, &pool) => current.clone(),
+                        _ => {
+                            let plain = Arc::new(self.new_pool(database_name, None)?);
+                            pools.insert(key, plain.clone());
+                            plain
+                        }
+                    }
+                };
+                tokio::time::timeout(self.config.timeout, pool.get_conn())
+                    .await
+                    .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+                    .map_err(|e| ConnectionError::Failed(e.to_string()))
+            }
+            result => result.map_err(|e| ConnectionError::Failed(e.to_string())),
+        }
+    }
+
+    fn new_pool(
+        &self,
+        database_name: Option<&str>,
+        ssl_opts: Option<mysql_async::SslOpts>,
+    ) -> Result<mysql_async::Pool> {
+        let opts = mysql_async::Opts::from_url(&self.config.connection_string)
+            .map_err(|e| ConnectionError::InvalidConnectionString(e.to_string()))?;
+
+        let constraints = mysql_async::PoolConstraints::new(0, POOL_SIZE).unwrap_or_default();
+        let mut builder = mysql_async::OptsBuilder::from_opts(opts)
+            .pool_opts(mysql_async::PoolOpts::new().with_constraints(constraints))
+            .ssl_opts(ssl_opts);
+        if let Some(name) = database_name {
+            builder = builder.db_name(Some(name));
+        }
+
+        Ok(mysql_async::Pool::new(builder))
+    }
+}
+
+/// TLS options for a connection's settings, or `None` for plain text.
+/// As with libpq, `prefer` and `require` only encrypt unless a CA is given.
+fn ssl_opts(tls: &TlsConfig) -> Result<Option<mysql_async::SslOpts>> {
+    if tls.ssl_mode == SslMode::Disable {
+        return Ok(None);
+    }
+
+    let check_chain = tls.ssl_mode.verifies_certificate()
+        || (tls.ssl_mode == SslMode::Require && tls.ca_cert_path.is_some());
+    let mut opts = mysql_async::SslOpts::default()
+        .with_danger_accept_invalid_certs(!check_chain)
+        .with_danger_skip_domain_validation(tls.ssl_mode != SslMode::VerifyFull);
+
+    if let Some(path) = &tls.ca_cert_path {
+        opts = opts.with_root_certs(vec![PathBuf::from(path).into()]);
+    }
+
+    // native-tls takes client certificates as a PKCS#12 bundle holding the key,
+    // so there is no separate key file
+    if let Some(path) = &tls.client_cert_path {
+        opts = opts.with_client_identity(Some(mysql_async::ClientIdentity::new(
+            PathBuf::from(path).into(),
+        )));
+    }
+
+    Ok(Some(opts))
+}
+
+/// Quote an identifier with backticks
+fn quote_ident(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// Convert a MySQL value into JSON, using the column type to interpret raw bytes
+fn value_to_json(value: Value, column: &Column) -> serde_json::Value {
+    match value {
+        Value::NULL => serde_json::Value::Null,
+        Value::Int(i) => serde_json::Value::from(i),
+        Value::UInt(u) => serde_json::Value::from(u),
+        Value::Float(f) => serde_json::Value::from(f as f64),
+        Value::Double(f) => serde_json::Value::from(f),
+        Value::Date(year, month, day, hour, minute, second, micros) => {
+            let mut text = format!("{:04}-{:02}-{:02}", year, month, day);
+            if column.column_type() != ColumnType::MYSQL_TYPE_DATE {
+                text.push_str(&format!(" {:02}:{:02}:{:02}", hour, minute, second));
+                if micros > 0 {
+                    text.push_str(&format!(".{:06}", micros));
+                }
+            }
+            serde_json::Value::String(text)
+        }
+        Value::Time(negative, days, hours, minutes, seconds, micros) => {
+            let total_hours = days * 24 + hours as u32;
+            let mut text = format!(
+                "{}{:02}:{:02}:{:02}",
+                if negative { "-" } else { "" },
+                total_hours,
+                minutes,
+                seconds
+            );
+            if micros > 0 {
+                text.push_str(&format!(".{:06}", micros));
+            }
+            serde_json::Value::String(text)
+        }
+        Value::Bytes(bytes) => match column.column_type() {
+            ColumnType::MYSQL_TYPE_JSON => serde_json::from_slice(&bytes)
+                .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&bytes).into_owned())),
+            // Numbers arrive as text from the text protocol (and DECIMAL always does)
+            ColumnType::MYSQL_TYPE_DECIMAL
+            | ColumnType::MYSQL_TYPE_NEWDECIMAL
+            | ColumnType::MYSQL_TYPE_TINY
+            | ColumnType::MYSQL_TYPE_SHORT
+            | ColumnType::MYSQL_TYPE_INT24
+            | ColumnType::MYSQL_TYPE_LONG
+            | ColumnType::MYSQL_TYPE_LONGLONG
+            | ColumnType::MYSQL_TYPE_YEAR
+            | ColumnType::MYSQL_TYPE_FLOAT
+            | ColumnType::MYSQL_TYPE_DOUBLE => {
+                let text = String::from_utf8_lossy(&bytes).into_owned();
+                serde_json::from_str::<serde_json::Number>(&text)
+                    .map(serde_json::Value::Number)
+                    .unwrap_or(serde_json::Value::String(text))
+            }
+            // Binary collation (63) marks BLOB/BINARY columns
+            _ if column.flags().contains(ColumnFlags::BINARY_FLAG) && column.character_set() == 63 => {
+                match String::from_utf8(bytes) {
+                    Ok(text) => serde_json::Value::String(text),
+                    Err(e) => serde_json::Value::String(
+                        base64::engine::general_purpose::STANDARD.encode(e.into_bytes()),
+                    ),
+                }
+            }
+            _ => serde_json::Value::String(String::from_utf8_lossy(&bytes).into_owned()),
+        },
     }
 }
 
+/// Convert a result row into a JSON object keyed by column name
+fn row_to_json(row: Row) -> serde_json::Value {
+    let columns = row.columns();
+    let values = row.unwrap();
+    let map = columns
+        .iter()
+        .zip(values)
+        .map(|(column, value)| (column.name_str().into_owned(), value_to_json(value, column)))
+        .collect();
+    serde_json::Value::Object(map)
+}
+
 #[async_trait]
 impl DatabaseConnection for MySqlConnection {
     async fn test_connection(&self) -> Result<ConnectionInfo> {
         let start = Instant::now();
 
-        // Parse connection string into options
-        let opts = mysql_async::Opts::from_url(&self.config.connection_string)
-            .map_err(|e| ConnectionError::InvalidConnectionString(e.to_string()))?;
-
-        // Create pool with single connection
-        let pool = mysql_async::Pool::new(opts);
-
-        // Get connection with timeout
-        let mut conn = tokio::time::timeout(self.config.timeout, pool.get_conn())
-            .await
-            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
-            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let mut conn = self.connect(None).await?;
 
         // Get server version
         let version: Option<String> = tokio::time::timeout(
@@ -52,13 +272,795 @@ impl DatabaseConnection for MySqlConnection {
 
         let latency = start.elapsed().as_millis() as u64;
 
-        // Clean up
-        drop(conn);
-        pool.disconnect().await.ok();
-
         Ok(ConnectionInfo {
             server_version: version.map(|v| format!("MySQL {}", v)),
             latency_ms: latency,
         })
     }
+
+    async fn list_databases(&self) -> Result<Vec<DatabaseInfo>> {
+        let mut conn = self.connect(None).await?;
+
+        let rows: Vec<(String, Option<u64>)> = tokio::time::timeout(
+            self.config.timeout,
+            conn.query(
+                "SELECT s.SCHEMA_NAME, CAST(SUM(t.DATA_LENGTH + t.INDEX_LENGTH) AS UNSIGNED) \
+                 FROM information_schema.SCHEMATA s \
+                 LEFT JOIN information_schema.TABLES t ON t.TABLE_SCHEMA = s.SCHEMA_NAME \
+                 GROUP BY s.SCHEMA_NAME \
+                 ORDER BY s.SCHEMA_NAME",
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        let result = rows
+            .into_iter()
+            .map(|(name, size)| DatabaseInfo {
+                name,
+                size_bytes: size,
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    async fn list_collections(&self, database_name: &str) -> Result<Vec<CollectionInfo>> {
+        let mut conn = self.connect(None).await?;
+
+        // TABLE_ROWS is an estimate for InnoDB and NULL for views
+        let rows: Vec<(String, Option<u64>)> = tokio::time::timeout(
+            self.config.timeout,
+            conn.exec(
+                "SELECT TABLE_NAME, TABLE_ROWS FROM information_schema.TABLES \
+                 WHERE TABLE_SCHEMA = ? ORDER BY TABLE_NAME",
+                (database_name,),
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        let result = rows
+            .into_iter()
+            .map(|(name, estimate)| CollectionInfo {
+                name,
+                document_count: estimate,
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    async fn query_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        limit: u32,
+        skip: u32,
+        filter: Option<&str>,
+        sort: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        let mut conn = self.connect(Some(database_name)).await?;
+
+        let query = page_query(
+            format!("SELECT * FROM {}", quote_ident(collection_name)),
+            filter,
+            sort,
+            limit,
+            skip,
+            quote_ident,
+        );
+
+        // Prepared statements use the binary protocol, so numbers and dates arrive typed
+        let rows: Vec<Row> = tokio::time::timeout(self.config.timeout, conn.exec(query, ()))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        Ok(rows.into_iter().map(row_to_json).collect())
+    }
+
+    async fn count_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        filter: Option<&str>,
+    ) -> Result<usize> {
+        let mut conn = self.connect(Some(database_name)).await?;
+
+        let mut query = format!("SELECT COUNT(*) FROM {}", quote_ident(collection_name));
+        if let Some(clause) = where_clause(filter) {
+            query.push_str(&format!(" WHERE {}", clause));
+        }
+
+        let count: Option<u64> = tokio::time::timeout(self.config.timeout, conn.query_first(query))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        Ok(count.unwrap_or(0) as usize)
+    }
+
+    async fn table_columns(&self, database_name: &str, collection_name: &str) -> Result<Vec<ColumnInfo>> {
+        let mut conn = self.connect(None).await?;
+
+        let rows: Vec<(String, String, String)> = tokio::time::timeout(
+            self.config.timeout,
+            conn.exec(
+                "SELECT COLUMN_NAME, IS_NULLABLE, COLUMN_KEY \
+                 FROM information_schema.COLUMNS \
+                 WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? ORDER BY ORDINAL_POSITION",
+                (database_name, collection_name),
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, nullable, key)| ColumnInfo {
+                name,
+                nullable: nullable == "YES",
+                primary_key: key == "PRI",
+            })
+            .collect())
+    }
+
+    async fn execute_transaction(&self, database_name: &str, statements: &[String]) -> Result<Vec<u64>> {
+        let mut conn = self.connect(Some(database_name)).await?;
+
+        // Dropping the transaction without committing rolls it back
+        let mut transaction = conn
+            .start_transaction(mysql_async::TxOpts::default())
+            .await
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let mut affected = Vec::with_capacity(statements.len());
+        for statement in statements {
+            transaction
+                .query_drop(statement)
+                .await
+                .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+            affected.push(transaction.affected_rows());
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        Ok(affected)
+    }
+
+    async fn execute_each(
+        &self,
+        database_name: &str,
+        statements: &[String],
+        stop_on_error: bool,
+    ) -> Result<BatchInsertResult> {
+        let mut conn = self.connect(Some(database_name)).await?;
+        let failed = |e: mysql_async::Error| ConnectionError::Failed(e.to_string());
+
+        // Dropping the transaction without committing rolls it back
+        let mut transaction = conn
+            .start_transaction(mysql_async::TxOpts::default())
+            .await
+            .map_err(failed)?;
+        let mut result = BatchInsertResult::default();
+        for (index, statement) in statements.iter().enumerate() {
+            transaction.query_drop("SAVEPOINT statement").await.map_err(failed)?;
+            match transaction.query_drop(statement).await {
+                Ok(()) => {
+                    transaction.query_drop("RELEASE SAVEPOINT statement").await.map_err(failed)?;
+                    result.inserted += 1;
+                }
+                // Only errors reported by the server are about the statement
+                Err(mysql_async::Error::Server(e)) => {
+                    result.rejected.push((index, e.to_string()));
+                    transaction.query_drop("ROLLBACK TO SAVEPOINT statement").await.map_err(failed)?;
+                    if stop_on_error {
+                        break;
+                    }
+                }
+                Err(e) => return Err(failed(e)),
+            }
+        }
+        transaction.commit().await.map_err(failed)?;
+
+        Ok(result)
+    }
+
+    async fn execute_query(&self, database_name: &str, query: &str) -> Result<QueryResult> {
+        let mut conn = self.connect((!database_name.is_empty()).then_some(database_name)).await?;
+        let start = Instant::now();
+
+        // The text protocol allows several statements in one call; each produces its
+        // own result set. The console may run long statements, so no timeout here.
+        let mut result = QueryResult::default();
+        {
+            let mut sets = conn
+                .query_iter(query)
+                .await
+                .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+            while !sets.is_empty() {
+                let columns = sets.columns().unwrap_or_default();
+                // Read before collecting: collecting advances to the next result set
+                let affected = sets.affected_rows();
+                let rows: Vec<Row> = sets
+                    .collect()
+                    .await
+                    .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+                result = QueryResult {
+                    columns: columns.iter().map(|c| c.name_str().into_owned()).collect(),
+                    rows: rows
+                        .into_iter()
+                        .map(|row| {
+                            let columns = row.columns();
+                            row.unwrap()
+                                .into_iter()
+                                .zip(columns.iter())
+                                .map(|(value, column)| value_to_json(value, column))
+                                .collect()
+                        })
+                        .collect(),
+                    affected_rows: columns.is_empty().then_some(affected),
+                    ..Default::default()
+                };
+            }
+        }
+
+        result.elapsed = start.elapsed();
+        Ok(result)
+    }
+
+    async fn explain_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        limit: u32,
+        skip: u32,
+        filter: Option<&str>,
+        sort: Option<&str>,
+    ) -> Result<QueryPlan> {
+        let query = page_query(
+            format!("SELECT * FROM {}", quote_ident(collection_name)),
+            filter,
+            sort,
+            limit,
+            skip,
+            quote_ident,
+        );
+        self.explain_query(database_name, &query).await
+    }
+
+    async fn explain_query(&self, database_name: &str, query: &str) -> Result<QueryPlan> {
+        let mut conn = self.connect((!database_name.is_empty()).then_some(database_name)).await?;
+
+        // EXPLAIN only plans the statement, so nothing is changed
+        let plan: Option<String> = tokio::time::timeout(
+            self.config.timeout,
+            conn.query_first(format!("EXPLAIN FORMAT=JSON {}", explain_statement(query))),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        let plan = plan.ok_or_else(|| ConnectionError::Failed("EXPLAIN returned no plan".into()))?;
+        let plan = serde_json::from_str(&plan).map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        Ok(explain::from_mysql(plan))
+    }
+
+    async fn list_indexes(&self, database_name: &str, collection_name: &str) -> Result<Vec<IndexInfo>> {
+        let mut conn = self.connect(None).await?;
+
+        // One row per indexed column; COLUMN_NAME is NULL for functional key parts
+        let rows: Vec<(String, i64, Option<String>, Option<String>, String)> = tokio::time::timeout(
+            self.config.timeout,
+            conn.exec(
+                "SELECT INDEX_NAME, NON_UNIQUE, COLUMN_NAME, COLLATION, INDEX_TYPE \
+                 FROM information_schema.STATISTICS \
+                 WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? \
+                 ORDER BY INDEX_NAME = 'PRIMARY' DESC, INDEX_NAME, SEQ_IN_INDEX",
+                (database_name, collection_name),
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        // Sizes and usage need access to the mysql and performance_schema
+        // databases, so they are left out when those cannot be read
+        let sizes: HashMap<String, u64> = conn
+            .exec(
+                "SELECT index_name, stat_value * @@innodb_page_size FROM mysql.innodb_index_stats \
+                 WHERE database_name = ? AND table_name = ? AND stat_name = 'size'",
+                (database_name, collection_name),
+            )
+            .await
+            .map(|rows: Vec<(String, u64)>| rows.into_iter().collect())
+            .unwrap_or_default();
+        let usage: HashMap<String, u64> = conn
+            .exec(
+                "SELECT INDEX_NAME, COUNT_STAR \
+                 FROM performance_schema.table_io_waits_summary_by_index_usage \
+                 WHERE OBJECT_SCHEMA = ? AND OBJECT_NAME = ? AND INDEX_NAME IS NOT NULL",
+                (database_name, collection_name),
+            )
+            .await
+            .map(|rows: Vec<(String, u64)>| rows.into_iter().collect())
+            .unwrap_or_default();
+
+        let mut indexes: Vec<IndexInfo> = Vec::new();
+        for (name, non_unique, column, collation, index_type) in rows {
+            let key = (
+                column.unwrap_or_else(|| "(expression)".to_string()),
+                if collation.as_deref() == Some("D") {
+                    IndexOrder::Descending
+                } else {
+                    IndexOrder::Ascending
+                },
+            );
+            match indexes.last_mut() {
+                Some(index) if index.name == name => index.keys.push(key),
+                _ => indexes.push(IndexInfo {
+                    keys: vec![key],
+                    method: (index_type != "BTREE").then_some(index_type),
+                    unique: non_unique == 0,
+                    primary: name == "PRIMARY",
+                    partial_filter: None,
+                    expire_after_seconds: None,
+                    size_bytes: sizes.get(&name).copied(),
+                    usage_count: usage.get(&name).copied(),
+                    name,
+                }),
+            }
+        }
+        Ok(indexes)
+    }
+
+    async fn create_index(&self, database_name: &str, collection_name: &str, spec: &IndexSpec) -> Result<()> {
+        if where_clause(spec.partial_filter.as_deref()).is_some() {
+            return Err(ConnectionError::Failed("MySQL does not support partial indexes".into()));
+        }
+        let mut conn = self.connect(Some(database_name)).await?;
+
+        let name = spec.name.clone().unwrap_or_else(|| default_index_name(collection_name, spec));
+        let statement = create_index_statement(spec, &quote_ident(&name), &quote_ident(collection_name), quote_ident)?;
+        conn.query_drop(statement)
+            .await
+            .map_err(|e| ConnectionError::Failed(e.to_string()))
+    }
+
+    async fn drop_index(&self, database_name: &str, collection_name: &str, index_name: &str) -> Result<()> {
+        let mut conn = self.connect(Some(database_name)).await?;
+
+        let statement = format!("DROP INDEX {} ON {}", quote_ident(index_name), quote_ident(collection_name));
+        tokio::time::timeout(self.config.timeout, conn.query_drop(statement))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(e.to_string()))
+    }
+
+    async fn index_build_progress(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<Option<IndexBuildProgress>> {
+        let _ = database_name;
+        let mut conn = self.connect(None).await?;
+
+        // InnoDB reports ALTER TABLE stages when the performance_schema stage
+        // instruments are enabled; the statement text ties a stage to the table
+        let stage: Option<(String, Option<u64>, Option<u64>)> = tokio::time::timeout(
+            self.config.timeout,
+            conn.exec_first(
+                "SELECT stage.EVENT_NAME, stage.WORK_COMPLETED, stage.WORK_ESTIMATED \
+                 FROM performance_schema.events_stages_current stage \
+                 JOIN performance_schema.events_statements_current stmt ON stmt.THREAD_ID = stage.THREAD_ID \
+                 WHERE stage.EVENT_NAME LIKE 'stage/innodb/alter table%' AND stmt.SQL_TEXT LIKE ?",
+                (format!("%{}%", collection_name),),
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        Ok(stage.map(|(event, done, total)| IndexBuildProgress {
+            phase: Some(event.trim_start_matches("stage/innodb/").to_string()),
+            done: done.unwrap_or(0),
+            total: total.unwrap_or(0),
+        }))
+    }
+
+    async fn table_structure(&self, database_name: &str, collection_name: &str) -> Result<TableStructure> {
+        let mut conn = self.connect(None).await?;
+
+        // Collations are shown only where they differ from the table's default
+        let columns: Vec<ColumnRow> = tokio::time::timeout(
+            self.config.timeout,
+            conn.exec(
+                "SELECT c.COLUMN_NAME, c.COLUMN_TYPE, c.IS_NULLABLE, c.COLUMN_DEFAULT, c.EXTRA, \
+                 c.GENERATION_EXPRESSION, IF(c.COLLATION_NAME <> t.TABLE_COLLATION, c.COLLATION_NAME, NULL), \
+                 c.COLUMN_COMMENT \
+                 FROM information_schema.COLUMNS c \
+                 JOIN information_schema.TABLES t ON t.TABLE_SCHEMA = c.TABLE_SCHEMA AND t.TABLE_NAME = c.TABLE_NAME \
+                 WHERE c.TABLE_SCHEMA = ? AND c.TABLE_NAME = ? ORDER BY c.ORDINAL_POSITION",
+                (database_name, collection_name),
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        // One row per constrained column
+        let constraints: Vec<ConstraintRow> = conn
+            .exec(
+                "SELECT tc.CONSTRAINT_NAME, tc.CONSTRAINT_TYPE, k.COLUMN_NAME, k.REFERENCED_TABLE_SCHEMA, \
+                 k.REFERENCED_TABLE_NAME, k.REFERENCED_COLUMN_NAME, r.UPDATE_RULE, r.DELETE_RULE \
+                 FROM information_schema.TABLE_CONSTRAINTS tc \
+                 JOIN information_schema.KEY_COLUMN_USAGE k ON k.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA \
+                 AND k.CONSTRAINT_NAME = tc.CONSTRAINT_NAME AND k.TABLE_NAME = tc.TABLE_NAME \
+                 LEFT JOIN information_schema.REFERENTIAL_CONSTRAINTS r ON r.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA \
+                 AND r.CONSTRAINT_NAME = tc.CONSTRAINT_NAME AND r.TABLE_NAME = tc.TABLE_NAME \
+                 WHERE tc.TABLE_SCHEMA = ? AND tc.TABLE_NAME = ? \
+                 AND tc.CONSTRAINT_TYPE IN ('PRIMARY KEY', 'UNIQUE', 'FOREIGN KEY') \
+                 ORDER BY FIELD(tc.CONSTRAINT_TYPE, 'PRIMARY KEY', 'UNIQUE', 'FOREIGN KEY'), \
+                 tc.CONSTRAINT_NAME, k.ORDINAL_POSITION",
+                (database_name, collection_name),
+            )
+            .await
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        // CHECK_CONSTRAINTS only exists from MySQL 8.0.16 and MariaDB 10.2
+        let checks: Vec<(String, String)> = conn
+            .exec(
+                "SELECT cc.CONSTRAINT_NAME, cc.CHECK_CLAUSE \
+                 FROM information_schema.CHECK_CONSTRAINTS cc \
+                 JOIN information_schema.TABLE_CONSTRAINTS tc ON tc.CONSTRAINT_SCHEMA = cc.CONSTRAINT_SCHEMA \
+                 AND tc.CONSTRAINT_NAME = cc.CONSTRAINT_NAME \
+                 WHERE tc.TABLE_SCHEMA = ? AND tc.TABLE_NAME = ? AND tc.CONSTRAINT_TYPE = 'CHECK' \
+                 ORDER BY cc.CONSTRAINT_NAME",
+                (database_name, collection_name),
+            )
+            .await
+            .unwrap_or_default();
+
+        let triggers: Vec<(String, String, String, String)> = conn
+            .exec(
+                "SELECT TRIGGER_NAME, ACTION_TIMING, EVENT_MANIPULATION, ACTION_STATEMENT \
+                 FROM information_schema.TRIGGERS \
+                 WHERE EVENT_OBJECT_SCHEMA = ? AND EVENT_OBJECT_TABLE = ? \
+                 ORDER BY ACTION_TIMING, EVENT_MANIPULATION, ACTION_ORDER",
+                (database_name, collection_name),
+            )
+            .await
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        let mut structure = TableStructure {
+            columns: columns
+                .into_iter()
+                .map(|(name, data_type, nullable, default, extra, expression, collation, comment)| {
+                    let generated = match expression.filter(|expression| !expression.is_empty()) {
+                        Some(expression) => {
+                            let kind = if extra.contains("STORED") { "STORED" } else { "VIRTUAL" };
+                            Some(format!("GENERATED ALWAYS AS ({}) {}", expression, kind))
+                        }
+                        // e.g. auto_increment, on update CURRENT_TIMESTAMP
+                        None => {
+                            let extra = extra.replace("DEFAULT_GENERATED", "").trim().to_string();
+                            (!extra.is_empty()).then_some(extra)
+                        }
+                    };
+                    ColumnDefinition {
+                        name,
+                        data_type,
+                        nullable: nullable == "YES",
+                        default,
+                        generated,
+                        collation,
+                        comment: (!comment.is_empty()).then_some(comment),
+                    }
+                })
+                .collect(),
+            check_constraints: checks
+                .into_iter()
+                .map(|(name, expression)| CheckConstraint { name: Some(name), expression })
+                .collect(),
+            triggers: triggers
+                .into_iter()
+                .map(|(name, timing, events, definition)| TriggerInfo { name, timing, events, definition })
+                .collect(),
+            ..Default::default()
+        };
+
+        // Rules other than NO ACTION are worth showing
+        let action = |rule: Option<String>| rule.filter(|rule| rule != "NO ACTION");
+        for (name, kind, column, referenced_schema, referenced_table, referenced_column, on_update, on_delete) in
+            constraints
+        {
+            match kind.as_str() {
+                "PRIMARY KEY" => structure
+                    .primary_key
+                    .get_or_insert_with(|| KeyConstraint { name: Some(name), columns: Vec::new() })
+                    .columns
+                    .push(column),
+                "UNIQUE" => match structure.unique_constraints.last_mut() {
+                    Some(key) if key.name.as_deref() == Some(name.as_str()) => key.columns.push(column),
+                    _ => structure.unique_constraints.push(KeyConstraint { name: Some(name), columns: vec![column] }),
+                },
+                _ => match structure.foreign_keys.last_mut() {
+                    Some(key) if key.name.as_deref() == Some(name.as_str()) => {
+                        key.columns.push(column);
+                        key.referenced_columns.extend(referenced_column);
+                    }
+                    _ => {
+                        let table = referenced_table.unwrap_or_default();
+                        structure.foreign_keys.push(ForeignKey {
+                            name: Some(name),
+                            columns: vec![column],
+                            referenced_table: match referenced_schema {
+                                Some(schema) if schema != database_name => format!("{}.{}", schema, table),
+                                _ => table,
+                            },
+                            referenced_columns: referenced_column.into_iter().collect(),
+                            on_update: action(on_update),
+                            on_delete: action(on_delete),
+                        });
+                    }
+                },
+            }
+        }
+
+        Ok(structure)
+    }
+
+    async fn table_ddl(&self, database_name: &str, collection_name: &str) -> Result<String> {
+        let mut conn = self.connect(None).await?;
+
+        let table = format!("{}.{}", quote_ident(database_name), quote_ident(collection_name));
+        let created: Option<(String, String)> = tokio::time::timeout(
+            self.config.timeout,
+            conn.query_first(format!("SHOW CREATE TABLE {}", table)),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let Some((_, create_table)) = created else {
+            return Err(ConnectionError::Failed(format!("Table {} not found", collection_name)));
+        };
+
+        let triggers: Vec<(String, String, String, String)> = conn
+            .exec(
+                "SELECT TRIGGER_NAME, ACTION_TIMING, EVENT_MANIPULATION, ACTION_STATEMENT \
+                 FROM information_schema.TRIGGERS \
+                 WHERE EVENT_OBJECT_SCHEMA = ? AND EVENT_OBJECT_TABLE = ? \
+                 ORDER BY ACTION_TIMING, EVENT_MANIPULATION, ACTION_ORDER",
+                (database_name, collection_name),
+            )
+            .await
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        // SHOW CREATE TABLE already lists the indexes
+        let mut ddl = format!("{};\n", create_table);
+        for (name, timing, event, statement) in triggers {
+            ddl.push_str(&format!(
+                "\nCREATE TRIGGER {} {} {} ON {} FOR EACH ROW {};\n",
+                quote_ident(&name),
+                timing,
+                event,
+                quote_ident(collection_name),
+                statement
+            ));
+        }
+        Ok(ddl)
+    }
+
+    async fn database_stats(&self, database_name: &str) -> Result<StorageStats> {
+        let mut conn = self.connect(None).await?;
+
+        // Sizes are what InnoDB allocated, and row counts its estimates
+        type Totals = (u64, Option<u64>, Option<u64>, Option<u64>, Option<u64>, u64, u64);
+        let totals: Option<Totals> = tokio::time::timeout(
+            self.config.timeout,
+            conn.exec_first(
+                "SELECT COUNT(*), CAST(SUM(TABLE_ROWS) AS UNSIGNED), CAST(SUM(DATA_LENGTH) AS UNSIGNED), \
+                 CAST(SUM(INDEX_LENGTH) AS UNSIGNED), CAST(SUM(DATA_FREE) AS UNSIGNED), \
+                 (SELECT COUNT(*) FROM information_schema.VIEWS WHERE TABLE_SCHEMA = ?), \
+                 (SELECT COUNT(DISTINCT TABLE_NAME, INDEX_NAME) FROM information_schema.STATISTICS \
+                  WHERE TABLE_SCHEMA = ?) \
+                 FROM information_schema.TABLES WHERE TABLE_SCHEMA = ? AND TABLE_TYPE = 'BASE TABLE'",
+                (database_name, database_name, database_name),
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let Some((tables, rows, data, indexes, free, views, index_count)) = totals else {
+            return Err(ConnectionError::Failed(format!("Database {} not found", database_name)));
+        };
+
+        let defaults: Option<(String, String)> = conn
+            .exec_first(
+                "SELECT DEFAULT_CHARACTER_SET_NAME, DEFAULT_COLLATION_NAME \
+                 FROM information_schema.SCHEMATA WHERE SCHEMA_NAME = ?",
+                (database_name,),
+            )
+            .await
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let mut properties = vec![("Views".to_string(), views.to_string())];
+        if let Some((charset, collation)) = defaults {
+            properties.push(("Character set".to_string(), charset));
+            properties.push(("Collation".to_string(), collation));
+        }
+
+        Ok(StorageStats {
+            document_count: rows,
+            collection_count: Some(tables),
+            storage_size: data,
+            free_size: free,
+            index_count: Some(index_count),
+            index_size: indexes,
+            total_size: Some(data.unwrap_or(0) + indexes.unwrap_or(0)),
+            avg_object_size: match (data, rows) {
+                (Some(data), Some(rows)) if rows > 0 => Some(data / rows),
+                _ => None,
+            },
+            properties,
+            ..Default::default()
+        })
+    }
+
+    async fn collection_stats(&self, database_name: &str, collection_name: &str) -> Result<StorageStats> {
+        let mut conn = self.connect(None).await?;
+
+        let table: Option<TableStatsRow> = tokio::time::timeout(
+            self.config.timeout,
+            conn.exec_first(
+                "SELECT TABLE_TYPE, ENGINE, ROW_FORMAT, TABLE_ROWS, AVG_ROW_LENGTH, DATA_LENGTH, \
+                 INDEX_LENGTH, DATA_FREE, AUTO_INCREMENT, TABLE_COLLATION, CREATE_OPTIONS, TABLE_COMMENT \
+                 FROM information_schema.TABLES WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+                (database_name, collection_name),
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let Some((kind, engine, row_format, rows, avg_row, data, indexes, free, auto_increment, collation, options, comment)) =
+            table
+        else {
+            return Err(ConnectionError::Failed(format!("Table {} not found", collection_name)));
+        };
+
+        let mut properties = vec![("Type".to_string(), kind.to_lowercase())];
+        let optional = [
+            ("Engine", engine),
+            ("Row format", row_format),
+            ("Next auto-increment", auto_increment.map(|n| n.to_string())),
+            ("Collation", collation),
+            ("Create options", options),
+            ("Comment", comment),
+        ];
+        for (label, value) in optional {
+            if let Some(value) = value.filter(|value| !value.is_empty()) {
+                properties.push((label.to_string(), value));
+            }
+        }
+        if kind == "VIEW" {
+            return Ok(StorageStats {
+                properties,
+                ..Default::default()
+            });
+        }
+
+        let index_count: Option<u64> = conn
+            .exec_first(
+                "SELECT COUNT(DISTINCT INDEX_NAME) FROM information_schema.STATISTICS \
+                 WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+                (database_name, collection_name),
+            )
+            .await
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        // Index sizes are InnoDB's persistent statistics, readable with
+        // privileges on the mysql schema only
+        let index_sizes: Vec<(String, u64)> = conn
+            .exec(
+                "SELECT index_name, CAST(stat_value * @@innodb_page_size AS UNSIGNED) \
+                 FROM mysql.innodb_index_stats \
+                 WHERE database_name = ? AND table_name = ? AND stat_name = 'size' \
+                 ORDER BY index_name",
+                (database_name, collection_name),
+            )
+            .await
+            .unwrap_or_default();
+
+        Ok(StorageStats {
+            document_count: rows,
+            storage_size: data,
+            free_size: free,
+            index_count,
+            index_size: indexes,
+            total_size: Some(data.unwrap_or(0) + indexes.unwrap_or(0)),
+            avg_object_size: avg_row,
+            index_sizes,
+            properties,
+            ..Default::default()
+        })
+    }
+
+    async fn server_status(&self) -> Result<ServerStatus> {
+        let mut conn = self.connect(None).await?;
+
+        // One timeout for the whole reading, so a stalled server can't hold up the poll
+        let readings = async {
+            let status: Vec<(String, String)> = conn.query("SHOW GLOBAL STATUS").await?;
+            let settings: Option<(String, u64)> =
+                conn.query_first("SELECT VERSION(), @@max_connections").await?;
+
+            // SHOW REPLICA STATUS needs MySQL 8.0.22 or MariaDB 10.5.1; older
+            // servers only know the SLAVE spelling. Both need REPLICATION CLIENT.
+            let mut replica: Option<Row> = conn.query_first("SHOW REPLICA STATUS").await.ok().flatten();
+            if replica.is_none() {
+                replica = conn.query_first("SHOW SLAVE STATUS").await.ok().flatten();
+            }
+            Ok::<_, mysql_async::Error>((status, settings, replica))
+        };
+        let (status, settings, replica) = tokio::time::timeout(self.config.timeout, readings)
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let status: HashMap<String, String> = status.into_iter().collect();
+        let number = |name: &str| status.get(name).and_then(|value| value.parse::<u64>().ok());
+
+        let replication_lag = replica.as_ref().and_then(|row| {
+            ["Seconds_Behind_Source", "Seconds_Behind_Master"]
+                .into_iter()
+                .find_map(|column| row.get_opt::<Option<u64>, _>(column)?.ok()?)
+                .map(|seconds| seconds as f64)
+        });
+
+        let mut properties = Vec::new();
+        if let Some((version, max_connections)) = settings {
+            properties.push(("Version".to_string(), format!("MySQL {}", version)));
+            properties.push(("Max connections".to_string(), max_connections.to_string()));
+        }
+        properties.push((
+            "Role".to_string(),
+            if replica.is_some() { "Replica" } else { "Primary" }.to_string(),
+        ));
+        for (name, label) in [
+            ("Threads_running", "Threads running"),
+            ("Max_used_connections", "Max used connections"),
+            ("Slow_queries", "Slow queries"),
+            ("Aborted_connects", "Aborted connects"),
+        ] {
+            if let Some(value) = status.get(name) {
+                properties.push((label.to_string(), value.clone()));
+            }
+        }
+
+        // Read requests are logical reads; those that missed the buffer pool went to disk
+        let requests = number("Innodb_buffer_pool_read_requests");
+        let reads = number("Innodb_buffer_pool_reads");
+        Ok(ServerStatus {
+            operations: number("Questions"),
+            connections: number("Threads_connected"),
+            cache_hits: requests.zip(reads).map(|(requests, reads)| requests.saturating_sub(reads)),
+            cache_misses: reads,
+            memory_bytes: number("Innodb_buffer_pool_bytes_data"),
+            replication_lag,
+            uptime: number("Uptime"),
+            properties,
+        })
+    }
+
+    async fn close(&self) {
+        let pools: Vec<Arc<mysql_async::Pool>> =
+            self.pools.lock().unwrap().drain().map(|(_, pool)| pool).collect();
+        for pool in pools {
+            mysql_async::Pool::clone(&pool).disconnect().await.ok();
+        }
+    }
 }
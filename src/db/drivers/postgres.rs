@@ -1,14 +1,32 @@
 //! PostgreSQL driver implementation
 
 use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use tokio_postgres::NoTls;
+use postgres_native_tls::MakeTlsConnector;
+use tokio_postgres::{Client, SimpleQueryMessage};
 
-use crate::db::driver::{ConnectionConfig, ConnectionInfo, DatabaseConnection};
+use super::sql::{
+    create_index_statement, default_index_name, explain_statement, page_query, quote_ident,
+    split_qualified, where_clause,
+};
+use crate::db::driver::{
+    BatchInsertResult, CheckConstraint, CollectionInfo, ColumnDefinition, ColumnInfo, ConnectionConfig, ConnectionInfo,
+    DatabaseConnection, DatabaseInfo, ForeignKey, IndexBuildProgress, IndexInfo, IndexOrder, IndexSpec,
+    KeyConstraint, QueryResult, ServerStatus, StorageStats, TableStructure, TriggerInfo,
+};
+use crate::db::connection::{DatabaseType, SslMode, TlsConfig};
 use crate::db::error::{ConnectionError, Result};
+use crate::db::explain::{self, QueryPlan};
+use crate::db::sql_edit::literal;
 
 pub struct PostgresConnection {
     config: ConnectionConfig,
+    /// Open clients keyed by database name ("" = connection string default).
+    /// A client pipelines concurrent queries over its connection, so one per
+    /// database is shared by all callers.
+    clients: Mutex<HashMap<String, Arc<Client>>>,
 }
 
 impl PostgresConnection {
@@ -20,22 +38,58 @@ impl PostgresConnection {
                 "PostgreSQL connection string must start with postgres:// or postgresql://".into(),
             ));
         }
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            clients: Mutex::new(HashMap::new()),
+        })
     }
-}
 
-#[async_trait]
-impl DatabaseConnection for PostgresConnection {
-    async fn test_connection(&self) -> Result<ConnectionInfo> {
-        let start = Instant::now();
+    /// Get a client, optionally overriding the database from the connection string.
+    /// PostgreSQL connections are bound to a single database, so browsing another
+    /// database requires a separate connection. Clients are reused until closed.
+    async fn connect(&self, database_name: Option<&str>) -> Result<Arc<Client>> {
+        let key = database_name.unwrap_or_default().to_string();
+        let cached = self.clients.lock().unwrap().get(&key).cloned();
+        if let Some(client) = cached.filter(|client| !client.is_closed()) {
+            return Ok(client);
+        }
 
-        // Connect with timeout
-        let connect_future = tokio_postgres::connect(&self.config.connection_string, NoTls);
-        
-        let (client, connection) = tokio::time::timeout(self.config.timeout, connect_future)
+        let client = Arc::new(self.open(database_name).await?);
+        self.clients.lock().unwrap().insert(key, client.clone());
+        Ok(client)
+    }
+
+    /// Open a new connection that is not shared with other calls. The shared clients
+    /// run every caller's queries over one session, so only calls that would change
+    /// that session (a transaction, `SET`) or hold it for long (an index build) open
+    /// their own; everything else goes through `connect`.
+    async fn open(&self, database_name: Option<&str>) -> Result<Client> {
+        let mut pg_config: tokio_postgres::Config = self
+            .config
+            .connection_string
+            .parse()
+            .map_err(|e: tokio_postgres::Error| ConnectionError::InvalidConnectionString(e.to_string()))?;
+
+        if let Some(name) = database_name {
+            pg_config.dbname(name);
+        }
+        pg_config.connect_timeout(self.config.timeout);
+
+        // The connection's TLS settings take precedence over `sslmode` in the URL
+        let tls = &self.config.tls;
+        pg_config.ssl_mode(match tls.ssl_mode {
+            SslMode::Disable => tokio_postgres::config::SslMode::Disable,
+            SslMode::Prefer => tokio_postgres::config::SslMode::Prefer,
+            SslMode::Require | SslMode::VerifyCa | SslMode::VerifyFull => {
+                tokio_postgres::config::SslMode::Require
+            }
+        });
+        let connector = tls_connector(tls)?;
+
+        let (client, connection) = tokio::time::timeout(self.config.timeout, pg_config.connect(connector))
             .await
             .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
-            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+            .map_err(|e| ConnectionError::Failed(with_cause(&e)))?;
 
         // Spawn connection handler (required by tokio-postgres)
         tokio::spawn(async move {
@@ -44,6 +98,256 @@ impl DatabaseConnection for PostgresConnection {
             }
         });
 
+        Ok(client)
+    }
+
+    /// Run a catalog query about the relation `table` (quoted, bound as `$1`)
+    async fn catalog_query(&self, client: &Client, query: &str, table: &str) -> Result<Vec<tokio_postgres::Row>> {
+        tokio::time::timeout(self.config.timeout, client.query(query, &[&table]))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| {
+                let message = e.as_db_error().map(|db| db.to_string()).unwrap_or_else(|| e.to_string());
+                ConnectionError::Failed(message)
+            })
+    }
+}
+
+/// Build the TLS connector for a connection's settings. As with libpq, `prefer` and
+/// `require` only encrypt unless a CA is given; `verify-ca` skips the host name check.
+fn tls_connector(tls: &TlsConfig) -> Result<MakeTlsConnector> {
+    let mut builder = native_tls::TlsConnector::builder();
+
+    let check_chain = tls.ssl_mode.verifies_certificate()
+        || (tls.ssl_mode == SslMode::Require && tls.ca_cert_path.is_some());
+    builder.danger_accept_invalid_certs(!check_chain);
+    builder.danger_accept_invalid_hostnames(tls.ssl_mode != SslMode::VerifyFull);
+
+    if let Some(path) = &tls.ca_cert_path {
+        let pem = read_tls_file(path, "CA certificate")?;
+        let certs = pem_certificates(&pem);
+        if certs.is_empty() {
+            return Err(ConnectionError::Tls(format!("No certificates found in {}", path)));
+        }
+        for cert in certs {
+            let cert = native_tls::Certificate::from_pem(cert)
+                .map_err(|e| ConnectionError::Tls(format!("Invalid CA certificate {}: {}", path, e)))?;
+            builder.add_root_certificate(cert);
+        }
+    }
+
+    match (&tls.client_cert_path, &tls.client_key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            let cert = read_tls_file(cert_path, "client certificate")?;
+            let key = read_tls_file(key_path, "client key")?;
+            let identity = native_tls::Identity::from_pkcs8(&cert, &key).map_err(|e| {
+                ConnectionError::Tls(format!(
+                    "Invalid client certificate or key (the key must be PKCS#8 PEM): {}",
+                    e
+                ))
+            })?;
+            builder.identity(identity);
+        }
+        (None, None) => {}
+        _ => {
+            return Err(ConnectionError::Tls(
+                "Client certificate and key must be set together".into(),
+            ))
+        }
+    }
+
+    let connector = builder
+        .build()
+        .map_err(|e| ConnectionError::Tls(e.to_string()))?;
+    Ok(MakeTlsConnector::new(connector))
+}
+
+/// Error text including the underlying cause, e.g. why a TLS handshake failed
+fn with_cause(error: &tokio_postgres::Error) -> String {
+    match std::error::Error::source(error) {
+        Some(cause) => format!("{}: {}", error, cause),
+        None => error.to_string(),
+    }
+}
+
+fn read_tls_file(path: &str, what: &str) -> Result<Vec<u8>> {
+    std::fs::read(path)
+        .map_err(|e| ConnectionError::Tls(format!("Failed to read {} {}: {}", what, path, e)))
+}
+
+/// Split a PEM bundle into its certificates; `Certificate::from_pem` only reads the first
+fn pem_certificates(pem: &[u8]) -> Vec<&[u8]> {
+    const END: &[u8] = b"-----END CERTIFICATE-----";
+    let mut certs = Vec::new();
+    let mut rest = pem;
+    while let Some(start) = find(rest, b"-----BEGIN CERTIFICATE-----") {
+        let Some(end) = find(&rest[start..], END) else {
+            break;
+        };
+        let end = start + end + END.len();
+        certs.push(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    certs
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Build a quoted relation name from a `schema.table` collection name
+fn qualified_table(collection_name: &str) -> String {
+    match split_qualified(collection_name) {
+        (Some(schema), table) => format!("{}.{}", quote_ident(schema), quote_ident(table)),
+        (None, table) => quote_ident(table),
+    }
+}
+
+/// Columns of the relation in `$1`, in table order
+const COLUMNS_QUERY: &str = "SELECT a.attname::text, format_type(a.atttypid, a.atttypmod), NOT a.attnotnull, \
+     pg_get_expr(d.adbin, d.adrelid), a.attidentity::text, a.attgenerated::text, \
+     CASE WHEN a.attcollation <> t.typcollation THEN co.collname::text END, \
+     col_description(a.attrelid, a.attnum) \
+     FROM pg_catalog.pg_attribute a \
+     JOIN pg_catalog.pg_type t ON t.oid = a.atttypid \
+     LEFT JOIN pg_catalog.pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum \
+     LEFT JOIN pg_catalog.pg_collation co ON co.oid = a.attcollation \
+     WHERE a.attrelid = $1::text::regclass AND a.attnum > 0 AND NOT a.attisdropped \
+     ORDER BY a.attnum";
+
+/// Constraints of the relation in `$1`: primary key, unique, check, then foreign keys
+const CONSTRAINTS_QUERY: &str = "SELECT c.conname::text, c.contype::text, pg_get_constraintdef(c.oid, true), \
+     ARRAY(SELECT a.attname::text FROM unnest(c.conkey) WITH ORDINALITY k(n, o) \
+     JOIN pg_catalog.pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.n ORDER BY k.o), \
+     CASE WHEN c.contype = 'f' THEN c.confrelid::regclass::text END, \
+     ARRAY(SELECT a.attname::text FROM unnest(c.confkey) WITH ORDINALITY k(n, o) \
+     JOIN pg_catalog.pg_attribute a ON a.attrelid = c.confrelid AND a.attnum = k.n ORDER BY k.o), \
+     c.confupdtype::text, c.confdeltype::text \
+     FROM pg_catalog.pg_constraint c \
+     WHERE c.conrelid = $1::text::regclass \
+     ORDER BY CASE c.contype WHEN 'p' THEN 0 WHEN 'u' THEN 1 WHEN 'c' THEN 2 WHEN 'f' THEN 3 ELSE 4 END, c.conname";
+
+/// User triggers of the relation in `$1`
+const TRIGGERS_QUERY: &str = "SELECT t.tgname::text, t.tgtype::int, \
+     pg_get_triggerdef(t.oid, true), pg_get_triggerdef(t.oid) \
+     FROM pg_catalog.pg_trigger t \
+     WHERE t.tgrelid = $1::text::regclass AND NOT t.tgisinternal \
+     ORDER BY t.tgname";
+
+/// Sizes, row counts and storage settings of the relation in `$1`
+const TABLE_STATS_QUERY: &str = "SELECT c.relkind::text, c.reltuples::bigint, s.n_live_tup, s.n_dead_tup, \
+     pg_relation_size(c.oid), pg_table_size(c.oid), pg_indexes_size(c.oid), pg_total_relation_size(c.oid), \
+     (SELECT count(*) FROM pg_catalog.pg_index i WHERE i.indrelid = c.oid), \
+     pg_get_userbyid(c.relowner)::text, COALESCE(ts.spcname::text, 'pg_default'), am.amname::text, \
+     c.relpersistence::text, array_to_string(c.reloptions, ', '), \
+     date_trunc('second', GREATEST(s.last_vacuum, s.last_autovacuum))::text, \
+     date_trunc('second', GREATEST(s.last_analyze, s.last_autoanalyze))::text \
+     FROM pg_catalog.pg_class c \
+     LEFT JOIN pg_catalog.pg_stat_user_tables s ON s.relid = c.oid \
+     LEFT JOIN pg_catalog.pg_tablespace ts ON ts.oid = c.reltablespace \
+     LEFT JOIN pg_catalog.pg_am am ON am.oid = c.relam \
+     WHERE c.oid = $1::text::regclass";
+
+/// Size of each index of the relation in `$1`
+const INDEX_SIZES_QUERY: &str = "SELECT i.relname::text, pg_relation_size(i.oid) \
+     FROM pg_catalog.pg_index x \
+     JOIN pg_catalog.pg_class i ON i.oid = x.indexrelid \
+     WHERE x.indrelid = $1::text::regclass \
+     ORDER BY 1";
+
+/// Totals over the user tables of the current database, and its settings
+const DATABASE_STATS_QUERY: &str = "WITH tables AS ( \
+     SELECT c.oid, c.reltuples FROM pg_catalog.pg_class c \
+     JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
+     WHERE c.relkind IN ('r', 'm') \
+     AND n.nspname NOT IN ('pg_catalog', 'information_schema') AND n.nspname NOT LIKE 'pg_toast%') \
+     SELECT (SELECT count(*) FROM tables), \
+     (SELECT COALESCE(sum(GREATEST(reltuples, 0)), 0)::bigint FROM tables), \
+     (SELECT COALESCE(sum(pg_relation_size(oid)), 0)::bigint FROM tables), \
+     (SELECT COALESCE(sum(pg_table_size(oid)), 0)::bigint FROM tables), \
+     (SELECT count(*) FROM pg_catalog.pg_index x JOIN tables t ON t.oid = x.indrelid), \
+     (SELECT COALESCE(sum(pg_indexes_size(oid)), 0)::bigint FROM tables), \
+     pg_database_size(d.oid), pg_encoding_to_char(d.encoding)::text, d.datcollate::text, d.datctype::text, \
+     pg_get_userbyid(d.datdba)::text, ts.spcname::text, d.datconnlimit, \
+     (SELECT count(*) FROM pg_catalog.pg_stat_activity a WHERE a.datid = d.oid) \
+     FROM pg_catalog.pg_database d \
+     JOIN pg_catalog.pg_tablespace ts ON ts.oid = d.dattablespace \
+     WHERE d.datname = current_database()";
+
+/// Activity summed over every database of the server, with the background
+/// writer's counters. Replay lag is only known on a standby.
+const SERVER_STATUS_QUERY: &str = "SELECT \
+     (SELECT sum(xact_commit + xact_rollback)::bigint FROM pg_catalog.pg_stat_database), \
+     (SELECT sum(numbackends)::bigint FROM pg_catalog.pg_stat_database), \
+     (SELECT sum(blks_hit)::bigint FROM pg_catalog.pg_stat_database), \
+     (SELECT sum(blks_read)::bigint FROM pg_catalog.pg_stat_database), \
+     CASE WHEN pg_is_in_recovery() \
+     THEN extract(epoch FROM now() - pg_last_xact_replay_timestamp())::float8 END, \
+     extract(epoch FROM now() - pg_postmaster_start_time())::bigint, \
+     current_setting('server_version'), current_setting('max_connections'), \
+     current_setting('shared_buffers'), \
+     (SELECT sum(deadlocks)::bigint FROM pg_catalog.pg_stat_database), \
+     b.buffers_clean, b.maxwritten_clean, b.buffers_alloc, pg_is_in_recovery() \
+     FROM pg_catalog.pg_stat_bgwriter b";
+
+fn column_definition(row: &tokio_postgres::Row) -> ColumnDefinition {
+    let default: Option<String> = row.get(3);
+    let identity: String = row.get(4);
+    let generated: String = row.get(5);
+    // Generated columns keep their expression where other columns keep their default
+    let (default, generated) = match (identity.as_str(), generated.as_str()) {
+        ("a", _) => (None, Some("GENERATED ALWAYS AS IDENTITY".to_string())),
+        ("d", _) => (None, Some("GENERATED BY DEFAULT AS IDENTITY".to_string())),
+        (_, "s") => (None, default.map(|expression| format!("GENERATED ALWAYS AS ({}) STORED", expression))),
+        _ => (default, None),
+    };
+    ColumnDefinition {
+        name: row.get(0),
+        data_type: row.get(1),
+        nullable: row.get(2),
+        default,
+        generated,
+        collation: row.get(6),
+        comment: row.get(7),
+    }
+}
+
+/// Spell out a `confupdtype`/`confdeltype` code; None for the default `NO ACTION`
+fn referential_action(code: String) -> Option<String> {
+    let action = match code.as_str() {
+        "r" => "RESTRICT",
+        "c" => "CASCADE",
+        "n" => "SET NULL",
+        "d" => "SET DEFAULT",
+        _ => return None,
+    };
+    Some(action.to_string())
+}
+
+/// Timing and events of a trigger from the bits of `pg_trigger.tgtype`
+fn trigger_kind(tgtype: i32) -> (String, String) {
+    let timing = if tgtype & (1 << 1) != 0 {
+        "BEFORE"
+    } else if tgtype & (1 << 6) != 0 {
+        "INSTEAD OF"
+    } else {
+        "AFTER"
+    };
+    let events: Vec<&str> = [(1 << 2, "INSERT"), (1 << 4, "UPDATE"), (1 << 3, "DELETE"), (1 << 5, "TRUNCATE")]
+        .into_iter()
+        .filter(|(bit, _)| tgtype & bit != 0)
+        .map(|(_, event)| event)
+        .collect();
+    (timing.to_string(), events.join(" OR "))
+}
+
+#[async_trait]
+impl DatabaseConnection for PostgresConnection {
+    async fn test_connection(&self) -> Result<ConnectionInfo> {
+        let start = Instant::now();
+
+        let client = self.connect(None).await?;
+
         // Get server version
         let row = client
             .query_one("SELECT version()", &[])
@@ -58,4 +362,734 @@ impl DatabaseConnection for PostgresConnection {
             latency_ms: latency,
         })
     }
+
+    async fn list_databases(&self) -> Result<Vec<DatabaseInfo>> {
+        let client = self.connect(None).await?;
+
+        // Size is only readable for databases we are allowed to connect to
+        let rows = tokio::time::timeout(
+            self.config.timeout,
+            client.query(
+                "SELECT datname::text, \
+                 CASE WHEN has_database_privilege(datname, 'CONNECT') \
+                 THEN pg_database_size(datname) END \
+                 FROM pg_catalog.pg_database \
+                 WHERE NOT datistemplate AND datallowconn \
+                 ORDER BY datname",
+                &[],
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        let result = rows
+            .iter()
+            .map(|row| {
+                let size: Option<i64> = row.get(1);
+                DatabaseInfo {
+                    name: row.get(0),
+                    size_bytes: size.map(|s| s as u64),
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    async fn list_collections(&self, database_name: &str) -> Result<Vec<CollectionInfo>> {
+        let client = self.connect(Some(database_name)).await?;
+
+        // Tables and views come from information_schema; materialized views are
+        // only in pg_matviews. Row counts are the planner estimates from pg_class.
+        let rows = tokio::time::timeout(
+            self.config.timeout,
+            client.query(
+                "SELECT t.table_schema::text, t.table_name::text, \
+                 CASE WHEN t.table_type = 'BASE TABLE' THEN c.reltuples::bigint END \
+                 FROM information_schema.tables t \
+                 LEFT JOIN pg_catalog.pg_namespace n ON n.nspname = t.table_schema \
+                 LEFT JOIN pg_catalog.pg_class c ON c.relnamespace = n.oid AND c.relname = t.table_name \
+                 WHERE t.table_schema NOT IN ('pg_catalog', 'information_schema') \
+                 UNION ALL \
+                 SELECT m.schemaname::text, m.matviewname::text, c.reltuples::bigint \
+                 FROM pg_catalog.pg_matviews m \
+                 JOIN pg_catalog.pg_namespace n ON n.nspname = m.schemaname \
+                 JOIN pg_catalog.pg_class c ON c.relnamespace = n.oid AND c.relname = m.matviewname \
+                 ORDER BY 1, 2",
+                &[],
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        let result = rows
+            .iter()
+            .map(|row| {
+                let schema: String = row.get(0);
+                let table: String = row.get(1);
+                let estimate: Option<i64> = row.get(2);
+                CollectionInfo {
+                    name: format!("{}.{}", schema, table),
+                    // reltuples is -1 for tables that have never been analyzed
+                    document_count: estimate.filter(|n| *n >= 0).map(|n| n as u64),
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    async fn query_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        limit: u32,
+        skip: u32,
+        filter: Option<&str>,
+        sort: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        let client = self.connect(Some(database_name)).await?;
+
+        // row_to_json keeps column order and maps numbers, booleans and json/jsonb
+        // columns to their JSON equivalents; everything else becomes a string.
+        let query = page_query(
+            format!("SELECT row_to_json(t)::text FROM {} t", qualified_table(collection_name)),
+            filter,
+            sort,
+            limit,
+            skip,
+            quote_ident,
+        );
+
+        let rows = tokio::time::timeout(self.config.timeout, client.query(query.as_str(), &[]))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        rows.iter()
+            .map(|row| {
+                let json: String = row.get(0);
+                serde_json::from_str(&json).map_err(|e| ConnectionError::Failed(e.to_string()))
+            })
+            .collect()
+    }
+
+    async fn count_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        filter: Option<&str>,
+    ) -> Result<usize> {
+        let client = self.connect(Some(database_name)).await?;
+
+        let mut query = format!("SELECT count(*) FROM {}", qualified_table(collection_name));
+        if let Some(clause) = where_clause(filter) {
+            query.push_str(&format!(" WHERE {}", clause));
+        }
+
+        let row = tokio::time::timeout(self.config.timeout, client.query_one(query.as_str(), &[]))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        let count: i64 = row.get(0);
+        Ok(count as usize)
+    }
+
+    async fn table_columns(&self, database_name: &str, collection_name: &str) -> Result<Vec<ColumnInfo>> {
+        let client = self.connect(Some(database_name)).await?;
+
+        let rows = tokio::time::timeout(
+            self.config.timeout,
+            client.query(
+                "SELECT a.attname::text, NOT a.attnotnull, \
+                 COALESCE(a.attnum = ANY(i.indkey), false) \
+                 FROM pg_catalog.pg_attribute a \
+                 LEFT JOIN pg_catalog.pg_index i ON i.indrelid = a.attrelid AND i.indisprimary \
+                 WHERE a.attrelid = $1::text::regclass AND a.attnum > 0 AND NOT a.attisdropped \
+                 ORDER BY a.attnum",
+                &[&qualified_table(collection_name)],
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ColumnInfo {
+                name: row.get(0),
+                nullable: row.get(1),
+                primary_key: row.get(2),
+            })
+            .collect())
+    }
+
+    async fn execute_transaction(&self, database_name: &str, statements: &[String]) -> Result<Vec<u64>> {
+        // A transaction holds its connection, so it gets its own rather than the shared one
+        let mut client = self.open(Some(database_name)).await?;
+        let failed = |e: tokio_postgres::Error| {
+            let message = e.as_db_error().map(|db| db.to_string()).unwrap_or_else(|| e.to_string());
+            ConnectionError::Failed(message)
+        };
+
+        // Dropping the transaction without committing rolls it back
+        let transaction = client.transaction().await.map_err(failed)?;
+        let mut affected = Vec::with_capacity(statements.len());
+        for statement in statements {
+            affected.push(transaction.execute(statement.as_str(), &[]).await.map_err(failed)?);
+        }
+        transaction.commit().await.map_err(failed)?;
+
+        Ok(affected)
+    }
+
+    async fn execute_each(
+        &self,
+        database_name: &str,
+        statements: &[String],
+        stop_on_error: bool,
+    ) -> Result<BatchInsertResult> {
+        // Savepoints only exist inside a transaction, which needs a client to itself
+        let mut client = self.open(Some(database_name)).await?;
+        let failed = |e: tokio_postgres::Error| ConnectionError::Failed(e.to_string());
+
+        let mut transaction = client.transaction().await.map_err(failed)?;
+        let mut result = BatchInsertResult::default();
+        for (index, statement) in statements.iter().enumerate() {
+            let savepoint = transaction.savepoint("statement").await.map_err(failed)?;
+            match savepoint.execute(statement.as_str(), &[]).await {
+                Ok(_) => {
+                    savepoint.commit().await.map_err(failed)?;
+                    result.inserted += 1;
+                }
+                // Only errors reported by the server are about the statement
+                Err(e) => match e.as_db_error() {
+                    Some(db) => {
+                        result.rejected.push((index, db.to_string()));
+                        savepoint.rollback().await.map_err(failed)?;
+                        if stop_on_error {
+                            break;
+                        }
+                    }
+                    None => return Err(failed(e)),
+                },
+            }
+        }
+        transaction.commit().await.map_err(failed)?;
+
+        Ok(result)
+    }
+
+    async fn execute_query(&self, database_name: &str, query: &str) -> Result<QueryResult> {
+        // Scripts may leave session state behind (SET, an open transaction), so they
+        // run on their own connection rather than the shared one
+        let client = self.open((!database_name.is_empty()).then_some(database_name)).await?;
+        let start = Instant::now();
+
+        // The simple query protocol accepts several statements separated by semicolons.
+        // Values come back as text, so no per-type decoding is needed. The console may
+        // run long statements, so only the connect is bounded by the timeout.
+        let messages = client
+            .simple_query(query)
+            .await
+            .map_err(|e| {
+                // Server errors only say "db error" unless the details are unwrapped
+                let message = e.as_db_error().map(|db| db.to_string()).unwrap_or_else(|| e.to_string());
+                ConnectionError::Failed(message)
+            })?;
+
+        let mut result = QueryResult::default();
+        let mut current = QueryResult::default();
+        for message in messages {
+            match message {
+                SimpleQueryMessage::RowDescription(columns) => {
+                    current.columns = columns.iter().map(|c| c.name().to_string()).collect();
+                }
+                SimpleQueryMessage::Row(row) => {
+                    let values = (0..row.len())
+                        .map(|i| match row.get(i) {
+                            Some(text) => serde_json::Value::String(text.to_string()),
+                            None => serde_json::Value::Null,
+                        })
+                        .collect();
+                    current.rows.push(values);
+                }
+                SimpleQueryMessage::CommandComplete(count) => {
+                    // Statements without a row description only report affected rows
+                    if current.columns.is_empty() {
+                        current.affected_rows = Some(count);
+                    }
+                    result = std::mem::take(&mut current);
+                }
+                _ => {}
+            }
+        }
+
+        result.elapsed = start.elapsed();
+        Ok(result)
+    }
+
+    async fn explain_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        limit: u32,
+        skip: u32,
+        filter: Option<&str>,
+        sort: Option<&str>,
+    ) -> Result<QueryPlan> {
+        let query = page_query(
+            format!("SELECT * FROM {}", qualified_table(collection_name)),
+            filter,
+            sort,
+            limit,
+            skip,
+            quote_ident,
+        );
+        self.explain_query(database_name, &query).await
+    }
+
+    async fn explain_query(&self, database_name: &str, query: &str) -> Result<QueryPlan> {
+        let failed = |e: tokio_postgres::Error| {
+            let message = e.as_db_error().map(|db| db.to_string()).unwrap_or_else(|| e.to_string());
+            ConnectionError::Failed(message)
+        };
+
+        // ANALYZE runs the statement, so it runs in a transaction that is rolled back;
+        // a transaction borrows its client mutably, which the shared one cannot lend
+        let mut client = self.open((!database_name.is_empty()).then_some(database_name)).await?;
+        let transaction = client.transaction().await.map_err(failed)?;
+        let messages = transaction
+            .simple_query(&format!("EXPLAIN (ANALYZE, FORMAT JSON) {}", explain_statement(query)))
+            .await
+            .map_err(failed)?;
+        transaction.rollback().await.map_err(failed)?;
+
+        let plan = messages
+            .iter()
+            .find_map(|message| match message {
+                SimpleQueryMessage::Row(row) => row.get(0),
+                _ => None,
+            })
+            .ok_or_else(|| ConnectionError::Failed("EXPLAIN returned no plan".into()))?;
+        let plan = serde_json::from_str(plan).map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        Ok(explain::from_postgres(plan))
+    }
+
+    async fn list_indexes(&self, database_name: &str, collection_name: &str) -> Result<Vec<IndexInfo>> {
+        let client = self.connect(Some(database_name)).await?;
+
+        // Key columns only (not INCLUDE columns); bit 0 of indoption marks DESC
+        let rows = tokio::time::timeout(
+            self.config.timeout,
+            client.query(
+                "SELECT i.relname::text, ix.indisunique, ix.indisprimary, \
+                 pg_get_expr(ix.indpred, ix.indrelid), pg_relation_size(i.oid), s.idx_scan, am.amname::text, \
+                 ARRAY(SELECT pg_get_indexdef(ix.indexrelid, k + 1, true) \
+                 FROM generate_series(0, ix.indnkeyatts - 1) k ORDER BY k), \
+                 ARRAY(SELECT (ix.indoption[k] & 1) = 1 FROM generate_series(0, ix.indnkeyatts - 1) k ORDER BY k) \
+                 FROM pg_catalog.pg_index ix \
+                 JOIN pg_catalog.pg_class i ON i.oid = ix.indexrelid \
+                 JOIN pg_catalog.pg_am am ON am.oid = i.relam \
+                 LEFT JOIN pg_catalog.pg_stat_user_indexes s ON s.indexrelid = ix.indexrelid \
+                 WHERE ix.indrelid = $1::text::regclass \
+                 ORDER BY ix.indisprimary DESC, i.relname",
+                &[&qualified_table(collection_name)],
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let size: i64 = row.get(4);
+                let scans: Option<i64> = row.get(5);
+                let method: String = row.get(6);
+                let columns: Vec<String> = row.get(7);
+                let descending: Vec<bool> = row.get(8);
+                IndexInfo {
+                    name: row.get(0),
+                    keys: columns
+                        .into_iter()
+                        .zip(descending)
+                        .map(|(column, desc)| {
+                            let order = if desc { IndexOrder::Descending } else { IndexOrder::Ascending };
+                            (column, order)
+                        })
+                        .collect(),
+                    method: (method != "btree").then_some(method),
+                    unique: row.get(1),
+                    primary: row.get(2),
+                    partial_filter: row.get(3),
+                    expire_after_seconds: None,
+                    size_bytes: Some(size as u64),
+                    usage_count: scans.map(|n| n as u64),
+                }
+            })
+            .collect())
+    }
+
+    async fn create_index(&self, database_name: &str, collection_name: &str, spec: &IndexSpec) -> Result<()> {
+        // A long build would hold up every query queued behind it on the shared
+        // client, so it gets its own connection
+        let client = self.open(Some(database_name)).await?;
+
+        // The index is created in the table's schema, so its name is not qualified
+        let (_, table) = split_qualified(collection_name);
+        let name = spec.name.clone().unwrap_or_else(|| default_index_name(table, spec));
+        let statement = create_index_statement(spec, &quote_ident(&name), &qualified_table(collection_name), quote_ident)?;
+
+        client.batch_execute(&statement).await.map_err(|e| {
+            let message = e.as_db_error().map(|db| db.to_string()).unwrap_or_else(|| e.to_string());
+            ConnectionError::Failed(message)
+        })
+    }
+
+    async fn drop_index(&self, database_name: &str, collection_name: &str, index_name: &str) -> Result<()> {
+        let client = self.connect(Some(database_name)).await?;
+
+        let statement = match split_qualified(collection_name) {
+            (Some(schema), _) => format!("DROP INDEX {}.{}", quote_ident(schema), quote_ident(index_name)),
+            (None, _) => format!("DROP INDEX {}", quote_ident(index_name)),
+        };
+        tokio::time::timeout(self.config.timeout, client.batch_execute(&statement))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| {
+                let message = e.as_db_error().map(|db| db.to_string()).unwrap_or_else(|| e.to_string());
+                ConnectionError::Failed(message)
+            })
+    }
+
+    async fn index_build_progress(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<Option<IndexBuildProgress>> {
+        let client = self.connect(Some(database_name)).await?;
+
+        // pg_stat_progress_create_index exists since PostgreSQL 12
+        let rows = tokio::time::timeout(
+            self.config.timeout,
+            client.query(
+                "SELECT phase, blocks_done, blocks_total, tuples_done, tuples_total \
+                 FROM pg_catalog.pg_stat_progress_create_index WHERE relid = $1::text::regclass",
+                &[&qualified_table(collection_name)],
+            ),
+        )
+        .await
+        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+
+        Ok(rows.first().map(|row| {
+            let phase: String = row.get(0);
+            let blocks: (i64, i64) = (row.get(1), row.get(2));
+            let tuples: (i64, i64) = (row.get(3), row.get(4));
+            // Table scans count blocks, later phases count tuples
+            let (done, total) = if blocks.1 > 0 { blocks } else { tuples };
+            IndexBuildProgress {
+                phase: Some(phase),
+                done: done as u64,
+                total: total as u64,
+            }
+        }))
+    }
+
+    async fn table_structure(&self, database_name: &str, collection_name: &str) -> Result<TableStructure> {
+        let client = self.connect(Some(database_name)).await?;
+        let table = qualified_table(collection_name);
+
+        let columns = self.catalog_query(&client, COLUMNS_QUERY, &table).await?;
+        let constraints = self.catalog_query(&client, CONSTRAINTS_QUERY, &table).await?;
+        let triggers = self.catalog_query(&client, TRIGGERS_QUERY, &table).await?;
+
+        let mut structure = TableStructure {
+            columns: columns.iter().map(column_definition).collect(),
+            ..Default::default()
+        };
+        for row in &constraints {
+            let name: String = row.get(0);
+            let kind: String = row.get(1);
+            let definition: String = row.get(2);
+            let columns: Vec<String> = row.get(3);
+            match kind.as_str() {
+                "p" => structure.primary_key = Some(KeyConstraint { name: Some(name), columns }),
+                "u" => structure.unique_constraints.push(KeyConstraint { name: Some(name), columns }),
+                "c" => structure.check_constraints.push(CheckConstraint {
+                    name: Some(name),
+                    expression: definition.strip_prefix("CHECK ").unwrap_or(&definition).to_string(),
+                }),
+                "f" => structure.foreign_keys.push(ForeignKey {
+                    name: Some(name),
+                    columns,
+                    referenced_table: row.get(4),
+                    referenced_columns: row.get(5),
+                    on_update: referential_action(row.get(6)),
+                    on_delete: referential_action(row.get(7)),
+                }),
+                // Exclusion and constraint triggers only show in the DDL
+                _ => {}
+            }
+        }
+        structure.triggers = triggers
+            .iter()
+            .map(|row| {
+                let (timing, events) = trigger_kind(row.get(1));
+                TriggerInfo {
+                    name: row.get(0),
+                    timing,
+                    events,
+                    definition: row.get(2),
+                }
+            })
+            .collect();
+
+        Ok(structure)
+    }
+
+    async fn table_ddl(&self, database_name: &str, collection_name: &str) -> Result<String> {
+        let client = self.connect(Some(database_name)).await?;
+        let table = qualified_table(collection_name);
+
+        let columns = self.catalog_query(&client, COLUMNS_QUERY, &table).await?;
+        let constraints = self.catalog_query(&client, CONSTRAINTS_QUERY, &table).await?;
+        let triggers = self.catalog_query(&client, TRIGGERS_QUERY, &table).await?;
+        // Indexes backing a constraint are created by the constraint
+        let indexes = self
+            .catalog_query(
+                &client,
+                "SELECT pg_get_indexdef(i.indexrelid) \
+                 FROM pg_catalog.pg_index i \
+                 WHERE i.indrelid = $1::text::regclass \
+                 AND NOT EXISTS (SELECT 1 FROM pg_catalog.pg_constraint c WHERE c.conindid = i.indexrelid) \
+                 ORDER BY 1",
+                &table,
+            )
+            .await?;
+        let comment = self
+            .catalog_query(&client, "SELECT obj_description($1::text::regclass, 'pg_class')", &table)
+            .await?
+            .first()
+            .and_then(|row| row.get::<_, Option<String>>(0));
+
+        let columns: Vec<ColumnDefinition> = columns.iter().map(column_definition).collect();
+        let mut lines: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                let mut line = format!("{} {}", quote_ident(&column.name), column.data_type);
+                if let Some(collation) = &column.collation {
+                    line.push_str(&format!(" COLLATE {}", quote_ident(collation)));
+                }
+                match (&column.generated, &column.default) {
+                    (Some(generated), _) => line.push_str(&format!(" {}", generated)),
+                    (None, Some(default)) => line.push_str(&format!(" DEFAULT {}", default)),
+                    (None, None) => {}
+                }
+                if !column.nullable {
+                    line.push_str(" NOT NULL");
+                }
+                line
+            })
+            .collect();
+        lines.extend(constraints.iter().map(|row| {
+            let name: String = row.get(0);
+            let definition: String = row.get(2);
+            format!("CONSTRAINT {} {}", quote_ident(&name), definition)
+        }));
+
+        let mut ddl = format!("CREATE TABLE {} (\n    {}\n);\n", table, lines.join(",\n    "));
+        let comment_literal = |text: &str| literal(DatabaseType::PostgreSQL, &serde_json::Value::String(text.to_string()));
+        if let Some(comment) = comment {
+            ddl.push_str(&format!("\nCOMMENT ON TABLE {} IS {};\n", table, comment_literal(&comment)));
+        }
+        for column in &columns {
+            if let Some(comment) = &column.comment {
+                ddl.push_str(&format!(
+                    "COMMENT ON COLUMN {}.{} IS {};\n",
+                    table,
+                    quote_ident(&column.name),
+                    comment_literal(comment)
+                ));
+            }
+        }
+        if !indexes.is_empty() {
+            ddl.push('\n');
+        }
+        for row in &indexes {
+            ddl.push_str(&format!("{};\n", row.get::<_, String>(0)));
+        }
+        for row in &triggers {
+            ddl.push_str(&format!("\n{};\n", row.get::<_, String>(3)));
+        }
+
+        Ok(ddl)
+    }
+
+    async fn database_stats(&self, database_name: &str) -> Result<StorageStats> {
+        let client = self.connect(Some(database_name)).await?;
+
+        let row = tokio::time::timeout(self.config.timeout, client.query_one(DATABASE_STATS_QUERY, &[]))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(with_cause(&e)))?;
+
+        let size = |index: usize| Some(row.get::<_, i64>(index) as u64);
+        let tables: i64 = row.get(0);
+        let rows: i64 = row.get(1);
+        let data_size: i64 = row.get(2);
+        let connection_limit: i32 = row.get(12);
+        let active_connections: i64 = row.get(13);
+        let properties = vec![
+            ("Owner".to_string(), row.get(10)),
+            ("Encoding".to_string(), row.get(7)),
+            ("Collation".to_string(), row.get(8)),
+            ("Character type".to_string(), row.get(9)),
+            ("Tablespace".to_string(), row.get(11)),
+            (
+                "Connections".to_string(),
+                if connection_limit < 0 {
+                    active_connections.to_string()
+                } else {
+                    format!("{} of {}", active_connections, connection_limit)
+                },
+            ),
+        ];
+
+        Ok(StorageStats {
+            // Row counts are the planner's estimates
+            document_count: Some(rows as u64),
+            collection_count: Some(tables as u64),
+            data_size: size(2),
+            storage_size: size(3),
+            index_count: size(4),
+            index_size: size(5),
+            // The whole database, system catalogs included
+            total_size: size(6),
+            avg_object_size: (rows > 0).then(|| (data_size / rows) as u64),
+            properties,
+            ..Default::default()
+        })
+    }
+
+    async fn collection_stats(&self, database_name: &str, collection_name: &str) -> Result<StorageStats> {
+        let client = self.connect(Some(database_name)).await?;
+        let table = qualified_table(collection_name);
+
+        let rows = self.catalog_query(&client, TABLE_STATS_QUERY, &table).await?;
+        let Some(row) = rows.first() else {
+            return Err(ConnectionError::Failed(format!("Table {} not found", collection_name)));
+        };
+        let index_sizes = self
+            .catalog_query(&client, INDEX_SIZES_QUERY, &table)
+            .await?
+            .iter()
+            .map(|row| (row.get::<_, String>(0), row.get::<_, i64>(1) as u64))
+            .collect();
+
+        let kind: String = row.get(0);
+        let estimate: i64 = row.get(1);
+        let live_rows: Option<i64> = row.get(2);
+        let dead_rows: Option<i64> = row.get(3);
+        let data_size: i64 = row.get(4);
+        let size = |index: usize| Some(row.get::<_, i64>(index) as u64);
+
+        let mut properties = vec![(
+            "Type".to_string(),
+            match kind.as_str() {
+                "r" => "table",
+                "p" => "partitioned table",
+                "v" => "view",
+                "m" => "materialized view",
+                "f" => "foreign table",
+                _ => "relation",
+            }
+            .to_string(),
+        )];
+        properties.push(("Owner".to_string(), row.get(9)));
+        properties.push(("Tablespace".to_string(), row.get(10)));
+        if let Some(method) = row.get::<_, Option<String>>(11) {
+            properties.push(("Access method".to_string(), method));
+        }
+        let persistence: String = row.get(12);
+        if persistence == "u" {
+            properties.push(("Persistence".to_string(), "unlogged".to_string()));
+        }
+        if let Some(options) = row.get::<_, Option<String>>(13) {
+            properties.push(("Storage options".to_string(), options));
+        }
+        if let Some(dead_rows) = dead_rows {
+            properties.push(("Dead rows".to_string(), dead_rows.to_string()));
+        }
+        if let Some(vacuumed) = row.get::<_, Option<String>>(14) {
+            properties.push(("Last vacuum".to_string(), vacuumed));
+        }
+        if let Some(analyzed) = row.get::<_, Option<String>>(15) {
+            properties.push(("Last analyze".to_string(), analyzed));
+        }
+
+        // Views have no storage of their own
+        if kind == "v" {
+            return Ok(StorageStats {
+                properties,
+                ..Default::default()
+            });
+        }
+
+        // Live rows are counted by the statistics collector; reltuples is the
+        // planner's estimate and -1 for tables that have never been analyzed
+        let document_count = live_rows.or(Some(estimate).filter(|n| *n >= 0)).map(|n| n as u64);
+        Ok(StorageStats {
+            document_count,
+            data_size: size(4),
+            storage_size: size(5),
+            index_count: size(8),
+            index_size: size(6),
+            total_size: size(7),
+            avg_object_size: document_count
+                .filter(|n| *n > 0)
+                .map(|n| data_size as u64 / n),
+            index_sizes,
+            properties,
+            ..Default::default()
+        })
+    }
+
+    async fn server_status(&self) -> Result<ServerStatus> {
+        let client = self.connect(None).await?;
+
+        let row = tokio::time::timeout(self.config.timeout, client.query_one(SERVER_STATUS_QUERY, &[]))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(with_cause(&e)))?;
+
+        let counter = |index: usize| row.get::<_, Option<i64>>(index).map(|n| n.max(0) as u64);
+        let in_recovery: bool = row.get(13);
+        let properties = vec![
+            ("Version".to_string(), format!("PostgreSQL {}", row.get::<_, String>(6))),
+            ("Role".to_string(), if in_recovery { "Standby" } else { "Primary" }.to_string()),
+            ("Max connections".to_string(), row.get(7)),
+            ("Shared buffers".to_string(), row.get(8)),
+            ("Deadlocks".to_string(), counter(9).unwrap_or(0).to_string()),
+            ("Buffers written by bgwriter".to_string(), row.get::<_, i64>(10).to_string()),
+            ("Bgwriter stops (too many buffers)".to_string(), row.get::<_, i64>(11).to_string()),
+            ("Buffers allocated".to_string(), row.get::<_, i64>(12).to_string()),
+        ];
+
+        Ok(ServerStatus {
+            operations: counter(0),
+            connections: counter(1),
+            cache_hits: counter(2),
+            cache_misses: counter(3),
+            // The server does not report its memory use
+            memory_bytes: None,
+            replication_lag: row.get::<_, Option<f64>>(4).map(|lag| lag.max(0.0)),
+            uptime: counter(5),
+            properties,
+        })
+    }
 }
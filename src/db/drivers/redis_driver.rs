@@ -1,13 +1,33 @@
 //! Redis driver implementation
+//!
+//! Logical databases (`db0`..`dbN`) are exposed as databases. Each has a single
+//! `REDIS_KEYSPACE` collection listing its keys; any other collection name is a key
+//! whose contents are returned as type-specific rows.
 
 use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use redis::IntoConnectionInfo;
+use std::collections::HashMap;
+use std::sync::Mutex;
 use std::time::Instant;
 
-use crate::db::driver::{ConnectionConfig, ConnectionInfo, DatabaseConnection};
+use crate::db::driver::{
+    CollectionInfo, ConnectionConfig, ConnectionInfo, DatabaseConnection, DatabaseInfo, KeyspaceCursor,
+    KeyspacePage, RespValue, ServerStatus, REDIS_COUNT_LIMIT, REDIS_KEYSPACE,
+};
 use crate::db::error::{ConnectionError, Result};
 
+/// Number of entries requested per SCAN round trip
+const SCAN_COUNT: usize = 500;
+
+/// Stream entry as returned by XRANGE: id and field/value pairs
+type StreamEntry = (String, Vec<(Vec<u8>, Vec<u8>)>);
+
 pub struct RedisConnection {
     config: ConnectionConfig,
+    /// Open connections keyed by database name ("" = connection string default).
+    /// Multiplexed connections are cheap to clone and safe to share.
+    connections: Mutex<HashMap<String, MultiplexedConnection>>,
 }
 
 impl RedisConnection {
@@ -18,7 +38,480 @@ impl RedisConnection {
                 "Redis connection string must start with redis:// or rediss://".into(),
             ));
         }
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            connections: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Connect, optionally selecting a logical database by its `dbN` name.
+    /// The connection is reused by later calls for the same database.
+    async fn connect(&self, database_name: Option<&str>) -> Result<MultiplexedConnection> {
+        let key = database_name.unwrap_or_default().to_string();
+        let cached = self.connections.lock().unwrap().get(&key).cloned();
+        if let Some(conn) = cached {
+            return Ok(conn);
+        }
+
+        let conn = self.open(database_name).await?;
+        self.connections.lock().unwrap().insert(key, conn.clone());
+        Ok(conn)
+    }
+
+    /// Open a new connection that is not shared with other calls
+    async fn open(&self, database_name: Option<&str>) -> Result<MultiplexedConnection> {
+        let mut info = self
+            .config
+            .connection_string
+            .as_str()
+            .into_connection_info()
+            .map_err(|e| ConnectionError::InvalidConnectionString(e.to_string()))?;
+
+        if let Some(name) = database_name {
+            info.redis.db = parse_db_index(name)?;
+        }
+
+        let client = redis::Client::open(info)
+            .map_err(|e| ConnectionError::InvalidConnectionString(e.to_string()))?;
+
+        tokio::time::timeout(self.config.timeout, client.get_multiplexed_async_connection())
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| ConnectionError::Failed(e.to_string()))
+    }
+
+    /// Convert a command error, forgetting cached connections if the socket broke
+    /// so the next call reconnects
+    fn command_error(&self, error: redis::RedisError) -> ConnectionError {
+        if error.is_connection_dropped() || error.is_io_error() {
+            self.connections.lock().unwrap().clear();
+        }
+        ConnectionError::Failed(error.to_string())
+    }
+
+    /// Run a command with the configured timeout
+    async fn query<T: redis::FromRedisValue>(
+        &self,
+        conn: &mut MultiplexedConnection,
+        cmd: &redis::Cmd,
+    ) -> Result<T> {
+        tokio::time::timeout(self.config.timeout, cmd.query_async(conn))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| self.command_error(e))
+    }
+
+    /// Iterate a SCAN-family command (`SCAN`, `HSCAN`, `SSCAN`, `ZSCAN`) until at least
+    /// `needed` items are collected or the cursor is exhausted
+    async fn scan(
+        &self,
+        conn: &mut MultiplexedConnection,
+        command: &str,
+        key: Option<&str>,
+        pattern: Option<&str>,
+        needed: Option<usize>,
+    ) -> Result<Vec<Vec<u8>>> {
+        let mut items = Vec::new();
+        let mut cursor: u64 = 0;
+        loop {
+            let (next, batch) = self.scan_step(conn, command, key, cursor, pattern).await?;
+            items.extend(batch);
+            cursor = next;
+
+            if cursor == 0 || needed.is_some_and(|n| items.len() >= n) {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    /// One round trip of a SCAN-family command. Returns the next cursor (0 when done)
+    /// and the items of this batch.
+    async fn scan_step(
+        &self,
+        conn: &mut MultiplexedConnection,
+        command: &str,
+        key: Option<&str>,
+        cursor: u64,
+        pattern: Option<&str>,
+    ) -> Result<(u64, Vec<Vec<u8>>)> {
+        let mut cmd = redis::cmd(command);
+        if let Some(key) = key {
+            cmd.arg(key);
+        }
+        cmd.arg(cursor);
+        if let Some(pattern) = pattern {
+            cmd.arg("MATCH").arg(pattern);
+        }
+        cmd.arg("COUNT").arg(SCAN_COUNT);
+
+        self.query(conn, &cmd).await
+    }
+
+    /// Number of items a SCAN-family command matches, stopping at `REDIS_COUNT_LIMIT`.
+    /// `per_item` is 2 for commands returning field/value or member/score pairs.
+    async fn count_matches(
+        &self,
+        conn: &mut MultiplexedConnection,
+        command: &str,
+        key: Option<&str>,
+        pattern: &str,
+        per_item: usize,
+    ) -> Result<usize> {
+        let mut items = self
+            .scan(conn, command, key, Some(pattern), Some(per_item * REDIS_COUNT_LIMIT))
+            .await?;
+        let count = if per_item == 1 {
+            // SCAN may return duplicates across iterations
+            items.sort_unstable();
+            items.dedup();
+            items.len()
+        } else {
+            items.len() / per_item
+        };
+        Ok(count.min(REDIS_COUNT_LIMIT))
+    }
+
+    /// Key listing rows: key, type and TTL
+    async fn query_keyspace(
+        &self,
+        conn: &mut MultiplexedConnection,
+        limit: usize,
+        skip: usize,
+        pattern: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        let keys: Vec<Vec<u8>> = match pattern.and_then(literal_pattern) {
+            // Exact key: look it up directly instead of scanning the whole keyspace
+            Some(key) => {
+                let exists: bool = self.query(conn, redis::cmd("EXISTS").arg(&key)).await?;
+                if exists && skip == 0 {
+                    vec![key.into_bytes()]
+                } else {
+                    Vec::new()
+                }
+            }
+            None => {
+                let mut keys = self.scan(conn, "SCAN", None, pattern, Some(skip + limit)).await?;
+                // SCAN may return duplicates across iterations
+                let mut seen = std::collections::HashSet::new();
+                keys.retain(|k| seen.insert(k.clone()));
+                keys.into_iter().skip(skip).take(limit).collect()
+            }
+        };
+
+        self.keyspace_rows(conn, &keys).await
+    }
+
+    /// Type and TTL of each key, in one pipeline
+    async fn keyspace_rows(
+        &self,
+        conn: &mut MultiplexedConnection,
+        keys: &[Vec<u8>],
+    ) -> Result<Vec<serde_json::Value>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.cmd("TYPE").arg(key).cmd("TTL").arg(key);
+        }
+        let meta: Vec<redis::Value> = tokio::time::timeout(self.config.timeout, pipe.query_async(conn))
+            .await
+            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
+            .map_err(|e| self.command_error(e))?;
+
+        let rows = keys
+            .iter()
+            .zip(meta.chunks(2))
+            .map(|(key, meta)| {
+                let key_type: String = redis::from_redis_value(&meta[0]).unwrap_or_default();
+                let ttl: i64 = redis::from_redis_value(&meta[1]).unwrap_or(-1);
+                serde_json::json!({
+                    "key": bytes_to_string(key),
+                    "type": key_type,
+                    "ttl": ttl_to_json(ttl),
+                })
+            })
+            .collect();
+
+        Ok(rows)
+    }
+
+    /// Rows for a single key, shaped by its type
+    async fn query_key(
+        &self,
+        conn: &mut MultiplexedConnection,
+        key: &str,
+        limit: usize,
+        skip: usize,
+        pattern: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        let key_type: String = self.query(conn, redis::cmd("TYPE").arg(key)).await?;
+        let end = (skip + limit) as isize - 1;
+
+        let rows = match key_type.as_str() {
+            "string" => {
+                if skip > 0 {
+                    return Ok(Vec::new());
+                }
+                let value: Vec<u8> = self.query(conn, redis::cmd("GET").arg(key)).await?;
+                vec![serde_json::json!({ "value": bytes_to_string(&value) })]
+            }
+            "hash" => {
+                let items = self.scan(conn, "HSCAN", Some(key), pattern, Some(2 * (skip + limit))).await?;
+                items
+                    .chunks(2)
+                    .skip(skip)
+                    .take(limit)
+                    .map(|pair| {
+                        serde_json::json!({
+                            "field": bytes_to_string(&pair[0]),
+                            "value": pair.get(1).map(|v| bytes_to_string(v)).unwrap_or_default(),
+                        })
+                    })
+                    .collect()
+            }
+            "list" => {
+                let items: Vec<Vec<u8>> =
+                    self.query(conn, redis::cmd("LRANGE").arg(key).arg(skip).arg(end)).await?;
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| serde_json::json!({ "index": skip + i, "value": bytes_to_string(value) }))
+                    .collect()
+            }
+            "set" => {
+                let items = self.scan(conn, "SSCAN", Some(key), pattern, Some(skip + limit)).await?;
+                items
+                    .iter()
+                    .skip(skip)
+                    .take(limit)
+                    .map(|member| serde_json::json!({ "member": bytes_to_string(member) }))
+                    .collect()
+            }
+            "zset" => {
+                // Without a pattern, ZRANGE pages in score order; with one we have to scan
+                let pairs: Vec<(Vec<u8>, f64)> = if pattern.is_some() {
+                    let items = self.scan(conn, "ZSCAN", Some(key), pattern, Some(2 * (skip + limit))).await?;
+                    items
+                        .chunks(2)
+                        .skip(skip)
+                        .take(limit)
+                        .map(|pair| {
+                            let score = pair
+                                .get(1)
+                                .and_then(|s| String::from_utf8_lossy(s).parse().ok())
+                                .unwrap_or_default();
+                            (pair[0].clone(), score)
+                        })
+                        .collect()
+                } else {
+                    self.query(
+                        conn,
+                        redis::cmd("ZRANGE").arg(key).arg(skip).arg(end).arg("WITHSCORES"),
+                    )
+                    .await?
+                };
+                pairs
+                    .iter()
+                    .map(|(member, score)| serde_json::json!({ "member": bytes_to_string(member), "score": score }))
+                    .collect()
+            }
+            "stream" => {
+                let entries: Vec<StreamEntry> = self
+                    .query(
+                        conn,
+                        redis::cmd("XRANGE").arg(key).arg("-").arg("+").arg("COUNT").arg(skip + limit),
+                    )
+                    .await?;
+                entries
+                    .into_iter()
+                    .skip(skip)
+                    .map(|(id, fields)| {
+                        let fields: serde_json::Map<String, serde_json::Value> = fields
+                            .iter()
+                            .map(|(f, v)| (bytes_to_string(f), serde_json::Value::String(bytes_to_string(v))))
+                            .collect();
+                        serde_json::json!({ "id": id, "fields": fields })
+                    })
+                    .collect()
+            }
+            "none" => Vec::new(),
+            other => {
+                return Err(ConnectionError::Failed(format!(
+                    "Browsing keys of type '{}' is not supported",
+                    other
+                )))
+            }
+        };
+
+        Ok(rows)
+    }
+}
+
+/// Parse a `dbN` database name into its index
+fn parse_db_index(name: &str) -> Result<i64> {
+    name.strip_prefix("db")
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| ConnectionError::Failed(format!("Invalid Redis database name: {}", name)))
+}
+
+/// Treat empty input and the MongoDB-style `{}` placeholder as "match everything"
+fn match_pattern(filter: Option<&str>) -> Option<&str> {
+    let filter = filter?.trim();
+    if filter.is_empty() || filter == "{}" || filter == "*" {
+        return None;
+    }
+    Some(filter)
+}
+
+/// If a MATCH pattern contains no glob characters, return the literal key it matches
+fn literal_pattern(pattern: &str) -> Option<String> {
+    let mut literal = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => literal.push(chars.next()?),
+            '*' | '?' | '[' => return None,
+            _ => literal.push(c),
+        }
+    }
+    Some(literal)
+}
+
+/// How many bytes the furthest-behind replica trails a primary, from the
+/// primary's `master_repl_offset` and its `slaveN:ip=...,offset=N,...` lines
+fn replica_offset_lag(fields: &HashMap<&str, &str>) -> Option<u64> {
+    let primary_offset: u64 = fields.get("master_repl_offset")?.parse().ok()?;
+    fields
+        .iter()
+        .filter(|(name, _)| {
+            name.strip_prefix("slave")
+                .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .filter_map(|(_, value)| {
+            value
+                .split(',')
+                .find_map(|pair| pair.strip_prefix("offset="))?
+                .parse::<u64>()
+                .ok()
+        })
+        .map(|offset| primary_offset.saturating_sub(offset))
+        .max()
+}
+
+fn bytes_to_string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// TTL in seconds, or null for keys without an expiry
+fn ttl_to_json(ttl: i64) -> serde_json::Value {
+    if ttl >= 0 {
+        serde_json::Value::from(ttl)
+    } else {
+        serde_json::Value::Null
+    }
+}
+
+/// Split a command line into arguments the way redis-cli does: whitespace separates
+/// arguments, double quotes support `\n`, `\t`, `\"` and `\xHH` escapes, single
+/// quotes only `\'`
+fn split_command_line(line: &str) -> Result<Vec<Vec<u8>>> {
+    let mut args = Vec::new();
+    let mut chars = line.trim().chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut arg = Vec::new();
+        let mut buf = [0u8; 4];
+        match c {
+            '"' => {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => arg.push(b'\n'),
+                            Some('r') => arg.push(b'\r'),
+                            Some('t') => arg.push(b'\t'),
+                            Some('b') => arg.push(0x08),
+                            Some('a') => arg.push(0x07),
+                            Some('x') => {
+                                let hex: String = chars.by_ref().take(2).collect();
+                                let byte = u8::from_str_radix(&hex, 16).map_err(|_| {
+                                    ConnectionError::Failed(format!("Invalid escape \\x{}", hex))
+                                })?;
+                                arg.push(byte);
+                            }
+                            Some(other) => arg.extend_from_slice(other.encode_utf8(&mut buf).as_bytes()),
+                            None => return Err(ConnectionError::Failed("Unbalanced quotes".into())),
+                        },
+                        Some(other) => arg.extend_from_slice(other.encode_utf8(&mut buf).as_bytes()),
+                        None => return Err(ConnectionError::Failed("Unbalanced quotes".into())),
+                    }
+                }
+            }
+            '\'' => {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            arg.push(b'\'');
+                        }
+                        Some(other) => arg.extend_from_slice(other.encode_utf8(&mut buf).as_bytes()),
+                        None => return Err(ConnectionError::Failed("Unbalanced quotes".into())),
+                    }
+                }
+            }
+            _ => {
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() {
+                        break;
+                    }
+                    arg.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                    chars.next();
+                }
+            }
+        }
+        args.push(arg);
+    }
+
+    Ok(args)
+}
+
+/// Convert a reply from the redis crate into the driver-neutral tree
+fn to_resp_value(value: redis::Value) -> RespValue {
+    match value {
+        redis::Value::Nil => RespValue::Nil,
+        redis::Value::Int(n) => RespValue::Integer(n),
+        redis::Value::BulkString(bytes) => RespValue::BulkString(bytes_to_string(&bytes)),
+        redis::Value::Array(items) => RespValue::Array(items.into_iter().map(to_resp_value).collect()),
+        redis::Value::SimpleString(s) => RespValue::Status(s),
+        redis::Value::Okay => RespValue::Status("OK".into()),
+        redis::Value::Map(entries) => RespValue::Map(
+            entries
+                .into_iter()
+                .map(|(k, v)| (to_resp_value(k), to_resp_value(v)))
+                .collect(),
+        ),
+        redis::Value::Attribute { data, .. } => to_resp_value(*data),
+        redis::Value::Set(items) => RespValue::Set(items.into_iter().map(to_resp_value).collect()),
+        redis::Value::Double(n) => RespValue::Double(n),
+        redis::Value::Boolean(b) => RespValue::Boolean(b),
+        redis::Value::VerbatimString { text, .. } => RespValue::BulkString(text),
+        redis::Value::BigNumber(n) => RespValue::BulkString(n.to_string()),
+        redis::Value::Push { data, .. } => RespValue::Array(data.into_iter().map(to_resp_value).collect()),
+        redis::Value::ServerError(e) => RespValue::Error(match e.details() {
+            Some(detail) => format!("{} {}", e.code(), detail),
+            None => e.code().to_string(),
+        }),
     }
 }
 
@@ -27,30 +520,14 @@ impl DatabaseConnection for RedisConnection {
     async fn test_connection(&self) -> Result<ConnectionInfo> {
         let start = Instant::now();
 
-        // Create client
-        let client = redis::Client::open(self.config.connection_string.as_str())
-            .map_err(|e| ConnectionError::InvalidConnectionString(e.to_string()))?;
-
         // Get async connection with timeout
-        let mut conn = tokio::time::timeout(
-            self.config.timeout,
-            client.get_multiplexed_async_connection(),
-        )
-        .await
-        .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
-        .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let mut conn = self.connect(None).await?;
 
         // Ping
-        let _: String = tokio::time::timeout(self.config.timeout, redis::cmd("PING").query_async(&mut conn))
-            .await
-            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
-            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let _: String = self.query(&mut conn, &redis::cmd("PING")).await?;
 
         // Get server info
-        let info: String = tokio::time::timeout(self.config.timeout, redis::cmd("INFO").arg("server").query_async(&mut conn))
-            .await
-            .map_err(|_| ConnectionError::Timeout(self.config.timeout))?
-            .map_err(|e| ConnectionError::Failed(e.to_string()))?;
+        let info: String = self.query(&mut conn, redis::cmd("INFO").arg("server")).await?;
 
         // Parse version from INFO output
         let version = info
@@ -65,4 +542,230 @@ impl DatabaseConnection for RedisConnection {
             latency_ms: latency,
         })
     }
+
+    async fn list_databases(&self) -> Result<Vec<DatabaseInfo>> {
+        let mut conn = self.connect(None).await?;
+
+        // Lines look like "db0:keys=12,expires=0,avg_ttl=0"; empty databases are not listed
+        let info: String = self.query(&mut conn, redis::cmd("INFO").arg("keyspace")).await?;
+        let mut indexes: Vec<i64> = info
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .filter_map(|(name, _)| parse_db_index(name).ok())
+            .collect();
+
+        // db0 always exists, even when empty
+        if !indexes.contains(&0) {
+            indexes.push(0);
+        }
+        indexes.sort_unstable();
+
+        let result = indexes
+            .into_iter()
+            .map(|index| DatabaseInfo {
+                name: format!("db{}", index),
+                size_bytes: None,
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    async fn list_collections(&self, database_name: &str) -> Result<Vec<CollectionInfo>> {
+        let mut conn = self.connect(Some(database_name)).await?;
+
+        let key_count: u64 = self.query(&mut conn, &redis::cmd("DBSIZE")).await?;
+
+        Ok(vec![CollectionInfo {
+            name: REDIS_KEYSPACE.to_string(),
+            document_count: Some(key_count),
+        }])
+    }
+
+    async fn query_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        limit: u32,
+        skip: u32,
+        filter: Option<&str>,
+        sort: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        let _ = sort; // Redis has no server-side ordering for these commands
+        let mut conn = self.connect(Some(database_name)).await?;
+        let pattern = match_pattern(filter);
+
+        if collection_name == REDIS_KEYSPACE {
+            self.query_keyspace(&mut conn, limit as usize, skip as usize, pattern)
+                .await
+        } else {
+            self.query_key(&mut conn, collection_name, limit as usize, skip as usize, pattern)
+                .await
+        }
+    }
+
+    async fn count_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        filter: Option<&str>,
+    ) -> Result<usize> {
+        let mut conn = self.connect(Some(database_name)).await?;
+        let pattern = match_pattern(filter);
+
+        if collection_name == REDIS_KEYSPACE {
+            return match pattern {
+                None => self.query(&mut conn, &redis::cmd("DBSIZE")).await,
+                Some(p) => match literal_pattern(p) {
+                    Some(key) => self.query(&mut conn, redis::cmd("EXISTS").arg(key)).await,
+                    None => self.count_matches(&mut conn, "SCAN", None, p, 1).await,
+                },
+            };
+        }
+
+        let key_type: String = self.query(&mut conn, redis::cmd("TYPE").arg(collection_name)).await?;
+        let key = collection_name;
+        match (key_type.as_str(), pattern) {
+            ("string", _) => Ok(1),
+            ("hash", None) => self.query(&mut conn, redis::cmd("HLEN").arg(key)).await,
+            ("hash", Some(p)) => self.count_matches(&mut conn, "HSCAN", Some(key), p, 2).await,
+            ("list", _) => self.query(&mut conn, redis::cmd("LLEN").arg(key)).await,
+            ("set", None) => self.query(&mut conn, redis::cmd("SCARD").arg(key)).await,
+            ("set", Some(p)) => self.count_matches(&mut conn, "SSCAN", Some(key), p, 1).await,
+            ("zset", None) => self.query(&mut conn, redis::cmd("ZCARD").arg(key)).await,
+            ("zset", Some(p)) => self.count_matches(&mut conn, "ZSCAN", Some(key), p, 2).await,
+            ("stream", _) => self.query(&mut conn, redis::cmd("XLEN").arg(key)).await,
+            _ => Ok(0),
+        }
+    }
+
+    async fn scan_keyspace(
+        &self,
+        database_name: &str,
+        filter: Option<&str>,
+        from: &KeyspaceCursor,
+        limit: u32,
+    ) -> Result<KeyspacePage> {
+        let mut conn = self.connect(Some(database_name)).await?;
+        let pattern = match_pattern(filter);
+        let limit = limit as usize;
+        let fresh = from.cursor == 0 && from.pending.is_empty() && !from.scanned;
+
+        // Exact key: look it up directly instead of scanning the whole keyspace
+        if pattern.and_then(literal_pattern).is_some() {
+            let rows = if fresh {
+                self.query_keyspace(&mut conn, limit, 0, pattern).await?
+            } else {
+                Vec::new()
+            };
+            return Ok(KeyspacePage { rows, next: None });
+        }
+
+        let mut position = from.clone();
+        while position.pending.len() < limit && !position.scanned {
+            let (next, batch) = self.scan_step(&mut conn, "SCAN", None, position.cursor, pattern).await?;
+            position.pending.extend(batch);
+            position.cursor = next;
+            position.scanned = next == 0;
+        }
+
+        let rest = position.pending.split_off(limit.min(position.pending.len()));
+        let mut keys = std::mem::replace(&mut position.pending, rest);
+        // SCAN may return a key twice while the keyspace is rehashed
+        let mut seen = std::collections::HashSet::new();
+        keys.retain(|k| seen.insert(k.clone()));
+
+        let rows = self.keyspace_rows(&mut conn, &keys).await?;
+        let next = (!position.scanned || !position.pending.is_empty()).then_some(position);
+        Ok(KeyspacePage { rows, next })
+    }
+
+    async fn execute_command(&self, database_name: &str, command: &str) -> Result<RespValue> {
+        let args = split_command_line(command)?;
+        let Some((name, rest)) = args.split_first() else {
+            return Err(ConnectionError::Failed("Empty command".into()));
+        };
+
+        // Console commands may change connection state (SELECT, CLIENT SETNAME) or
+        // block (BLPOP, MONITOR), so they never run on the shared connection
+        let mut conn = self
+            .open((!database_name.is_empty()).then_some(database_name))
+            .await?;
+
+        let mut cmd = redis::cmd(&bytes_to_string(name));
+        for arg in rest {
+            cmd.arg(arg.as_slice());
+        }
+
+        let reply: redis::RedisResult<redis::Value> =
+            tokio::time::timeout(self.config.timeout, cmd.query_async(&mut conn))
+                .await
+                .map_err(|_| ConnectionError::Timeout(self.config.timeout))?;
+
+        match reply {
+            Ok(value) => Ok(to_resp_value(value)),
+            // Error replies (ERR, WRONGTYPE, ...) are part of the result, not a failure
+            Err(e) if e.code().is_some() => Ok(RespValue::Error(match e.detail() {
+                Some(detail) => format!("{} {}", e.code().unwrap_or_default(), detail),
+                None => e.to_string(),
+            })),
+            Err(e) => Err(ConnectionError::Failed(e.to_string())),
+        }
+    }
+
+    async fn server_status(&self) -> Result<ServerStatus> {
+        let mut conn = self.connect(None).await?;
+
+        // Lines look like "connected_clients:3"; section headers start with '#'
+        let info: String = self.query(&mut conn, &redis::cmd("INFO")).await?;
+        let fields: HashMap<&str, &str> = info
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name, value.trim()))
+            .collect();
+        let number = |name: &str| fields.get(name).and_then(|value| value.parse::<u64>().ok());
+
+        let is_replica = fields.get("role") == Some(&"slave");
+
+        let mut properties = Vec::new();
+        if let Some(version) = fields.get("redis_version") {
+            properties.push(("Version".to_string(), format!("Redis {}", version)));
+        }
+        properties.push(("Role".to_string(), if is_replica { "Replica" } else { "Primary" }.to_string()));
+        // Redis tracks replication by byte offset, not time, so there is no lag in
+        // seconds to chart. A replica knows when it last heard from its primary
+        // (-1 while the link is down); a primary knows how far each replica trails.
+        if is_replica {
+            if let Some(seconds) = fields.get("master_last_io_seconds_ago").filter(|s| !s.starts_with('-')) {
+                properties.push(("Last I/O with primary".to_string(), format!("{} s ago", seconds)));
+            }
+        } else if let Some(lag) = replica_offset_lag(&fields) {
+            properties.push(("Replica offset lag".to_string(), format!("{} bytes", lag)));
+        }
+        for (name, label) in [
+            ("used_memory_peak_human", "Peak memory"),
+            ("maxmemory_human", "Max memory"),
+            ("maxmemory_policy", "Eviction policy"),
+            ("blocked_clients", "Blocked clients"),
+            ("expired_keys", "Expired keys"),
+            ("evicted_keys", "Evicted keys"),
+            ("connected_slaves", "Connected replicas"),
+        ] {
+            if let Some(value) = fields.get(name) {
+                properties.push((label.to_string(), value.to_string()));
+            }
+        }
+
+        Ok(ServerStatus {
+            operations: number("total_commands_processed"),
+            connections: number("connected_clients"),
+            cache_hits: number("keyspace_hits"),
+            cache_misses: number("keyspace_misses"),
+            memory_bytes: number("used_memory"),
+            replication_lag: None,
+            uptime: number("uptime_in_seconds"),
+            properties,
+        })
+    }
 }
@@ -0,0 +1,130 @@
+//! Helpers shared by the SQL drivers (PostgreSQL, MySQL, SQLite)
+
+use crate::db::driver::{IndexOrder, IndexSpec};
+use crate::db::error::{ConnectionError, Result};
+
+/// Treat the filter box contents as a raw `WHERE` clause.
+/// Empty input and the MongoDB-style `{}` placeholder mean "no filter".
+pub fn where_clause(filter: Option<&str>) -> Option<&str> {
+    let filter = filter?.trim();
+    if filter.is_empty() || filter == "{}" {
+        return None;
+    }
+    Some(filter)
+}
+
+/// Parse the `{"field": 1}` / `{"field": -1}` sort spec used by `CollectionView`
+/// into `(column, ascending)` pairs.
+pub fn parse_sort(sort: Option<&str>) -> Vec<(String, bool)> {
+    let Some(sort) = sort else {
+        return Vec::new();
+    };
+    match serde_json::from_str::<serde_json::Value>(sort) {
+        Ok(serde_json::Value::Object(map)) => map
+            .into_iter()
+            .map(|(field, dir)| (field, dir.as_i64() != Some(-1)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Build an `ORDER BY ...` clause (with leading space) using the given identifier quoting
+pub fn order_by_clause(sort: Option<&str>, quote: fn(&str) -> String) -> String {
+    let fields = parse_sort(sort);
+    if fields.is_empty() {
+        return String::new();
+    }
+    let parts: Vec<String> = fields
+        .iter()
+        .map(|(field, asc)| format!("{} {}", quote(field), if *asc { "ASC" } else { "DESC" }))
+        .collect();
+    format!(" ORDER BY {}", parts.join(", "))
+}
+
+/// Add the filter, sort and paging of a table view page to `SELECT ... FROM ...`
+pub fn page_query(
+    select: String,
+    filter: Option<&str>,
+    sort: Option<&str>,
+    limit: u32,
+    skip: u32,
+    quote: fn(&str) -> String,
+) -> String {
+    let mut query = select;
+    if let Some(clause) = where_clause(filter) {
+        query.push_str(&format!(" WHERE {}", clause));
+    }
+    query.push_str(&order_by_clause(sort, quote));
+    query.push_str(&format!(" LIMIT {} OFFSET {}", limit, skip));
+    query
+}
+
+/// The single statement of console text, without trailing semicolons, ready
+/// to be prefixed with `EXPLAIN`
+pub fn explain_statement(query: &str) -> &str {
+    query.trim().trim_end_matches(|c: char| c == ';' || c.is_whitespace())
+}
+
+/// Name for an index created without one: `<table>_<columns>_idx`
+pub fn default_index_name(table: &str, spec: &IndexSpec) -> String {
+    let mut parts = vec![table.to_string()];
+    parts.extend(spec.keys.iter().map(|(column, _)| {
+        column
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect::<String>()
+    }));
+    format!("{}_idx", parts.join("_"))
+}
+
+/// `CREATE [UNIQUE] INDEX <index> ON <relation> (...) [WHERE ...]`, with the
+/// index and relation names already quoted
+pub fn create_index_statement(
+    spec: &IndexSpec,
+    index: &str,
+    relation: &str,
+    quote: fn(&str) -> String,
+) -> Result<String> {
+    if spec.keys.is_empty() {
+        return Err(ConnectionError::Failed("An index needs at least one column".into()));
+    }
+    let mut columns = Vec::with_capacity(spec.keys.len());
+    for (column, order) in &spec.keys {
+        let direction = match order {
+            IndexOrder::Ascending => "ASC",
+            IndexOrder::Descending => "DESC",
+            IndexOrder::Special(kind) => {
+                return Err(ConnectionError::Failed(format!(
+                    "Index type {} is only supported by MongoDB",
+                    kind
+                )))
+            }
+        };
+        columns.push(format!("{} {}", quote(column), direction));
+    }
+
+    let mut statement = format!(
+        "CREATE {}INDEX {} ON {} ({})",
+        if spec.unique { "UNIQUE " } else { "" },
+        index,
+        relation,
+        columns.join(", ")
+    );
+    if let Some(predicate) = where_clause(spec.partial_filter.as_deref()) {
+        statement.push_str(&format!(" WHERE {}", predicate));
+    }
+    Ok(statement)
+}
+
+/// Quote an identifier with double quotes (PostgreSQL, SQLite)
+pub fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Split a `schema.table` collection name into its parts
+pub fn split_qualified(name: &str) -> (Option<&str>, &str) {
+    match name.split_once('.') {
+        Some((schema, table)) => (Some(schema), table),
+        None => (None, name),
+    }
+}
@@ -1,32 +1,273 @@
 //! SQLite driver implementation
 
 use async_trait::async_trait;
+use base64::Engine;
+use rusqlite::types::ValueRef;
 use std::path::Path;
 use std::time::Instant;
 
-use crate::db::driver::{ConnectionConfig, ConnectionInfo, DatabaseConnection};
+use super::sql::{
+    create_index_statement, default_index_name, explain_statement, page_query, quote_ident,
+    where_clause,
+};
+use crate::db::driver::{
+    BatchInsertResult, CheckConstraint, CollectionInfo, ColumnDefinition, ColumnInfo,
+    ConnectionConfig, ConnectionInfo, DatabaseConnection, DatabaseInfo, ForeignKey, IndexInfo,
+    IndexOrder, IndexSpec, KeyConstraint, QueryResult, StorageStats, TableStructure, TriggerInfo,
+};
 use crate::db::error::{ConnectionError, Result};
+use crate::db::explain::{self, QueryPlan};
 
+/// SQLite connection. The connection string is a file path (or `:memory:`), optionally
+/// followed by databases to attach: `/data/app.db?attach=/data/archive.db&attach=logs.db`.
+/// Attached databases are named after their file stem.
 pub struct SqliteConnection {
     config: ConnectionConfig,
+    path: String,
+    attachments: Vec<(String, String)>,
 }
 
 impl SqliteConnection {
     pub fn new(config: ConnectionConfig) -> Result<Self> {
+        let (path, attachments) = parse_connection_string(&config.connection_string);
+
         // For SQLite, connection string is just a file path or :memory:
-        let path = &config.connection_string;
-        if path != ":memory:" && !path.is_empty() {
-            // Check if parent directory exists for file paths
-            if let Some(parent) = Path::new(path).parent() {
-                if !parent.as_os_str().is_empty() && !parent.exists() {
-                    return Err(ConnectionError::InvalidConnectionString(format!(
-                        "Parent directory does not exist: {}",
-                        parent.display()
-                    )));
+        for file in
+            std::iter::once(path.as_str()).chain(attachments.iter().map(|(_, f)| f.as_str()))
+        {
+            if file != ":memory:" && !file.is_empty() {
+                // Check if parent directory exists for file paths
+                if let Some(parent) = Path::new(file).parent() {
+                    if !parent.as_os_str().is_empty() && !parent.exists() {
+                        return Err(ConnectionError::InvalidConnectionString(format!(
+                            "Parent directory does not exist: {}",
+                            parent.display()
+                        )));
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            config,
+            path,
+            attachments,
+        })
+    }
+
+    /// Open the database (attaching any extra files) and run `f` on a blocking thread
+    async fn with_connection<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&rusqlite::Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let path = self.path.clone();
+        let attachments = self.attachments.clone();
+        let busy_timeout = self.config.timeout;
+
+        // SQLite is synchronous, so we run it in a blocking task
+        tokio::task::spawn_blocking(move || {
+            let conn = rusqlite::Connection::open(&path)?;
+            conn.busy_timeout(busy_timeout)?;
+            for (alias, file) in &attachments {
+                conn.execute("ATTACH DATABASE ?1 AS ?2", [file, alias])?;
+            }
+            f(&conn)
+        })
+        .await
+        .map_err(|e| ConnectionError::Failed(e.to_string()))?
+        .map_err(|e| ConnectionError::Failed(e.to_string()))
+    }
+}
+
+/// The `WHERE` predicate at the end of a partial index's `CREATE INDEX` statement
+fn partial_predicate(sql: &str) -> Option<String> {
+    let at = sql.to_ascii_uppercase().rfind(" WHERE ")?;
+    Some(sql[at + " WHERE ".len()..].trim().to_string())
+}
+
+/// Piece of SQL text, as far as CHECK constraints and triggers need to tell them apart
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    /// Quoted identifier or string literal, without its quotes
+    Quoted(String),
+    Open,
+    Close,
+    Other,
+}
+
+/// Split SQL into tokens with their byte ranges, skipping whitespace and comments
+fn tokenize(sql: &str) -> Vec<(usize, usize, Token)> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '-' if chars.peek().map(|(_, c)| *c) == Some('-') => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+                continue;
+            }
+            '/' if chars.peek().map(|(_, c)| *c) == Some('*') => {
+                chars.next();
+                let mut previous = ' ';
+                for (_, c) in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
                 }
+                continue;
             }
+            '\'' | '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                let mut text = String::new();
+                while let Some((_, c)) = chars.next() {
+                    if c == close {
+                        // A doubled quote stands for itself
+                        if close != ']' && chars.next_if(|(_, next)| *next == close).is_some() {
+                            text.push(c);
+                            continue;
+                        }
+                        break;
+                    }
+                    text.push(c);
+                }
+                Token::Quoted(text)
+            }
+            '(' => Token::Open,
+            ')' => Token::Close,
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some((_, c)) =
+                    chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_' || *c == '$')
+                {
+                    word.push(c);
+                }
+                Token::Word(word.to_ascii_uppercase())
+            }
+            _ => Token::Other,
+        };
+        let end = chars.peek().map_or(sql.len(), |(at, _)| *at);
+        tokens.push((start, end, token));
+    }
+    tokens
+}
+
+/// CHECK constraints of a `CREATE TABLE` statement, on columns or on the table
+fn check_constraints(sql: &str) -> Vec<CheckConstraint> {
+    let tokens = tokenize(sql);
+    let mut checks = Vec::new();
+    for (at, (_, _, token)) in tokens.iter().enumerate() {
+        if *token != Token::Word("CHECK".into()) {
+            continue;
+        }
+        let Some((open, _, Token::Open)) = tokens.get(at + 1) else {
+            continue;
+        };
+        let mut depth = 0;
+        let close = tokens[at + 1..].iter().find_map(|(_, end, token)| {
+            match token {
+                Token::Open => depth += 1,
+                Token::Close => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(*end)
+        });
+        let Some(close) = close else {
+            continue;
+        };
+        let name = match at.checked_sub(2).map(|before| &tokens[before..at]) {
+            Some([(_, _, Token::Word(keyword)), (start, end, name)]) if keyword == "CONSTRAINT" => {
+                match name {
+                    Token::Quoted(name) => Some(name.clone()),
+                    // Words were upper-cased; take the name as written
+                    Token::Word(_) => Some(sql[*start..*end].to_string()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        checks.push(CheckConstraint {
+            name,
+            expression: sql[*open..close].to_string(),
+        });
+    }
+    checks
+}
+
+/// Timing and events of a `CREATE TRIGGER` statement
+fn trigger_kind(sql: &str) -> (String, String) {
+    let tokens = tokenize(sql);
+    let words: Vec<&str> = tokens
+        .iter()
+        .map(|(_, _, token)| match token {
+            Token::Word(word) => word.as_str(),
+            _ => "",
+        })
+        .collect();
+    // Skip past the trigger's name, which may itself read like a keyword
+    let mut at = words
+        .iter()
+        .position(|word| *word == "TRIGGER")
+        .map_or(0, |at| at + 1);
+    if words.get(at..at + 3) == Some(&["IF", "NOT", "EXISTS"][..]) {
+        at += 3;
+    }
+    at += 1;
+    if tokens.get(at).map(|(_, _, token)| token) == Some(&Token::Other) {
+        at += 2;
+    }
+
+    let rest = words.get(at..).unwrap_or_default();
+    let timing = match rest.first() {
+        Some(&"AFTER") => "AFTER",
+        Some(&"INSTEAD") => "INSTEAD OF",
+        // BEFORE is also the default
+        _ => "BEFORE",
+    };
+    let event = rest
+        .iter()
+        .find(|word| matches!(**word, "INSERT" | "UPDATE" | "DELETE"))
+        .copied()
+        .unwrap_or_default();
+    (timing.to_string(), event.to_string())
+}
+
+/// Split a connection string into the main database path and `(alias, path)` attachments
+fn parse_connection_string(conn_str: &str) -> (String, Vec<(String, String)>) {
+    let Some((path, query)) = conn_str.split_once('?') else {
+        return (conn_str.to_string(), Vec::new());
+    };
+
+    let attachments = query
+        .split('&')
+        .filter_map(|pair| pair.strip_prefix("attach="))
+        .filter(|file| !file.is_empty())
+        .map(|file| {
+            let alias = Path::new(file)
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| file.to_string());
+            (alias, file.to_string())
+        })
+        .collect();
+
+    (path.to_string(), attachments)
+}
+
+/// Map a SQLite value to JSON by its storage class
+fn value_to_json(value: ValueRef<'_>) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => serde_json::Value::from(i),
+        ValueRef::Real(f) => serde_json::Value::from(f),
+        ValueRef::Text(text) => {
+            serde_json::Value::String(String::from_utf8_lossy(text).into_owned())
+        }
+        ValueRef::Blob(bytes) => {
+            serde_json::Value::String(base64::engine::general_purpose::STANDARD.encode(bytes))
         }
-        Ok(Self { config })
     }
 }
 
@@ -34,22 +275,13 @@ impl SqliteConnection {
 impl DatabaseConnection for SqliteConnection {
     async fn test_connection(&self) -> Result<ConnectionInfo> {
         let start = Instant::now();
-        let path = self.config.connection_string.clone();
 
-        // SQLite is synchronous, so we run it in a blocking task
-        let result = tokio::task::spawn_blocking(move || {
-            let conn = rusqlite::Connection::open(&path)
-                .map_err(|e| ConnectionError::Failed(e.to_string()))?;
-
-            // Get SQLite version
-            let version: String = conn
-                .query_row("SELECT sqlite_version()", [], |row| row.get(0))
-                .map_err(|e| ConnectionError::Failed(e.to_string()))?;
-
-            Ok::<_, ConnectionError>(version)
-        })
-        .await
-        .map_err(|e| ConnectionError::Failed(e.to_string()))??;
+        // Get SQLite version
+        let result = self
+            .with_connection(|conn| {
+                conn.query_row("SELECT sqlite_version()", [], |row| row.get::<_, String>(0))
+            })
+            .await?;
 
         let latency = start.elapsed().as_millis() as u64;
 
@@ -58,4 +290,749 @@ impl DatabaseConnection for SqliteConnection {
             latency_ms: latency,
         })
     }
+
+    async fn list_databases(&self) -> Result<Vec<DatabaseInfo>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare("PRAGMA database_list")?;
+            let names: Vec<String> = stmt
+                .query_map([], |row| row.get(1))?
+                .collect::<rusqlite::Result<_>>()?;
+
+            let mut databases = Vec::new();
+            for name in names {
+                let schema = quote_ident(&name);
+                let page_count: i64 =
+                    conn.query_row(&format!("PRAGMA {}.page_count", schema), [], |row| {
+                        row.get(0)
+                    })?;
+                let page_size: i64 =
+                    conn.query_row(&format!("PRAGMA {}.page_size", schema), [], |row| {
+                        row.get(0)
+                    })?;
+                databases.push(DatabaseInfo {
+                    name,
+                    size_bytes: Some((page_count * page_size) as u64),
+                });
+            }
+            Ok(databases)
+        })
+        .await
+    }
+
+    async fn list_collections(&self, database_name: &str) -> Result<Vec<CollectionInfo>> {
+        let query = format!(
+            "SELECT name FROM {}.sqlite_schema \
+             WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' \
+             ORDER BY name",
+            quote_ident(database_name)
+        );
+
+        self.with_connection(move |conn| {
+            let mut stmt = conn.prepare(&query)?;
+            let collections = stmt
+                .query_map([], |row| {
+                    Ok(CollectionInfo {
+                        name: row.get(0)?,
+                        document_count: None, // SQLite keeps no row estimates
+                    })
+                })?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(collections)
+        })
+        .await
+    }
+
+    async fn query_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        limit: u32,
+        skip: u32,
+        filter: Option<&str>,
+        sort: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        let query = page_query(
+            format!(
+                "SELECT * FROM {}.{}",
+                quote_ident(database_name),
+                quote_ident(collection_name)
+            ),
+            filter,
+            sort,
+            limit,
+            skip,
+            quote_ident,
+        );
+
+        self.with_connection(move |conn| {
+            let mut stmt = conn.prepare(&query)?;
+            let column_names: Vec<String> =
+                stmt.column_names().into_iter().map(String::from).collect();
+
+            let mut rows = stmt.query([])?;
+            let mut documents = Vec::new();
+            while let Some(row) = rows.next()? {
+                let mut map = serde_json::Map::new();
+                for (i, name) in column_names.iter().enumerate() {
+                    map.insert(name.clone(), value_to_json(row.get_ref(i)?));
+                }
+                documents.push(serde_json::Value::Object(map));
+            }
+            Ok(documents)
+        })
+        .await
+    }
+
+    async fn count_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        filter: Option<&str>,
+    ) -> Result<usize> {
+        let mut query = format!(
+            "SELECT count(*) FROM {}.{}",
+            quote_ident(database_name),
+            quote_ident(collection_name)
+        );
+        if let Some(clause) = where_clause(filter) {
+            query.push_str(&format!(" WHERE {}", clause));
+        }
+
+        let count: i64 = self
+            .with_connection(move |conn| conn.query_row(&query, [], |row| row.get(0)))
+            .await?;
+
+        Ok(count as usize)
+    }
+
+    async fn table_columns(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<Vec<ColumnInfo>> {
+        let database_name = database_name.to_string();
+        let collection_name = collection_name.to_string();
+
+        self.with_connection(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT name, \"notnull\", pk FROM pragma_table_info(?1, ?2) ORDER BY cid",
+            )?;
+            let columns = stmt
+                .query_map([&collection_name, &database_name], |row| {
+                    Ok(ColumnInfo {
+                        name: row.get(0)?,
+                        nullable: !row.get::<_, bool>(1)?,
+                        // Position within the primary key, 0 for other columns
+                        primary_key: row.get::<_, i64>(2)? > 0,
+                    })
+                })?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(columns)
+        })
+        .await
+    }
+
+    async fn execute_transaction(
+        &self,
+        database_name: &str,
+        statements: &[String],
+    ) -> Result<Vec<u64>> {
+        let _ = database_name;
+        let statements = statements.to_vec();
+
+        self.with_connection(move |conn| {
+            // Dropping the transaction without committing rolls it back
+            let transaction = conn.unchecked_transaction()?;
+            let affected = statements
+                .iter()
+                .map(|statement| transaction.execute(statement, []).map(|n| n as u64))
+                .collect::<rusqlite::Result<_>>()?;
+            transaction.commit()?;
+            Ok(affected)
+        })
+        .await
+    }
+
+    async fn execute_each(
+        &self,
+        database_name: &str,
+        statements: &[String],
+        stop_on_error: bool,
+    ) -> Result<BatchInsertResult> {
+        let _ = database_name;
+        let statements = statements.to_vec();
+
+        self.with_connection(move |conn| {
+            let mut transaction = conn.unchecked_transaction()?;
+            let mut result = BatchInsertResult::default();
+            for (index, statement) in statements.iter().enumerate() {
+                // Dropping the savepoint without committing rolls it back
+                let savepoint = transaction.savepoint()?;
+                match savepoint.execute(statement, []) {
+                    Ok(_) => {
+                        savepoint.commit()?;
+                        result.inserted += 1;
+                    }
+                    Err(e) => {
+                        result.rejected.push((index, e.to_string()));
+                        drop(savepoint);
+                        if stop_on_error {
+                            break;
+                        }
+                    }
+                }
+            }
+            transaction.commit()?;
+            Ok(result)
+        })
+        .await
+    }
+
+    async fn execute_query(&self, database_name: &str, query: &str) -> Result<QueryResult> {
+        // Every attached database is reachable through its schema name, so the
+        // selected database only matters for unqualified names in `main`
+        let _ = database_name;
+        let query = query.to_string();
+
+        self.with_connection(move |conn| {
+            let start = Instant::now();
+            let mut result = QueryResult::default();
+
+            let mut batch = rusqlite::Batch::new(conn, &query);
+            while let Some(mut stmt) = batch.next()? {
+                let mut current = QueryResult::default();
+                if stmt.column_count() > 0 {
+                    current.columns = stmt.column_names().into_iter().map(String::from).collect();
+                    let column_count = current.columns.len();
+                    let mut rows = stmt.query([])?;
+                    while let Some(row) = rows.next()? {
+                        let values = (0..column_count)
+                            .map(|i| row.get_ref(i).map(value_to_json))
+                            .collect::<rusqlite::Result<_>>()?;
+                        current.rows.push(values);
+                    }
+                } else {
+                    current.affected_rows = Some(stmt.execute([])? as u64);
+                }
+                result = current;
+            }
+
+            result.elapsed = start.elapsed();
+            Ok(result)
+        })
+        .await
+    }
+
+    async fn explain_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        limit: u32,
+        skip: u32,
+        filter: Option<&str>,
+        sort: Option<&str>,
+    ) -> Result<QueryPlan> {
+        let query = page_query(
+            format!(
+                "SELECT * FROM {}.{}",
+                quote_ident(database_name),
+                quote_ident(collection_name)
+            ),
+            filter,
+            sort,
+            limit,
+            skip,
+            quote_ident,
+        );
+        self.explain_query(database_name, &query).await
+    }
+
+    async fn explain_query(&self, database_name: &str, query: &str) -> Result<QueryPlan> {
+        let _ = database_name;
+        let query = format!("EXPLAIN QUERY PLAN {}", explain_statement(query));
+
+        let rows = self
+            .with_connection(move |conn| {
+                let mut stmt = conn.prepare(&query)?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(3)?)))?
+                    .collect::<rusqlite::Result<_>>()?;
+                Ok(rows)
+            })
+            .await?;
+
+        Ok(explain::from_sqlite(rows))
+    }
+
+    async fn list_indexes(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<Vec<IndexInfo>> {
+        let database_name = database_name.to_string();
+        let collection_name = collection_name.to_string();
+        let schema_query = format!(
+            "SELECT sql FROM {}.sqlite_schema WHERE type = 'index' AND name = ?1",
+            quote_ident(&database_name)
+        );
+
+        self.with_connection(move |conn| {
+            let mut list = conn.prepare(
+                "SELECT name, \"unique\", origin, partial FROM pragma_index_list(?1, ?2) ORDER BY name",
+            )?;
+            let listed: Vec<(String, bool, String, bool)> = list
+                .query_map([&collection_name, &database_name], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                })?
+                .collect::<rusqlite::Result<_>>()?;
+
+            let mut columns = conn.prepare(
+                "SELECT name, \"desc\" FROM pragma_index_xinfo(?1, ?2) WHERE key ORDER BY seqno",
+            )?;
+            // dbstat is an optional SQLite extension; without it sizes are unknown
+            let mut size = conn
+                .prepare("SELECT sum(pgsize) FROM dbstat(?1) WHERE name = ?2")
+                .ok();
+
+            let mut indexes = Vec::with_capacity(listed.len());
+            for (name, unique, origin, partial) in listed {
+                let keys = columns
+                    .query_map([&name, &database_name], |row| {
+                        let column: Option<String> = row.get(0)?;
+                        let order = if row.get(1)? {
+                            IndexOrder::Descending
+                        } else {
+                            IndexOrder::Ascending
+                        };
+                        // Expression key parts have no column name
+                        Ok((column.unwrap_or_else(|| "(expression)".to_string()), order))
+                    })?
+                    .collect::<rusqlite::Result<_>>()?;
+                let partial_filter = if partial {
+                    conn.query_row(&schema_query, [&name], |row| row.get::<_, Option<String>>(0))?
+                        .as_deref()
+                        .and_then(partial_predicate)
+                } else {
+                    None
+                };
+                let size_bytes = size.as_mut().and_then(|stmt| {
+                    stmt.query_row([&database_name, &name], |row| row.get::<_, Option<i64>>(0))
+                        .ok()
+                        .flatten()
+                        .map(|bytes| bytes as u64)
+                });
+
+                indexes.push(IndexInfo {
+                    // "pk" is the index behind a PRIMARY KEY that is not the rowid
+                    primary: origin == "pk",
+                    name,
+                    keys,
+                    method: None,
+                    unique,
+                    partial_filter,
+                    expire_after_seconds: None,
+                    size_bytes,
+                    usage_count: None,
+                });
+            }
+            Ok(indexes)
+        })
+        .await
+    }
+
+    async fn create_index(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        spec: &IndexSpec,
+    ) -> Result<()> {
+        // SQLite names the schema on the index, and the table must be in that schema
+        let name = spec
+            .name
+            .clone()
+            .unwrap_or_else(|| default_index_name(collection_name, spec));
+        let statement = create_index_statement(
+            spec,
+            &format!("{}.{}", quote_ident(database_name), quote_ident(&name)),
+            &quote_ident(collection_name),
+            quote_ident,
+        )?;
+
+        self.with_connection(move |conn| conn.execute_batch(&statement))
+            .await
+    }
+
+    async fn drop_index(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        index_name: &str,
+    ) -> Result<()> {
+        let _ = collection_name;
+        let statement = format!(
+            "DROP INDEX {}.{}",
+            quote_ident(database_name),
+            quote_ident(index_name)
+        );
+
+        self.with_connection(move |conn| conn.execute_batch(&statement))
+            .await
+    }
+
+    async fn table_structure(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<TableStructure> {
+        let database_name = database_name.to_string();
+        let collection_name = collection_name.to_string();
+        let schema = quote_ident(&database_name);
+
+        self.with_connection(move |conn| {
+            // hidden is 2 for virtual and 3 for stored generated columns
+            let mut columns = conn.prepare(
+                "SELECT name, type, \"notnull\", dflt_value, pk, hidden \
+                 FROM pragma_table_xinfo(?1, ?2) ORDER BY cid",
+            )?;
+            let mut key_columns: Vec<(i64, String)> = Vec::new();
+            let columns = columns
+                .query_map([&collection_name, &database_name], |row| {
+                    Ok((
+                        ColumnDefinition {
+                            name: row.get(0)?,
+                            data_type: row.get(1)?,
+                            nullable: !row.get::<_, bool>(2)?,
+                            default: row.get(3)?,
+                            generated: match row.get::<_, i64>(5)? {
+                                2 => Some("VIRTUAL GENERATED".to_string()),
+                                3 => Some("STORED GENERATED".to_string()),
+                                _ => None,
+                            },
+                            collation: None,
+                            comment: None,
+                        },
+                        row.get::<_, i64>(4)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+                .into_iter()
+                .map(|(column, key_position)| {
+                    if key_position > 0 {
+                        key_columns.push((key_position, column.name.clone()));
+                    }
+                    column
+                })
+                .collect();
+            key_columns.sort();
+
+            // Unique constraints are backed by automatic indexes
+            let mut unique_indexes = conn.prepare(
+                "SELECT name FROM pragma_index_list(?1, ?2) WHERE origin = 'u' ORDER BY seq DESC",
+            )?;
+            let unique_indexes: Vec<String> = unique_indexes
+                .query_map([&collection_name, &database_name], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            let mut index_columns =
+                conn.prepare("SELECT name FROM pragma_index_info(?1, ?2) ORDER BY seqno")?;
+            let mut unique_constraints = Vec::with_capacity(unique_indexes.len());
+            for index in &unique_indexes {
+                unique_constraints.push(KeyConstraint {
+                    name: None,
+                    columns: index_columns
+                        .query_map([index, &database_name], |row| row.get(0))?
+                        .collect::<rusqlite::Result<_>>()?,
+                });
+            }
+
+            let mut foreign_keys: Vec<ForeignKey> = Vec::new();
+            let mut references = conn.prepare(
+                "SELECT id, \"table\", \"from\", \"to\", on_update, on_delete \
+                 FROM pragma_foreign_key_list(?1, ?2) ORDER BY id, seq",
+            )?;
+            let mut rows = references.query([&collection_name, &database_name])?;
+            let mut last_id = None;
+            while let Some(row) = rows.next()? {
+                let id: i64 = row.get(0)?;
+                let column: String = row.get(2)?;
+                let referenced: Option<String> = row.get(3)?;
+                match foreign_keys.last_mut() {
+                    Some(key) if last_id == Some(id) => {
+                        key.columns.push(column);
+                        key.referenced_columns.extend(referenced);
+                    }
+                    _ => {
+                        let action = |rule: String| (rule != "NO ACTION").then_some(rule);
+                        foreign_keys.push(ForeignKey {
+                            name: None,
+                            columns: vec![column],
+                            referenced_table: row.get(1)?,
+                            referenced_columns: referenced.into_iter().collect(),
+                            on_update: action(row.get(4)?),
+                            on_delete: action(row.get(5)?),
+                        });
+                    }
+                }
+                last_id = Some(id);
+            }
+            drop(rows);
+
+            // SQLite keeps CHECK constraints and trigger details only in the SQL text
+            let table_sql: Option<String> = conn
+                .query_row(
+                    &format!(
+                        "SELECT sql FROM {}.sqlite_schema WHERE type = 'table' AND name = ?1",
+                        schema
+                    ),
+                    [&collection_name],
+                    |row| row.get(0),
+                )
+                .unwrap_or_default();
+            let mut triggers = conn.prepare(&format!(
+                "SELECT name, sql FROM {}.sqlite_schema \
+                 WHERE type = 'trigger' AND tbl_name = ?1 ORDER BY name",
+                schema
+            ))?;
+            let triggers = triggers
+                .query_map([&collection_name], |row| {
+                    let definition: String = row.get(1)?;
+                    let (timing, events) = trigger_kind(&definition);
+                    Ok(TriggerInfo {
+                        name: row.get(0)?,
+                        timing,
+                        events,
+                        definition,
+                    })
+                })?
+                .collect::<rusqlite::Result<_>>()?;
+
+            Ok(TableStructure {
+                columns,
+                primary_key: (!key_columns.is_empty()).then(|| KeyConstraint {
+                    name: None,
+                    columns: key_columns.into_iter().map(|(_, name)| name).collect(),
+                }),
+                unique_constraints,
+                check_constraints: table_sql
+                    .as_deref()
+                    .map(check_constraints)
+                    .unwrap_or_default(),
+                foreign_keys,
+                triggers,
+            })
+        })
+        .await
+    }
+
+    async fn table_ddl(&self, database_name: &str, collection_name: &str) -> Result<String> {
+        let collection_name = collection_name.to_string();
+        // Automatic indexes have no SQL of their own
+        let query = format!(
+            "SELECT sql FROM {}.sqlite_schema WHERE tbl_name = ?1 AND sql IS NOT NULL \
+             ORDER BY CASE type WHEN 'table' THEN 0 WHEN 'index' THEN 1 ELSE 2 END, name",
+            quote_ident(database_name)
+        );
+
+        self.with_connection(move |conn| {
+            let mut stmt = conn.prepare(&query)?;
+            let statements: Vec<String> = stmt
+                .query_map([&collection_name], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(statements
+                .iter()
+                .map(|statement| format!("{};\n", statement))
+                .collect::<Vec<_>>()
+                .join("\n"))
+        })
+        .await
+    }
+
+    async fn database_stats(&self, database_name: &str) -> Result<StorageStats> {
+        let database_name = database_name.to_string();
+        let schema = quote_ident(&database_name);
+
+        self.with_connection(move |conn| {
+            let pragma = |name: &str| -> rusqlite::Result<i64> {
+                conn.query_row(&format!("PRAGMA {}.{}", schema, name), [], |row| row.get(0))
+            };
+            let page_size = pragma("page_size")?;
+            let page_count = pragma("page_count")?;
+            let free_pages = pragma("freelist_count")?;
+            let auto_vacuum = pragma("auto_vacuum")?;
+            let user_version = pragma("user_version")?;
+            let journal_mode: String = conn.query_row(
+                &format!("PRAGMA {}.journal_mode", schema),
+                [],
+                |row| row.get(0),
+            )?;
+            let encoding: String = conn.query_row("PRAGMA encoding", [], |row| row.get(0))?;
+            let (tables, indexes): (i64, i64) = conn.query_row(
+                &format!(
+                    "SELECT count(*) FILTER (WHERE type = 'table'), count(*) FILTER (WHERE type = 'index') \
+                     FROM {}.sqlite_schema WHERE name NOT LIKE 'sqlite_%'",
+                    schema
+                ),
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            let sizes = page_sizes(conn, &schema, &database_name, None);
+
+            let mut stats = StorageStats {
+                collection_count: Some(tables as u64),
+                free_size: Some((free_pages * page_size) as u64),
+                index_count: Some(indexes as u64),
+                total_size: Some((page_count * page_size) as u64),
+                properties: vec![
+                    ("Page size".to_string(), page_size.to_string()),
+                    ("Encoding".to_string(), encoding),
+                    ("Journal mode".to_string(), journal_mode),
+                    (
+                        "Auto-vacuum".to_string(),
+                        match auto_vacuum {
+                            1 => "full",
+                            2 => "incremental",
+                            _ => "none",
+                        }
+                        .to_string(),
+                    ),
+                    ("User version".to_string(), user_version.to_string()),
+                ],
+                ..Default::default()
+            };
+            if let Some(sizes) = sizes {
+                stats.data_size = Some(sizes.payload);
+                stats.storage_size = Some(sizes.table_pages);
+                stats.index_size = Some(sizes.index_pages);
+            }
+            Ok(stats)
+        })
+        .await
+    }
+
+    async fn collection_stats(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<StorageStats> {
+        let database_name = database_name.to_string();
+        let collection_name = collection_name.to_string();
+        let schema = quote_ident(&database_name);
+
+        self.with_connection(move |conn| {
+            let kind: String = conn.query_row(
+                &format!("SELECT type FROM {}.sqlite_schema WHERE name = ?1", schema),
+                [&collection_name],
+                |row| row.get(0),
+            )?;
+            let mut stats = StorageStats {
+                properties: vec![("Type".to_string(), kind.clone())],
+                ..Default::default()
+            };
+            // STRICT and WITHOUT ROWID are reported since SQLite 3.37
+            if let Ok((without_rowid, strict)) = conn.query_row(
+                "SELECT wr, strict FROM pragma_table_list(?1, ?2)",
+                [&collection_name, &database_name],
+                |row| Ok((row.get::<_, bool>(0)?, row.get::<_, bool>(1)?)),
+            ) {
+                if without_rowid {
+                    stats
+                        .properties
+                        .push(("WITHOUT ROWID".to_string(), "yes".to_string()));
+                }
+                if strict {
+                    stats
+                        .properties
+                        .push(("STRICT".to_string(), "yes".to_string()));
+                }
+            }
+            if kind == "view" {
+                return Ok(stats);
+            }
+
+            // SQLite keeps no row estimate, so rows are counted
+            let rows: i64 = conn.query_row(
+                &format!(
+                    "SELECT count(*) FROM {}.{}",
+                    schema,
+                    quote_ident(&collection_name)
+                ),
+                [],
+                |row| row.get(0),
+            )?;
+            let indexes: i64 = conn.query_row(
+                "SELECT count(*) FROM pragma_index_list(?1, ?2)",
+                [&collection_name, &database_name],
+                |row| row.get(0),
+            )?;
+            stats.document_count = Some(rows as u64);
+            stats.index_count = Some(indexes as u64);
+
+            if let Some(sizes) = page_sizes(conn, &schema, &database_name, Some(&collection_name)) {
+                stats.data_size = Some(sizes.payload);
+                stats.storage_size = Some(sizes.table_pages);
+                stats.index_size = Some(sizes.index_pages);
+                stats.total_size = Some(sizes.table_pages + sizes.index_pages);
+                stats.avg_object_size = (rows > 0).then(|| sizes.payload / rows as u64);
+                stats.index_sizes = sizes.index_sizes;
+            }
+            Ok(stats)
+        })
+        .await
+    }
+}
+
+/// Space taken by tables and indexes, from the `dbstat` virtual table
+struct PageSizes {
+    /// Bytes of row data stored in the table pages
+    payload: u64,
+    table_pages: u64,
+    index_pages: u64,
+    index_sizes: Vec<(String, u64)>,
+}
+
+/// Page sizes of one table and its indexes, or of the whole database. None when
+/// SQLite was built without `dbstat`.
+fn page_sizes(
+    conn: &rusqlite::Connection,
+    schema: &str,
+    database_name: &str,
+    table: Option<&str>,
+) -> Option<PageSizes> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT s.type, d.name, sum(d.pgsize), sum(d.payload) \
+             FROM dbstat(?1) d JOIN {}.sqlite_schema s ON s.name = d.name \
+             WHERE ?2 IS NULL OR s.tbl_name = ?2 \
+             GROUP BY s.type, d.name ORDER BY d.name",
+            schema
+        ))
+        .ok()?;
+    let rows = stmt
+        .query_map(rusqlite::params![database_name, table], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)? as u64,
+                row.get::<_, i64>(3)? as u64,
+            ))
+        })
+        .ok()?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .ok()?;
+
+    let mut sizes = PageSizes {
+        payload: 0,
+        table_pages: 0,
+        index_pages: 0,
+        index_sizes: Vec::new(),
+    };
+    for (kind, name, pages, payload) in rows {
+        if kind == "index" {
+            sizes.index_pages += pages;
+            sizes.index_sizes.push((name, pages));
+        } else {
+            sizes.payload += payload;
+            sizes.table_pages += pages;
+        }
+    }
+    Some(sizes)
 }
@@ -13,6 +13,10 @@ pub enum ConnectionError {
     Timeout(Duration),
     #[error("Invalid connection string: {0}")]
     InvalidConnectionString(String),
+    #[error("TLS configuration error: {0}")]
+    Tls(String),
+    #[error("SSH tunnel error: {0}")]
+    Ssh(String),
     #[error("Driver not available: {0} (not compiled)")]
     DriverNotAvailable(&'static str),
 }
@@ -0,0 +1,480 @@
+//! Query plans from each driver's explain output, brought into one tree shape
+//! so they can be shown the same way
+
+use serde_json::Value;
+
+/// One operation of a query plan
+#[derive(Debug, Clone, Default)]
+pub struct PlanNode {
+    /// What the node does, e.g. `IXSCAN`, `Seq Scan` or `SEARCH users USING INDEX ...`
+    pub operation: String,
+    /// Table or collection the node reads
+    pub target: Option<String>,
+    /// Index the node reads through
+    pub index: Option<String>,
+    /// Planner cost estimate (PostgreSQL and MySQL)
+    pub cost: Option<f64>,
+    /// Rows the planner expected
+    pub estimated_rows: Option<f64>,
+    /// Rows (or documents) the node actually returned
+    pub actual_rows: Option<f64>,
+    /// Time spent in the node and its children, in milliseconds
+    pub time_ms: Option<f64>,
+    /// The node reads a whole table or collection (`COLLSCAN`, `Seq Scan`, ...)
+    pub full_scan: bool,
+    /// Everything else the server reported for the node
+    pub details: Vec<(String, String)>,
+    /// Inputs of the node
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    pub fn new(operation: impl Into<String>) -> Self {
+        Self {
+            operation: operation.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether this node or any node below it is a full scan
+    pub fn has_full_scan(&self) -> bool {
+        self.full_scan || self.children.iter().any(PlanNode::has_full_scan)
+    }
+}
+
+/// A query plan: the tree of operations plus figures for the whole query
+#[derive(Debug, Clone)]
+pub struct QueryPlan {
+    pub root: PlanNode,
+    /// Whole-query figures such as planning and execution time
+    pub summary: Vec<(String, String)>,
+    /// The explain output as returned by the server
+    pub raw: Value,
+}
+
+/// Plan from MongoDB's `explain` with `executionStats` verbosity, for find,
+/// count, distinct and aggregate commands
+pub fn from_mongo(explain: Value) -> QueryPlan {
+    let mut summary = Vec::new();
+    let root = match explain.get("stages").and_then(Value::as_array) {
+        Some(stages) => mongo_pipeline(stages, &mut summary),
+        None => mongo_query(&explain, &mut summary),
+    };
+    QueryPlan {
+        root,
+        summary,
+        raw: explain,
+    }
+}
+
+/// The winning plan of a query, with execution figures when they were collected
+fn mongo_query(explain: &Value, summary: &mut Vec<(String, String)>) -> PlanNode {
+    if let Some(stats) = explain.get("executionStats") {
+        let figures = [
+            ("nReturned", "Returned"),
+            ("executionTimeMillis", "Execution time"),
+            ("totalKeysExamined", "Keys examined"),
+            ("totalDocsExamined", "Documents examined"),
+        ];
+        for (key, label) in figures {
+            if let Some(value) = stats.get(key).and_then(number) {
+                let value = if key == "executionTimeMillis" {
+                    format_ms(value)
+                } else {
+                    format_count(value)
+                };
+                summary.push((label.to_string(), value));
+            }
+        }
+        if let Some(stages) = stats.get("executionStages") {
+            return mongo_stage(stages);
+        }
+    }
+
+    // Since MongoDB 7 the classic plan is nested under `queryPlan`
+    let winning_plan = explain.pointer("/queryPlanner/winningPlan");
+    match winning_plan.map(|plan| plan.get("queryPlan").unwrap_or(plan)) {
+        Some(plan) => mongo_stage(plan),
+        None => PlanNode::new("EXPLAIN"),
+    }
+}
+
+/// Aggregation stages that were not pushed down into the query, as a chain with
+/// the last stage on top
+fn mongo_pipeline(stages: &[Value], summary: &mut Vec<(String, String)>) -> PlanNode {
+    let mut chain: Option<PlanNode> = None;
+    for stage in stages {
+        let Some(object) = stage.as_object() else {
+            continue;
+        };
+        let Some((name, spec)) = object.iter().find(|(key, _)| key.starts_with('$')) else {
+            continue;
+        };
+
+        let mut node = PlanNode::new(name.clone());
+        if name == "$cursor" {
+            node.children.push(mongo_query(spec, summary));
+        } else {
+            node.details.push(("Stage".to_string(), detail_text(spec)));
+        }
+        node.actual_rows = object.get("nReturned").and_then(number);
+        node.time_ms = object.get("executionTimeMillisEstimate").and_then(number);
+        if let Some(input) = chain.take() {
+            node.children.insert(0, input);
+        }
+        chain = Some(node);
+    }
+    chain.unwrap_or_else(|| PlanNode::new("EXPLAIN"))
+}
+
+/// One stage of a classic query plan or of its execution stats
+fn mongo_stage(stage: &Value) -> PlanNode {
+    const SINGLE_INPUTS: [&str; 5] = [
+        "inputStage",
+        "innerStage",
+        "outerStage",
+        "thenStage",
+        "elseStage",
+    ];
+
+    let Some(object) = stage.as_object() else {
+        return PlanNode::new("STAGE");
+    };
+    let operation = object
+        .get("stage")
+        .and_then(Value::as_str)
+        .unwrap_or("STAGE");
+    let mut node = PlanNode::new(operation);
+    node.full_scan = operation == "COLLSCAN";
+    node.index = object
+        .get("indexName")
+        .and_then(Value::as_str)
+        .map(String::from);
+    node.actual_rows = object.get("nReturned").and_then(number);
+    node.time_ms = object.get("executionTimeMillisEstimate").and_then(number);
+
+    for (key, value) in object {
+        match key.as_str() {
+            "stage" | "indexName" | "nReturned" | "executionTimeMillisEstimate" => {}
+            key if SINGLE_INPUTS.contains(&key) => node.children.push(mongo_stage(value)),
+            "inputStages" => {
+                let inputs = value.as_array().map(Vec::as_slice).unwrap_or_default();
+                node.children.extend(inputs.iter().map(mongo_stage));
+            }
+            // Sharded clusters report a plan per shard
+            "shards" => {
+                let shards = value.as_array().map(Vec::as_slice).unwrap_or_default();
+                for shard in shards {
+                    let plan = shard
+                        .get("executionStages")
+                        .or_else(|| shard.get("winningPlan"))
+                        .map(|plan| plan.get("queryPlan").unwrap_or(plan));
+                    let mut child = plan
+                        .map(mongo_stage)
+                        .unwrap_or_else(|| PlanNode::new("SHARD"));
+                    child.target = shard
+                        .get("shardName")
+                        .and_then(Value::as_str)
+                        .map(String::from);
+                    node.children.push(child);
+                }
+            }
+            _ => node.details.push((key.clone(), detail_text(value))),
+        }
+    }
+    node
+}
+
+/// Plan from PostgreSQL's `EXPLAIN (ANALYZE, FORMAT JSON)`
+pub fn from_postgres(explain: Value) -> QueryPlan {
+    let entry = explain.get(0).unwrap_or(&explain);
+    let mut summary = Vec::new();
+    for (key, label) in [
+        ("Planning Time", "Planning time"),
+        ("Execution Time", "Execution time"),
+    ] {
+        if let Some(ms) = entry.get(key).and_then(number) {
+            summary.push((label.to_string(), format_ms(ms)));
+        }
+    }
+    let root = entry
+        .get("Plan")
+        .map(postgres_node)
+        .unwrap_or_else(|| PlanNode::new("Plan"));
+    QueryPlan {
+        root,
+        summary,
+        raw: explain,
+    }
+}
+
+fn postgres_node(plan: &Value) -> PlanNode {
+    const USED: [&str; 11] = [
+        "Node Type",
+        "Relation Name",
+        "Schema",
+        "Alias",
+        "Index Name",
+        "Total Cost",
+        "Plan Rows",
+        "Actual Rows",
+        "Actual Total Time",
+        "Actual Loops",
+        "Plans",
+    ];
+
+    let Some(object) = plan.as_object() else {
+        return PlanNode::new("Plan");
+    };
+    let operation = object
+        .get("Node Type")
+        .and_then(Value::as_str)
+        .unwrap_or("Plan");
+    let mut node = PlanNode::new(operation);
+    node.full_scan = operation == "Seq Scan";
+
+    let relation = object.get("Relation Name").and_then(Value::as_str);
+    let alias = object.get("Alias").and_then(Value::as_str);
+    node.target = relation.map(|relation| {
+        let relation = match object.get("Schema").and_then(Value::as_str) {
+            Some(schema) => format!("{}.{}", schema, relation),
+            None => relation.to_string(),
+        };
+        match alias {
+            Some(alias) if !relation.ends_with(alias) => format!("{} {}", relation, alias),
+            _ => relation,
+        }
+    });
+    node.index = object
+        .get("Index Name")
+        .and_then(Value::as_str)
+        .map(String::from);
+    node.cost = object.get("Total Cost").and_then(number);
+    node.estimated_rows = object.get("Plan Rows").and_then(number);
+
+    // Actual figures are per loop; report the totals
+    let loops = object.get("Actual Loops").and_then(number).unwrap_or(1.0);
+    node.actual_rows = object
+        .get("Actual Rows")
+        .and_then(number)
+        .map(|rows| rows * loops);
+    node.time_ms = object
+        .get("Actual Total Time")
+        .and_then(number)
+        .map(|ms| ms * loops);
+    if loops != 1.0 {
+        node.details
+            .push(("Loops".to_string(), format_count(loops)));
+    }
+
+    for (key, value) in object {
+        if !USED.contains(&key.as_str()) {
+            node.details.push((key.clone(), detail_text(value)));
+        }
+    }
+    let children = object.get("Plans").and_then(Value::as_array);
+    node.children = children.into_iter().flatten().map(postgres_node).collect();
+    node
+}
+
+/// Plan from MySQL's `EXPLAIN FORMAT=JSON`
+#[cfg(feature = "mysql")]
+pub fn from_mysql(explain: Value) -> QueryPlan {
+    let mut summary = Vec::new();
+    let root = match explain.get("query_block") {
+        Some(block) => {
+            if let Some(cost) = block.pointer("/cost_info/query_cost").and_then(number) {
+                summary.push(("Query cost".to_string(), format_cost(cost)));
+            }
+            mysql_node("query_block", block)
+        }
+        None => mysql_node("EXPLAIN", &explain),
+    };
+    QueryPlan {
+        root,
+        summary,
+        raw: explain,
+    }
+}
+
+/// A MySQL plan object. Objects nested under it (tables, nested loops, sorts,
+/// subqueries) become children named after their key.
+#[cfg(feature = "mysql")]
+fn mysql_node(name: &str, value: &Value) -> PlanNode {
+    let empty = serde_json::Map::new();
+    let object = value.as_object().unwrap_or(&empty);
+    let mut node = if name == "table" {
+        mysql_table(object)
+    } else {
+        let mut node = PlanNode::new(name.replace('_', " "));
+        node.cost = object
+            .get("cost_info")
+            .and_then(|cost| cost.get("query_cost").or_else(|| cost.get("sort_cost")))
+            .and_then(number);
+        node
+    };
+
+    for (key, value) in object {
+        match value {
+            Value::Object(cost) if key == "cost_info" => {
+                for (key, value) in cost {
+                    node.details
+                        .push((key.replace('_', " "), detail_text(value)));
+                }
+            }
+            Value::Object(_) => node.children.push(mysql_node(key, value)),
+            // `nested_loop`, `query_specifications`, `attached_subqueries`...
+            Value::Array(items) if items.iter().any(Value::is_object) => {
+                let mut group = PlanNode::new(key.replace('_', " "));
+                for item in items.iter().filter_map(Value::as_object) {
+                    for (key, value) in item {
+                        if value.is_object() {
+                            group.children.push(mysql_node(key, value));
+                        }
+                    }
+                }
+                node.children.push(group);
+            }
+            _ if name == "table" && MYSQL_TABLE_FIELDS.contains(&key.as_str()) => {}
+            _ => node
+                .details
+                .push((key.replace('_', " "), detail_text(value))),
+        }
+    }
+    node
+}
+
+/// Table fields shown on the node itself rather than as details
+#[cfg(feature = "mysql")]
+const MYSQL_TABLE_FIELDS: [&str; 4] =
+    ["table_name", "access_type", "key", "rows_examined_per_scan"];
+
+#[cfg(feature = "mysql")]
+fn mysql_table(table: &serde_json::Map<String, Value>) -> PlanNode {
+    let access_type = table
+        .get("access_type")
+        .and_then(Value::as_str)
+        .unwrap_or("");
+    let operation = match access_type {
+        "ALL" => "Full table scan",
+        "index" => "Full index scan",
+        "range" => "Index range scan",
+        "ref" | "eq_ref" | "ref_or_null" => "Index lookup",
+        "const" | "system" => "Constant row",
+        "" => "Table",
+        other => other,
+    };
+    let mut node = PlanNode::new(operation);
+    node.full_scan = access_type == "ALL";
+    node.target = table
+        .get("table_name")
+        .and_then(Value::as_str)
+        .map(String::from);
+    node.index = table.get("key").and_then(Value::as_str).map(String::from);
+    node.estimated_rows = table.get("rows_examined_per_scan").and_then(number);
+    node.cost = table
+        .get("cost_info")
+        .and_then(|cost| cost.get("prefix_cost"))
+        .and_then(number);
+    node
+}
+
+/// Plan from SQLite's `EXPLAIN QUERY PLAN`, given its `(id, parent, detail)` rows
+#[cfg(feature = "sqlite-driver")]
+pub fn from_sqlite(rows: Vec<(i64, i64, String)>) -> QueryPlan {
+    fn children(rows: &[(i64, i64, String)], parent: i64) -> Vec<PlanNode> {
+        rows.iter()
+            .filter(|(_, row_parent, _)| *row_parent == parent)
+            .map(|(id, _, detail)| {
+                let mut node = sqlite_node(detail);
+                node.children = children(rows, *id);
+                node
+            })
+            .collect()
+    }
+
+    let mut root = PlanNode::new("QUERY PLAN");
+    root.children = children(&rows, 0);
+    let raw = rows
+        .iter()
+        .map(|(id, parent, detail)| serde_json::json!({ "id": id, "parent": parent, "detail": detail }))
+        .collect();
+    QueryPlan {
+        root,
+        summary: Vec::new(),
+        raw: Value::Array(raw),
+    }
+}
+
+/// `SCAN users`, `SEARCH users USING INDEX idx_email (email=?)`, `USE TEMP B-TREE FOR ORDER BY`...
+#[cfg(feature = "sqlite-driver")]
+fn sqlite_node(detail: &str) -> PlanNode {
+    let mut node = PlanNode::new(detail);
+    let mut words = detail.split_whitespace();
+    let verb = words.next().unwrap_or("");
+    if verb == "SCAN" || verb == "SEARCH" {
+        node.target = words
+            .next()
+            .filter(|name| !name.starts_with('(') && *name != "CONSTANT")
+            .map(String::from);
+    }
+    node.full_scan = verb == "SCAN" && node.target.is_some() && !detail.contains(" USING ");
+
+    for marker in ["USING COVERING INDEX ", "USING INDEX "] {
+        if let Some(start) = detail.find(marker) {
+            let rest = &detail[start + marker.len()..];
+            node.index = rest.split_whitespace().next().map(String::from);
+            break;
+        }
+    }
+    if node.index.is_none() && detail.contains("USING INTEGER PRIMARY KEY") {
+        node.index = Some("INTEGER PRIMARY KEY".to_string());
+    }
+    node
+}
+
+/// A number, or a number written as a string (MySQL costs)
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Scalars as written, lists of scalars comma-separated, anything else as JSON
+fn detail_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items)
+            if items
+                .iter()
+                .all(|item| !item.is_object() && !item.is_array()) =>
+        {
+            items.iter().map(detail_text).collect::<Vec<_>>().join(", ")
+        }
+        other => other.to_string(),
+    }
+}
+
+pub fn format_ms(ms: f64) -> String {
+    if ms < 1.0 {
+        format!("{:.3} ms", ms)
+    } else if ms < 1000.0 {
+        format!("{:.1} ms", ms)
+    } else {
+        format!("{:.2} s", ms / 1000.0)
+    }
+}
+
+pub fn format_count(count: f64) -> String {
+    if count.fract() == 0.0 {
+        format!("{}", count as i64)
+    } else {
+        format!("{:.1}", count)
+    }
+}
+
+pub fn format_cost(cost: f64) -> String {
+    format!("{:.2}", cost)
+}
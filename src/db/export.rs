@@ -0,0 +1,603 @@
+//! Export rows and documents to CSV, JSON Lines, Extended JSON or SQL `INSERT`s
+//!
+//! Rows are written to a temporary file next to the target, which replaces the
+//! target only once every row was written: a cancelled or failed export leaves
+//! no partial file behind. Whole collections are fetched in batches, so large
+//! exports never hold more than one batch in memory.
+//!
+//! Batches need a stable order so they neither skip nor repeat rows. SQL tables
+//! with a primary key and no sort are paged by key (`WHERE key > last`); otherwise
+//! the sort gets the primary key or `_id` as a tie-breaker and batches are paged
+//! with OFFSET. MongoDB collections are not paged by `_id` ranges because `$gt`
+//! only matches ids of the same BSON type. Redis keyspaces continue their SCAN.
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use super::connection::DatabaseType;
+use super::driver::{DatabaseConnection, KeyspaceCursor, REDIS_KEYSPACE};
+use super::extended_json::{self, ExtJsonFormat};
+use super::sql_edit::{self, RowChange};
+
+/// Rows fetched per query when exporting a whole collection or table
+const EXPORT_BATCH_SIZE: u32 = 1000;
+
+/// File formats rows can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    JsonLines,
+    ExtendedJson,
+    SqlInsert,
+}
+
+impl ExportFormat {
+    /// Formats offered for a driver. Extended JSON only makes sense for documents.
+    pub fn available(driver: DatabaseType) -> Vec<ExportFormat> {
+        let mut formats = vec![ExportFormat::Csv, ExportFormat::JsonLines];
+        if driver == DatabaseType::MongoDB {
+            formats.push(ExportFormat::ExtendedJson);
+        }
+        formats.push(ExportFormat::SqlInsert);
+        formats
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::JsonLines => "JSON Lines",
+            ExportFormat::ExtendedJson => "Extended JSON",
+            ExportFormat::SqlInsert => "SQL INSERT",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::JsonLines => "jsonl",
+            ExportFormat::ExtendedJson => "json",
+            ExportFormat::SqlInsert => "sql",
+        }
+    }
+}
+
+/// When CSV fields are quoted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvQuoting {
+    /// Only fields containing the delimiter, a quote or a line break
+    Needed,
+    /// Every field
+    All,
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub quoting: CsvQuoting,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quoting: CsvQuoting::Needed,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub csv: CsvOptions,
+    /// Driver the rows come from; decides how SQL identifiers and literals are written
+    pub driver: DatabaseType,
+    /// Table named in `INSERT` statements, written as given
+    pub table: String,
+}
+
+/// Rows to export
+pub enum ExportSource {
+    /// Rows already loaded, e.g. the current page or the selected rows
+    Rows(Vec<Value>),
+    /// Every row of a collection or table matching a filter, fetched in batches
+    Query {
+        connection: Arc<dyn DatabaseConnection>,
+        database: String,
+        collection: String,
+        filter: Option<String>,
+        sort: Option<String>,
+    },
+}
+
+/// Progress of a running export, shared with the UI, which can also cancel it
+#[derive(Default)]
+pub struct ExportProgress {
+    written: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl ExportProgress {
+    /// Rows written so far
+    pub fn written(&self) -> usize {
+        self.written.load(Ordering::Relaxed)
+    }
+
+    /// Stop the export after the current batch
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Write the rows of `source` to `path`, returning how many were written.
+/// Fails with "Export cancelled" if cancelled through `progress`.
+pub async fn export(
+    source: ExportSource,
+    path: &Path,
+    options: &ExportOptions,
+    progress: &ExportProgress,
+) -> Result<usize> {
+    let mut writer = RowWriter::create(path, options)?;
+    let result = write_source(source, options.driver, &mut writer, progress).await;
+    match result {
+        Ok(()) => writer.finish(progress),
+        Err(e) => {
+            writer.discard();
+            Err(e)
+        }
+    }
+}
+
+async fn write_source(
+    source: ExportSource,
+    driver: DatabaseType,
+    writer: &mut RowWriter,
+    progress: &ExportProgress,
+) -> Result<()> {
+    match source {
+        ExportSource::Rows(rows) => {
+            for chunk in rows.chunks(EXPORT_BATCH_SIZE as usize) {
+                check_cancelled(progress)?;
+                writer.write_rows(chunk, progress)?;
+            }
+        }
+        ExportSource::Query {
+            connection,
+            database,
+            collection,
+            filter,
+            sort,
+        } => {
+            let paging = paging(driver, connection.as_ref(), &database, &collection, sort).await?;
+            match paging {
+                Paging::Keyset(key) => {
+                    let order = key_order(&key);
+                    let mut after: Option<String> = None;
+                    loop {
+                        check_cancelled(progress)?;
+                        let batch_filter = match (&after, filter_text(filter.as_deref())) {
+                            (None, _) => filter.clone(),
+                            (Some(after), None) => Some(after.clone()),
+                            (Some(after), Some(filter)) => {
+                                Some(format!("({}) AND {}", filter, after))
+                            }
+                        };
+                        let rows = connection
+                            .query_documents(
+                                &database,
+                                &collection,
+                                EXPORT_BATCH_SIZE,
+                                0,
+                                batch_filter.as_deref(),
+                                Some(&order),
+                            )
+                            .await?;
+                        writer.write_rows(&rows, progress)?;
+                        match rows.last() {
+                            Some(last) if rows.len() == EXPORT_BATCH_SIZE as usize => {
+                                after = Some(after_row(driver, &key, last)?);
+                            }
+                            _ => break,
+                        }
+                    }
+                }
+                Paging::Offset(sort) => {
+                    let mut skip = 0u32;
+                    loop {
+                        check_cancelled(progress)?;
+                        let rows = connection
+                            .query_documents(
+                                &database,
+                                &collection,
+                                EXPORT_BATCH_SIZE,
+                                skip,
+                                filter.as_deref(),
+                                sort.as_deref(),
+                            )
+                            .await?;
+                        writer.write_rows(&rows, progress)?;
+                        if rows.len() < EXPORT_BATCH_SIZE as usize {
+                            break;
+                        }
+                        skip += EXPORT_BATCH_SIZE;
+                    }
+                }
+                Paging::Scan => {
+                    let mut from = KeyspaceCursor::default();
+                    loop {
+                        check_cancelled(progress)?;
+                        let page = connection
+                            .scan_keyspace(&database, filter.as_deref(), &from, EXPORT_BATCH_SIZE)
+                            .await?;
+                        writer.write_rows(&page.rows, progress)?;
+                        match page.next {
+                            Some(next) => from = next,
+                            None => break,
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// How the batches of a whole collection or table are fetched
+enum Paging {
+    /// In primary key order, each batch after the last key of the previous one
+    Keyset(Vec<String>),
+    /// With OFFSET, in this order
+    Offset(Option<String>),
+    /// Continuing a Redis SCAN
+    Scan,
+}
+
+async fn paging(
+    driver: DatabaseType,
+    connection: &dyn DatabaseConnection,
+    database: &str,
+    collection: &str,
+    sort: Option<String>,
+) -> Result<Paging> {
+    if driver == DatabaseType::Redis && collection == REDIS_KEYSPACE {
+        return Ok(Paging::Scan);
+    }
+
+    let key: Vec<String> = if driver.is_sql() {
+        connection
+            .table_columns(database, collection)
+            .await?
+            .into_iter()
+            .filter(|column| column.primary_key)
+            .map(|column| column.name)
+            .collect()
+    } else if driver == DatabaseType::MongoDB {
+        vec!["_id".to_string()]
+    } else {
+        Vec::new()
+    };
+
+    // Tables without a primary key are left in storage order
+    if key.is_empty() {
+        return Ok(Paging::Offset(sort));
+    }
+    if sort.is_none() && driver.is_sql() {
+        return Ok(Paging::Keyset(key));
+    }
+    Ok(Paging::Offset(Some(with_tie_breaker(
+        sort.as_deref(),
+        &key,
+    ))))
+}
+
+/// The filter, unless it matches everything
+fn filter_text(filter: Option<&str>) -> Option<&str> {
+    filter
+        .map(str::trim)
+        .filter(|filter| !filter.is_empty() && *filter != "{}")
+}
+
+/// Ascending sort spec on the key columns, e.g. `{"id": 1}`
+fn key_order(key: &[String]) -> String {
+    let fields: serde_json::Map<String, Value> = key
+        .iter()
+        .map(|column| (column.clone(), Value::from(1)))
+        .collect();
+    Value::Object(fields).to_string()
+}
+
+/// `sort` followed by the key fields it doesn't already sort on, so rows with
+/// equal sort values still come in the same order on every query
+fn with_tie_breaker(sort: Option<&str>, key: &[String]) -> String {
+    let mut fields = match sort.map(serde_json::from_str::<Value>) {
+        Some(Ok(Value::Object(fields))) => fields,
+        Some(_) => return sort.unwrap_or_default().to_string(),
+        None => serde_json::Map::new(),
+    };
+    for column in key {
+        if !fields.contains_key(column) {
+            fields.insert(column.clone(), Value::from(1));
+        }
+    }
+    Value::Object(fields).to_string()
+}
+
+/// SQL condition selecting the rows whose key sorts after the key of `row`
+fn after_row(driver: DatabaseType, key: &[String], row: &Value) -> Result<String> {
+    let columns: Vec<String> = key
+        .iter()
+        .map(|column| sql_edit::quote_identifier(driver, column))
+        .collect();
+    let values = key
+        .iter()
+        .map(|column| match row.get(column) {
+            Some(Value::Null) | None => bail!("Row has no value for key column {}", column),
+            Some(value) => Ok(sql_edit::literal(driver, value)),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(if key.len() == 1 {
+        format!("{} > {}", columns[0], values[0])
+    } else {
+        format!("({}) > ({})", columns.join(", "), values.join(", "))
+    })
+}
+
+fn check_cancelled(progress: &ExportProgress) -> Result<()> {
+    if progress.is_cancelled() {
+        bail!("Export cancelled");
+    }
+    Ok(())
+}
+
+/// Writes rows in the chosen format to a temporary file. CSV rows are first
+/// spooled as flattened JSON so the header can list every field, including
+/// ones that only appear in later batches.
+struct RowWriter {
+    options: ExportOptions,
+    path: PathBuf,
+    temp_path: PathBuf,
+    out: BufWriter<File>,
+    rows: usize,
+    /// CSV columns in the order they were first seen
+    columns: Vec<String>,
+    seen_columns: HashSet<String>,
+}
+
+impl RowWriter {
+    fn create(path: &Path, options: &ExportOptions) -> Result<Self> {
+        let temp_path = temp_path(path, "part");
+        let file = File::create(&temp_path)
+            .with_context(|| format!("Cannot write {}", temp_path.display()))?;
+        let mut out = BufWriter::new(file);
+        if options.format == ExportFormat::ExtendedJson {
+            out.write_all(b"[")?;
+        }
+        Ok(Self {
+            options: options.clone(),
+            path: path.to_path_buf(),
+            temp_path,
+            out,
+            rows: 0,
+            columns: Vec::new(),
+            seen_columns: HashSet::new(),
+        })
+    }
+
+    fn write_rows(&mut self, rows: &[Value], progress: &ExportProgress) -> Result<()> {
+        for row in rows {
+            self.write_row(row)?;
+            self.rows += 1;
+        }
+        progress.written.store(self.rows, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn write_row(&mut self, row: &Value) -> Result<()> {
+        let driver = self.options.driver;
+        match self.options.format {
+            ExportFormat::Csv => {
+                let mut fields = serde_json::Map::new();
+                flatten(None, &relaxed(driver, row), &mut fields);
+                for column in fields.keys() {
+                    if self.seen_columns.insert(column.clone()) {
+                        self.columns.push(column.clone());
+                    }
+                }
+                serde_json::to_writer(&mut self.out, &fields)?;
+                self.out.write_all(b"\n")?;
+            }
+            ExportFormat::JsonLines => {
+                serde_json::to_writer(&mut self.out, &relaxed(driver, row))?;
+                self.out.write_all(b"\n")?;
+            }
+            ExportFormat::ExtendedJson => {
+                let canonical = extended_json::convert(row, ExtJsonFormat::Canonical)?;
+                let separator: &[u8] = if self.rows == 0 { b"\n" } else { b",\n" };
+                self.out.write_all(separator)?;
+                serde_json::to_writer(&mut self.out, &canonical)?;
+            }
+            ExportFormat::SqlInsert => {
+                let values: serde_json::Map<String, Value> = match relaxed(driver, row) {
+                    Value::Object(fields) => fields
+                        .into_iter()
+                        .map(|(field, value)| (field, sql_value(value)))
+                        .collect(),
+                    _ => return Ok(()),
+                };
+                let change = RowChange::Insert { values: &values };
+                let statement = sql_edit::statement(driver, &self.options.table, &change);
+                writeln!(self.out, "{};", statement)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Complete the file and move it into place
+    fn finish(mut self, progress: &ExportProgress) -> Result<usize> {
+        let result = self.complete(progress);
+        if let Err(e) = result {
+            self.discard();
+            return Err(e);
+        }
+        fs::rename(&self.temp_path, &self.path)
+            .with_context(|| format!("Cannot write {}", self.path.display()))?;
+        Ok(self.rows)
+    }
+
+    fn complete(&mut self, progress: &ExportProgress) -> Result<()> {
+        match self.options.format {
+            ExportFormat::ExtendedJson => self.out.write_all(b"\n]\n")?,
+            ExportFormat::Csv => {
+                self.out.flush()?;
+                // The spool becomes the input; the CSV is written to a new temporary file
+                let spool_path = temp_path(&self.path, "rows");
+                fs::rename(&self.temp_path, &spool_path)?;
+                let result = self.write_csv(&spool_path, progress);
+                fs::remove_file(&spool_path).ok();
+                result?;
+            }
+            _ => {}
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn write_csv(&mut self, spool_path: &Path, progress: &ExportProgress) -> Result<()> {
+        let file = File::create(&self.temp_path)
+            .with_context(|| format!("Cannot write {}", self.temp_path.display()))?;
+        self.out = BufWriter::new(file);
+
+        let csv = self.options.csv.clone();
+        let header: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| csv_field(column, &csv))
+            .collect();
+        writeln!(self.out, "{}", header.join(&csv.delimiter.to_string()))?;
+
+        for line in BufReader::new(File::open(spool_path)?).lines() {
+            check_cancelled(progress)?;
+            let fields: serde_json::Map<String, Value> = serde_json::from_str(&line?)?;
+            let record: Vec<String> = self
+                .columns
+                .iter()
+                .map(|column| {
+                    let text = fields.get(column).map(scalar_text).unwrap_or_default();
+                    csv_field(&text, &csv)
+                })
+                .collect();
+            writeln!(self.out, "{}", record.join(&csv.delimiter.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Remove the temporary file of a failed or cancelled export
+    fn discard(self) {
+        let temp_path = self.temp_path.clone();
+        drop(self.out);
+        fs::remove_file(temp_path).ok();
+    }
+}
+
+/// `<path>.<suffix>` next to the target file
+fn temp_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", suffix));
+    path.with_file_name(name)
+}
+
+/// Documents are written as relaxed Extended JSON (plain numbers and ISO dates);
+/// SQL rows are already plain JSON
+fn relaxed(driver: DatabaseType, row: &Value) -> Value {
+    if driver != DatabaseType::MongoDB {
+        return row.clone();
+    }
+    extended_json::convert(row, ExtJsonFormat::Relaxed).unwrap_or_else(|_| row.clone())
+}
+
+/// An Extended JSON value wrapper such as `{"$oid": "..."}`
+fn is_wrapper(fields: &serde_json::Map<String, Value>) -> bool {
+    fields.keys().next().is_some_and(|key| key.starts_with('$'))
+}
+
+/// Flatten nested objects into dotted field names (`address.city`). Arrays and
+/// Extended JSON values like ObjectIds stay single fields.
+fn flatten(prefix: Option<&str>, value: &Value, out: &mut serde_json::Map<String, Value>) {
+    match value {
+        Value::Object(fields) if !fields.is_empty() && !is_wrapper(fields) => {
+            for (key, value) in fields {
+                let name = match prefix {
+                    Some(prefix) => format!("{}.{}", prefix, key),
+                    None => key.clone(),
+                };
+                flatten(Some(&name), value, out);
+            }
+        }
+        _ => {
+            out.insert(prefix.unwrap_or_default().to_string(), value.clone());
+        }
+    }
+}
+
+/// Text of a single value: strings as they are, ObjectIds as hex, dates as
+/// ISO 8601, nulls empty and anything else as compact JSON
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Object(fields) if fields.len() == 1 => {
+            match fields
+                .iter()
+                .next()
+                .map(|(key, value)| (key.as_str(), value))
+            {
+                Some(("$oid", Value::String(hex))) => hex.clone(),
+                Some(("$date", Value::String(date))) => date.clone(),
+                Some(("$date", Value::Object(millis))) => millis
+                    .get("$numberLong")
+                    .and_then(Value::as_str)
+                    .and_then(|millis| millis.parse::<i64>().ok())
+                    .and_then(chrono::DateTime::from_timestamp_millis)
+                    .map(|date| date.to_rfc3339())
+                    .unwrap_or_else(|| value.to_string()),
+                Some((
+                    "$numberLong" | "$numberInt" | "$numberDouble" | "$numberDecimal",
+                    Value::String(n),
+                )) => n.clone(),
+                _ => value.to_string(),
+            }
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Extended JSON scalars become their text for `INSERT` statements
+fn sql_value(value: Value) -> Value {
+    match &value {
+        Value::Object(fields) if fields.len() == 1 && is_wrapper(fields) => {
+            Value::String(scalar_text(&value))
+        }
+        _ => value,
+    }
+}
+
+fn csv_field(text: &str, options: &CsvOptions) -> String {
+    let needs_quotes = options.quoting == CsvQuoting::All
+        || text.contains(options.delimiter)
+        || text.contains(['"', '\n', '\r']);
+    if needs_quotes {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
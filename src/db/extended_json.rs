@@ -0,0 +1,127 @@
+//! Extended JSON text for the document editor
+//!
+//! Documents are read from MongoDB as Extended JSON and can be edited in either
+//! of its two formats: canonical, which spells out the BSON type of every value,
+//! or relaxed, which writes numbers and recent dates the way plain JSON would.
+
+use serde_json::Value;
+
+use super::error::{ConnectionError, Result};
+
+/// The two Extended JSON formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtJsonFormat {
+    Canonical,
+    Relaxed,
+}
+
+impl ExtJsonFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            ExtJsonFormat::Canonical => "Canonical",
+            ExtJsonFormat::Relaxed => "Relaxed",
+        }
+    }
+}
+
+/// Position of an error in the editor text. `line` and `column` are 1-based,
+/// `offset` is the byte offset of the column within the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextPosition {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+/// Why editor text is not a valid document. Syntax errors carry their position;
+/// values that are valid JSON but not valid Extended JSON do not.
+#[derive(Debug, Clone)]
+pub struct DocumentError {
+    pub message: String,
+    pub position: Option<TextPosition>,
+}
+
+/// Rewrite an Extended JSON value (in either format) in the given format
+#[cfg(feature = "mongodb")]
+pub fn convert(value: &Value, format: ExtJsonFormat) -> Result<Value> {
+    let bson = mongodb::bson::Bson::try_from(value.clone())
+        .map_err(|e| ConnectionError::Failed(format!("Invalid Extended JSON: {}", e)))?;
+    Ok(match format {
+        ExtJsonFormat::Canonical => bson.into_canonical_extjson(),
+        ExtJsonFormat::Relaxed => bson.into_relaxed_extjson(),
+    })
+}
+
+#[cfg(not(feature = "mongodb"))]
+pub fn convert(value: &Value, format: ExtJsonFormat) -> Result<Value> {
+    let _ = (value, format);
+    Err(ConnectionError::Failed(
+        "MongoDB support not compiled. Rebuild with --features mongodb".into(),
+    ))
+}
+
+/// Pretty-print a value in the given format
+pub fn to_text(value: &Value, format: ExtJsonFormat) -> Result<String> {
+    serde_json::to_string_pretty(&convert(value, format)?)
+        .map_err(|e| ConnectionError::Failed(e.to_string()))
+}
+
+/// Parse editor text as a single Extended JSON document (a JSON object whose
+/// `$`-keyed values are valid BSON types)
+pub fn parse_document(text: &str) -> std::result::Result<Value, DocumentError> {
+    let value = serde_json::from_str::<Value>(text).map_err(|e| {
+        let message = e.to_string();
+        // serde_json appends the position, which is reported separately
+        let message = match message.rsplit_once(" at line ") {
+            Some((message, _)) => message.to_string(),
+            None => message,
+        };
+        DocumentError {
+            message: capitalize(&message),
+            position: Some(position_in(text, e.line(), e.column())),
+        }
+    })?;
+
+    if !value.is_object() {
+        return Err(DocumentError {
+            message: "A document must be a JSON object".into(),
+            position: Some(position_in(text, 1, 1)),
+        });
+    }
+
+    convert(&value, ExtJsonFormat::Canonical).map_err(|e| DocumentError {
+        message: match e {
+            ConnectionError::Failed(message) => message,
+            other => other.to_string(),
+        },
+        position: None,
+    })?;
+    Ok(value)
+}
+
+/// Byte offset of a 1-based line and column reported by serde_json, clamped to
+/// the text and moved back onto a character boundary
+fn position_in(text: &str, line: usize, column: usize) -> TextPosition {
+    let line_start = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    let mut offset = (line_start + column.saturating_sub(1)).min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    TextPosition {
+        line: line.max(1),
+        column: column.max(1),
+        offset,
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,875 @@
+//! Import rows from CSV, JSON Lines or JSON array files into a collection or table
+//!
+//! The file is read one record at a time and inserted in batches: documents with
+//! `insertMany`, SQL rows as multi-row `INSERT` statements. A record that cannot
+//! be read, converted or inserted is reported by its row number and the import
+//! goes on, unless it was asked to stop at the first error. Rows inserted before
+//! an import stops, fails or is cancelled are kept.
+
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use super::connection::DatabaseType;
+use super::driver::DatabaseConnection;
+use super::error::ConnectionError;
+use super::sql_edit;
+
+/// Rows sent to the server per insert
+const IMPORT_BATCH_SIZE: usize = 500;
+
+/// Records read for the preview
+pub const PREVIEW_ROWS: usize = 20;
+
+/// Row errors kept for the report; later ones are only counted
+const MAX_REPORTED_ERRORS: usize = 1000;
+
+/// File formats rows can be imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Csv,
+    JsonLines,
+    JsonArray,
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 3] = [
+        ImportFormat::Csv,
+        ImportFormat::JsonLines,
+        ImportFormat::JsonArray,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ImportFormat::Csv => "CSV",
+            ImportFormat::JsonLines => "JSON Lines",
+            ImportFormat::JsonArray => "JSON array",
+        }
+    }
+}
+
+/// How to read the source file
+#[derive(Debug, Clone)]
+pub struct SourceOptions {
+    pub format: ImportFormat,
+    /// CSV field delimiter
+    pub delimiter: char,
+    /// The first CSV record names the columns
+    pub has_header: bool,
+}
+
+impl SourceOptions {
+    /// Guess the format from the file extension, and for `.json` files from
+    /// whether the content starts with `[`
+    pub fn detect(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let format = match extension.as_str() {
+            "jsonl" | "ndjson" => ImportFormat::JsonLines,
+            "json" if starts_with_bracket(path) => ImportFormat::JsonArray,
+            "json" => ImportFormat::JsonLines,
+            _ => ImportFormat::Csv,
+        };
+        Self {
+            format,
+            delimiter: if extension == "tsv" { '\t' } else { ',' },
+            has_header: true,
+        }
+    }
+}
+
+fn starts_with_bracket(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    BufReader::new(file)
+        .bytes()
+        .map_while(|byte| byte.ok())
+        .find(|byte| !byte.is_ascii_whitespace())
+        == Some(b'[')
+}
+
+/// Type a source value is converted to before it is inserted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    /// JSON values as they are; CSV text as a number or boolean when it reads as one
+    Auto,
+    String,
+    Integer,
+    Double,
+    Boolean,
+    Date,
+    ObjectId,
+    /// Text holding JSON, e.g. an array in a CSV field
+    Json,
+}
+
+impl FieldType {
+    /// Types offered for a driver. ObjectIds only exist in MongoDB.
+    pub fn available(driver: DatabaseType) -> Vec<FieldType> {
+        let mut types = vec![
+            FieldType::Auto,
+            FieldType::String,
+            FieldType::Integer,
+            FieldType::Double,
+            FieldType::Boolean,
+            FieldType::Date,
+        ];
+        if driver == DatabaseType::MongoDB {
+            types.push(FieldType::ObjectId);
+        }
+        types.push(FieldType::Json);
+        types
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FieldType::Auto => "Auto",
+            FieldType::String => "String",
+            FieldType::Integer => "Integer",
+            FieldType::Double => "Double",
+            FieldType::Boolean => "Boolean",
+            FieldType::Date => "Date",
+            FieldType::ObjectId => "ObjectId",
+            FieldType::Json => "JSON",
+        }
+    }
+}
+
+/// Where a source column goes. An empty `target` skips the column; for
+/// documents, a dotted target (`address.city`) writes a nested field.
+#[derive(Debug, Clone)]
+pub struct FieldMapping {
+    pub source: String,
+    pub target: String,
+    pub field_type: FieldType,
+}
+
+/// First records of a file, to choose the mapping from
+#[derive(Debug, Clone, Default)]
+pub struct SourcePreview {
+    /// Source columns in the order they first appear
+    pub columns: Vec<String>,
+    pub rows: Vec<Map<String, Value>>,
+    /// Records among the first ones that could not be read
+    pub errors: Vec<RowError>,
+}
+
+/// A record that could not be read, converted or inserted. `row` is the
+/// 1-based position of the record in the file, not counting a CSV header.
+#[derive(Debug, Clone)]
+pub struct RowError {
+    pub row: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    pub source: SourceOptions,
+    pub mappings: Vec<FieldMapping>,
+    /// Driver of the target; decides how rows are inserted
+    pub driver: DatabaseType,
+    /// Table named in `INSERT` statements (see `sql_edit::table_reference`)
+    pub table: String,
+    /// Stop at the first row that fails instead of reporting it and going on
+    pub stop_on_error: bool,
+}
+
+/// Collection or table to import into
+pub struct ImportTarget {
+    pub connection: Arc<dyn DatabaseConnection>,
+    pub database: String,
+    pub collection: String,
+}
+
+/// Progress of a running import, shared with the UI, which can also cancel it
+#[derive(Default)]
+pub struct ImportProgress {
+    bytes_read: AtomicU64,
+    bytes_total: AtomicU64,
+    rows_read: AtomicUsize,
+    inserted: AtomicUsize,
+    failed: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl ImportProgress {
+    /// Share of the file read so far, from 0 to 1
+    pub fn fraction(&self) -> f32 {
+        let total = self.bytes_total.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0.0;
+        }
+        (self.bytes_read.load(Ordering::Relaxed) as f32 / total as f32).min(1.0)
+    }
+
+    pub fn rows_read(&self) -> usize {
+        self.rows_read.load(Ordering::Relaxed)
+    }
+
+    pub fn inserted(&self) -> usize {
+        self.inserted.load(Ordering::Relaxed)
+    }
+
+    pub fn failed(&self) -> usize {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    /// Stop the import after the current batch
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// What an import did
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub inserted: usize,
+    pub failed: usize,
+    /// The first failed rows, with the reason
+    pub errors: Vec<RowError>,
+    /// The import stopped at the first failed row
+    pub stopped_at_error: bool,
+    pub cancelled: bool,
+}
+
+impl ImportReport {
+    fn add_error(&mut self, row: usize, message: String) {
+        self.failed += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(RowError { row, message });
+        }
+    }
+}
+
+/// Read the first records of a file
+pub fn preview(path: &Path, source: &SourceOptions) -> Result<SourcePreview> {
+    let progress = ImportProgress::default();
+    let mut records = Records::open(path, source, &progress)?;
+    let mut preview = SourcePreview::default();
+    let mut seen = HashSet::new();
+    if let Records::Csv { header, .. } = &records {
+        for column in header {
+            if seen.insert(column.clone()) {
+                preview.columns.push(column.clone());
+            }
+        }
+    }
+
+    while preview.rows.len() + preview.errors.len() < PREVIEW_ROWS {
+        let Some((row, record)) = records.next_record()? else {
+            break;
+        };
+        match record {
+            Ok(record) => {
+                for column in record.keys() {
+                    if seen.insert(column.clone()) {
+                        preview.columns.push(column.clone());
+                    }
+                }
+                preview.rows.push(record);
+            }
+            Err(message) => preview.errors.push(RowError { row, message }),
+        }
+    }
+    Ok(preview)
+}
+
+/// Why a mapping cannot be imported, if it cannot. `columns` are the columns
+/// of the target table; when empty (collections), any target name is allowed.
+pub fn mapping_error(mappings: &[FieldMapping], columns: &[String]) -> Option<String> {
+    let mut targets = HashSet::new();
+    for mapping in mappings {
+        let target = mapping.target.as_str();
+        if target.is_empty() {
+            continue;
+        }
+        if !targets.insert(target) {
+            return Some(format!("{} is the target of more than one column", target));
+        }
+        if !columns.is_empty() && !columns.iter().any(|column| column == target) {
+            return Some(format!("The table has no column {}", target));
+        }
+    }
+    if targets.is_empty() {
+        return Some("Map at least one column to a target".into());
+    }
+    None
+}
+
+/// Import every record of `path` into the target
+pub async fn import(
+    path: &Path,
+    target: ImportTarget,
+    options: &ImportOptions,
+    progress: &ImportProgress,
+) -> Result<ImportReport> {
+    if let Some(error) = mapping_error(&options.mappings, &[]) {
+        bail!(error);
+    }
+
+    let mut records = Records::open(path, &options.source, progress)?;
+    let mut report = ImportReport::default();
+    let mut batch = Vec::new();
+
+    loop {
+        if progress.is_cancelled() {
+            report.cancelled = true;
+            return Ok(report);
+        }
+        let Some((row, record)) = records.next_record()? else {
+            break;
+        };
+        progress.rows_read.store(row, Ordering::Relaxed);
+
+        match record.and_then(|record| convert_record(&record, options)) {
+            Ok(values) => batch.push((row, values)),
+            Err(message) => {
+                if options.stop_on_error {
+                    // The rows before the failed one are still inserted
+                    insert_batch(&target, options, &mut batch, &mut report, progress).await?;
+                    if !report.stopped_at_error {
+                        report.add_error(row, message);
+                        report.stopped_at_error = true;
+                    }
+                    progress.failed.store(report.failed, Ordering::Relaxed);
+                    return Ok(report);
+                }
+                report.add_error(row, message);
+                progress.failed.store(report.failed, Ordering::Relaxed);
+            }
+        }
+
+        if batch.len() >= IMPORT_BATCH_SIZE {
+            insert_batch(&target, options, &mut batch, &mut report, progress).await?;
+            if report.stopped_at_error {
+                return Ok(report);
+            }
+        }
+    }
+
+    insert_batch(&target, options, &mut batch, &mut report, progress).await?;
+    Ok(report)
+}
+
+/// Insert the rows of a batch, recording the ones the server rejects
+async fn insert_batch(
+    target: &ImportTarget,
+    options: &ImportOptions,
+    batch: &mut Vec<(usize, Map<String, Value>)>,
+    report: &mut ImportReport,
+    progress: &ImportProgress,
+) -> Result<()> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+    let (rows, values): (Vec<usize>, Vec<Map<String, Value>>) =
+        std::mem::take(batch).into_iter().unzip();
+    let connection = &target.connection;
+
+    if options.driver == DatabaseType::MongoDB {
+        let documents: Vec<Value> = values.into_iter().map(Value::Object).collect();
+        let result = connection
+            .insert_documents(
+                &target.database,
+                &target.collection,
+                &documents,
+                options.stop_on_error,
+            )
+            .await?;
+        report.inserted += result.inserted;
+        if options.stop_on_error && !result.rejected.is_empty() {
+            report.stopped_at_error = true;
+        }
+        for (index, message) in result.rejected {
+            report.add_error(rows[index], message);
+        }
+    } else {
+        let columns = target_columns(&options.mappings);
+        let values: Vec<Vec<Value>> = values
+            .iter()
+            .map(|row| {
+                columns
+                    .iter()
+                    .map(|column| row.get(column).cloned().unwrap_or(Value::Null))
+                    .collect()
+            })
+            .collect();
+        let statement = sql_edit::insert_rows(options.driver, &options.table, &columns, &values);
+
+        match connection
+            .execute_transaction(&target.database, &[statement])
+            .await
+        {
+            Ok(_) => report.inserted += values.len(),
+            // Insert the rows again one per savepoint to find the ones the server rejects
+            Err(ConnectionError::Failed(_)) => {
+                let statements: Vec<String> = values
+                    .iter()
+                    .map(|values| {
+                        sql_edit::insert_rows(
+                            options.driver,
+                            &options.table,
+                            &columns,
+                            std::slice::from_ref(values),
+                        )
+                    })
+                    .collect();
+                let result = connection
+                    .execute_each(&target.database, &statements, options.stop_on_error)
+                    .await?;
+                report.inserted += result.inserted;
+                if options.stop_on_error && !result.rejected.is_empty() {
+                    report.stopped_at_error = true;
+                }
+                for (index, message) in result.rejected {
+                    report.add_error(rows[index], message);
+                }
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    progress.inserted.store(report.inserted, Ordering::Relaxed);
+    progress.failed.store(report.failed, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Mapped targets in mapping order, the columns of every `INSERT`
+fn target_columns(mappings: &[FieldMapping]) -> Vec<String> {
+    mappings
+        .iter()
+        .filter(|mapping| !mapping.target.is_empty())
+        .map(|mapping| mapping.target.clone())
+        .collect()
+}
+
+/// The document or row to insert for a source record. Documents leave out
+/// fields missing from the record; SQL rows get NULL for them.
+fn convert_record(
+    record: &Map<String, Value>,
+    options: &ImportOptions,
+) -> std::result::Result<Map<String, Value>, String> {
+    let is_document = options.driver == DatabaseType::MongoDB;
+    let infer = options.source.format == ImportFormat::Csv;
+    let mut values = Map::new();
+
+    for mapping in &options.mappings {
+        if mapping.target.is_empty() {
+            continue;
+        }
+        let value = match record.get(&mapping.source) {
+            Some(value) => convert_value(value, mapping.field_type, options.driver, infer)
+                .map_err(|e| format!("{}: {}", mapping.source, e))?,
+            None if is_document => continue,
+            None => Value::Null,
+        };
+        if is_document {
+            insert_path(&mut values, &mapping.target, value)?;
+        } else {
+            values.insert(mapping.target.clone(), value);
+        }
+    }
+    Ok(values)
+}
+
+/// Set a dotted field (`address.city`) in a document, creating the objects on the way
+fn insert_path(
+    document: &mut Map<String, Value>,
+    path: &str,
+    value: Value,
+) -> std::result::Result<(), String> {
+    match path.split_once('.') {
+        None => {
+            document.insert(path.to_string(), value);
+            Ok(())
+        }
+        Some((field, rest)) => match document
+            .entry(field.to_string())
+            .or_insert_with(|| Value::Object(Map::new()))
+        {
+            Value::Object(fields) => insert_path(fields, rest, value),
+            _ => Err(format!(
+                "{} is mapped both as a value and as an object",
+                field
+            )),
+        },
+    }
+}
+
+/// Convert a source value. Values for documents come out as Extended JSON.
+/// `infer` is set for CSV, where every value is text and Auto guesses the type.
+/// Nulls stay null, and so does empty text unless the type is String.
+fn convert_value(
+    value: &Value,
+    field_type: FieldType,
+    driver: DatabaseType,
+    infer: bool,
+) -> std::result::Result<Value, String> {
+    let text = match value {
+        Value::Null => return Ok(Value::Null),
+        Value::String(text) => Some(text.as_str()),
+        _ => None,
+    };
+    let trimmed = text.map(str::trim);
+    if trimmed == Some("") && field_type != FieldType::String {
+        return Ok(Value::Null);
+    }
+    let invalid = |what: &str| {
+        Err(format!(
+            "cannot read {} as {}",
+            trimmed.map_or_else(|| value.to_string(), |text| format!("\"{}\"", text)),
+            what
+        ))
+    };
+
+    match field_type {
+        FieldType::Auto => Ok(match trimmed {
+            Some(text) if infer => infer_value(text),
+            _ => value.clone(),
+        }),
+        FieldType::String => Ok(Value::String(match text {
+            Some(text) => text.to_string(),
+            None => value.to_string(),
+        })),
+        FieldType::Integer => {
+            let integer = match (trimmed, value) {
+                (Some(text), _) => text.parse::<i64>().ok(),
+                (None, Value::Number(n)) => n
+                    .as_i64()
+                    .or_else(|| n.as_f64().filter(|f| f.fract() == 0.0).map(|f| f as i64)),
+                _ => None,
+            };
+            match integer {
+                Some(integer) => Ok(Value::from(integer)),
+                None => invalid("an integer"),
+            }
+        }
+        FieldType::Double => {
+            let double = match (trimmed, value) {
+                (Some(text), _) => text.parse::<f64>().ok(),
+                (None, Value::Number(n)) => n.as_f64(),
+                _ => None,
+            };
+            match double.and_then(serde_json::Number::from_f64) {
+                Some(n) => Ok(Value::Number(n)),
+                None => invalid("a double"),
+            }
+        }
+        FieldType::Boolean => {
+            let boolean = match (trimmed, value) {
+                (Some(text), _) => match text.to_ascii_lowercase().as_str() {
+                    "true" | "t" | "yes" | "y" | "1" => Some(true),
+                    "false" | "f" | "no" | "n" | "0" => Some(false),
+                    _ => None,
+                },
+                (None, Value::Bool(b)) => Some(*b),
+                (None, Value::Number(n)) => {
+                    n.as_i64().filter(|n| *n == 0 || *n == 1).map(|n| n == 1)
+                }
+                _ => None,
+            };
+            match boolean {
+                Some(b) => Ok(Value::Bool(b)),
+                None => invalid("a boolean"),
+            }
+        }
+        FieldType::Date => {
+            let date = match (trimmed, value) {
+                (Some(text), _) => parse_date(text),
+                // Milliseconds since the epoch
+                (None, Value::Number(n)) => n
+                    .as_i64()
+                    .and_then(chrono::DateTime::from_timestamp_millis)
+                    .map(|date| (date.naive_utc(), true)),
+                _ => None,
+            };
+            let Some((date, has_time)) = date else {
+                return invalid("a date");
+            };
+            Ok(if driver == DatabaseType::MongoDB {
+                serde_json::json!({
+                    "$date": { "$numberLong": date.and_utc().timestamp_millis().to_string() }
+                })
+            } else if has_time {
+                Value::String(date.format("%Y-%m-%d %H:%M:%S%.f").to_string())
+            } else {
+                Value::String(date.format("%Y-%m-%d").to_string())
+            })
+        }
+        FieldType::ObjectId => match trimmed {
+            Some(hex) if hex.len() == 24 && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+                Ok(serde_json::json!({ "$oid": hex.to_ascii_lowercase() }))
+            }
+            // Already Extended JSON
+            None if value.get("$oid").is_some() => Ok(value.clone()),
+            _ => invalid("an ObjectId"),
+        },
+        FieldType::Json => match trimmed {
+            Some(text) => serde_json::from_str(text).map_err(|e| format!("invalid JSON: {}", e)),
+            None => Ok(value.clone()),
+        },
+    }
+}
+
+/// CSV text as an integer, a double or a boolean when it reads as one. Numbers
+/// with leading zeros (zip codes, phone numbers) stay text.
+fn infer_value(text: &str) -> Value {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    if digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.") {
+        return Value::String(text.to_string());
+    }
+    if let Ok(integer) = text.parse::<i64>() {
+        return Value::from(integer);
+    }
+    if text.chars().any(|c| c.is_ascii_digit()) {
+        if let Some(n) = text
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+        {
+            return Value::Number(n);
+        }
+    }
+    match text {
+        "true" | "TRUE" | "True" => Value::Bool(true),
+        "false" | "FALSE" | "False" => Value::Bool(false),
+        _ => Value::String(text.to_string()),
+    }
+}
+
+/// Parse a date or date and time (taken as UTC), telling whether it had a time
+fn parse_date(text: &str) -> Option<(chrono::NaiveDateTime, bool)> {
+    if let Ok(date) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some((date.naive_utc(), true));
+    }
+    for format in [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(date) = chrono::NaiveDateTime::parse_from_str(text, format) {
+            return Some((date, true));
+        }
+    }
+    chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| (date, false))
+}
+
+/// A source record keyed by source column, or why it could not be read
+type Record = std::result::Result<Map<String, Value>, String>;
+
+/// Reads the file and counts the bytes read, for the progress
+struct CountingReader<'a> {
+    file: File,
+    progress: &'a ImportProgress,
+}
+
+impl Read for CountingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.file.read(buf)?;
+        self.progress
+            .bytes_read
+            .fetch_add(read as u64, Ordering::Relaxed);
+        Ok(read)
+    }
+}
+
+/// Records of a source file, each an object keyed by source column
+enum Records<'a> {
+    Csv {
+        reader: CsvReader<BufReader<CountingReader<'a>>>,
+        /// Column names; `column1`, `column2`... without a header
+        header: Vec<String>,
+        has_header: bool,
+        row: usize,
+    },
+    JsonLines {
+        reader: BufReader<CountingReader<'a>>,
+        row: usize,
+    },
+    JsonArray {
+        items: std::iter::Enumerate<std::vec::IntoIter<Value>>,
+    },
+}
+
+impl<'a> Records<'a> {
+    fn open(path: &Path, source: &SourceOptions, progress: &'a ImportProgress) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Cannot read {}", path.display()))?;
+        progress
+            .bytes_total
+            .store(file.metadata()?.len(), Ordering::Relaxed);
+        let reader = BufReader::new(CountingReader { file, progress });
+
+        Ok(match source.format {
+            ImportFormat::Csv => {
+                let mut reader = CsvReader {
+                    input: reader,
+                    delimiter: source.delimiter,
+                    started: false,
+                };
+                let header = if source.has_header {
+                    reader.read_record()?.unwrap_or_default()
+                } else {
+                    Vec::new()
+                };
+                Records::Csv {
+                    reader,
+                    header,
+                    has_header: source.has_header,
+                    row: 0,
+                }
+            }
+            ImportFormat::JsonLines => Records::JsonLines { reader, row: 0 },
+            ImportFormat::JsonArray => {
+                let items = match serde_json::from_reader(reader)
+                    .with_context(|| format!("{} is not valid JSON", path.display()))?
+                {
+                    Value::Array(items) => items,
+                    _ => bail!("{} does not hold a JSON array", path.display()),
+                };
+                Records::JsonArray {
+                    items: items.into_iter().enumerate(),
+                }
+            }
+        })
+    }
+
+    /// The next record and its row number, or `None` at the end of the file.
+    /// A record that cannot be read is returned as its error message; reading
+    /// the file itself failing is an error.
+    fn next_record(&mut self) -> Result<Option<(usize, Record)>> {
+        match self {
+            Records::Csv {
+                reader,
+                header,
+                has_header,
+                row,
+            } => loop {
+                let Some(fields) = reader.read_record()? else {
+                    return Ok(None);
+                };
+                // Blank line
+                if fields.len() == 1 && fields[0].is_empty() {
+                    continue;
+                }
+                *row += 1;
+                if *has_header && fields.len() > header.len() {
+                    let message = format!(
+                        "Row has {} fields, the header has {}",
+                        fields.len(),
+                        header.len()
+                    );
+                    return Ok(Some((*row, Err(message))));
+                }
+                while header.len() < fields.len() {
+                    header.push(format!("column{}", header.len() + 1));
+                }
+                let record = header
+                    .iter()
+                    .cloned()
+                    .zip(fields.into_iter().map(Value::String))
+                    .collect();
+                return Ok(Some((*row, Ok(record))));
+            },
+            Records::JsonLines { reader, row } => loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line)? == 0 {
+                    return Ok(None);
+                }
+                if line.trim().is_empty() {
+                    continue;
+                }
+                *row += 1;
+                let record = match serde_json::from_str(&line) {
+                    Ok(Value::Object(record)) => Ok(record),
+                    Ok(_) => Err("A row must be a JSON object".to_string()),
+                    Err(e) => Err(format!("Invalid JSON: {}", e)),
+                };
+                return Ok(Some((*row, record)));
+            },
+            Records::JsonArray { items } => Ok(items.next().map(|(index, item)| {
+                let record = match item {
+                    Value::Object(record) => Ok(record),
+                    _ => Err("A row must be a JSON object".to_string()),
+                };
+                (index + 1, record)
+            })),
+        }
+    }
+}
+
+/// Minimal CSV reader (RFC 4180): fields may be quoted, with `""` for a quote,
+/// and quoted fields may contain the delimiter and line breaks
+struct CsvReader<R> {
+    input: R,
+    delimiter: char,
+    /// The first line was read (and a byte order mark skipped)
+    started: bool,
+}
+
+impl<R: BufRead> CsvReader<R> {
+    /// The fields of the next record, or `None` at the end of the input
+    fn read_record(&mut self) -> io::Result<Option<Vec<String>>> {
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if !self.started {
+            self.started = true;
+            if let Some(rest) = line.strip_prefix('\u{feff}') {
+                line = rest.to_string();
+            }
+        }
+
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        loop {
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                if in_quotes {
+                    if c != '"' {
+                        field.push(c);
+                    } else if chars.peek() == Some(&'"') {
+                        chars.next();
+                        field.push('"');
+                    } else {
+                        in_quotes = false;
+                    }
+                } else if c == '"' && field.is_empty() {
+                    in_quotes = true;
+                } else if c == self.delimiter {
+                    fields.push(std::mem::take(&mut field));
+                } else if c != '\n' && c != '\r' {
+                    field.push(c);
+                }
+            }
+            if !in_quotes {
+                break;
+            }
+            // The quoted field goes on on the next line; an unterminated quote
+            // ends at the end of the input
+            line.clear();
+            if self.input.read_line(&mut line)? == 0 {
+                break;
+            }
+        }
+        fields.push(field);
+        Ok(Some(fields))
+    }
+}
@@ -1,11 +1,27 @@
 //! Database module - connection management and drivers
 
+pub mod aggregation;
 pub mod connection;
+pub mod diagram;
 pub mod driver;
 pub mod drivers;
 pub mod error;
+pub mod explain;
+pub mod export;
+pub mod extended_json;
+pub mod import;
+pub mod registry;
+pub mod runtime;
+pub mod schema;
+pub mod secret_store;
+pub mod sql_edit;
 pub mod storage;
+#[cfg(feature = "ssh")]
+pub mod tunnel;
+pub mod vault;
 
-pub use connection::{Connection, DatabaseType};
+pub use connection::{
+    Connection, DatabaseType, HostKeyCheck, SshAuth, SshTunnelConfig, SslMode, TlsConfig,
+};
 pub use driver::{create_connection, ConnectionConfig};
 pub use storage::ConnectionStorage;
@@ -0,0 +1,163 @@
+//! Registry of live connections keyed by `Connection::id`
+//!
+//! Driver instances hold pooled clients (Mongo `Client`, MySQL `Pool`, cached
+//! PostgreSQL clients and Redis multiplexed connections), so the sidebar, collection
+//! views and consoles share one instance per saved connection instead of reconnecting
+//! on every call. Entries are dropped after a period without use or when the user
+//! disconnects.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+
+use super::driver::{create_connection, ConnectionConfig, DatabaseConnection};
+use super::error::Result;
+
+/// Connections unused for this long are closed
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// How often idle connections are looked for
+const EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+struct RegistryEntry {
+    config: ConnectionConfig,
+    connection: Arc<dyn DatabaseConnection>,
+    last_used: Instant,
+}
+
+#[derive(Default)]
+pub struct ConnectionRegistry {
+    entries: Mutex<HashMap<String, RegistryEntry>>,
+}
+
+static REGISTRY: OnceLock<ConnectionRegistry> = OnceLock::new();
+
+/// The app-wide connection registry
+pub fn registry() -> &'static ConnectionRegistry {
+    REGISTRY.get_or_init(ConnectionRegistry::default)
+}
+
+impl ConnectionRegistry {
+    /// Get the live connection for a saved connection, creating it on first use.
+    /// If the connection was edited (different driver, connection string, TLS or
+    /// SSH settings), the old instance is closed and replaced.
+    ///
+    /// The connection is created without holding the registry lock, since opening
+    /// an SSH tunnel can block for the whole handshake. If another caller created
+    /// a matching connection in the meantime, that one wins and ours is closed.
+    pub async fn get(
+        &self,
+        connection_id: &str,
+        config: ConnectionConfig,
+    ) -> Result<Arc<dyn DatabaseConnection>> {
+        if let Some(connection) = self.lookup(connection_id, &config) {
+            return Ok(connection);
+        }
+
+        let created: Arc<dyn DatabaseConnection> =
+            Arc::from(create_connection(config.clone()).await?);
+
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.get_mut(connection_id) {
+            if same_target(&entry.config, &config) {
+                entry.last_used = Instant::now();
+                let existing = entry.connection.clone();
+                drop(entries);
+                close(created);
+                return Ok(existing);
+            }
+        }
+
+        let replaced = entries.insert(
+            connection_id.to_string(),
+            RegistryEntry {
+                config,
+                connection: created.clone(),
+                last_used: Instant::now(),
+            },
+        );
+        drop(entries);
+        if let Some(old) = replaced {
+            close(old.connection);
+        }
+
+        Ok(created)
+    }
+
+    /// The live connection for a saved connection if it still matches `config`
+    fn lookup(
+        &self,
+        connection_id: &str,
+        config: &ConnectionConfig,
+    ) -> Option<Arc<dyn DatabaseConnection>> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get_mut(connection_id)?;
+        if !same_target(&entry.config, config) {
+            return None;
+        }
+        entry.last_used = Instant::now();
+        Some(entry.connection.clone())
+    }
+
+    /// Close the live connection for a saved connection, if any.
+    /// Returns whether there was one.
+    pub fn disconnect(&self, connection_id: &str) -> bool {
+        let removed = self.entries.lock().unwrap().remove(connection_id);
+        match removed {
+            Some(entry) => {
+                close(entry.connection);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether a live connection exists for a saved connection
+    pub fn is_connected(&self, connection_id: &str) -> bool {
+        self.entries.lock().unwrap().contains_key(connection_id)
+    }
+
+    /// Close every connection that has not been used for `max_idle`
+    fn evict_idle(&self, max_idle: Duration) {
+        let mut entries = self.entries.lock().unwrap();
+        let idle: Vec<String> = entries
+            .iter()
+            .filter(|(_, entry)| entry.last_used.elapsed() >= max_idle)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for id in idle {
+            if let Some(entry) = entries.remove(&id) {
+                close(entry.connection);
+            }
+        }
+    }
+}
+
+/// Whether two configs connect to the same server the same way
+fn same_target(a: &ConnectionConfig, b: &ConnectionConfig) -> bool {
+    a.driver == b.driver
+        && a.connection_string == b.connection_string
+        && a.tls == b.tls
+        && a.ssh == b.ssh
+}
+
+/// Let the driver shut its clients down gracefully. Calls still holding the
+/// connection finish first; the clients are dropped with the last reference.
+fn close(connection: Arc<dyn DatabaseConnection>) {
+    super::runtime::spawn(async move {
+        connection.close().await;
+    });
+}
+
+/// Periodically evict idle connections; called once when the runtime starts
+pub fn start_idle_eviction(runtime: &Runtime) {
+    runtime.spawn(async {
+        let mut interval = tokio::time::interval(EVICTION_INTERVAL);
+        loop {
+            interval.tick().await;
+            registry().evict_idle(IDLE_TIMEOUT);
+        }
+    });
+}
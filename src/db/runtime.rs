@@ -0,0 +1,35 @@
+//! Shared Tokio runtime for all database work
+//!
+//! Drivers keep live clients (whose background tasks run on this runtime), so every
+//! driver call must run here rather than on a throwaway runtime. UI code spawns the
+//! work with [`spawn`] and awaits the returned handle from its own executor.
+
+use std::future::Future;
+use std::sync::OnceLock;
+use tokio::runtime::{Builder, Runtime};
+use tokio::task::JoinHandle;
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+/// The app-wide runtime, created on first use
+pub fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
+        let runtime = Builder::new_multi_thread()
+            .thread_name("chambers-db")
+            .enable_all()
+            .build()
+            .expect("Failed to start database runtime");
+        super::registry::start_idle_eviction(&runtime);
+        runtime
+    })
+}
+
+/// Run a future on the shared runtime. The handle can be awaited from any executor;
+/// it resolves to an error only if the task panicked.
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    runtime().spawn(future)
+}
@@ -0,0 +1,221 @@
+//! Schema of a MongoDB collection, inferred from a random sample of its documents
+//!
+//! Documents are drawn with `$sample` and walked field by field: every dotted path
+//! is recorded with the BSON types it was seen with, the share of documents that
+//! hold it, and a few example values. Fields inside arrays of subdocuments are
+//! recorded under the array's path, as the mongo shell's dot notation reaches them.
+
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// Documents sampled unless another size is chosen
+pub const DEFAULT_SAMPLE_SIZE: u32 = 1_000;
+
+/// Sample sizes offered in the Schema tab
+pub const SAMPLE_SIZES: [u32; 4] = [100, 1_000, 5_000, 10_000];
+
+/// Fields held by fewer than this share of the sampled documents are rare
+pub const RARE_PRESENCE: f64 = 0.1;
+
+/// Distinct example values kept for each field
+const MAX_EXAMPLES: usize = 3;
+
+/// Pipeline drawing `size` random documents
+pub fn sample_pipeline(size: u32) -> Vec<Value> {
+    vec![json!({ "$sample": { "size": size } })]
+}
+
+/// BSON type name of an Extended JSON value
+pub fn bson_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "Null",
+        Value::Bool(_) => "Boolean",
+        Value::Number(n) if n.is_f64() => "Double",
+        Value::Number(_) => "Int32",
+        Value::String(_) => "String",
+        Value::Array(_) => "Array",
+        Value::Object(obj) => [
+            ("$oid", "ObjectId"),
+            ("$date", "Date"),
+            ("$numberLong", "Int64"),
+            ("$numberInt", "Int32"),
+            ("$numberDouble", "Double"),
+            ("$numberDecimal", "Decimal128"),
+            ("$binary", "Binary"),
+            ("$timestamp", "Timestamp"),
+            ("$regularExpression", "Regex"),
+        ]
+        .iter()
+        .find(|(key, _)| obj.contains_key(*key))
+        .map_or("Object", |(_, name)| name),
+    }
+}
+
+/// One field path seen in the sample
+#[derive(Debug, Clone)]
+pub struct FieldSchema {
+    /// Dotted path, e.g. `address.city`
+    pub path: String,
+    /// Number of parent fields: 0 for top-level fields
+    pub depth: usize,
+    /// Sampled documents holding the field
+    pub count: usize,
+    /// Types seen and the number of documents holding each, most common first
+    pub types: Vec<(&'static str, usize)>,
+    /// A few distinct scalar values
+    pub examples: Vec<Value>,
+}
+
+impl FieldSchema {
+    /// Holds values of more than one type; a missing value stored as null does
+    /// not count as a conflict
+    pub fn has_type_conflict(&self) -> bool {
+        self.types
+            .iter()
+            .filter(|(name, _)| *name != "Null")
+            .count()
+            > 1
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SchemaAnalysis {
+    /// Documents actually sampled; smaller than requested for small collections
+    pub sample_size: usize,
+    /// Parents come before their children, `_id` first
+    pub fields: Vec<FieldSchema>,
+}
+
+#[derive(Default)]
+struct FieldStats {
+    count: usize,
+    types: HashMap<&'static str, usize>,
+    examples: Vec<Value>,
+}
+
+impl SchemaAnalysis {
+    pub fn analyze(documents: &[Value]) -> Self {
+        let mut stats: BTreeMap<Vec<String>, FieldStats> = BTreeMap::new();
+        let mut sample_size = 0;
+
+        for document in documents {
+            let Value::Object(map) = document else {
+                continue;
+            };
+            sample_size += 1;
+
+            // A field inside an array counts once per document, whatever the
+            // number of elements holding it
+            let mut seen = HashMap::new();
+            walk(map, &[], &mut seen);
+            for (path, types) in seen {
+                let field = stats.entry(path).or_default();
+                field.count += 1;
+                for (name, example) in types {
+                    *field.types.entry(name).or_default() += 1;
+                    if let Some(example) = example {
+                        if field.examples.len() < MAX_EXAMPLES && !field.examples.contains(&example)
+                        {
+                            field.examples.push(example);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut fields: Vec<(Vec<String>, FieldStats)> = stats.into_iter().collect();
+        fields.sort_by(|(a, _), (b, _)| (a[0] != "_id", a).cmp(&(b[0] != "_id", b)));
+
+        let fields = fields
+            .into_iter()
+            .map(|(path, field)| {
+                let mut types: Vec<(&'static str, usize)> = field.types.into_iter().collect();
+                types.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+                FieldSchema {
+                    path: path.join("."),
+                    depth: path.len() - 1,
+                    count: field.count,
+                    types,
+                    examples: field.examples,
+                }
+            })
+            .collect();
+
+        Self {
+            sample_size,
+            fields,
+        }
+    }
+
+    /// Share of the sampled documents holding the field, from 0 to 1
+    pub fn presence(&self, field: &FieldSchema) -> f64 {
+        if self.sample_size == 0 {
+            return 0.0;
+        }
+        field.count as f64 / self.sample_size as f64
+    }
+
+    pub fn is_rare(&self, field: &FieldSchema) -> bool {
+        self.presence(field) < RARE_PRESENCE
+    }
+
+    pub fn conflict_count(&self) -> usize {
+        self.fields
+            .iter()
+            .filter(|field| field.has_type_conflict())
+            .count()
+    }
+
+    pub fn rare_count(&self) -> usize {
+        self.fields
+            .iter()
+            .filter(|field| self.is_rare(field))
+            .count()
+    }
+
+    /// Top-level fields, `_id` first, as columns of the documents table
+    pub fn columns(&self) -> Vec<String> {
+        self.fields
+            .iter()
+            .filter(|field| field.depth == 0)
+            .map(|field| field.path.clone())
+            .collect()
+    }
+}
+
+/// Types seen for each path of one document, with one scalar example per type
+type DocumentFields = HashMap<Vec<String>, HashMap<&'static str, Option<Value>>>;
+
+fn walk(map: &serde_json::Map<String, Value>, parent: &[String], seen: &mut DocumentFields) {
+    for (key, value) in map {
+        let mut path = parent.to_vec();
+        path.push(key.clone());
+        record(value, &path, seen);
+    }
+}
+
+fn record(value: &Value, path: &[String], seen: &mut DocumentFields) {
+    let name = bson_type_name(value);
+    let example = match name {
+        "Object" | "Array" | "Null" => None,
+        _ => Some(value.clone()),
+    };
+    seen.entry(path.to_vec())
+        .or_default()
+        .entry(name)
+        .or_insert(example);
+
+    match value {
+        Value::Object(map) if name == "Object" => walk(map, path, seen),
+        Value::Array(items) => {
+            for item in items {
+                if let Value::Object(map) = item {
+                    if bson_type_name(item) == "Object" {
+                        walk(map, path, seen);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
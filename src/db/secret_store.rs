@@ -0,0 +1,227 @@
+//! Pluggable storage for connection secrets
+//!
+//! `ConnectionStorage` keeps passwords and connection strings out of the
+//! `connections` table and hands them to a [`SecretStore`]: the desktop keyring via
+//! the freedesktop Secret Service API where available, or the `secrets` table
+//! encrypted under the master passphrase (see [`super::vault`]).
+
+use anyhow::Result;
+use rusqlite::{params, Connection as SqliteConnection};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::vault::{Secrets, VaultKey};
+
+/// Where connection secrets are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretBackend {
+    /// Desktop keyring (freedesktop Secret Service)
+    Keyring,
+    /// `secrets` table encrypted under the master passphrase
+    EncryptedFile,
+}
+
+impl SecretBackend {
+    /// Short description for the UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            SecretBackend::Keyring => "System keyring",
+            SecretBackend::EncryptedFile => "Encrypted file",
+        }
+    }
+
+    /// Value persisted in the settings table
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecretBackend::Keyring => "keyring",
+            SecretBackend::EncryptedFile => "file",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "keyring" => Some(SecretBackend::Keyring),
+            "file" => Some(SecretBackend::EncryptedFile),
+            _ => None,
+        }
+    }
+}
+
+/// Storage for the secret fields of saved connections, keyed by connection id
+pub trait SecretStore: Send + Sync {
+    fn backend(&self) -> SecretBackend;
+
+    /// Secrets of every connection that has any
+    fn get_all(&self) -> Result<HashMap<String, Secrets>>;
+
+    /// Store a connection's secrets, replacing what was there.
+    /// Empty secrets remove the entry.
+    fn set(&self, connection_id: &str, secrets: &Secrets) -> Result<()>;
+
+    fn delete(&self, connection_id: &str) -> Result<()>;
+}
+
+/// Secrets encrypted with the master passphrase key, stored next to the connections
+pub struct FileSecretStore {
+    conn: Arc<Mutex<SqliteConnection>>,
+    key: VaultKey,
+}
+
+impl FileSecretStore {
+    pub fn new(conn: Arc<Mutex<SqliteConnection>>, key: VaultKey) -> Self {
+        Self { conn, key }
+    }
+}
+
+impl SecretStore for FileSecretStore {
+    fn backend(&self) -> SecretBackend {
+        SecretBackend::EncryptedFile
+    }
+
+    fn get_all(&self) -> Result<HashMap<String, Secrets>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT connection_id, blob FROM secrets")?;
+        let blobs: Vec<(String, Vec<u8>)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut secrets = HashMap::new();
+        for (id, blob) in blobs {
+            match self.key.open(&id, &blob) {
+                Ok(s) => {
+                    secrets.insert(id, s);
+                }
+                Err(e) => eprintln!("Failed to read secrets for connection {}: {}", id, e),
+            }
+        }
+        Ok(secrets)
+    }
+
+    fn set(&self, connection_id: &str, secrets: &Secrets) -> Result<()> {
+        if secrets.is_empty() {
+            return self.delete(connection_id);
+        }
+        let blob = self.key.seal(connection_id, secrets)?;
+        self.conn.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO secrets (connection_id, blob) VALUES (?1, ?2)",
+            params![connection_id, blob],
+        )?;
+        Ok(())
+    }
+
+    fn delete(&self, connection_id: &str) -> Result<()> {
+        self.conn.lock().unwrap().execute(
+            "DELETE FROM secrets WHERE connection_id = ?1",
+            params![connection_id],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "keyring"))]
+pub use keyring::KeyringSecretStore;
+
+#[cfg(all(target_os = "linux", feature = "keyring"))]
+mod keyring {
+    use anyhow::{anyhow, Result};
+    use secret_service::blocking::{Collection, SecretService};
+    use secret_service::EncryptionType;
+    use std::collections::HashMap;
+
+    use super::{SecretBackend, SecretStore};
+    use crate::db::vault::Secrets;
+
+    /// Attribute marking items created by this app
+    const APPLICATION: &str = "chambers";
+
+    /// Secrets kept in the default collection of the desktop keyring (GNOME Keyring,
+    /// KWallet, KeePassXC...) through the Secret Service D-Bus API. One item per
+    /// connection holds its secrets as JSON.
+    pub struct KeyringSecretStore;
+
+    impl KeyringSecretStore {
+        /// Connect to the Secret Service, failing if no keyring is running
+        pub fn connect() -> Result<Self> {
+            Self::with_collection(|_| Ok(()))?;
+            Ok(Self)
+        }
+
+        /// Run `f` on the default collection, unlocking it first if needed.
+        /// A new session per call keeps the store `Send + Sync`; calls are rare.
+        fn with_collection<T>(f: impl FnOnce(&Collection) -> Result<T>) -> Result<T> {
+            let service = SecretService::connect(EncryptionType::Dh)?;
+            let collection = service.get_default_collection()?;
+            if collection.is_locked()? {
+                // Shows the keyring's own unlock dialog
+                collection.unlock()?;
+            }
+            f(&collection)
+        }
+    }
+
+    impl SecretStore for KeyringSecretStore {
+        fn backend(&self) -> SecretBackend {
+            SecretBackend::Keyring
+        }
+
+        fn get_all(&self) -> Result<HashMap<String, Secrets>> {
+            Self::with_collection(|collection| {
+                let items =
+                    collection.search_items(HashMap::from([("application", APPLICATION)]))?;
+                let mut secrets = HashMap::new();
+                for item in items {
+                    let attributes = item.get_attributes()?;
+                    let Some(id) = attributes.get("connection_id") else {
+                        continue;
+                    };
+                    if item.is_locked()? {
+                        item.unlock()?;
+                    }
+                    match serde_json::from_slice::<Secrets>(&item.get_secret()?) {
+                        Ok(s) => {
+                            secrets.insert(id.clone(), s);
+                        }
+                        Err(e) => eprintln!("Ignoring keyring item for connection {}: {}", id, e),
+                    }
+                }
+                Ok(secrets)
+            })
+        }
+
+        fn set(&self, connection_id: &str, secrets: &Secrets) -> Result<()> {
+            if secrets.is_empty() {
+                return self.delete(connection_id);
+            }
+            let json = serde_json::to_vec(secrets)?;
+            Self::with_collection(|collection| {
+                collection
+                    .create_item(
+                        &format!("Chambers connection {}", connection_id),
+                        HashMap::from([
+                            ("application", APPLICATION),
+                            ("connection_id", connection_id),
+                        ]),
+                        &json,
+                        true,
+                        "application/json",
+                    )
+                    .map_err(|e| anyhow!("Failed to save to keyring: {}", e))?;
+                Ok(())
+            })
+        }
+
+        fn delete(&self, connection_id: &str) -> Result<()> {
+            Self::with_collection(|collection| {
+                let items = collection.search_items(HashMap::from([
+                    ("application", APPLICATION),
+                    ("connection_id", connection_id),
+                ]))?;
+                for item in items {
+                    item.delete()?;
+                }
+                Ok(())
+            })
+        }
+    }
+}
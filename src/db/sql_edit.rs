@@ -0,0 +1,156 @@
+//! SQL statements for rows edited in the table view
+//!
+//! Staged edits are written out as plain `UPDATE`/`INSERT`/`DELETE` statements
+//! with literal values, so the exact SQL can be previewed before it is committed.
+//! Edited values are sent as string literals and left to the server to convert
+//! to the column type, as in a hand-written statement.
+
+use serde_json::Value;
+
+use super::connection::DatabaseType;
+
+/// A staged change to one row of a SQL table. Rows are identified by the values
+/// of their primary key columns.
+pub enum RowChange<'a> {
+    Update {
+        key: &'a serde_json::Map<String, Value>,
+        values: &'a serde_json::Map<String, Value>,
+    },
+    Insert {
+        values: &'a serde_json::Map<String, Value>,
+    },
+    Delete {
+        key: &'a serde_json::Map<String, Value>,
+    },
+}
+
+/// Quote an identifier for the given driver
+pub fn quote_identifier(driver: DatabaseType, name: &str) -> String {
+    match driver {
+        DatabaseType::MySQL => format!("`{}`", name.replace('`', "``")),
+        _ => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+/// Qualified table name, matching how each driver names its collections:
+/// `schema.table` for PostgreSQL, the table within the database otherwise
+pub fn table_reference(driver: DatabaseType, database_name: &str, collection_name: &str) -> String {
+    match driver {
+        DatabaseType::PostgreSQL => match collection_name.split_once('.') {
+            Some((schema, table)) => format!(
+                "{}.{}",
+                quote_identifier(driver, schema),
+                quote_identifier(driver, table)
+            ),
+            None => quote_identifier(driver, collection_name),
+        },
+        _ => format!(
+            "{}.{}",
+            quote_identifier(driver, database_name),
+            quote_identifier(driver, collection_name)
+        ),
+    }
+}
+
+/// Write a JSON value as a SQL literal
+pub fn literal(driver: DatabaseType, value: &Value) -> String {
+    let text = match value {
+        Value::Null => return "NULL".to_string(),
+        Value::Bool(b) => return if *b { "TRUE" } else { "FALSE" }.to_string(),
+        Value::Number(n) => return n.to_string(),
+        Value::String(s) => s.clone(),
+        // JSON columns
+        Value::Array(_) | Value::Object(_) => value.to_string(),
+    };
+    let mut escaped = text.replace('\'', "''");
+    // MySQL treats backslashes in string literals as escapes by default
+    if driver == DatabaseType::MySQL {
+        escaped = escaped.replace('\\', "\\\\");
+    }
+    format!("'{}'", escaped)
+}
+
+/// The statement that applies a change to `table` (see `table_reference`)
+pub fn statement(driver: DatabaseType, table: &str, change: &RowChange) -> String {
+    match change {
+        RowChange::Update { key, values } => format!(
+            "UPDATE {} SET {} WHERE {}",
+            table,
+            values
+                .iter()
+                .map(|(column, value)| format!(
+                    "{} = {}",
+                    quote_identifier(driver, column),
+                    literal(driver, value)
+                ))
+                .collect::<Vec<_>>()
+                .join(", "),
+            key_condition(driver, key)
+        ),
+        RowChange::Insert { values } if values.is_empty() => match driver {
+            DatabaseType::MySQL => format!("INSERT INTO {} () VALUES ()", table),
+            _ => format!("INSERT INTO {} DEFAULT VALUES", table),
+        },
+        RowChange::Insert { values } => format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table,
+            values
+                .keys()
+                .map(|column| quote_identifier(driver, column))
+                .collect::<Vec<_>>()
+                .join(", "),
+            values
+                .values()
+                .map(|value| literal(driver, value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        RowChange::Delete { key } => {
+            format!("DELETE FROM {} WHERE {}", table, key_condition(driver, key))
+        }
+    }
+}
+
+/// One `INSERT` of several rows into `table`, each row holding a value for
+/// every column in `columns`
+pub fn insert_rows(
+    driver: DatabaseType,
+    table: &str,
+    columns: &[String],
+    rows: &[Vec<Value>],
+) -> String {
+    format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        table,
+        columns
+            .iter()
+            .map(|column| quote_identifier(driver, column))
+            .collect::<Vec<_>>()
+            .join(", "),
+        rows.iter()
+            .map(|row| format!(
+                "({})",
+                row.iter()
+                    .map(|value| literal(driver, value))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// `WHERE` condition matching a row by its primary key values
+fn key_condition(driver: DatabaseType, key: &serde_json::Map<String, Value>) -> String {
+    key.iter()
+        .map(|(column, value)| {
+            let column = quote_identifier(driver, column);
+            if value.is_null() {
+                format!("{} IS NULL", column)
+            } else {
+                format!("{} = {}", column, literal(driver, value))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
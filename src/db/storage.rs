@@ -1,13 +1,27 @@
-use anyhow::Result;
-use rusqlite::{params, Connection as SqliteConnection};
+use anyhow::{anyhow, Result};
+use rusqlite::{params, Connection as SqliteConnection, OptionalExtension};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
-use super::connection::{Connection, DatabaseType};
+use super::connection::{Connection, DatabaseType, SshTunnelConfig, SslMode, TlsConfig};
+use super::secret_store::{FileSecretStore, SecretBackend, SecretStore};
+use super::vault::{KdfParams, Secrets, VaultKey, VaultState};
 
-/// SQLite-based storage for database connections
+/// Number of console commands remembered per connection
+const COMMAND_HISTORY_LIMIT: i64 = 500;
+
+/// Settings key holding the chosen `SecretBackend`
+const SECRET_BACKEND_SETTING: &str = "secret_backend";
+
+/// SQLite-based storage for database connections.
+/// Passwords and connection strings are kept in a `SecretStore` (the desktop keyring
+/// or the encrypted `secrets` table) and are only readable once one has been opened.
+///
+/// Lock order: `secret_store` before `conn`. The file store locks `conn` itself, so
+/// `conn` must not be held while calling into a store.
 pub struct ConnectionStorage {
     conn: Arc<Mutex<SqliteConnection>>,
+    secret_store: Mutex<Option<Box<dyn SecretStore>>>,
 }
 
 impl ConnectionStorage {
@@ -35,7 +49,13 @@ impl ConnectionStorage {
                 password TEXT,
                 connection_string TEXT,
                 visible_databases TEXT,
-                show_all_databases INTEGER
+                show_all_databases INTEGER,
+                ask_password INTEGER,
+                ssl_mode TEXT,
+                ssl_ca_cert TEXT,
+                ssl_client_cert TEXT,
+                ssl_client_key TEXT,
+                ssh_tunnel TEXT
             )",
             [],
         )?;
@@ -76,8 +96,88 @@ impl ConnectionStorage {
             .ok();
         }
 
+        // Migration: add ask_password column if it doesn't exist
+        let has_ask_password: bool = conn
+            .prepare("SELECT ask_password FROM connections LIMIT 1")
+            .is_ok();
+        if !has_ask_password {
+            conn.execute(
+                "ALTER TABLE connections ADD COLUMN ask_password INTEGER",
+                [],
+            )
+            .ok();
+        }
+
+        // Migration: add TLS columns if they don't exist
+        for column in [
+            "ssl_mode",
+            "ssl_ca_cert",
+            "ssl_client_cert",
+            "ssl_client_key",
+        ] {
+            let has_column: bool = conn
+                .prepare(&format!("SELECT {} FROM connections LIMIT 1", column))
+                .is_ok();
+            if !has_column {
+                conn.execute(
+                    &format!("ALTER TABLE connections ADD COLUMN {} TEXT", column),
+                    [],
+                )
+                .ok();
+            }
+        }
+
+        // Migration: add ssh_tunnel column if it doesn't exist
+        let has_ssh_tunnel: bool = conn
+            .prepare("SELECT ssh_tunnel FROM connections LIMIT 1")
+            .is_ok();
+        if !has_ssh_tunnel {
+            conn.execute("ALTER TABLE connections ADD COLUMN ssh_tunnel TEXT", [])
+                .ok();
+        }
+
+        // Console command history, newest rows have the highest id
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS command_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                connection_id TEXT NOT NULL,
+                command TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Master passphrase parameters (single row) and encrypted connection secrets
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS vault (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                salt BLOB NOT NULL,
+                m_cost INTEGER NOT NULL,
+                t_cost INTEGER NOT NULL,
+                p_cost INTEGER NOT NULL,
+                verifier BLOB NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS secrets (
+                connection_id TEXT PRIMARY KEY,
+                blob BLOB NOT NULL
+            )",
+            [],
+        )?;
+
+        // App-wide key/value settings
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+            [],
+        )?;
+
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            secret_store: Mutex::new(None),
         })
     }
 
@@ -89,12 +189,13 @@ impl ConnectionStorage {
 
     /// Get all saved connections
     pub fn get_all(&self) -> Result<Vec<Connection>> {
+        let store = self.secret_store.lock().unwrap();
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, name, db_type, host, port, database, username, password, connection_string, visible_databases, show_all_databases FROM connections",
+            "SELECT id, name, db_type, host, port, database, username, password, connection_string, visible_databases, show_all_databases, ask_password, ssl_mode, ssl_ca_cert, ssl_client_cert, ssl_client_key, ssh_tunnel FROM connections",
         )?;
 
-        let connections = stmt
+        let mut connections: Vec<Connection> = stmt
             .query_map([], |row| {
                 let db_type_str: String = row.get(2)?;
                 let db_type = match db_type_str.as_str() {
@@ -115,6 +216,24 @@ impl ConnectionStorage {
                 let show_all_int: Option<i32> = row.get(10)?;
                 let show_all_databases = show_all_int.map(|v| v != 0);
 
+                let ask_password_int: Option<i32> = row.get(11)?;
+
+                let ssl_mode: Option<String> = row.get(12)?;
+                let tls = TlsConfig {
+                    ssl_mode: ssl_mode
+                        .as_deref()
+                        .and_then(SslMode::parse)
+                        .unwrap_or_default(),
+                    ca_cert_path: row.get(13)?,
+                    client_cert_path: row.get(14)?,
+                    client_key_path: row.get(15)?,
+                };
+
+                // SSH tunnel settings are stored as JSON, without the password
+                let ssh_json: Option<String> = row.get(16)?;
+                let ssh: Option<SshTunnelConfig> =
+                    ssh_json.and_then(|json| serde_json::from_str(&json).ok());
+
                 Ok(Connection {
                     id: row.get(0)?,
                     name: row.get(1)?,
@@ -127,17 +246,51 @@ impl ConnectionStorage {
                     connection_string: row.get(8)?,
                     visible_databases,
                     show_all_databases,
+                    ask_password: ask_password_int.is_some_and(|v| v != 0),
+                    tls,
+                    ssh,
                 })
             })?
             .filter_map(|r| r.ok())
             .collect();
 
+        drop(stmt);
+        drop(conn);
+
+        // Fill in secrets from the secret store
+        if let Some(store) = store.as_ref() {
+            let mut secrets = store.get_all()?;
+            for connection in &mut connections {
+                if let Some(s) = secrets.remove(&connection.id) {
+                    connection.password = s.password;
+                    connection.connection_string = s.connection_string;
+                    if let Some(ssh) = connection.ssh.as_mut() {
+                        ssh.password = s.ssh_password;
+                    }
+                }
+            }
+        }
+
         Ok(connections)
     }
 
-    /// Save a connection (insert or update)
+    /// Save a connection (insert or update). Its secrets go to the secret store,
+    /// so this fails while the store is locked. With `ask_password` set the
+    /// password is not stored at all.
     pub fn save(&self, connection: &Connection) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
+        let store = self.secret_store.lock().unwrap();
+        let store = store
+            .as_ref()
+            .ok_or_else(|| anyhow!("Credential store is locked"))?;
+        let secrets = Secrets {
+            password: if connection.ask_password {
+                None
+            } else {
+                connection.password.clone()
+            },
+            connection_string: connection.connection_string.clone(),
+            ssh_password: connection.ssh.as_ref().and_then(|ssh| ssh.password.clone()),
+        };
 
         // Serialize visible_databases to JSON
         let visible_databases_json: Option<String> = connection
@@ -149,9 +302,17 @@ impl ConnectionStorage {
         let show_all_int: Option<i32> =
             connection.show_all_databases.map(|b| if b { 1 } else { 0 });
 
-        conn.execute(
-            "INSERT OR REPLACE INTO connections (id, name, db_type, host, port, database, username, password, connection_string, visible_databases, show_all_databases)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        let ssh_json: Option<String> = connection.ssh.as_ref().map(|ssh| {
+            let settings = SshTunnelConfig {
+                password: None,
+                ..ssh.clone()
+            };
+            serde_json::to_string(&settings).unwrap_or_default()
+        });
+
+        self.conn.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO connections (id, name, db_type, host, port, database, username, password, connection_string, visible_databases, show_all_databases, ask_password, ssl_mode, ssl_ca_cert, ssl_client_cert, ssl_client_key, ssh_tunnel)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, NULL, NULL, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
             params![
                 connection.id,
                 connection.name,
@@ -160,19 +321,62 @@ impl ConnectionStorage {
                 connection.port,
                 connection.database,
                 connection.username,
-                connection.password,
-                connection.connection_string,
                 visible_databases_json,
                 show_all_int,
+                connection.ask_password as i32,
+                connection.tls.ssl_mode.name(),
+                connection.tls.ca_cert_path,
+                connection.tls.client_cert_path,
+                connection.tls.client_key_path,
+                ssh_json,
             ],
         )?;
-        Ok(())
+        store.set(&connection.id, &secrets)
     }
 
     /// Delete a connection by ID
     pub fn delete(&self, id: &str) -> Result<()> {
+        let store = self.secret_store.lock().unwrap();
+        {
+            let conn = self.conn.lock().unwrap();
+            conn.execute("DELETE FROM connections WHERE id = ?1", params![id])?;
+            conn.execute(
+                "DELETE FROM command_history WHERE connection_id = ?1",
+                params![id],
+            )?;
+        }
+        if let Some(store) = store.as_ref() {
+            store.delete(id)?;
+        }
+        Ok(())
+    }
+
+    /// Get the console command history for a connection, oldest first
+    pub fn get_command_history(&self, connection_id: &str) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT command FROM command_history WHERE connection_id = ?1 ORDER BY id")?;
+        let history = stmt
+            .query_map(params![connection_id], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(history)
+    }
+
+    /// Append a command to a connection's history, keeping only the newest
+    /// `COMMAND_HISTORY_LIMIT` entries
+    pub fn add_command_history(&self, connection_id: &str, command: &str) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM connections WHERE id = ?1", params![id])?;
+        conn.execute(
+            "INSERT INTO command_history (connection_id, command) VALUES (?1, ?2)",
+            params![connection_id, command],
+        )?;
+        conn.execute(
+            "DELETE FROM command_history WHERE connection_id = ?1 AND id NOT IN (
+                SELECT id FROM command_history WHERE connection_id = ?1 ORDER BY id DESC LIMIT ?2
+            )",
+            params![connection_id, COMMAND_HISTORY_LIMIT],
+        )?;
         Ok(())
     }
 
@@ -195,4 +399,222 @@ impl ConnectionStorage {
         )?;
         Ok(())
     }
+
+    /// Whether a secret store has been opened this session, or whether the
+    /// master passphrase still needs to be entered (or chosen)
+    pub fn vault_state(&self) -> VaultState {
+        if self.secret_store.lock().unwrap().is_some() {
+            return VaultState::Unlocked;
+        }
+        match Self::load_vault(&self.conn.lock().unwrap()) {
+            Ok(Some(_)) => VaultState::Locked,
+            _ => VaultState::Uninitialized,
+        }
+    }
+
+    /// Backend of the open secret store, if any
+    pub fn secret_backend(&self) -> Option<SecretBackend> {
+        self.secret_store
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|store| store.backend())
+    }
+
+    /// Backend chosen the last time a store was opened
+    pub fn preferred_secret_backend(&self) -> Option<SecretBackend> {
+        let conn = self.conn.lock().unwrap();
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![SECRET_BACKEND_SETTING],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten();
+        value.as_deref().and_then(SecretBackend::parse)
+    }
+
+    /// Whether this build can store secrets in the desktop keyring
+    pub fn keyring_supported() -> bool {
+        cfg!(all(target_os = "linux", feature = "keyring"))
+    }
+
+    /// Keep secrets in the desktop keyring. Fails if no Secret Service is running
+    /// (e.g. headless sessions); the encrypted file store is the fallback.
+    pub fn use_keyring(&self) -> Result<()> {
+        self.open_store(Self::keyring_store()?)
+    }
+
+    /// Move the secrets of the encrypted file into the desktop keyring, which keeps
+    /// them from then on. Fails if the passphrase is wrong or the keyring cannot be
+    /// reached, leaving the file as it was.
+    pub fn move_vault_to_keyring(&self, passphrase: &str) -> Result<()> {
+        let file = FileSecretStore::new(self.conn.clone(), self.vault_key(passphrase)?);
+        let keyring = Self::keyring_store()?;
+
+        // Write everything to the keyring before deleting anything
+        for (id, secrets) in file.get_all()? {
+            keyring.set(&id, &secrets)?;
+        }
+        Self::delete_vault(&self.conn.lock().unwrap())?;
+        self.open_store(keyring)
+    }
+
+    /// Create the credential store with a new master passphrase, then move any
+    /// plaintext secrets left by older versions into it
+    pub fn create_vault(&self, passphrase: &str) -> Result<()> {
+        let params = KdfParams::generate();
+        let key = VaultKey::derive(passphrase, &params)?;
+        let verifier = key.verifier()?;
+
+        self.conn.lock().unwrap().execute(
+            "INSERT INTO vault (id, salt, m_cost, t_cost, p_cost, verifier) VALUES (1, ?1, ?2, ?3, ?4, ?5)",
+            params![params.salt, params.m_cost, params.t_cost, params.p_cost, verifier],
+        )?;
+        self.open_store(Box::new(FileSecretStore::new(self.conn.clone(), key)))
+    }
+
+    /// Unlock the credential store. Fails if the passphrase is wrong.
+    pub fn unlock_vault(&self, passphrase: &str) -> Result<()> {
+        let key = self.vault_key(passphrase)?;
+        self.open_store(Box::new(FileSecretStore::new(self.conn.clone(), key)))
+    }
+
+    /// Forget the master passphrase. Every password and connection string in the
+    /// encrypted file is deleted; the connections themselves are kept.
+    pub fn reset_vault(&self) -> Result<()> {
+        let mut store = self.secret_store.lock().unwrap();
+        Self::delete_vault(&self.conn.lock().unwrap())?;
+        if store
+            .as_ref()
+            .is_some_and(|s| s.backend() == SecretBackend::EncryptedFile)
+        {
+            *store = None;
+        }
+        Ok(())
+    }
+
+    /// Number of connections whose password or connection string is still stored in plain text
+    pub fn plaintext_secret_count(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM connections WHERE password IS NOT NULL OR connection_string IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Make `store` the place secrets are read from and written to, and remember the choice
+    fn open_store(&self, store: Box<dyn SecretStore>) -> Result<()> {
+        self.migrate_plaintext_secrets(store.as_ref())?;
+        self.conn.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            params![SECRET_BACKEND_SETTING, store.backend().as_str()],
+        )?;
+        *self.secret_store.lock().unwrap() = Some(store);
+        Ok(())
+    }
+
+    /// Derive the key of the credential store. Fails if the passphrase is wrong.
+    fn vault_key(&self, passphrase: &str) -> Result<VaultKey> {
+        let (params, verifier) = Self::load_vault(&self.conn.lock().unwrap())?
+            .ok_or_else(|| anyhow!("No master passphrase has been set"))?;
+
+        // Derive without holding the lock; this takes a while
+        let key = VaultKey::derive(passphrase, &params)?;
+        if !key.matches_verifier(&verifier) {
+            return Err(anyhow!("Incorrect passphrase"));
+        }
+        Ok(key)
+    }
+
+    #[cfg(all(target_os = "linux", feature = "keyring"))]
+    fn keyring_store() -> Result<Box<dyn SecretStore>> {
+        Ok(Box::new(super::secret_store::KeyringSecretStore::connect()?))
+    }
+
+    #[cfg(not(all(target_os = "linux", feature = "keyring")))]
+    fn keyring_store() -> Result<Box<dyn SecretStore>> {
+        Err(anyhow!("Keyring support is not available in this build"))
+    }
+
+    /// Delete the encrypted secrets together with the passphrase verifier
+    fn delete_vault(conn: &SqliteConnection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM secrets", [])?;
+        tx.execute("DELETE FROM vault", [])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn load_vault(conn: &SqliteConnection) -> Result<Option<(KdfParams, Vec<u8>)>> {
+        let vault = conn
+            .query_row(
+                "SELECT salt, m_cost, t_cost, p_cost, verifier FROM vault WHERE id = 1",
+                [],
+                |row| {
+                    Ok((
+                        KdfParams {
+                            salt: row.get(0)?,
+                            m_cost: row.get(1)?,
+                            t_cost: row.get(2)?,
+                            p_cost: row.get(3)?,
+                        },
+                        row.get(4)?,
+                    ))
+                },
+            )
+            .optional()?;
+        Ok(vault)
+    }
+
+    /// Move secrets still stored in the `connections` table into `store` and clear the plaintext
+    fn migrate_plaintext_secrets(&self, store: &dyn SecretStore) -> Result<()> {
+        let plaintext: Vec<(String, Secrets)> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT id, password, connection_string FROM connections
+                 WHERE password IS NOT NULL OR connection_string IS NOT NULL",
+            )?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get(0)?,
+                        Secrets {
+                            password: row.get(1)?,
+                            connection_string: row.get(2)?,
+                            ssh_password: None,
+                        },
+                    ))
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
+            rows
+        };
+        if plaintext.is_empty() {
+            return Ok(());
+        }
+
+        // Write everything to the store before clearing anything
+        for (id, secrets) in &plaintext {
+            store.set(id, secrets)?;
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        for (id, _) in &plaintext {
+            tx.execute(
+                "UPDATE connections SET password = NULL, connection_string = NULL WHERE id = ?1",
+                params![id],
+            )?;
+        }
+        tx.commit()?;
+
+        // Rewrite the file so the old plaintext does not linger in free pages
+        conn.execute("VACUUM", [])?;
+        Ok(())
+    }
 }
@@ -0,0 +1,983 @@
+//! SSH tunnels to databases behind a jump host
+//!
+//! [`SshTunnel`] logs into the jump host, listens on a random local port and
+//! forwards every connection accepted there to the database through a
+//! `direct-tcpip` channel. `create_connection` points the driver's connection
+//! string at that port, so the drivers themselves know nothing about SSH.
+//!
+//! libssh2 is blocking, so each tunnel serves its channels from its own thread.
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use ssh2::{CheckResult, HashType, HostKeyType, KnownHostFileKind, Session};
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use url::Url;
+
+use super::connection::{DatabaseType, HostKeyCheck, SshAuth, SshTunnelConfig};
+use super::driver::{
+    BatchInsertResult, CollectionInfo, ColumnInfo, ConnectionInfo, DatabaseConnection,
+    DatabaseInfo, IndexBuildProgress, IndexInfo, IndexSpec, KeyspaceCursor, KeyspacePage,
+    QueryResult, RespValue, ServerStatus, StorageStats, TableStructure,
+};
+use super::error::{ConnectionError, Result};
+use super::explain::QueryPlan;
+
+/// Shortest and longest sleep of the forwarding loop while no data moves
+const MIN_IDLE_SLEEP: Duration = Duration::from_millis(1);
+const MAX_IDLE_SLEEP: Duration = Duration::from_millis(10);
+
+/// Keepalive interval, so idle tunnels survive NAT and firewall timeouts
+const KEEPALIVE_INTERVAL_SECS: u32 = 30;
+
+const BUFFER_SIZE: usize = 32 * 1024;
+
+/// Database host and port parsed from a connection string, as seen from the jump host
+pub struct TunnelTarget {
+    url: Url,
+    pub host: String,
+    pub port: u16,
+}
+
+impl TunnelTarget {
+    pub fn parse(driver: DatabaseType, connection_string: &str) -> Result<Self> {
+        if driver == DatabaseType::SQLite {
+            return Err(ConnectionError::Ssh(
+                "SQLite databases are local files and can't be reached through SSH".into(),
+            ));
+        }
+        let url = Url::parse(connection_string).map_err(|e| {
+            ConnectionError::InvalidConnectionString(format!(
+                "SSH tunnels need a single-host URL (scheme://host:port/...): {}",
+                e
+            ))
+        })?;
+        if url.scheme() == "mongodb+srv" {
+            return Err(ConnectionError::InvalidConnectionString(
+                "mongodb+srv:// can't be used through an SSH tunnel; use mongodb://host:port"
+                    .into(),
+            ));
+        }
+        let host = url
+            .host_str()
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| {
+                ConnectionError::InvalidConnectionString("Connection string has no host".into())
+            })?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let port = url.port().unwrap_or(driver.default_port());
+
+        Ok(Self { url, host, port })
+    }
+
+    /// The connection string pointed at the local end of a tunnel
+    pub fn connection_string(&self, local_port: u16) -> String {
+        let mut url = self.url.clone();
+        url.set_host(Some("127.0.0.1")).ok();
+        url.set_port(Some(local_port)).ok();
+
+        // Without this the Mongo driver follows the replica set members' own
+        // addresses, which the tunnel doesn't cover
+        if url.scheme() == "mongodb"
+            && !url
+                .query_pairs()
+                .any(|(key, _)| key.eq_ignore_ascii_case("directConnection"))
+        {
+            url.query_pairs_mut()
+                .append_pair("directConnection", "true");
+        }
+
+        url.to_string()
+    }
+}
+
+/// A live port forward through an SSH jump host. Closed when dropped.
+pub struct SshTunnel {
+    local_port: u16,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl SshTunnel {
+    /// Log into the jump host and start forwarding a local port to `target`.
+    /// Blocks for the SSH handshake.
+    pub fn open(
+        config: &SshTunnelConfig,
+        target: &TunnelTarget,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let session = connect(config, timeout)?;
+
+        // Fail here rather than on the driver's first connection, whose error
+        // would only say the socket was closed
+        let mut probe = session
+            .channel_direct_tcpip(&target.host, target.port, None)
+            .map_err(|e| {
+                ssh_error(format!(
+                    "{} could not connect to {}:{}: {}",
+                    config.host,
+                    target.host,
+                    target.port,
+                    e.message()
+                ))
+            })?;
+        probe.close().ok();
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).map_err(io_error)?;
+        listener.set_nonblocking(true).map_err(io_error)?;
+        let local_port = listener.local_addr().map_err(io_error)?.port();
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let forwarder = Forwarder {
+            session,
+            listener,
+            target_host: target.host.clone(),
+            target_port: target.port,
+            forwards: Vec::new(),
+        };
+        let flag = shutdown.clone();
+        thread::Builder::new()
+            .name(format!("ssh-tunnel-{}", local_port))
+            .spawn(move || forwarder.run(&flag))
+            .map_err(io_error)?;
+
+        Ok(Self {
+            local_port,
+            shutdown,
+        })
+    }
+
+    pub fn local_port(&self) -> u16 {
+        self.local_port
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        // The forwarding thread notices within one idle sleep and disconnects
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+fn ssh_error(message: impl Into<String>) -> ConnectionError {
+    ConnectionError::Ssh(message.into())
+}
+
+fn io_error(error: io::Error) -> ConnectionError {
+    ConnectionError::Ssh(error.to_string())
+}
+
+/// Connect and authenticate to the jump host
+fn connect(config: &SshTunnelConfig, timeout: Duration) -> Result<Session> {
+    if config.host.is_empty() || config.username.is_empty() {
+        return Err(ssh_error("SSH host and user are required"));
+    }
+
+    let address = (config.host.as_str(), config.port)
+        .to_socket_addrs()
+        .map_err(|e| ssh_error(format!("Could not resolve {}: {}", config.host, e)))?
+        .next()
+        .ok_or_else(|| ssh_error(format!("Could not resolve {}", config.host)))?;
+    let stream = TcpStream::connect_timeout(&address, timeout).map_err(|e| {
+        ssh_error(format!(
+            "Could not connect to {}:{}: {}",
+            config.host, config.port, e
+        ))
+    })?;
+
+    let mut session = Session::new().map_err(|e| ssh_error(e.message()))?;
+    session.set_timeout(timeout.as_millis() as u32);
+    session.set_tcp_stream(stream);
+    session.handshake().map_err(|e| {
+        ssh_error(format!(
+            "SSH handshake with {} failed: {}",
+            config.host,
+            e.message()
+        ))
+    })?;
+
+    verify_host_key(&session, config)?;
+    authenticate(&session, config)?;
+
+    session.set_keepalive(false, KEEPALIVE_INTERVAL_SECS);
+    Ok(session)
+}
+
+fn authenticate(session: &Session, config: &SshTunnelConfig) -> Result<()> {
+    let result = match config.auth {
+        SshAuth::Password => {
+            let password = config
+                .password
+                .as_deref()
+                .ok_or_else(|| ssh_error("No SSH password set"))?;
+            session.userauth_password(&config.username, password)
+        }
+        SshAuth::PrivateKey => {
+            let key_path = config
+                .private_key_path
+                .as_deref()
+                .filter(|path| !path.is_empty())
+                .ok_or_else(|| ssh_error("No SSH private key file set"))?;
+            let key_path = expand_home(key_path);
+            if !key_path.exists() {
+                return Err(ssh_error(format!(
+                    "Private key {} does not exist",
+                    key_path.display()
+                )));
+            }
+            session.userauth_pubkey_file(
+                &config.username,
+                None,
+                &key_path,
+                config.password.as_deref(),
+            )
+        }
+    };
+
+    result.map_err(|e| {
+        ssh_error(format!(
+            "SSH authentication as {} failed: {}",
+            config.username,
+            e.message()
+        ))
+    })
+}
+
+/// Check the jump host's key against known_hosts, adding it under `AcceptNew`
+fn verify_host_key(session: &Session, config: &SshTunnelConfig) -> Result<()> {
+    if config.host_key_check == HostKeyCheck::Off {
+        return Ok(());
+    }
+
+    let (key, key_type) = session
+        .host_key()
+        .ok_or_else(|| ssh_error("Server sent no host key"))?;
+    let fingerprint = session
+        .host_key_hash(HashType::Sha256)
+        .map(|hash| format!("SHA256:{}", STANDARD_NO_PAD.encode(hash)))
+        .unwrap_or_default();
+    let path = match &config.known_hosts_path {
+        Some(path) if !path.is_empty() => expand_home(path),
+        _ => dirs::home_dir()
+            .ok_or_else(|| ssh_error("Could not find the home directory for known_hosts"))?
+            .join(".ssh")
+            .join("known_hosts"),
+    };
+    // OpenSSH writes non-standard ports as [host]:port
+    let host_name = if config.port == 22 {
+        config.host.clone()
+    } else {
+        format!("[{}]:{}", config.host, config.port)
+    };
+
+    let mut known_hosts = session.known_hosts().map_err(|e| ssh_error(e.message()))?;
+    if let Ok(contents) = fs::read_to_string(&path) {
+        // Line by line, because libssh2 stops reading a file at the first
+        // entry it can't parse (e.g. security key types)
+        for line in contents.lines() {
+            known_hosts.read_str(line, KnownHostFileKind::OpenSSH).ok();
+        }
+    }
+
+    match known_hosts.check_port(&config.host, config.port, key) {
+        CheckResult::Match => Ok(()),
+        CheckResult::Mismatch => Err(ssh_error(format!(
+            "Host key of {} ({}) does not match the one in {}. It may have been replaced, \
+             or someone may be intercepting the connection.",
+            host_name,
+            fingerprint,
+            path.display()
+        ))),
+        CheckResult::NotFound if config.host_key_check == HostKeyCheck::AcceptNew => {
+            append_known_host(&path, &host_name, key, key_type)
+        }
+        CheckResult::NotFound => Err(ssh_error(format!(
+            "Host key of {} ({}) is not in {}",
+            host_name,
+            fingerprint,
+            path.display()
+        ))),
+        CheckResult::Failure => Err(ssh_error(format!(
+            "Could not check the host key of {} ({}) against {}",
+            host_name,
+            fingerprint,
+            path.display()
+        ))),
+    }
+}
+
+fn append_known_host(
+    path: &Path,
+    host_name: &str,
+    key: &[u8],
+    key_type: HostKeyType,
+) -> Result<()> {
+    let key_type = match key_type {
+        HostKeyType::Rsa => "ssh-rsa",
+        HostKeyType::Dss => "ssh-dss",
+        HostKeyType::Ecdsa256 => "ecdsa-sha2-nistp256",
+        HostKeyType::Ecdsa384 => "ecdsa-sha2-nistp384",
+        HostKeyType::Ecdsa521 => "ecdsa-sha2-nistp521",
+        HostKeyType::Ed25519 => "ssh-ed25519",
+        HostKeyType::Unknown => return Err(ssh_error("Unsupported host key type")),
+    };
+    let write_error =
+        |e: io::Error| ssh_error(format!("Failed to update {}: {}", path.display(), e));
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(write_error)?;
+    }
+    let needs_newline = fs::read(path)
+        .map(|contents| contents.last().is_some_and(|&b| b != b'\n'))
+        .unwrap_or(false);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(write_error)?;
+    let line = format!(
+        "{}{} {} {}\n",
+        if needs_newline { "\n" } else { "" },
+        host_name,
+        key_type,
+        base64::engine::general_purpose::STANDARD.encode(key)
+    );
+    file.write_all(line.as_bytes()).map_err(write_error)
+}
+
+/// `~/...` relative to the home directory, as users tend to type key paths
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Owns the SSH session and pumps data for every forwarded connection
+struct Forwarder {
+    session: Session,
+    listener: TcpListener,
+    target_host: String,
+    target_port: u16,
+    forwards: Vec<Forward>,
+}
+
+impl Forwarder {
+    fn run(mut self, shutdown: &AtomicBool) {
+        self.session.set_blocking(false);
+        let mut idle_sleep = Duration::ZERO;
+
+        while !shutdown.load(Ordering::Relaxed) {
+            let mut progress = self.accept();
+            self.forwards.retain_mut(|forward| match forward.pump() {
+                Ok(moved) => {
+                    progress |= moved;
+                    !forward.is_done()
+                }
+                Err(e) => {
+                    eprintln!("SSH tunnel connection closed: {}", e);
+                    false
+                }
+            });
+            self.session.keepalive_send().ok();
+
+            if progress {
+                idle_sleep = Duration::ZERO;
+            } else {
+                idle_sleep = (idle_sleep * 2).clamp(MIN_IDLE_SLEEP, MAX_IDLE_SLEEP);
+                thread::sleep(idle_sleep);
+            }
+        }
+
+        for mut forward in self.forwards.drain(..) {
+            forward.channel.close().ok();
+        }
+        self.session.disconnect(None, "", None).ok();
+    }
+
+    /// Open a channel for each new local connection. Returns whether there were any.
+    fn accept(&mut self) -> bool {
+        let mut accepted = false;
+        loop {
+            match self.listener.accept() {
+                Ok((local, _)) => {
+                    accepted = true;
+                    match self.open_forward(local) {
+                        Ok(forward) => self.forwards.push(forward),
+                        Err(e) => eprintln!(
+                            "SSH tunnel failed to reach {}:{}: {}",
+                            self.target_host, self.target_port, e
+                        ),
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    eprintln!("SSH tunnel failed to accept a connection: {}", e);
+                    break;
+                }
+            }
+        }
+        accepted
+    }
+
+    fn open_forward(&self, local: TcpStream) -> io::Result<Forward> {
+        local.set_nonblocking(true)?;
+        local.set_nodelay(true).ok();
+
+        // Opening a channel is a request/reply exchange, simplest done blocking
+        // (bounded by the session timeout)
+        self.session.set_blocking(true);
+        let channel = self
+            .session
+            .channel_direct_tcpip(&self.target_host, self.target_port, None);
+        self.session.set_blocking(false);
+
+        Ok(Forward {
+            local,
+            channel: channel?,
+            to_remote: Vec::new(),
+            to_local: Vec::new(),
+            local_eof: false,
+            remote_eof: false,
+            eof_sent: false,
+        })
+    }
+}
+
+/// One client connection forwarded over an SSH channel
+struct Forward {
+    local: TcpStream,
+    channel: ssh2::Channel,
+    /// Read from the client, not yet written to the channel
+    to_remote: Vec<u8>,
+    /// Read from the channel, not yet written to the client
+    to_local: Vec<u8>,
+    local_eof: bool,
+    remote_eof: bool,
+    eof_sent: bool,
+}
+
+impl Forward {
+    /// Move whatever data is ready in both directions. Returns whether any moved.
+    fn pump(&mut self) -> io::Result<bool> {
+        let mut progress = false;
+        let mut buf = [0u8; BUFFER_SIZE];
+
+        // Client -> database
+        if !self.local_eof && self.to_remote.is_empty() {
+            match self.local.read(&mut buf) {
+                Ok(0) => self.local_eof = true,
+                Ok(n) => self.to_remote.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+            progress |= self.local_eof || !self.to_remote.is_empty();
+        }
+        if !self.to_remote.is_empty() {
+            match self.channel.write(&self.to_remote) {
+                Ok(n) => {
+                    self.to_remote.drain(..n);
+                    progress |= n > 0;
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if self.local_eof && self.to_remote.is_empty() && !self.eof_sent {
+            match self.channel.send_eof().map_err(io::Error::from) {
+                Ok(()) => self.eof_sent = true,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                // The server may already have closed the channel after its own EOF
+                Err(_) if self.remote_eof => self.eof_sent = true,
+                Err(e) => return Err(e),
+            }
+        }
+
+        // Database -> client
+        if !self.remote_eof && self.to_local.is_empty() {
+            match self.channel.read(&mut buf) {
+                Ok(0) => self.remote_eof = self.channel.eof(),
+                Ok(n) => self.to_local.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+            progress |= self.remote_eof || !self.to_local.is_empty();
+        }
+        if !self.to_local.is_empty() {
+            match self.local.write(&self.to_local) {
+                Ok(n) => {
+                    self.to_local.drain(..n);
+                    progress |= n > 0;
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if self.remote_eof && self.to_local.is_empty() {
+            self.local.shutdown(Shutdown::Write).ok();
+        }
+
+        Ok(progress)
+    }
+
+    /// Done once both directions are: a client that half-closes its socket
+    /// still gets the rest of the response
+    fn is_done(&self) -> bool {
+        self.eof_sent && self.remote_eof && self.to_local.is_empty()
+    }
+}
+
+/// A driver connection together with the tunnel it goes through
+pub struct TunneledConnection {
+    // Declared first so the driver is dropped before its tunnel
+    inner: Box<dyn DatabaseConnection>,
+    _tunnel: SshTunnel,
+}
+
+impl TunneledConnection {
+    pub fn new(inner: Box<dyn DatabaseConnection>, tunnel: SshTunnel) -> Self {
+        Self {
+            inner,
+            _tunnel: tunnel,
+        }
+    }
+}
+
+// Every method is forwarded, including those with default implementations
+#[async_trait]
+impl DatabaseConnection for TunneledConnection {
+    async fn test_connection(&self) -> Result<ConnectionInfo> {
+        self.inner.test_connection().await
+    }
+
+    async fn list_databases(&self) -> Result<Vec<DatabaseInfo>> {
+        self.inner.list_databases().await
+    }
+
+    async fn list_collections(&self, database_name: &str) -> Result<Vec<CollectionInfo>> {
+        self.inner.list_collections(database_name).await
+    }
+
+    async fn query_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        limit: u32,
+        skip: u32,
+        filter: Option<&str>,
+        sort: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        self.inner
+            .query_documents(database_name, collection_name, limit, skip, filter, sort)
+            .await
+    }
+
+    async fn count_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        filter: Option<&str>,
+    ) -> Result<usize> {
+        self.inner
+            .count_documents(database_name, collection_name, filter)
+            .await
+    }
+
+    async fn scan_keyspace(
+        &self,
+        database_name: &str,
+        filter: Option<&str>,
+        from: &KeyspaceCursor,
+        limit: u32,
+    ) -> Result<KeyspacePage> {
+        self.inner
+            .scan_keyspace(database_name, filter, from, limit)
+            .await
+    }
+
+    async fn table_columns(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<Vec<ColumnInfo>> {
+        self.inner
+            .table_columns(database_name, collection_name)
+            .await
+    }
+
+    async fn execute_transaction(
+        &self,
+        database_name: &str,
+        statements: &[String],
+    ) -> Result<Vec<u64>> {
+        self.inner
+            .execute_transaction(database_name, statements)
+            .await
+    }
+
+    async fn execute_each(
+        &self,
+        database_name: &str,
+        statements: &[String],
+        stop_on_error: bool,
+    ) -> Result<BatchInsertResult> {
+        self.inner
+            .execute_each(database_name, statements, stop_on_error)
+            .await
+    }
+
+    async fn execute_query(&self, database_name: &str, query: &str) -> Result<QueryResult> {
+        self.inner.execute_query(database_name, query).await
+    }
+
+    async fn explain_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        limit: u32,
+        skip: u32,
+        filter: Option<&str>,
+        sort: Option<&str>,
+    ) -> Result<QueryPlan> {
+        self.inner
+            .explain_documents(database_name, collection_name, limit, skip, filter, sort)
+            .await
+    }
+
+    async fn explain_query(&self, database_name: &str, query: &str) -> Result<QueryPlan> {
+        self.inner.explain_query(database_name, query).await
+    }
+
+    async fn execute_command(&self, database_name: &str, command: &str) -> Result<RespValue> {
+        self.inner.execute_command(database_name, command).await
+    }
+
+    async fn update_document(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        id: &serde_json::Value,
+        changes: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<()> {
+        self.inner
+            .update_document(database_name, collection_name, id, changes)
+            .await
+    }
+
+    async fn insert_document(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        document: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        self.inner
+            .insert_document(database_name, collection_name, document)
+            .await
+    }
+
+    async fn insert_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        documents: &[serde_json::Value],
+        ordered: bool,
+    ) -> Result<BatchInsertResult> {
+        self.inner
+            .insert_documents(database_name, collection_name, documents, ordered)
+            .await
+    }
+
+    async fn replace_document(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        id: &serde_json::Value,
+        document: &serde_json::Value,
+    ) -> Result<()> {
+        self.inner
+            .replace_document(database_name, collection_name, id, document)
+            .await
+    }
+
+    async fn delete_documents(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        ids: &[serde_json::Value],
+    ) -> Result<u64> {
+        self.inner
+            .delete_documents(database_name, collection_name, ids)
+            .await
+    }
+
+    async fn aggregate(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        pipeline: &[serde_json::Value],
+        limit: u32,
+    ) -> Result<Vec<serde_json::Value>> {
+        self.inner
+            .aggregate(database_name, collection_name, pipeline, limit)
+            .await
+    }
+
+    async fn list_indexes(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<Vec<IndexInfo>> {
+        self.inner
+            .list_indexes(database_name, collection_name)
+            .await
+    }
+
+    async fn create_index(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        spec: &IndexSpec,
+    ) -> Result<()> {
+        self.inner
+            .create_index(database_name, collection_name, spec)
+            .await
+    }
+
+    async fn drop_index(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+        index_name: &str,
+    ) -> Result<()> {
+        self.inner
+            .drop_index(database_name, collection_name, index_name)
+            .await
+    }
+
+    async fn index_build_progress(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<Option<IndexBuildProgress>> {
+        self.inner
+            .index_build_progress(database_name, collection_name)
+            .await
+    }
+
+    async fn table_structure(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<TableStructure> {
+        self.inner
+            .table_structure(database_name, collection_name)
+            .await
+    }
+
+    async fn table_ddl(&self, database_name: &str, collection_name: &str) -> Result<String> {
+        self.inner.table_ddl(database_name, collection_name).await
+    }
+
+    async fn database_stats(&self, database_name: &str) -> Result<StorageStats> {
+        self.inner.database_stats(database_name).await
+    }
+
+    async fn collection_stats(
+        &self,
+        database_name: &str,
+        collection_name: &str,
+    ) -> Result<StorageStats> {
+        self.inner
+            .collection_stats(database_name, collection_name)
+            .await
+    }
+
+    async fn server_status(&self) -> Result<ServerStatus> {
+        self.inner.server_status().await
+    }
+
+    async fn drop_database(&self, database_name: &str) -> Result<()> {
+        self.inner.drop_database(database_name).await
+    }
+
+    async fn drop_collection(&self, database_name: &str, collection_name: &str) -> Result<()> {
+        self.inner
+            .drop_collection(database_name, collection_name)
+            .await
+    }
+
+    async fn close(&self) {
+        self.inner.close().await
+    }
+}
+
+/// These need an sshd that allows TCP forwarding, such as the container from the
+/// README, and are ignored by default. Run them with
+/// `cargo test --features ssh -- --ignored` and `CHAMBERS_TEST_SSH_HOST`, `_PORT`,
+/// `_USER` and `_PASSWORD` set. `CHAMBERS_TEST_SSH_TARGET_HOST` is this machine as
+/// seen from the sshd (`host.docker.internal` for the container).
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn env(name: &str, default: &str) -> String {
+        std::env::var(name).unwrap_or_else(|_| default.to_string())
+    }
+
+    fn ssh_config(known_hosts: &Path, host_key_check: HostKeyCheck) -> SshTunnelConfig {
+        SshTunnelConfig {
+            host: env("CHAMBERS_TEST_SSH_HOST", "localhost"),
+            port: env("CHAMBERS_TEST_SSH_PORT", "2222").parse().unwrap(),
+            username: env("CHAMBERS_TEST_SSH_USER", "chambers"),
+            auth: SshAuth::Password,
+            password: Some(env("CHAMBERS_TEST_SSH_PASSWORD", "secret")),
+            host_key_check,
+            known_hosts_path: Some(known_hosts.to_string_lossy().into_owned()),
+            ..Default::default()
+        }
+    }
+
+    /// A known_hosts path that doesn't exist yet
+    fn known_hosts_file() -> PathBuf {
+        std::env::temp_dir().join(format!("chambers-known-hosts-{}", uuid::Uuid::new_v4()))
+    }
+
+    /// Echo server on all interfaces, so the sshd can reach it. With `after_eof`
+    /// it only replies once the client has shut down its sending side.
+    fn echo_server(after_eof: bool) -> u16 {
+        let listener = TcpListener::bind(("0.0.0.0", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                thread::spawn(move || {
+                    if after_eof {
+                        let mut data = Vec::new();
+                        stream.read_to_end(&mut data).unwrap();
+                        stream.write_all(&data).unwrap();
+                    } else {
+                        let mut reader = stream.try_clone().unwrap();
+                        io::copy(&mut reader, &mut stream).unwrap();
+                    }
+                });
+            }
+        });
+        port
+    }
+
+    /// A tunnel to an echo server, connected to from this side
+    fn connect_to_echo(after_eof: bool) -> (SshTunnel, TcpStream) {
+        let target_host = env("CHAMBERS_TEST_SSH_TARGET_HOST", "127.0.0.1");
+        let url = format!("redis://{}:{}", target_host, echo_server(after_eof));
+        let target = TunnelTarget::parse(DatabaseType::Redis, &url).unwrap();
+        let config = ssh_config(&known_hosts_file(), HostKeyCheck::Off);
+        let tunnel = SshTunnel::open(&config, &target, TIMEOUT).unwrap();
+
+        let stream = TcpStream::connect(("127.0.0.1", tunnel.local_port())).unwrap();
+        stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+        (tunnel, stream)
+    }
+
+    fn payload(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    #[ignore]
+    fn connects_to_the_jump_host() {
+        let config = ssh_config(&known_hosts_file(), HostKeyCheck::Off);
+        let session = connect(&config, TIMEOUT).unwrap();
+        assert!(session.authenticated());
+    }
+
+    #[test]
+    #[ignore]
+    fn accept_new_records_the_host_key_for_strict() {
+        let path = known_hosts_file();
+        connect(&ssh_config(&path, HostKeyCheck::AcceptNew), TIMEOUT).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+
+        connect(&ssh_config(&path, HostKeyCheck::Strict), TIMEOUT).unwrap();
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    #[ignore]
+    fn strict_rejects_an_unknown_host() {
+        let path = known_hosts_file();
+        let error = connect(&ssh_config(&path, HostKeyCheck::Strict), TIMEOUT)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("is not in"), "{}", error);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    #[ignore]
+    fn strict_rejects_a_changed_host_key() {
+        let path = known_hosts_file();
+        connect(&ssh_config(&path, HostKeyCheck::AcceptNew), TIMEOUT).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let fields: Vec<&str> = contents.split_whitespace().collect();
+        let mut key = base64::engine::general_purpose::STANDARD
+            .decode(fields[2])
+            .unwrap();
+        *key.last_mut().unwrap() ^= 0xff;
+        let changed = base64::engine::general_purpose::STANDARD.encode(key);
+        fs::write(&path, format!("{} {} {}\n", fields[0], fields[1], changed)).unwrap();
+
+        let error = connect(&ssh_config(&path, HostKeyCheck::Strict), TIMEOUT)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("does not match"), "{}", error);
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    #[ignore]
+    fn forwards_data_both_ways() {
+        let (_tunnel, mut stream) = connect_to_echo(false);
+
+        for message in [&b"ping"[..], &b"a second message"[..]] {
+            stream.write_all(message).unwrap();
+            let mut reply = vec![0u8; message.len()];
+            stream.read_exact(&mut reply).unwrap();
+            assert_eq!(reply, message);
+        }
+
+        // More than the forwarding buffers hold, written while the echo is read
+        let sent = payload(4 * BUFFER_SIZE + 17);
+        let mut writer = stream.try_clone().unwrap();
+        let expected = sent.clone();
+        let writing = thread::spawn(move || writer.write_all(&sent).unwrap());
+        let mut reply = vec![0u8; expected.len()];
+        stream.read_exact(&mut reply).unwrap();
+        writing.join().unwrap();
+        assert!(reply == expected);
+    }
+
+    #[test]
+    #[ignore]
+    fn delivers_the_reply_after_the_client_half_closes() {
+        let (_tunnel, mut stream) = connect_to_echo(true);
+
+        let sent = payload(2 * BUFFER_SIZE + 5);
+        stream.write_all(&sent).unwrap();
+        stream.shutdown(Shutdown::Write).unwrap();
+
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply).unwrap();
+        assert!(reply == sent);
+    }
+}
@@ -0,0 +1,151 @@
+//! Encryption for connection secrets
+//!
+//! Passwords and connection strings are encrypted with XChaCha20-Poly1305 under a
+//! 256-bit key derived from the master passphrase with Argon2id. A blob is
+//! `nonce || ciphertext`, and the owning connection id is bound as associated data so
+//! blobs cannot be swapped between connections.
+
+use anyhow::{anyhow, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+const SALT_LEN: usize = 16;
+
+/// Known plaintext encrypted at creation, used to check the passphrase on unlock
+const VERIFIER_PLAINTEXT: &[u8] = b"chambers credential store";
+const VERIFIER_AAD: &[u8] = b"verifier";
+
+/// State of the credential store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultState {
+    /// No master passphrase has been set yet
+    Uninitialized,
+    Locked,
+    Unlocked,
+}
+
+/// Argon2id parameters, stored with the salt so the cost can be raised later
+#[derive(Debug, Clone)]
+pub struct KdfParams {
+    pub salt: Vec<u8>,
+    /// Memory cost in KiB
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+}
+
+impl KdfParams {
+    /// Parameters for a new store: random salt, 64 MiB, 3 passes, 1 lane
+    pub fn generate() -> Self {
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            salt,
+            m_cost: 64 * 1024,
+            t_cost: 3,
+            p_cost: 1,
+        }
+    }
+}
+
+/// The secret fields of a connection, encrypted together
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Secrets {
+    pub password: Option<String>,
+    pub connection_string: Option<String>,
+    /// SSH tunnel password or private key passphrase
+    #[serde(default)]
+    pub ssh_password: Option<String>,
+}
+
+impl Secrets {
+    pub fn is_empty(&self) -> bool {
+        self.password.is_none() && self.connection_string.is_none() && self.ssh_password.is_none()
+    }
+}
+
+/// Key derived from the master passphrase; wiped from memory when dropped
+pub struct VaultKey(Zeroizing<[u8; KEY_LEN]>);
+
+impl VaultKey {
+    /// Derive the key for a passphrase. This is deliberately slow (a few hundred ms).
+    pub fn derive(passphrase: &str, params: &KdfParams) -> Result<Self> {
+        let argon_params = Params::new(params.m_cost, params.t_cost, params.p_cost, Some(KEY_LEN))
+            .map_err(|e| anyhow!("Invalid key derivation parameters: {}", e))?;
+        let argon = Argon2::new(Algorithm::Argon2id, Version::V0x13, argon_params);
+
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        argon
+            .hash_password_into(passphrase.as_bytes(), &params.salt, &mut key[..])
+            .map_err(|e| anyhow!("Key derivation failed: {}", e))?;
+        Ok(Self(key))
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(Key::from_slice(&self.0[..]))
+    }
+
+    fn encrypt(&self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher()
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .map_err(|_| anyhow!("Failed to encrypt secret"))?;
+
+        let mut blob = nonce.to_vec();
+        blob.extend_from_slice(&ciphertext);
+        Ok(blob)
+    }
+
+    fn decrypt(&self, blob: &[u8], aad: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        if blob.len() < NONCE_LEN {
+            return Err(anyhow!("Encrypted secret is truncated"));
+        }
+        let (nonce, ciphertext) = blob.split_at(NONCE_LEN);
+        self.cipher()
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad,
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| anyhow!("Failed to decrypt secret"))
+    }
+
+    /// Blob stored at creation to recognise the right passphrase later
+    pub fn verifier(&self) -> Result<Vec<u8>> {
+        self.encrypt(VERIFIER_PLAINTEXT, VERIFIER_AAD)
+    }
+
+    /// Whether this key opens the stored verifier (i.e. the passphrase was right)
+    pub fn matches_verifier(&self, verifier: &[u8]) -> bool {
+        self.decrypt(verifier, VERIFIER_AAD)
+            .is_ok_and(|plaintext| plaintext.as_slice() == VERIFIER_PLAINTEXT)
+    }
+
+    /// Encrypt a connection's secrets
+    pub fn seal(&self, connection_id: &str, secrets: &Secrets) -> Result<Vec<u8>> {
+        let json = Zeroizing::new(serde_json::to_vec(secrets)?);
+        self.encrypt(&json, connection_id.as_bytes())
+    }
+
+    /// Decrypt a connection's secrets
+    pub fn open(&self, connection_id: &str, blob: &[u8]) -> Result<Secrets> {
+        let json = self.decrypt(blob, connection_id.as_bytes())?;
+        Ok(serde_json::from_slice(&json)?)
+    }
+}
@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use gpui::{App, AppContext, Application, AssetSource, SharedString, WindowDecorations, px, rgb};
 
@@ -8,9 +9,18 @@ mod db;
 mod ui;
 
 use ui::connection_modal::register_connection_modal_bindings;
+use ui::document_editor::register_document_editor_bindings;
+use ui::password_prompt::register_password_prompt_bindings;
+use ui::query_console::register_query_console_bindings;
+use ui::redis_console::register_redis_console_bindings;
 use ui::selectable_text::register_selectable_text_bindings;
+use ui::table_view::register_table_view_bindings;
+use ui::text_editor::register_text_editor_bindings;
 use ui::text_input::register_text_input_bindings;
+use ui::unlock_prompt::{register_unlock_prompt_bindings, UnlockPrompt};
 use ui::workspace::{register_workspace_bindings, ChambersWorkspace};
+use db::secret_store::SecretBackend;
+use db::ConnectionStorage;
 
 /// Asset source for loading icons and other resources
 struct Assets {
@@ -68,6 +78,54 @@ impl AssetSource for Assets {
     }
 }
 
+/// Ask for the master passphrase before anything reads saved connections, or, with
+/// `keyring_error`, for the keyring chosen earlier to be made reachable
+fn open_unlock_window(storage: Arc<ConnectionStorage>, keyring_error: Option<String>, cx: &mut App) {
+    let bounds = gpui::Bounds::centered(None, gpui::size(gpui::px(480.0), gpui::px(360.0)), cx);
+    cx.open_window(
+        gpui::WindowOptions {
+            window_bounds: Some(gpui::WindowBounds::Windowed(bounds)),
+            window_decorations: Some(WindowDecorations::Client),
+            titlebar: Some(gpui::TitlebarOptions {
+                title: Some(gpui::SharedString::new_static("Unlock Chambers")),
+                appears_transparent: true,
+                ..Default::default()
+            }),
+            is_resizable: false,
+            ..Default::default()
+        },
+        |window, cx| {
+            let prompt = cx.new(|cx| UnlockPrompt::new(storage, keyring_error, open_main_window, cx));
+            prompt.update(cx, |prompt, cx| prompt.focus_input(window, cx));
+            prompt
+        },
+    )
+    .unwrap();
+    cx.activate(true);
+}
+
+fn open_main_window(storage: Arc<ConnectionStorage>, cx: &mut App) {
+    let bounds =
+        gpui::Bounds::centered(None, gpui::size(gpui::px(1200.0), gpui::px(800.0)), cx);
+    cx.open_window(
+        gpui::WindowOptions {
+            window_bounds: Some(gpui::WindowBounds::Windowed(bounds)),
+            // Use client-side decorations (hides system title bar)
+            window_decorations: Some(WindowDecorations::Client),
+            // Transparent titlebar allows custom title bar rendering
+            titlebar: Some(gpui::TitlebarOptions {
+                title: Some(gpui::SharedString::new_static("Chambers")),
+                appears_transparent: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        |_, cx| cx.new(|cx| ChambersWorkspace::new(storage, cx)),
+    )
+    .unwrap();
+    cx.activate(true);
+}
+
 fn main() {
     // Set up asset path - in release, this would be embedded
     let assets_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets");
@@ -126,33 +184,52 @@ fn main() {
         // Register text input key bindings
         register_text_input_bindings(cx);
 
+        // Register multi-line editor key bindings
+        register_text_editor_bindings(cx);
+
+        // Register query console key bindings (Ctrl+Enter to run)
+        register_query_console_bindings(cx);
+
+        // Register document editor key bindings (Ctrl+Enter to save)
+        register_document_editor_bindings(cx);
+
+        // Register Redis console key bindings (Enter to run, Up/Down for history)
+        register_redis_console_bindings(cx);
+
         // Register selectable text key bindings
         register_selectable_text_bindings(cx);
 
+        // Register table cell editor key bindings (Enter to confirm, Escape to cancel)
+        register_table_view_bindings(cx);
+
         // Register connection modal key bindings (Tab navigation)
         register_connection_modal_bindings(cx);
 
         // Register workspace key bindings (zoom in/out/reset)
         register_workspace_bindings(cx);
 
-        let bounds =
-            gpui::Bounds::centered(None, gpui::size(gpui::px(1200.0), gpui::px(800.0)), cx);
-        cx.open_window(
-            gpui::WindowOptions {
-                window_bounds: Some(gpui::WindowBounds::Windowed(bounds)),
-                // Use client-side decorations (hides system title bar)
-                window_decorations: Some(WindowDecorations::Client),
-                // Transparent titlebar allows custom title bar rendering
-                titlebar: Some(gpui::TitlebarOptions {
-                    title: Some(gpui::SharedString::new_static("Chambers")),
-                    appears_transparent: true,
-                    ..Default::default()
-                }),
-                ..Default::default()
-            },
-            |_, cx| cx.new(ChambersWorkspace::new),
-        )
-        .unwrap();
-        cx.activate(true);
+        // Register unlock prompt key bindings (Enter to unlock)
+        register_unlock_prompt_bindings(cx);
+
+        // Register password prompt key bindings (Enter to connect)
+        register_password_prompt_bindings(cx);
+
+        let storage =
+            Arc::new(ConnectionStorage::new().expect("Failed to initialize connection storage"));
+
+        // Secrets kept in the desktop keyring need no passphrase. If the keyring was
+        // chosen but is not reachable this session, the prompt says why and lets the
+        // user retry rather than starting over with an empty encrypted file.
+        let mut keyring_error = None;
+        if storage.preferred_secret_backend() == Some(SecretBackend::Keyring) {
+            match storage.use_keyring() {
+                Ok(()) => {
+                    open_main_window(storage, cx);
+                    return;
+                }
+                Err(e) => keyring_error = Some(e.to_string()),
+            }
+        }
+        open_unlock_window(storage, keyring_error, cx);
     });
 }
@@ -1,15 +1,30 @@
 use gpui::{prelude::*, rems, *};
 use gpui_component::menu::{PopupMenu, PopupMenuItem};
-use serde_json::Value;
-use std::collections::BTreeSet;
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashSet};
 
-use crate::db::driver::{create_connection, ConnectionConfig};
-use crate::db::DatabaseType;
+use crate::db::driver::{
+    escape_redis_pattern, ColumnInfo, ConnectionConfig, KeyspaceCursor, REDIS_COUNT_LIMIT, REDIS_KEYSPACE,
+};
+use crate::db::error::ConnectionError;
+use crate::db::registry::registry;
+use crate::db::schema::bson_type_name;
+use crate::db::sql_edit::{self, RowChange};
+use crate::db::{runtime, DatabaseType};
+use crate::ui::document_editor::{DocumentEditor, DocumentEditorClosed, DocumentSubmitted};
+use crate::ui::explain_panel::{ExplainPanel, ExplainPanelClosed, ExplainRequest, ExplainTarget};
+use crate::ui::export_panel::{ExportAll, ExportPanel, ExportPanelClosed, ExportQuery, ExportRequest};
+use crate::ui::import_wizard::{ImportDestination, ImportFinished, ImportWizard, ImportWizardClosed};
+use crate::ui::indexes_view::IndexesView;
+use crate::ui::pipeline_builder::PipelineBuilder;
+use crate::ui::schema_view::{SchemaSampled, SchemaView};
 use crate::ui::selectable_text::SelectableTextArea;
+use crate::ui::structure_view::StructureView;
 use crate::ui::table_view::{
-    CellContextMenuRequested, CellDoubleClicked, Column, FilterQuerySubmitted,
-    HeaderContextMenuRequested, PageChangeRequested, Row, SortChangeRequested, SortDirection,
-    SortQuerySubmitted, TableView, ViewDropdownToggled, ViewMode, ViewModeChanged, PAGE_SIZE,
+    AddRowRequested, CellContextMenuRequested, CellDoubleClicked, CellEditSubmitted, CellMarks, Column,
+    ExplainRequested, ExportRequested, FilterQuerySubmitted, ImportRequested, HeaderContextMenuRequested, PageChangeRequested, Row,
+    SortChangeRequested, SortDirection, SortQuerySubmitted, TableView, ViewDropdownToggled,
+    ViewMode, ViewModeChanged, PAGE_SIZE,
 };
 use crate::ui::theme::AppColors;
 
@@ -19,6 +34,12 @@ pub struct LoadingStateChanged(pub bool);
 
 impl EventEmitter<LoadingStateChanged> for CollectionView {}
 
+/// Event emitted when a key is double-clicked in a Redis keyspace listing
+#[derive(Clone)]
+pub struct OpenKeyRequested(pub String);
+
+impl EventEmitter<OpenKeyRequested> for CollectionView {}
+
 /// Loading state for the collection view
 #[derive(Clone, PartialEq)]
 pub enum LoadingState {
@@ -27,11 +48,36 @@ pub enum LoadingState {
     Error(String),
 }
 
-/// Collection view - displays documents from a MongoDB collection
+/// Sub-views of a collection tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionTab {
+    Documents,
+    Structure,
+    Schema,
+    Aggregation,
+    Indexes,
+}
+
+impl CollectionTab {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CollectionTab::Documents => "Documents",
+            CollectionTab::Structure => "Structure",
+            CollectionTab::Schema => "Schema",
+            CollectionTab::Aggregation => "Aggregation",
+            CollectionTab::Indexes => "Indexes",
+        }
+    }
+}
+
+/// Collection view - displays documents from a MongoDB collection or rows from a SQL table
 pub struct CollectionView {
+    /// Saved connection this view reads through (key into the connection registry)
+    connection_id: String,
     collection_name: String,
     database_name: String,
-    connection_string: String,
+    config: ConnectionConfig,
+    db_type: DatabaseType,
     loading_state: LoadingState,
     table_view: Entity<TableView>,
     /// Raw documents from query
@@ -40,12 +86,26 @@ pub struct CollectionView {
     columns: Vec<String>,
     /// Total document count in collection
     total_count: usize,
+    /// `total_count` is a lower bound (capped Redis count)
+    total_at_least: bool,
     /// Current page (0-indexed)
     current_page: usize,
+    /// Where each loaded page of a Redis keyspace listing starts its SCAN, by page
+    keyspace_cursors: Vec<KeyspaceCursor>,
     /// Detail panel content (for viewing large values)
     detail_content: Option<DetailContent>,
     /// Selectable text area for detail panel
     detail_text_area: Option<Entity<SelectableTextArea>>,
+    /// Extended JSON editor for inserting or replacing a whole document
+    document_editor: Option<Entity<DocumentEditor>>,
+    /// Open the document editor for a new document (deferred to render for window access)
+    pending_insert_document: bool,
+    /// Export options and progress
+    export_panel: Option<Entity<ExportPanel>>,
+    /// Import from a file into this collection or table
+    import_wizard: Option<Entity<ImportWizard>>,
+    /// Query plan of the filter
+    explain_panel: Option<Entity<ExplainPanel>>,
     /// Current sort field
     sort_field: Option<String>,
     /// Current sort direction
@@ -69,11 +129,73 @@ pub struct CollectionView {
     last_view_dropdown_dismiss: Option<std::time::Instant>,
     /// Current view mode (tracked here for dropdown rendering)
     current_view_mode: ViewMode,
+    /// Type and TTL summary when viewing a single Redis key
+    key_info: Option<String>,
+    /// Columns of a SQL table, with its primary key (loaded with the first page)
+    table_columns: Vec<ColumnInfo>,
+    /// Edits, inserts and deletions waiting to be applied (MongoDB and SQL tables)
+    pending_changes: Vec<PendingChange>,
+    /// Whether the pending changes panel is open
+    show_changes: bool,
+    /// Pending changes are being written to the server
+    applying_changes: bool,
+    /// Last rejected edit or failed write
+    edit_error: Option<String>,
+    /// Sub-view shown below the tab bar
+    active_tab: CollectionTab,
+    /// Aggregation pipeline editor (MongoDB), created when first opened
+    pipeline_builder: Option<Entity<PipelineBuilder>>,
+    /// Index list and create form, created when first opened
+    indexes_view: Option<Entity<IndexesView>>,
+    /// Sampled field analysis (MongoDB), created when first opened
+    schema_view: Option<Entity<SchemaView>>,
+    /// Top-level fields found by the last sample, `_id` first. Once there is a
+    /// sample the table's columns no longer depend on the current page.
+    sampled_columns: Vec<String>,
+    /// Columns, constraints and DDL (SQL tables), created when first opened
+    structure_view: Option<Entity<StructureView>>,
+}
+
+/// A change to a MongoDB collection or SQL table, queued until the user applies it.
+/// Rows are identified by their key: the `_id` of a document, or an object holding
+/// the primary key values of a SQL row.
+#[derive(Clone)]
+enum PendingChange {
+    /// New value for a top-level field of the row with this key
+    Update {
+        id: Value,
+        field: String,
+        /// Server value, `None` if the document lacks the field
+        original: Option<Value>,
+        value: Value,
+    },
+    /// Insert a document (a copy of an existing one, without its `_id`) or a SQL row.
+    /// Pending inserts are shown, and can be edited, after the loaded rows.
+    Insert(Value),
+    /// Delete the row with this key
+    Delete(Value),
+}
+
+/// One write sent to the server when pending changes are applied
+enum DocumentWrite {
+    Update {
+        id: Value,
+        changes: serde_json::Map<String, Value>,
+    },
+    Insert(Value),
+    Delete(Vec<Value>),
+}
+
+/// Kind of row change a generated SQL statement applies
+#[derive(Clone, Copy, PartialEq)]
+enum ChangeKind {
+    Update,
+    Insert,
+    Delete,
 }
 
 /// Pending cell context menu request data
 #[derive(Clone)]
-#[allow(dead_code)]
 struct PendingCellContextMenu {
     row_index: usize,
     col_index: usize,
@@ -90,12 +212,26 @@ struct DetailContent {
 
 impl CollectionView {
     pub fn new(
+        connection_id: String,
         collection_name: String,
         database_name: String,
-        connection_string: String,
+        config: ConnectionConfig,
         cx: &mut Context<Self>,
     ) -> Self {
-        let table_view = cx.new(|_| TableView::new());
+        let db_type = config.driver;
+        let table_view = cx.new(|_| {
+            let mut table = TableView::new();
+            if db_type.is_sql() {
+                table.set_filter_placeholder("WHERE clause, e.g. id > 10");
+            } else if db_type == DatabaseType::Redis {
+                table.set_filter_placeholder(if collection_name == REDIS_KEYSPACE {
+                    "MATCH pattern, e.g. user:*"
+                } else {
+                    "MATCH pattern for fields or members"
+                });
+            }
+            table
+        });
 
         // Subscribe to table view events
         cx.subscribe(&table_view, |this, _, event: &PageChangeRequested, cx| {
@@ -167,6 +303,31 @@ impl CollectionView {
         })
         .detach();
 
+        cx.subscribe(&table_view, |this, _, event: &CellEditSubmitted, cx| {
+            this.on_cell_edit_submitted(event, cx);
+        })
+        .detach();
+
+        cx.subscribe(&table_view, |this, _, _: &AddRowRequested, cx| {
+            this.add_row(cx);
+        })
+        .detach();
+
+        cx.subscribe(&table_view, |this, _, _: &ExportRequested, cx| {
+            this.open_export_panel(cx);
+        })
+        .detach();
+
+        cx.subscribe(&table_view, |this, _, _: &ImportRequested, cx| {
+            this.open_import_wizard(cx);
+        })
+        .detach();
+
+        cx.subscribe(&table_view, |this, _, event: &ExplainRequested, cx| {
+            this.open_explain_panel(event.query.clone(), cx);
+        })
+        .detach();
+
         cx.subscribe(&table_view, |this, _, event: &FilterQuerySubmitted, cx| {
             this.filter_query = event.query.clone();
             this.current_page = 0;
@@ -199,17 +360,26 @@ impl CollectionView {
         .detach();
 
         let mut view = Self {
+            connection_id,
             collection_name,
             database_name,
-            connection_string,
+            config,
+            db_type,
             loading_state: LoadingState::Loading,
             table_view,
             documents: Vec::new(),
             columns: Vec::new(),
             total_count: 0,
+            total_at_least: false,
             current_page: 0,
+            keyspace_cursors: Vec::new(),
             detail_content: None,
             detail_text_area: None,
+            document_editor: None,
+            pending_insert_document: false,
+            export_panel: None,
+            import_wizard: None,
+            explain_panel: None,
             sort_field: None,
             sort_direction: None,
             filter_query: String::new(),
@@ -221,6 +391,18 @@ impl CollectionView {
             view_dropdown_open: false,
             last_view_dropdown_dismiss: None,
             current_view_mode: ViewMode::Table,
+            key_info: None,
+            table_columns: Vec::new(),
+            pending_changes: Vec::new(),
+            show_changes: false,
+            applying_changes: false,
+            edit_error: None,
+            active_tab: CollectionTab::Documents,
+            pipeline_builder: None,
+            indexes_view: None,
+            schema_view: None,
+            sampled_columns: Vec::new(),
+            structure_view: None,
         };
 
         // Start loading data
@@ -235,13 +417,696 @@ impl CollectionView {
         self.load_documents(cx);
     }
 
-    /// Handle cell double-click to copy value
+    /// Handle cell double-click: edit MongoDB fields and SQL cells in place, copy other values
     fn on_cell_double_clicked(&mut self, event: &CellDoubleClicked, cx: &mut Context<Self>) {
+        // In a Redis keyspace listing, double-click opens the key instead
+        if self.is_redis_keyspace() {
+            let key = self
+                .document_at(event.row_index)
+                .and_then(|doc| doc.get("key"))
+                .and_then(|key| key.as_str());
+            if let Some(key) = key {
+                cx.emit(OpenKeyRequested(key.to_string()));
+                return;
+            }
+        }
+
+        if self.is_editable() {
+            self.start_edit(event.row_index, event.col_index, cx);
+            return;
+        }
+
         // Copy the value to clipboard
         cx.write_to_clipboard(ClipboardItem::new_string(event.value.to_string()));
         cx.notify();
     }
 
+    /// Whether rows can be edited in place: documents are written back by `_id`,
+    /// SQL rows by their primary key
+    fn is_editable(&self) -> bool {
+        match self.db_type {
+            DatabaseType::MongoDB => true,
+            db_type if db_type.is_sql() => self.has_primary_key(),
+            _ => false,
+        }
+    }
+
+    fn has_primary_key(&self) -> bool {
+        self.table_columns.iter().any(|column| column.primary_key)
+    }
+
+    /// "row" or "document", for messages
+    fn row_noun(&self) -> &'static str {
+        if self.db_type.is_sql() {
+            "row"
+        } else {
+            "document"
+        }
+    }
+
+    /// Index into `documents` of a table row; pending inserts follow the loaded rows
+    fn local_row(&self, row_index: usize) -> usize {
+        row_index.saturating_sub(self.current_page * PAGE_SIZE)
+    }
+
+    fn document_at(&self, row_index: usize) -> Option<&Value> {
+        self.documents.get(self.local_row(row_index))
+    }
+
+    /// Index into `pending_changes` of the insert shown at a table row
+    fn insert_at(&self, row_index: usize) -> Option<usize> {
+        let nth = self.local_row(row_index).checked_sub(self.documents.len())?;
+        self.pending_changes
+            .iter()
+            .enumerate()
+            .filter(|(_, change)| matches!(change, PendingChange::Insert(_)))
+            .nth(nth)
+            .map(|(index, _)| index)
+    }
+
+    /// Key a row is written back by: the document's `_id`, or an object of the
+    /// primary key values of a SQL row
+    fn row_key(&self, doc: &Value) -> Option<Value> {
+        if !self.db_type.is_sql() {
+            return doc.get("_id").cloned();
+        }
+        let mut key = serde_json::Map::new();
+        for column in self.table_columns.iter().filter(|column| column.primary_key) {
+            key.insert(column.name.clone(), doc.get(&column.name)?.clone());
+        }
+        (!key.is_empty()).then_some(Value::Object(key))
+    }
+
+    /// Current value of a row's field, with any pending edit applied
+    fn pending_value<'a>(&'a self, doc: &'a Value, field: &str) -> Option<&'a Value> {
+        if self.pending_changes.is_empty() {
+            return doc.get(field);
+        }
+        let id = self.row_key(doc);
+        self.pending_changes
+            .iter()
+            .find_map(|change| match change {
+                PendingChange::Update { id: edited, field: edited_field, value, .. }
+                    if Some(edited) == id.as_ref() && edited_field == field =>
+                {
+                    Some(value)
+                }
+                _ => None,
+            })
+            .or_else(|| doc.get(field))
+    }
+
+    fn is_pending_delete(&self, id: &Value) -> bool {
+        self.pending_changes
+            .iter()
+            .any(|change| matches!(change, PendingChange::Delete(deleted) if deleted == id))
+    }
+
+    /// Open the in-cell editor for a field of a loaded row or a pending insert
+    fn start_edit(&mut self, row_index: usize, col_index: usize, cx: &mut Context<Self>) {
+        let Some(col_name) = self.columns.get(col_index) else {
+            return;
+        };
+        let noun = self.row_noun();
+
+        let current = if let Some(index) = self.insert_at(row_index) {
+            match &self.pending_changes[index] {
+                PendingChange::Insert(row) => Ok(row.get(col_name)),
+                _ => return,
+            }
+        } else {
+            let Some(doc) = self.document_at(row_index) else {
+                return;
+            };
+            match self.row_key(doc) {
+                None => Err(format!("This {} has no key to write it back by", noun)),
+                Some(id) if self.is_pending_delete(&id) => {
+                    Err(format!("This {} is marked for deletion", noun))
+                }
+                Some(_) => Ok(self.pending_value(doc, col_name)),
+            }
+        };
+        let current = match current {
+            _ if col_name == "_id" && !self.db_type.is_sql() => {
+                Err("_id cannot be changed; duplicate the document instead".to_string())
+            }
+            current => current,
+        };
+
+        let text = match current {
+            Ok(value) => value.map(edit_text_for_value).unwrap_or_default(),
+            Err(error) => {
+                self.edit_error = Some(error);
+                cx.notify();
+                return;
+            }
+        };
+        self.edit_error = None;
+        self.table_view.update(cx, |table, cx| {
+            table.start_cell_edit(row_index, col_index, &text, cx);
+        });
+        cx.notify();
+    }
+
+    /// Read an edited cell back with its original type and queue the change
+    fn on_cell_edit_submitted(&mut self, event: &CellEditSubmitted, cx: &mut Context<Self>) {
+        // Edits to a pending insert change the row to be inserted
+        if let Some(index) = self.insert_at(event.row_index) {
+            let PendingChange::Insert(row) = &self.pending_changes[index] else {
+                return;
+            };
+            let original = row.get(&event.col_name).cloned();
+            match self.parse_edit(original.as_ref(), &event.text) {
+                Ok(value) => {
+                    self.edit_error = None;
+                    self.set_insert_value(index, &event.col_name, value, cx);
+                }
+                Err(e) => {
+                    self.edit_error = Some(format!("{}: {}", event.col_name, e));
+                    cx.notify();
+                }
+            }
+            return;
+        }
+
+        let Some(doc) = self.document_at(event.row_index) else {
+            return;
+        };
+        let Some(id) = self.row_key(doc) else {
+            return;
+        };
+        let original = doc.get(&event.col_name).cloned();
+
+        match self.parse_edit(original.as_ref(), &event.text) {
+            Ok(value) => {
+                self.edit_error = None;
+                self.queue_update(id, event.col_name.clone(), original, value, cx);
+            }
+            Err(e) => {
+                self.edit_error = Some(format!("{}: {}", event.col_name, e));
+                cx.notify();
+            }
+        }
+    }
+
+    /// Parse edited text as a value of the same type as `original`. `None` means
+    /// no value: leaving a missing field (or a SQL NULL) empty is not a change.
+    fn parse_edit(&self, original: Option<&Value>, text: &str) -> Result<Option<Value>, String> {
+        if self.db_type.is_sql() {
+            if matches!(original, None | Some(Value::Null)) && text.is_empty() {
+                return Ok(None);
+            }
+            return parse_sql_edited_value(original.unwrap_or(&Value::Null), text).map(Some);
+        }
+
+        if original.is_none() && text.trim().is_empty() {
+            return Ok(None);
+        }
+        parse_edited_value(original.unwrap_or(&Value::Null), text).map(Some)
+    }
+
+    /// Record a new field value, replacing an earlier edit of the same field.
+    /// Setting the field back to its server value (or to `None`) drops the edit.
+    fn queue_update(
+        &mut self,
+        id: Value,
+        field: String,
+        original: Option<Value>,
+        value: Option<Value>,
+        cx: &mut Context<Self>,
+    ) {
+        self.pending_changes.retain(|change| {
+            !matches!(change, PendingChange::Update { id: edited, field: edited_field, .. }
+                if *edited == id && *edited_field == field)
+        });
+        if let Some(value) = value.filter(|value| Some(value) != original.as_ref()) {
+            self.pending_changes.push(PendingChange::Update { id, field, original, value });
+        }
+        self.refresh_pending(cx);
+    }
+
+    /// Set (or with `None`, unset) a field of a pending insert
+    fn set_insert_value(&mut self, index: usize, field: &str, value: Option<Value>, cx: &mut Context<Self>) {
+        if let Some(PendingChange::Insert(Value::Object(row))) = self.pending_changes.get_mut(index) {
+            match value {
+                Some(value) => row.insert(field.to_string(), value),
+                None => row.remove(field),
+            };
+        }
+        self.refresh_pending(cx);
+    }
+
+    /// Set a SQL cell to NULL
+    fn set_null(&mut self, row_index: usize, col_index: usize, cx: &mut Context<Self>) {
+        let Some(col_name) = self.columns.get(col_index).cloned() else {
+            return;
+        };
+        if let Some(index) = self.insert_at(row_index) {
+            self.set_insert_value(index, &col_name, Some(Value::Null), cx);
+            return;
+        }
+        let Some(doc) = self.document_at(row_index) else {
+            return;
+        };
+        if let Some(id) = self.row_key(doc).filter(|id| !self.is_pending_delete(id)) {
+            let original = doc.get(&col_name).cloned();
+            self.queue_update(id, col_name, original, Some(Value::Null), cx);
+        }
+    }
+
+    /// Queue an empty SQL row (columns left unset get their defaults), or open
+    /// the document editor on a new document
+    fn add_row(&mut self, cx: &mut Context<Self>) {
+        if !self.is_editable() {
+            return;
+        }
+        if !self.db_type.is_sql() {
+            self.pending_insert_document = true;
+            cx.notify();
+            return;
+        }
+        self.pending_changes.push(PendingChange::Insert(Value::Object(serde_json::Map::new())));
+        self.edit_error = None;
+        self.refresh_pending(cx);
+    }
+
+    /// Queue a copy of the row, without its key so the server assigns a new one
+    fn duplicate_document(&mut self, row_index: usize, cx: &mut Context<Self>) {
+        let row = match self.insert_at(row_index) {
+            Some(index) => match &self.pending_changes[index] {
+                PendingChange::Insert(Value::Object(row)) => Some(row.clone()),
+                _ => None,
+            },
+            None => self.document_at(row_index).and_then(|doc| self.shown_row(doc)),
+        };
+        let Some(mut copy) = row else {
+            return;
+        };
+
+        if self.db_type.is_sql() {
+            for column in self.table_columns.iter().filter(|column| column.primary_key) {
+                copy.remove(&column.name);
+            }
+        } else {
+            copy.remove("_id");
+        }
+        self.pending_changes.push(PendingChange::Insert(Value::Object(copy)));
+        self.refresh_pending(cx);
+    }
+
+    /// A loaded row as it is shown, with pending edits applied
+    fn shown_row(&self, doc: &Value) -> Option<serde_json::Map<String, Value>> {
+        let mut row: serde_json::Map<String, Value> = doc
+            .as_object()?
+            .keys()
+            .filter_map(|field| Some((field.clone(), self.pending_value(doc, field)?.clone())))
+            .collect();
+        // Fields the row lacks that were given a value
+        let id = self.row_key(doc);
+        for change in &self.pending_changes {
+            if let PendingChange::Update { id: edited, field, value, .. } = change {
+                if Some(edited) == id.as_ref() && !row.contains_key(field) {
+                    row.insert(field.clone(), value.clone());
+                }
+            }
+        }
+        Some(row)
+    }
+
+    /// Open the Extended JSON editor on a loaded document, or with `None` on an
+    /// empty document to insert
+    fn open_document_editor(&mut self, row_index: Option<usize>, window: &mut Window, cx: &mut Context<Self>) {
+        let document = match row_index {
+            Some(row_index) => {
+                let Some(doc) = self.document_at(row_index) else {
+                    return;
+                };
+                let error = match self.row_key(doc) {
+                    None => Some("This document has no _id to replace it by"),
+                    Some(id) if self.is_pending_delete(&id) => Some("This document is marked for deletion"),
+                    Some(_) => None,
+                };
+                if let Some(error) = error {
+                    self.edit_error = Some(error.to_string());
+                    cx.notify();
+                    return;
+                }
+                self.shown_row(doc).map(Value::Object)
+            }
+            None => None,
+        };
+
+        let editor = cx.new(|cx| DocumentEditor::new(document.as_ref(), cx));
+        cx.subscribe(&editor, |this, _, event: &DocumentSubmitted, cx| {
+            this.save_document(event, cx);
+        })
+        .detach();
+        cx.subscribe(&editor, |this, _, _: &DocumentEditorClosed, cx| {
+            this.close_document_editor(cx);
+        })
+        .detach();
+        editor.read(cx).focus(window, cx);
+
+        self.close_side_panels(cx);
+        self.edit_error = None;
+        self.document_editor = Some(editor);
+        cx.notify();
+    }
+
+    fn close_document_editor(&mut self, cx: &mut Context<Self>) {
+        self.document_editor = None;
+        cx.notify();
+    }
+
+    /// Write a document from the editor right away: insert it, or replace the
+    /// document with its `_id`. The editor showed the document with its pending
+    /// edits, so those are dropped once it is replaced.
+    fn save_document(&mut self, event: &DocumentSubmitted, cx: &mut Context<Self>) {
+        let id = event.id.clone();
+        let document = event.document.clone();
+        let db_name = self.database_name.clone();
+        let coll_name = self.collection_name.clone();
+        let config = self.config.clone();
+        let connection_id = self.connection_id.clone();
+
+        let task = runtime::spawn({
+            let id = id.clone();
+            async move {
+                let conn = registry().get(&connection_id, config).await?;
+                match &id {
+                    Some(id) => conn.replace_document(&db_name, &coll_name, id, &document).await,
+                    None => conn
+                        .insert_document(&db_name, &coll_name, &document)
+                        .await
+                        .map(|_| ()),
+                }
+            }
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |view, cx| {
+                let error = match result {
+                    Ok(Ok(())) => {
+                        if let Some(id) = &id {
+                            view.pending_changes.retain(
+                                |change| !matches!(change, PendingChange::Update { id: edited, .. } if edited == id),
+                            );
+                        }
+                        view.document_editor = None;
+                        view.load_documents(cx);
+                        return;
+                    }
+                    Ok(Err(e)) => e.to_string(),
+                    Err(_) => "Failed to save document".to_string(),
+                };
+                if let Some(editor) = &view.document_editor {
+                    editor.update(cx, |editor, cx| editor.set_save_error(error, cx));
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Mark the row for deletion, or unmark it. A pending insert is simply dropped.
+    fn toggle_delete_document(&mut self, row_index: usize, cx: &mut Context<Self>) {
+        if let Some(index) = self.insert_at(row_index) {
+            self.revert_change(index, cx);
+            return;
+        }
+
+        let Some(id) = self.document_at(row_index).and_then(|doc| self.row_key(doc)) else {
+            self.edit_error = Some(format!("This {} has no key to delete it by", self.row_noun()));
+            cx.notify();
+            return;
+        };
+
+        if self.is_pending_delete(&id) {
+            self.pending_changes
+                .retain(|change| !matches!(change, PendingChange::Delete(deleted) if *deleted == id));
+        } else {
+            // Edits to a deleted row would be lost anyway
+            self.pending_changes.retain(
+                |change| !matches!(change, PendingChange::Update { id: edited, .. } if *edited == id),
+            );
+            self.pending_changes.push(PendingChange::Delete(id));
+        }
+        self.refresh_pending(cx);
+    }
+
+    fn revert_change(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index < self.pending_changes.len() {
+            self.pending_changes.remove(index);
+            self.refresh_pending(cx);
+        }
+    }
+
+    fn discard_changes(&mut self, cx: &mut Context<Self>) {
+        self.pending_changes.clear();
+        self.edit_error = None;
+        self.refresh_pending(cx);
+    }
+
+    /// Redraw the table after the pending changes were modified
+    fn refresh_pending(&mut self, cx: &mut Context<Self>) {
+        if self.pending_changes.is_empty() {
+            self.show_changes = false;
+        }
+        self.populate_table(self.current_page, cx);
+        cx.notify();
+    }
+
+    fn toggle_changes_panel(&mut self, cx: &mut Context<Self>) {
+        let show = !self.show_changes;
+        if show {
+            self.close_side_panels(cx);
+        }
+        self.show_changes = show;
+        cx.notify();
+    }
+
+    /// Close the side panels before another one opens. Panels with an export or
+    /// import still running stay open, since closing them would cancel it.
+    fn close_side_panels(&mut self, cx: &App) {
+        self.detail_content = None;
+        self.detail_text_area = None;
+        self.show_changes = false;
+        self.document_editor = None;
+        self.explain_panel = None;
+        if !self.export_panel.as_ref().is_some_and(|panel| panel.read(cx).is_running()) {
+            self.export_panel = None;
+        }
+        if !self.import_wizard.as_ref().is_some_and(|wizard| wizard.read(cx).is_running()) {
+            self.import_wizard = None;
+        }
+    }
+
+    /// Group pending changes into server writes: one `$set` per edited document,
+    /// one insert per copy and a single delete. Each write lists the indices of
+    /// the pending changes it covers.
+    fn build_writes(&self) -> Vec<(DocumentWrite, Vec<usize>)> {
+        let mut writes: Vec<(DocumentWrite, Vec<usize>)> = Vec::new();
+        let mut deleted = Vec::new();
+        let mut deleted_indices = Vec::new();
+
+        for (index, change) in self.pending_changes.iter().enumerate() {
+            match change {
+                PendingChange::Update { id, field, value, .. } => {
+                    let existing = writes.iter_mut().find(|(write, _)| {
+                        matches!(write, DocumentWrite::Update { id: written, .. } if written == id)
+                    });
+                    match existing {
+                        Some((DocumentWrite::Update { changes, .. }, indices)) => {
+                            changes.insert(field.clone(), value.clone());
+                            indices.push(index);
+                        }
+                        _ => {
+                            let mut changes = serde_json::Map::new();
+                            changes.insert(field.clone(), value.clone());
+                            writes.push((DocumentWrite::Update { id: id.clone(), changes }, vec![index]));
+                        }
+                    }
+                }
+                PendingChange::Insert(document) => {
+                    writes.push((DocumentWrite::Insert(document.clone()), vec![index]));
+                }
+                PendingChange::Delete(id) => {
+                    deleted.push(id.clone());
+                    deleted_indices.push(index);
+                }
+            }
+        }
+
+        if !deleted.is_empty() {
+            writes.push((DocumentWrite::Delete(deleted), deleted_indices));
+        }
+        writes
+    }
+
+    /// `UPDATE`/`INSERT`/`DELETE` statements for the pending changes of a SQL
+    /// table: one `UPDATE` per edited row, keyed on its primary key. Each statement
+    /// comes with the kind of change it applies.
+    fn sql_statements(&self) -> Vec<(ChangeKind, String)> {
+        let driver = self.db_type;
+        let table = sql_edit::table_reference(driver, &self.database_name, &self.collection_name);
+
+        let mut statements = Vec::new();
+        for (write, _) in self.build_writes() {
+            match &write {
+                DocumentWrite::Update { id: Value::Object(key), changes } => {
+                    let change = RowChange::Update { key, values: changes };
+                    statements.push((ChangeKind::Update, sql_edit::statement(driver, &table, &change)));
+                }
+                DocumentWrite::Insert(Value::Object(values)) => {
+                    let change = RowChange::Insert { values };
+                    statements.push((ChangeKind::Insert, sql_edit::statement(driver, &table, &change)));
+                }
+                DocumentWrite::Delete(ids) => {
+                    for key in ids.iter().filter_map(Value::as_object) {
+                        let change = RowChange::Delete { key };
+                        statements.push((ChangeKind::Delete, sql_edit::statement(driver, &table, &change)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        statements
+    }
+
+    /// Write all pending changes. Writes run in order; on failure the ones that
+    /// succeeded are dropped from the queue and the rest stay pending.
+    fn apply_changes(&mut self, cx: &mut Context<Self>) {
+        if self.applying_changes || self.pending_changes.is_empty() {
+            return;
+        }
+        self.applying_changes = true;
+        self.edit_error = None;
+        cx.notify();
+
+        if self.db_type.is_sql() {
+            self.commit_sql_changes(cx);
+            return;
+        }
+
+        let (writes, covered): (Vec<_>, Vec<_>) = self.build_writes().into_iter().unzip();
+        let db_name = self.database_name.clone();
+        let coll_name = self.collection_name.clone();
+        let config = self.config.clone();
+        let connection_id = self.connection_id.clone();
+
+        let task = runtime::spawn(async move {
+            let conn = match registry().get(&connection_id, config).await {
+                Ok(conn) => conn,
+                Err(e) => return (0, Err(e)),
+            };
+            for (done, write) in writes.iter().enumerate() {
+                let result = match write {
+                    DocumentWrite::Update { id, changes } => {
+                        conn.update_document(&db_name, &coll_name, id, changes).await
+                    }
+                    DocumentWrite::Insert(document) => conn
+                        .insert_document(&db_name, &coll_name, document)
+                        .await
+                        .map(|_| ()),
+                    DocumentWrite::Delete(ids) => conn
+                        .delete_documents(&db_name, &coll_name, ids)
+                        .await
+                        .map(|_| ()),
+                };
+                if let Err(e) = result {
+                    return (done, Err(e));
+                }
+            }
+            (writes.len(), Ok(()))
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |view, cx| {
+                view.applying_changes = false;
+                match result {
+                    Ok((done, result)) => {
+                        let applied: HashSet<usize> =
+                            covered.into_iter().take(done).flatten().collect();
+                        let mut index = 0;
+                        view.pending_changes.retain(|_| {
+                            let keep = !applied.contains(&index);
+                            index += 1;
+                            keep
+                        });
+                        if let Err(e) = result {
+                            view.edit_error = Some(e.to_string());
+                        }
+                    }
+                    Err(_) => {
+                        view.edit_error = Some("Failed to apply changes".to_string());
+                    }
+                }
+                if view.pending_changes.is_empty() {
+                    view.show_changes = false;
+                }
+                view.load_documents(cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Run the pending SQL changes in one transaction: either all of them are
+    /// written or, if any statement fails, none are and the queue is kept
+    fn commit_sql_changes(&mut self, cx: &mut Context<Self>) {
+        let (kinds, statements): (Vec<_>, Vec<_>) = self.sql_statements().into_iter().unzip();
+        let db_name = self.database_name.clone();
+        let config = self.config.clone();
+        let connection_id = self.connection_id.clone();
+
+        let task = runtime::spawn(async move {
+            let conn = registry().get(&connection_id, config).await?;
+            conn.execute_transaction(&db_name, &statements).await
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |view, cx| {
+                view.applying_changes = false;
+                match result {
+                    Ok(Ok(affected)) => {
+                        view.pending_changes.clear();
+                        view.show_changes = false;
+                        // The row may have been changed or deleted since it was loaded
+                        let missed = affected
+                            .iter()
+                            .zip(&kinds)
+                            .filter(|(count, kind)| **count == 0 && **kind != ChangeKind::Insert)
+                            .count();
+                        if missed > 0 {
+                            view.edit_error = Some(format!(
+                                "Committed, but {} of the updates and deletes matched no rows",
+                                missed
+                            ));
+                        }
+                        view.load_documents(cx);
+                    }
+                    Ok(Err(e)) => {
+                        view.edit_error = Some(format!("Rolled back: {}", e));
+                        cx.notify();
+                    }
+                    Err(_) => {
+                        view.edit_error = Some("Failed to commit changes".to_string());
+                        cx.notify();
+                    }
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn is_redis_keyspace(&self) -> bool {
+        self.db_type == DatabaseType::Redis && self.collection_name == REDIS_KEYSPACE
+    }
+
     /// Handle sort change
     fn on_sort_change(&mut self, event: &SortChangeRequested, cx: &mut Context<Self>) {
         // Empty field means clear sort
@@ -372,14 +1237,13 @@ impl CollectionView {
         cx: &mut Context<Self>,
     ) {
         let row_index = pending.row_index;
-        let _col_index = pending.col_index;
         let col_name = pending.col_name.clone();
         let value = pending.value.clone();
         let value_for_copy = pending.value.to_string();
         let position = pending.position;
 
         // Get the raw JSON value for pretty printing in detail view
-        let local_row_idx = row_index % PAGE_SIZE;
+        let local_row_idx = self.local_row(row_index);
         let pretty_value = if let Some(Value::Object(map)) = self.documents.get(local_row_idx) {
             if let Some(raw_value) = map.get(&col_name) {
                 serde_json::to_string_pretty(raw_value)
@@ -397,8 +1261,23 @@ impl CollectionView {
         // Get entity for View action callback
         let view_entity = cx.entity().clone();
 
+        // Row actions for collections and tables that can be edited
+        let editable = self.is_editable();
+        let is_sql = self.db_type.is_sql();
+        let col_index = pending.col_index;
+        let marked_for_delete = self
+            .document_at(row_index)
+            .and_then(|doc| self.row_key(doc))
+            .is_some_and(|id| self.is_pending_delete(&id));
+        let is_insert = self.insert_at(row_index).is_some();
+        let nullable = self
+            .table_columns
+            .iter()
+            .find(|column| column.name == col_name)
+            .is_some_and(|column| column.nullable);
+
         let menu = PopupMenu::build(window, cx, move |menu, _window, _cx| {
-            menu.item(
+            let menu = menu.item(
                 PopupMenuItem::new("View")
                     .icon(gpui_component::IconName::Eye)
                     .on_click({
@@ -422,6 +1301,111 @@ impl CollectionView {
                         }
                     }),
             )
+            .item(
+                PopupMenuItem::new("Export...")
+                    .icon(gpui_component::IconName::ExternalLink)
+                    .on_click({
+                        let entity = view_entity.clone();
+                        move |_, _, cx| {
+                            entity.update(cx, |this, cx| {
+                                this.open_export_panel(cx);
+                            });
+                        }
+                    }),
+            );
+            if !editable {
+                return menu;
+            }
+            let menu = menu.separator().item(
+                PopupMenuItem::new("Edit Value")
+                    .icon(gpui_component::IconName::Replace)
+                    .on_click({
+                        let entity = view_entity.clone();
+                        move |_, _, cx| {
+                            entity.update(cx, |this, cx| {
+                                this.start_edit(row_index, col_index, cx);
+                            });
+                        }
+                    }),
+            );
+            let menu = if is_sql && nullable && !marked_for_delete {
+                menu.item(
+                    PopupMenuItem::new("Set NULL")
+                        .icon(gpui_component::IconName::Close)
+                        .on_click({
+                            let entity = view_entity.clone();
+                            move |_, _, cx| {
+                                entity.update(cx, |this, cx| {
+                                    this.set_null(row_index, col_index, cx);
+                                });
+                            }
+                        }),
+                )
+            } else {
+                menu
+            };
+            let menu = if !is_sql && !is_insert && !marked_for_delete {
+                menu.item(
+                    PopupMenuItem::new("Edit Document")
+                        .icon(gpui_component::IconName::Replace)
+                        .on_click({
+                            let entity = view_entity.clone();
+                            move |_, window, cx| {
+                                entity.update(cx, |this, cx| {
+                                    this.open_document_editor(Some(row_index), window, cx);
+                                });
+                            }
+                        }),
+                )
+            } else {
+                menu
+            };
+            menu.item(
+                PopupMenuItem::new(if is_sql { "Add Row" } else { "Insert Document" })
+                    .icon(gpui_component::IconName::Plus)
+                    .on_click({
+                        let entity = view_entity.clone();
+                        move |_, _, cx| {
+                            entity.update(cx, |this, cx| {
+                                this.add_row(cx);
+                            });
+                        }
+                    }),
+            )
+            .item(
+                PopupMenuItem::new(if is_sql { "Duplicate Row" } else { "Duplicate Document" })
+                    .icon(gpui_component::IconName::Plus)
+                    .on_click({
+                        let entity = view_entity.clone();
+                        move |_, _, cx| {
+                            entity.update(cx, |this, cx| {
+                                this.duplicate_document(row_index, cx);
+                            });
+                        }
+                    }),
+            )
+            .item(
+                PopupMenuItem::new(if marked_for_delete {
+                    "Undo Delete"
+                } else if is_insert && is_sql {
+                    "Remove Row"
+                } else if is_insert {
+                    "Remove Document"
+                } else if is_sql {
+                    "Delete Row"
+                } else {
+                    "Delete Document"
+                })
+                .icon(gpui_component::IconName::Delete)
+                .on_click({
+                    let entity = view_entity.clone();
+                    move |_, _, cx| {
+                        entity.update(cx, |this, cx| {
+                            this.toggle_delete_document(row_index, cx);
+                        });
+                    }
+                }),
+            )
         });
 
         // Subscribe to dismiss events
@@ -452,7 +1436,7 @@ impl CollectionView {
         cx: &mut Context<Self>,
     ) {
         // Get the raw JSON value from documents for pretty printing
-        let local_row_idx = row_index % PAGE_SIZE;
+        let local_row_idx = self.local_row(row_index);
         let pretty_value = if let Some(Value::Object(map)) = self.documents.get(local_row_idx) {
             if let Some(raw_value) = map.get(&col_name) {
                 serde_json::to_string_pretty(raw_value)
@@ -482,6 +1466,8 @@ impl CollectionView {
 
     /// Open detail panel directly with pre-computed values (from context menu "View" action)
     fn open_detail_panel(&mut self, col_name: String, pretty_value: String, cx: &mut Context<Self>) {
+        self.close_side_panels(cx);
+
         // Create or update the selectable text area
         if let Some(text_area) = &self.detail_text_area {
             text_area.update(cx, |ta, cx| {
@@ -502,98 +1488,396 @@ impl CollectionView {
         cx.notify();
     }
 
+    /// Current filter, if any
+    fn filter(&self) -> Option<String> {
+        if self.filter_query.is_empty() { None } else { Some(self.filter_query.clone()) }
+    }
+
+    /// Current sort as a JSON sort document, if any
+    fn sort(&self) -> Option<String> {
+        match (&self.sort_field, &self.sort_direction) {
+            (Some(field), Some(SortDirection::Ascending)) => Some(format!("{{\"{}\": 1}}", field)),
+            (Some(field), Some(SortDirection::Descending)) => Some(format!("{{\"{}\": -1}}", field)),
+            _ => None,
+        }
+    }
+
+    /// Open the export panel on the current page, the selected rows, or every row
+    /// matching the filter
+    fn open_export_panel(&mut self, cx: &mut Context<Self>) {
+        let selected = self
+            .table_view
+            .read(cx)
+            .selected_rows()
+            .into_iter()
+            .filter_map(|ix| self.documents.get(ix).cloned())
+            .collect();
+        let table = if self.db_type.is_sql() {
+            sql_edit::table_reference(self.db_type, &self.database_name, &self.collection_name)
+        } else {
+            sql_edit::quote_identifier(self.db_type, &self.collection_name)
+        };
+        let request = ExportRequest {
+            page: self.documents.clone(),
+            selected,
+            all: ExportAll::Query(Box::new(ExportQuery {
+                connection_id: self.connection_id.clone(),
+                config: self.config.clone(),
+                database: self.database_name.clone(),
+                collection: self.collection_name.clone(),
+                filter: self.filter(),
+                sort: self.sort(),
+            })),
+            total: self.total_count,
+            driver: self.db_type,
+            table,
+            file_stem: self.collection_name.replace(['/', '\\', ':'], "_"),
+        };
+
+        let panel = cx.new(|cx| ExportPanel::new(request, cx));
+        cx.subscribe(&panel, |this, _, _: &ExportPanelClosed, cx| {
+            this.export_panel = None;
+            cx.notify();
+        })
+        .detach();
+
+        self.close_side_panels(cx);
+        self.export_panel = Some(panel);
+        cx.notify();
+    }
+
+    /// Collections, and tables whose columns are known, can be imported into
+    fn can_import(&self) -> bool {
+        match self.db_type {
+            DatabaseType::MongoDB => true,
+            db_type => db_type.is_sql() && !self.table_columns.is_empty(),
+        }
+    }
+
+    fn open_import_wizard(&mut self, cx: &mut Context<Self>) {
+        if self.import_wizard.is_some() {
+            return;
+        }
+        let destination = ImportDestination {
+            connection_id: self.connection_id.clone(),
+            config: self.config.clone(),
+            database: self.database_name.clone(),
+            collection: self.collection_name.clone(),
+            driver: self.db_type,
+            columns: self.table_columns.iter().map(|column| column.name.clone()).collect(),
+        };
+
+        let wizard = cx.new(|cx| ImportWizard::new(destination, cx));
+        cx.subscribe(&wizard, |this, _, _: &ImportFinished, cx| {
+            this.load_documents(cx);
+        })
+        .detach();
+        cx.subscribe(&wizard, |this, _, _: &ImportWizardClosed, cx| {
+            this.import_wizard = None;
+            cx.notify();
+        })
+        .detach();
+
+        self.close_side_panels(cx);
+        self.import_wizard = Some(wizard);
+        cx.notify();
+    }
+
+    /// Explain the page loaded with this filter and the current sort
+    fn open_explain_panel(&mut self, filter: String, cx: &mut Context<Self>) {
+        let filter = filter.trim();
+        let request = ExplainRequest {
+            connection_id: self.connection_id.clone(),
+            config: self.config.clone(),
+            database: self.database_name.clone(),
+            target: ExplainTarget::Documents {
+                collection: self.collection_name.clone(),
+                limit: PAGE_SIZE as u32,
+                skip: (self.current_page * PAGE_SIZE) as u32,
+                filter: if filter.is_empty() { None } else { Some(filter.to_string()) },
+                sort: self.sort(),
+            },
+        };
+
+        let panel = cx.new(|cx| ExplainPanel::new(request, cx));
+        cx.subscribe(&panel, |this, _, _: &ExplainPanelClosed, cx| {
+            this.explain_panel = None;
+            cx.notify();
+        })
+        .detach();
+
+        self.close_side_panels(cx);
+        self.explain_panel = Some(panel);
+        cx.notify();
+    }
+
+    /// Sub-views offered for this collection or table
+    fn tabs(&self) -> Vec<CollectionTab> {
+        let mut tabs = vec![CollectionTab::Documents];
+        if self.db_type.is_sql() {
+            tabs.push(CollectionTab::Structure);
+        }
+        if self.db_type == DatabaseType::MongoDB {
+            tabs.push(CollectionTab::Schema);
+            tabs.push(CollectionTab::Aggregation);
+        }
+        if self.db_type != DatabaseType::Redis {
+            tabs.push(CollectionTab::Indexes);
+        }
+        tabs
+    }
+
+    fn set_tab(&mut self, tab: CollectionTab, cx: &mut Context<Self>) {
+        if tab == CollectionTab::Aggregation && self.pipeline_builder.is_none() {
+            let connection_id = self.connection_id.clone();
+            let config = self.config.clone();
+            let database_name = self.database_name.clone();
+            let collection_name = self.collection_name.clone();
+            self.pipeline_builder = Some(cx.new(|cx| {
+                PipelineBuilder::new(connection_id, config, database_name, collection_name, cx)
+            }));
+        }
+        if tab == CollectionTab::Schema && self.schema_view.is_none() {
+            let connection_id = self.connection_id.clone();
+            let config = self.config.clone();
+            let database_name = self.database_name.clone();
+            let collection_name = self.collection_name.clone();
+            let schema_view = cx.new(|cx| {
+                SchemaView::new(connection_id, config, database_name, collection_name, cx)
+            });
+            cx.subscribe(&schema_view, |this, _, event: &SchemaSampled, cx| {
+                this.sampled_columns = event.0.clone();
+                if matches!(this.loading_state, LoadingState::Loaded) {
+                    this.extract_schema();
+                    this.populate_table(this.current_page, cx);
+                }
+            })
+            .detach();
+            self.schema_view = Some(schema_view);
+        }
+        if tab == CollectionTab::Structure && self.structure_view.is_none() {
+            let connection_id = self.connection_id.clone();
+            let config = self.config.clone();
+            let database_name = self.database_name.clone();
+            let collection_name = self.collection_name.clone();
+            self.structure_view = Some(cx.new(|cx| {
+                StructureView::new(connection_id, config, database_name, collection_name, cx)
+            }));
+        }
+        if tab == CollectionTab::Indexes && self.indexes_view.is_none() {
+            let connection_id = self.connection_id.clone();
+            let config = self.config.clone();
+            let database_name = self.database_name.clone();
+            let collection_name = self.collection_name.clone();
+            self.indexes_view = Some(cx.new(|cx| {
+                IndexesView::new(connection_id, config, database_name, collection_name, cx)
+            }));
+        }
+        self.active_tab = tab;
+        cx.notify();
+    }
+
+    /// Put the sub-view tab bar above `content` when there is more than one tab
+    fn render_with_tabs(&self, content: AnyElement, cx: &mut Context<Self>) -> AnyElement {
+        let tabs = self.tabs();
+        if tabs.len() < 2 {
+            return content;
+        }
+        let active_tab = self.active_tab;
+
+        div()
+            .flex()
+            .flex_col()
+            .size_full()
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .flex_shrink_0()
+                    .h(rems(2.0)) // 32px
+                    .px(rems(0.5)) // 8px
+                    .bg(AppColors::bg_secondary())
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .children(tabs.into_iter().map(|tab| {
+                        let selected = tab == active_tab;
+                        div()
+                            .id(tab.label())
+                            .flex()
+                            .items_center()
+                            .h_full()
+                            .px(rems(0.75)) // 12px
+                            .text_size(rems(0.75)) // 12px
+                            .border_b_2()
+                            .when(selected, |el| {
+                                el.border_color(AppColors::accent())
+                                    .text_color(AppColors::text())
+                            })
+                            .when(!selected, |el| {
+                                el.cursor_pointer()
+                                    .border_color(transparent_black())
+                                    .text_color(AppColors::text_muted())
+                                    .hover(|s| s.text_color(AppColors::text()))
+                            })
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.set_tab(tab, cx);
+                            }))
+                            .child(tab.label())
+                    })),
+            )
+            .child(div().flex_1().min_h_0().child(content))
+            .into_any_element()
+    }
+
     /// Load documents from the collection
     fn load_documents(&mut self, cx: &mut Context<Self>) {
         self.loading_state = LoadingState::Loading;
         cx.emit(LoadingStateChanged(true));
         cx.notify();
 
-        let conn_string = self.connection_string.clone();
         let db_name = self.database_name.clone();
         let coll_name = self.collection_name.clone();
         let offset = self.current_page * PAGE_SIZE;
         let limit = PAGE_SIZE as u32;
-        let filter_str = if self.filter_query.is_empty() { None } else { Some(self.filter_query.clone()) };
-        let sort_str = match (&self.sort_field, &self.sort_direction) {
-            (Some(field), Some(SortDirection::Ascending)) => Some(format!("{{\"{}\": 1}}", field)),
-            (Some(field), Some(SortDirection::Descending)) => Some(format!("{{\"{}\": -1}}", field)),
-            _ => None,
+        let filter_str = self.filter();
+        let sort_str = self.sort();
+
+        let config = self.config.clone();
+        let is_redis = self.db_type == DatabaseType::Redis;
+        let is_redis_key = is_redis && !self.is_redis_keyspace();
+        // Column types and the primary key only need loading once
+        let load_columns = self.db_type.is_sql() && self.table_columns.is_empty();
+
+        // Redis keyspace pages continue the SCAN where the previous page stopped;
+        // `None` past the last page
+        let keyspace_from = if self.is_redis_keyspace() {
+            if self.current_page == 0 {
+                self.keyspace_cursors = vec![KeyspaceCursor::default()];
+            }
+            Some(self.keyspace_cursors.get(self.current_page).cloned())
+        } else {
+            None
         };
 
-        let config = ConnectionConfig::new(DatabaseType::MongoDB, conn_string);
-
-        // Use channel for async communication
-        let (tx, rx) = std::sync::mpsc::channel();
+        let connection_id = self.connection_id.clone();
 
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            let result = rt.block_on(async {
-                match create_connection(config) {
-                    Ok(conn) => {
-                        // Get documents for current page
-                        let docs = conn
-                            .query_documents(&db_name, &coll_name, limit, offset as u32, filter_str.as_deref(), sort_str.as_deref())
-                            .await?;
+        let task = runtime::spawn(async move {
+            let conn = registry().get(&connection_id, config).await?;
 
-                        // Get total count
-                        let count = conn.count_documents(&db_name, &coll_name, filter_str.as_deref()).await?;
-
-                        Ok((docs, count))
-                    }
-                    Err(e) => Err(e),
+            // Get documents for current page
+            let mut next_cursor = None;
+            let docs = match &keyspace_from {
+                Some(Some(from)) => {
+                    let page = conn.scan_keyspace(&db_name, filter_str.as_deref(), from, limit).await?;
+                    next_cursor = page.next;
+                    page.rows
                 }
-            });
-            tx.send(result).ok();
+                Some(None) => Vec::new(),
+                None => {
+                    conn.query_documents(&db_name, &coll_name, limit, offset as u32, filter_str.as_deref(), sort_str.as_deref())
+                        .await?
+                }
+            };
+
+            // Get total count
+            let count = conn.count_documents(&db_name, &coll_name, filter_str.as_deref()).await?;
+            let count_capped = is_redis && filter_str.is_some() && count >= REDIS_COUNT_LIMIT;
+
+            // For a single Redis key, look up its type and TTL from the keyspace listing
+            let key_info = if is_redis_key {
+                let pattern = escape_redis_pattern(&coll_name);
+                let rows = conn
+                    .query_documents(&db_name, REDIS_KEYSPACE, 1, 0, Some(pattern.as_str()), None)
+                    .await?;
+                rows.first().map(format_key_info)
+            } else {
+                None
+            };
+
+            let columns = if load_columns {
+                conn.table_columns(&db_name, &coll_name).await?
+            } else {
+                Vec::new()
+            };
+
+            Ok::<_, ConnectionError>((docs, count, count_capped, next_cursor, key_info, columns))
         });
 
         let current_page = self.current_page;
 
         cx.spawn(async move |this, cx| {
-            loop {
-                match rx.try_recv() {
-                    Ok(result) => {
-                        this.update(cx, |view, cx| {
-                            match result {
-                                Ok((documents, count)) => {
-                                    view.documents = documents;
-                                    view.total_count = count;
-                                    view.extract_schema();
-                                    view.populate_table(current_page, cx);
-                                    view.loading_state = LoadingState::Loaded;
+            let result = task.await;
+            this.update(cx, |view, cx| {
+                match result {
+                    Ok(Ok((documents, mut count, mut count_capped, next_cursor, key_info, columns))) => {
+                        if view.is_redis_keyspace() {
+                            // Keep the pager consistent with what the scan found, even if
+                            // keys were added or removed since the count
+                            let seen = current_page * PAGE_SIZE + documents.len();
+                            view.keyspace_cursors.truncate(current_page + 1);
+                            match next_cursor {
+                                Some(next) => {
+                                    view.keyspace_cursors.push(next);
+                                    count = count.max(seen + 1);
                                 }
-                                Err(e) => {
-                                    view.loading_state = LoadingState::Error(e.to_string());
+                                None => {
+                                    count = seen;
+                                    count_capped = false;
                                 }
                             }
-                            cx.emit(LoadingStateChanged(false));
-                            cx.notify();
-                        })
-                        .ok();
-                        break;
+                        }
+                        view.documents = documents;
+                        view.total_count = count;
+                        view.total_at_least = count_capped;
+                        view.key_info = key_info;
+                        if load_columns {
+                            view.table_columns = columns;
+                        }
+                        view.extract_schema();
+                        view.table_view.update(cx, |table, _| table.clear_row_selection());
+                        view.populate_table(current_page, cx);
+                        view.loading_state = LoadingState::Loaded;
                     }
-                    Err(std::sync::mpsc::TryRecvError::Empty) => {
-                        cx.background_executor()
-                            .timer(std::time::Duration::from_millis(50))
-                            .await;
+                    Ok(Err(e)) => {
+                        view.loading_state = LoadingState::Error(e.to_string());
                     }
-                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                        this.update(cx, |view, cx| {
-                            view.loading_state =
-                                LoadingState::Error("Failed to load documents".to_string());
-                            cx.emit(LoadingStateChanged(false));
-                            cx.notify();
-                        })
-                        .ok();
-                        break;
+                    Err(_) => {
+                        view.loading_state =
+                            LoadingState::Error("Failed to load documents".to_string());
                     }
                 }
-            }
+                cx.emit(LoadingStateChanged(false));
+                cx.notify();
+            })
+            .ok();
         })
         .detach();
     }
 
-    /// Extract unique field names from documents to build schema
+    /// Columns of the table: declared columns for tables, otherwise the sampled
+    /// fields and the keys of the documents on the current page
     fn extract_schema(&mut self) {
+        // Tables list their columns even when no rows match
+        if !self.table_columns.is_empty() {
+            self.columns = self.table_columns.iter().map(|column| column.name.clone()).collect();
+            return;
+        }
+
+        // SQL rows and Redis entries share one column list; keep the driver's column order
+        if self.db_type != DatabaseType::MongoDB {
+            self.columns = Vec::new();
+            for doc in &self.documents {
+                if let Value::Object(map) = doc {
+                    for key in map.keys() {
+                        if !self.columns.contains(key) {
+                            self.columns.push(key.clone());
+                        }
+                    }
+                }
+            }
+            return;
+        }
+
         let mut field_names: BTreeSet<String> = BTreeSet::new();
 
         // _id should always be first
@@ -607,10 +1891,15 @@ impl CollectionView {
             }
         }
 
-        // Convert to vec, ensuring _id is first
-        self.columns = vec!["_id".to_string()];
+        // Sampled fields keep their place as we page; fields the sample missed
+        // are added after them
+        self.columns = if self.sampled_columns.is_empty() {
+            vec!["_id".to_string()]
+        } else {
+            self.sampled_columns.clone()
+        };
         for name in field_names {
-            if name != "_id" {
+            if !self.columns.contains(&name) {
                 self.columns.push(name);
             }
         }
@@ -636,17 +1925,28 @@ impl CollectionView {
             })
             .collect();
 
+        // Pending inserts are shown after the loaded rows
+        let inserts: Vec<&Value> = self
+            .pending_changes
+            .iter()
+            .filter_map(|change| match change {
+                PendingChange::Insert(row) => Some(row),
+                _ => None,
+            })
+            .collect();
+
         // Create rows
         let rows: Vec<Row> = self
             .documents
             .iter()
+            .chain(inserts.iter().copied())
             .map(|doc| {
                 let values: Vec<SharedString> = self
                     .columns
                     .iter()
                     .map(|col_name| {
-                        if let Value::Object(map) = doc {
-                            map.get(col_name)
+                        if let Value::Object(_) = doc {
+                            self.pending_value(doc, col_name)
                                 .map(value_to_display_string)
                                 .unwrap_or_else(|| SharedString::from(""))
                         } else {
@@ -658,12 +1958,48 @@ impl CollectionView {
             })
             .collect();
 
+        // Highlight edited cells, rows marked for deletion and rows to insert
+        let mut marks = CellMarks::default();
+        for (row_ix, doc) in self.documents.iter().enumerate() {
+            let Some(id) = self.row_key(doc) else {
+                continue;
+            };
+            for change in &self.pending_changes {
+                match change {
+                    PendingChange::Update { id: edited, field, .. } if *edited == id => {
+                        if let Some(col_ix) = self.columns.iter().position(|c| c == field) {
+                            marks.modified.insert((row_ix, col_ix));
+                        }
+                    }
+                    PendingChange::Delete(deleted) if *deleted == id => {
+                        marks.deleted_rows.insert(row_ix);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        marks.inserted_rows = (self.documents.len()..self.documents.len() + inserts.len()).collect();
+
         // Update table view
         let total_count = self.total_count;
+        let total_at_least = self.total_at_least;
+        let can_import = self.can_import();
+        let can_explain = self.db_type != DatabaseType::Redis;
+        let add_row_label = match self.db_type {
+            _ if !self.is_editable() => None,
+            DatabaseType::MongoDB => Some("Insert Document"),
+            _ => Some("Add Row"),
+        };
         self.table_view.update(cx, |table, cx| {
             table.set_columns(columns, cx);
             table.set_rows(rows, cx);
+            table.set_cell_marks(marks, cx);
+            table.set_add_row_label(add_row_label, cx);
+            table.set_export_enabled(true, cx);
+            table.set_import_enabled(can_import, cx);
+            table.set_explain_enabled(can_explain, cx);
             table.set_total_items(total_count, cx);
+            table.set_total_at_least(total_at_least, cx);
             table.set_page(page, cx);
         });
     }
@@ -673,11 +2009,343 @@ impl CollectionView {
         self.current_page = 0;
         self.load_documents(cx);
     }
+
+    /// Bar above the table with the pending change count or the last edit error
+    fn render_changes_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let count = self.pending_changes.len();
+        let summary = match &self.edit_error {
+            Some(error) => error.clone(),
+            None if count == 1 => "1 pending change".to_string(),
+            None => format!("{} pending changes", count),
+        };
+        let text_color = if self.edit_error.is_some() {
+            AppColors::error()
+        } else {
+            AppColors::warning()
+        };
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .gap(rems(0.75)) // 12px
+            .h(rems(1.75)) // 28px
+            .px(rems(0.75)) // 12px
+            .bg(AppColors::bg_secondary())
+            .border_b_1()
+            .border_color(AppColors::border_subtle())
+            .text_size(rems(0.6875)) // 11px
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .text_color(text_color)
+                    .child(summary),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.25)) // 4px
+                    .when(count > 0, |el| {
+                        el.child(changes_bar_button("review-changes", "Review").on_click(
+                            cx.listener(|this, _, _, cx| {
+                                this.toggle_changes_panel(cx);
+                            }),
+                        ))
+                        .child(changes_bar_button("discard-changes", "Discard").on_click(
+                            cx.listener(|this, _, _, cx| {
+                                this.discard_changes(cx);
+                            }),
+                        ))
+                    })
+                    .when(count == 0, |el| {
+                        el.child(changes_bar_button("dismiss-edit-error", "Dismiss").on_click(
+                            cx.listener(|this, _, _, cx| {
+                                this.edit_error = None;
+                                cx.notify();
+                            }),
+                        ))
+                    }),
+            )
+    }
+
+    /// Describe a row key, e.g. `_id 65f0…` or `id = 1, region = "eu"`
+    fn format_row_key(&self, id: &Value) -> String {
+        match id {
+            Value::Object(key) if self.db_type.is_sql() => key
+                .iter()
+                .map(|(column, value)| format!("{} = {}", column, format_bson_value(value)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => format!("_id {}", format_bson_value(id)),
+        }
+    }
+
+    /// Side panel listing pending changes as a diff, with Apply and Discard.
+    /// For SQL tables it also previews the statements, which commit in one transaction.
+    fn render_changes_panel(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let applying = self.applying_changes;
+        let count = self.pending_changes.len();
+        let is_sql = self.db_type.is_sql();
+        let noun = self.row_noun();
+        // BSON types matter for documents; SQL values are converted by the server
+        let with_type = |value: &Value| {
+            if is_sql {
+                format_bson_value(value)
+            } else {
+                format!("{}  {}", format_bson_value(value), bson_type_name(value))
+            }
+        };
+        let statements = if is_sql { self.sql_statements() } else { Vec::new() };
+
+        let entries: Vec<AnyElement> = self
+            .pending_changes
+            .iter()
+            .enumerate()
+            .map(|(index, change)| {
+                let (title, title_color, lines): (String, Hsla, Vec<(&'static str, String, Hsla)>) =
+                    match change {
+                        PendingChange::Update { id, field, original, value } => (
+                            format!("{} · {}", field, self.format_row_key(id)),
+                            AppColors::accent(),
+                            vec![
+                                (
+                                    "-",
+                                    original.as_ref().map_or("(missing)".to_string(), with_type),
+                                    AppColors::error(),
+                                ),
+                                ("+", with_type(value), AppColors::success()),
+                            ],
+                        ),
+                        PendingChange::Insert(document) => (
+                            format!("Insert {}", noun),
+                            AppColors::success(),
+                            vec![("+", truncate_str(&document.to_string(), 300), AppColors::success())],
+                        ),
+                        PendingChange::Delete(id) => (
+                            format!("Delete {}", noun),
+                            AppColors::error(),
+                            vec![("-", self.format_row_key(id), AppColors::error())],
+                        ),
+                    };
+
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(rems(0.25)) // 4px
+                    .px(rems(0.75)) // 12px
+                    .py(rems(0.5)) // 8px
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .justify_between()
+                            .gap(rems(0.5)) // 8px
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .min_w_0()
+                                    .overflow_hidden()
+                                    .text_ellipsis()
+                                    .whitespace_nowrap()
+                                    .text_size(rems(0.6875)) // 11px
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .text_color(title_color)
+                                    .child(title),
+                            )
+                            .child(
+                                div()
+                                    .id(("revert-change", index))
+                                    .cursor_pointer()
+                                    .p(rems(0.25)) // 4px
+                                    .rounded(px(3.0))
+                                    .hover(|s| s.bg(AppColors::bg_hover()))
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.revert_change(index, cx);
+                                    }))
+                                    .child(
+                                        svg()
+                                            .path("icons/close.svg")
+                                            .size(rems(0.625)) // 10px
+                                            .text_color(AppColors::text_muted()),
+                                    ),
+                            ),
+                    )
+                    .children(lines.into_iter().map(|(sign, text, color)| {
+                        div()
+                            .flex()
+                            .flex_row()
+                            .gap(rems(0.375)) // 6px
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(color)
+                            .child(div().flex_none().child(sign))
+                            .child(div().flex_1().min_w_0().child(text))
+                    }))
+                    .into_any_element()
+            })
+            .collect();
+
+        div()
+            .id("changes-panel")
+            .flex()
+            .flex_col()
+            .w(rems(25.0)) // 400px
+            .h_full()
+            .bg(AppColors::bg_secondary())
+            .border_l_1()
+            .border_color(AppColors::border_subtle())
+            // Title bar
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .h(rems(2.0)) // 32px
+                    .px(rems(0.75)) // 12px
+                    .bg(AppColors::bg_header())
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(AppColors::text())
+                            .child("Pending Changes"),
+                    )
+                    .child(
+                        div()
+                            .id("close-changes")
+                            .cursor_pointer()
+                            .p(rems(0.25)) // 4px
+                            .rounded(px(3.0))
+                            .hover(|s| s.bg(AppColors::bg_hover()))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.toggle_changes_panel(cx);
+                            }))
+                            .child(
+                                svg()
+                                    .path("icons/close.svg")
+                                    .size(rems(0.75)) // 12px
+                                    .text_color(AppColors::text_muted()),
+                            ),
+                    ),
+            )
+            // Diff entries, then the SQL that will run
+            .child(
+                div()
+                    .id("changes-scroll")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .children(entries)
+                    .when(!statements.is_empty(), |el| {
+                        el.child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap(rems(0.375)) // 6px
+                                .px(rems(0.75)) // 12px
+                                .py(rems(0.5)) // 8px
+                                .child(
+                                    div()
+                                        .text_size(rems(0.6875)) // 11px
+                                        .font_weight(FontWeight::MEDIUM)
+                                        .text_color(AppColors::text_muted())
+                                        .child("SQL (one transaction)"),
+                                )
+                                .children(statements.into_iter().map(|(_, statement)| {
+                                    div()
+                                        .font_family("Fira Code")
+                                        .text_size(rems(0.6875)) // 11px
+                                        .text_color(AppColors::text_secondary())
+                                        .child(format!("{};", statement))
+                                })),
+                        )
+                    }),
+            )
+            // Actions
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_end()
+                    .gap(rems(0.5)) // 8px
+                    .h(rems(2.5)) // 40px
+                    .px(rems(0.75)) // 12px
+                    .border_t_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        changes_bar_button("discard-all-changes", if is_sql { "Roll Back" } else { "Discard All" })
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.discard_changes(cx);
+                            })),
+                    )
+                    .child(
+                        div()
+                            .id("apply-changes")
+                            .cursor_pointer()
+                            .px(rems(0.75)) // 12px
+                            .py(rems(0.25)) // 4px
+                            .rounded(px(4.0))
+                            .bg(AppColors::accent())
+                            .hover(|s| s.bg(AppColors::accent_hover()))
+                            .text_size(rems(0.75)) // 12px
+                            .text_color(AppColors::text())
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.apply_changes(cx);
+                            }))
+                            .child(if applying {
+                                "Applying...".to_string()
+                            } else if is_sql {
+                                "Commit".to_string()
+                            } else if count == 1 {
+                                "Apply 1 Change".to_string()
+                            } else {
+                                format!("Apply {} Changes", count)
+                            }),
+                    ),
+            )
+    }
+}
+
+/// Small text button used by the pending changes bar and panel
+fn changes_bar_button(id: &'static str, label: &'static str) -> Stateful<Div> {
+    div()
+        .id(id)
+        .cursor_pointer()
+        .px(rems(0.5)) // 8px
+        .py(rems(0.125)) // 2px
+        .rounded(px(3.0))
+        .text_size(rems(0.6875)) // 11px
+        .text_color(AppColors::text_secondary())
+        .hover(|s| s.bg(AppColors::bg_hover()).text_color(AppColors::text()))
+        .child(label)
+}
+
+/// Summarize a Redis keyspace row as "Type: hash · TTL: 120s"
+fn format_key_info(row: &Value) -> String {
+    let key_type = row.get("type").and_then(|t| t.as_str()).unwrap_or("none");
+    let ttl = match row.get("ttl").and_then(|t| t.as_i64()) {
+        Some(seconds) => format!("{}s", seconds),
+        None => "no expiry".to_string(),
+    };
+    format!("Type: {} · TTL: {}", key_type, ttl)
 }
 
 /// Convert a JSON value to a human-readable display string
 /// Handles MongoDB Extended JSON format (BSON types serialized to JSON)
-fn value_to_display_string(value: &Value) -> SharedString {
+pub fn value_to_display_string(value: &Value) -> SharedString {
     match value {
         Value::Null => SharedString::from("null"),
         Value::Bool(b) => SharedString::from(b.to_string()),
@@ -816,6 +2484,122 @@ fn format_bson_object(obj: &serde_json::Map<String, Value>) -> String {
     format!("{{{}}}", formatted.join(", "))
 }
 
+/// Extended JSON wrappers edited as plain text (e.g. the hex of an ObjectId)
+const SCALAR_EJSON_KEYS: [&str; 6] = [
+    "$oid",
+    "$date",
+    "$numberLong",
+    "$numberInt",
+    "$numberDouble",
+    "$numberDecimal",
+];
+
+/// Text put in the cell editor for a value; `parse_edited_value` reads it back
+fn edit_text_for_value(value: &Value) -> String {
+    match value {
+        Value::Object(obj) if SCALAR_EJSON_KEYS.iter().any(|key| obj.contains_key(*key)) => {
+            format_bson_object(obj)
+        }
+        Value::Array(_) | Value::Object(_) => serde_json::to_string(value).unwrap_or_default(),
+        other => value_to_display_string(other).to_string(),
+    }
+}
+
+/// Parse edited text as Extended JSON of the same BSON type as `original`.
+/// Null (or missing) fields take whatever JSON is typed; bare text becomes a string.
+fn parse_edited_value(original: &Value, text: &str) -> Result<Value, String> {
+    let trimmed = text.trim();
+    match original {
+        Value::Object(obj) if obj.contains_key("$oid") => {
+            if trimmed.len() == 24 && trimmed.chars().all(|c| c.is_ascii_hexdigit()) {
+                Ok(json!({ "$oid": trimmed.to_ascii_lowercase() }))
+            } else {
+                Err("an ObjectId is 24 hex characters".to_string())
+            }
+        }
+        Value::Object(obj) if obj.contains_key("$date") => parse_date_millis(trimmed)
+            .map(|ms| json!({ "$date": { "$numberLong": ms.to_string() } }))
+            .ok_or_else(|| "expected a UTC date like 2024-01-31 09:30:00 or RFC 3339".to_string()),
+        Value::Object(obj) if obj.contains_key("$numberLong") => trimmed
+            .parse::<i64>()
+            .map(|n| json!({ "$numberLong": n.to_string() }))
+            .map_err(|_| "expected a 64-bit integer".to_string()),
+        Value::Object(obj) if obj.contains_key("$numberInt") => trimmed
+            .parse::<i32>()
+            .map(|n| json!({ "$numberInt": n.to_string() }))
+            .map_err(|_| "expected a 32-bit integer".to_string()),
+        Value::Object(obj) if obj.contains_key("$numberDouble") => trimmed
+            .parse::<f64>()
+            .map(|_| json!({ "$numberDouble": trimmed }))
+            .map_err(|_| "expected a number".to_string()),
+        Value::Object(obj) if obj.contains_key("$numberDecimal") => {
+            let special = matches!(trimmed, "NaN" | "Infinity" | "-Infinity");
+            let numeric = trimmed.parse::<f64>().is_ok()
+                && trimmed.chars().all(|c| c.is_ascii_digit() || "+-.eE".contains(c));
+            if special || numeric {
+                Ok(json!({ "$numberDecimal": trimmed }))
+            } else {
+                Err("expected a decimal number".to_string())
+            }
+        }
+        Value::Number(n) if n.is_f64() => trimmed
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| "expected a number".to_string()),
+        // Int64 values arrive tagged as $numberLong, so plain integers are Int32
+        Value::Number(_) => trimmed
+            .parse::<i32>()
+            .map(Value::from)
+            .map_err(|_| "expected a 32-bit integer".to_string()),
+        Value::Bool(_) => match trimmed {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => Err("expected true or false".to_string()),
+        },
+        Value::String(_) => Ok(Value::String(text.to_string())),
+        Value::Null => Ok(serde_json::from_str(trimmed).unwrap_or_else(|_| Value::String(text.to_string()))),
+        Value::Array(_) | Value::Object(_) => {
+            serde_json::from_str(trimmed).map_err(|e| format!("invalid JSON: {}", e))
+        }
+    }
+}
+
+/// Parse edited text for a SQL column. Numbers and booleans stay typed while the
+/// text still reads as one, and JSON columns must hold valid JSON; anything else is
+/// sent as a string for the server to convert to the column type.
+fn parse_sql_edited_value(original: &Value, text: &str) -> Result<Value, String> {
+    let trimmed = text.trim();
+    match original {
+        Value::Number(_) => Ok(serde_json::from_str::<Value>(trimmed)
+            .ok()
+            .filter(Value::is_number)
+            .unwrap_or_else(|| Value::String(text.to_string()))),
+        Value::Bool(_) if trimmed == "true" || trimmed == "false" => Ok(Value::Bool(trimmed == "true")),
+        Value::Array(_) | Value::Object(_) => {
+            serde_json::from_str(trimmed).map_err(|e| format!("invalid JSON: {}", e))
+        }
+        _ => Ok(Value::String(text.to_string())),
+    }
+}
+
+/// Parse a date typed in the editor (UTC) into milliseconds since the epoch
+fn parse_date_millis(text: &str) -> Option<i64> {
+    if let Ok(date) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some(date.timestamp_millis());
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M"] {
+        if let Ok(date) = chrono::NaiveDateTime::parse_from_str(text, format) {
+            return Some(date.and_utc().timestamp_millis());
+        }
+    }
+    chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc().timestamp_millis())
+}
+
 /// Format a MongoDB date value
 fn format_bson_date(date: &Value) -> String {
     match date {
@@ -916,7 +2700,33 @@ impl Render for CollectionView {
             self.show_cell_context_menu(pending, window, cx);
         }
 
-        match &self.loading_state {
+        // Open the document editor requested from the toolbar (needs window to focus)
+        if std::mem::take(&mut self.pending_insert_document) {
+            self.open_document_editor(None, window, cx);
+        }
+
+        if self.active_tab == CollectionTab::Aggregation {
+            if let Some(builder) = self.pipeline_builder.clone() {
+                return self.render_with_tabs(builder.into_any_element(), cx);
+            }
+        }
+        if self.active_tab == CollectionTab::Structure {
+            if let Some(structure) = self.structure_view.clone() {
+                return self.render_with_tabs(structure.into_any_element(), cx);
+            }
+        }
+        if self.active_tab == CollectionTab::Schema {
+            if let Some(schema) = self.schema_view.clone() {
+                return self.render_with_tabs(schema.into_any_element(), cx);
+            }
+        }
+        if self.active_tab == CollectionTab::Indexes {
+            if let Some(indexes) = self.indexes_view.clone() {
+                return self.render_with_tabs(indexes.into_any_element(), cx);
+            }
+        }
+
+        let documents = match &self.loading_state {
             LoadingState::Loading => {
                 div()
                     .id("collection-view-loading")
@@ -1020,270 +2830,337 @@ impl Render for CollectionView {
                 let has_detail = self.detail_content.is_some();
                 let detail_content = self.detail_content.clone();
                 let detail_text_area = self.detail_text_area.clone();
+                let document_editor = self.document_editor.clone();
+                let export_panel = self.export_panel.clone();
+                let import_wizard = self.import_wizard.clone();
+                let explain_panel = self.explain_panel.clone();
                 let context_menu = self.context_menu.clone();
                 let view_dropdown_open = self.view_dropdown_open;
                 let current_view_mode = self.current_view_mode;
                 let table_view = self.table_view.clone();
+                let key_info = self.key_info.clone();
+                let show_changes_bar = !self.pending_changes.is_empty() || self.edit_error.is_some();
+                let show_changes = self.show_changes;
+                // Without a primary key, edited rows could not be matched on write
+                let read_only_notice = (self.db_type.is_sql()
+                    && !self.table_columns.is_empty()
+                    && !self.has_primary_key())
+                .then_some("Read-only: this table has no primary key to identify changed rows by");
 
                 div()
-                    .id("collection-view")
                     .flex()
-                    .flex_row()
+                    .flex_col()
                     .size_full()
-                    .bg(AppColors::bg_main())
-                    .relative()
-                    // Table view (relative so the view dropdown anchors here, not the full container)
+                    // Redis key type and TTL
+                    .when_some(key_info, |el, info| {
+                        el.child(
+                            div()
+                                .flex()
+                                .flex_row()
+                                .items_center()
+                                .h(rems(1.75)) // 28px
+                                .px(rems(0.75)) // 12px
+                                .bg(AppColors::bg_secondary())
+                                .border_b_1()
+                                .border_color(AppColors::border_subtle())
+                                .text_size(rems(0.6875)) // 11px
+                                .text_color(AppColors::text_muted())
+                                .child(info),
+                        )
+                    })
+                    // Why a SQL table cannot be edited
+                    .when_some(read_only_notice, |el, notice| {
+                        el.child(
+                            div()
+                                .flex()
+                                .flex_row()
+                                .items_center()
+                                .h(rems(1.75)) // 28px
+                                .px(rems(0.75)) // 12px
+                                .bg(AppColors::bg_secondary())
+                                .border_b_1()
+                                .border_color(AppColors::border_subtle())
+                                .text_size(rems(0.6875)) // 11px
+                                .text_color(AppColors::text_muted())
+                                .child(notice),
+                        )
+                    })
+                    // Pending edits or the last edit error
+                    .when(show_changes_bar, |el| el.child(self.render_changes_bar(cx)))
                     .child(
                         div()
+                            .id("collection-view")
+                            .flex()
+                            .flex_row()
+                            .w_full()
                             .flex_1()
-                            .min_w_0()
-                            .h_full()
-                            .overflow_hidden()
+                            .min_h_0()
+                            .bg(AppColors::bg_main())
                             .relative()
-                            .child(self.table_view.clone())
-                            // View dropdown overlay (anchored to table area, not full container)
-                            .when(view_dropdown_open, |el| {
-                                el.child(
-                                    div()
-                                        .id("view-dropdown-backdrop")
-                                        .absolute()
-                                        .top_0()
-                                        .left_0()
-                                        .right_0()
-                                        .bottom_0()
-                                        .on_mouse_down(MouseButton::Left, cx.listener(|this, _, _, cx| {
-                                            this.close_view_dropdown(cx);
-                                        }))
-                                        .child(
+                            // Table view (relative so the view dropdown anchors here, not the full container)
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .min_w_0()
+                                    .h_full()
+                                    .overflow_hidden()
+                                    .relative()
+                                    .child(self.table_view.clone())
+                                    // View dropdown overlay (anchored to table area, not full container)
+                                    .when(view_dropdown_open, |el| {
+                                        el.child(
                                             div()
-                                                .id("view-dropdown-menu")
+                                                .id("view-dropdown-backdrop")
                                                 .absolute()
-                                                .top(rems(2.375)) // toolbar height + small offset
-                                                .right(rems(0.875)) // align with switcher button
-                                                .occlude()
-                                                .min_w(rems(6.875)) // 110px
-                                                .bg(AppColors::menu_bg())
-                                                .border_1()
-                                                .border_color(AppColors::border())
-                                                .rounded(px(4.0))
-                                                .shadow_lg()
-                                                .py(rems(0.25)) // 4px
+                                                .top_0()
+                                                .left_0()
+                                                .right_0()
+                                                .bottom_0()
+                                                .on_mouse_down(MouseButton::Left, cx.listener(|this, _, _, cx| {
+                                                    this.close_view_dropdown(cx);
+                                                }))
                                                 .child(
                                                     div()
-                                                        .id("view-table")
-                                                        .px(rems(0.75))
-                                                        .py(rems(0.375))
-                                                        .cursor_pointer()
-                                                        .hover(|s| s.bg(AppColors::menu_hover()))
-                                                        .on_click({
-                                                            let table_view = table_view.clone();
-                                                            cx.listener(move |this, _, _, cx| {
-                                                                table_view.update(cx, |t, cx| {
-                                                                    t.set_view_mode(ViewMode::Table, cx);
-                                                                });
-                                                                this.view_dropdown_open = false;
-                                                                this.current_view_mode = ViewMode::Table;
-                                                                cx.notify();
-                                                            })
-                                                        })
+                                                        .id("view-dropdown-menu")
+                                                        .absolute()
+                                                        .top(rems(2.375)) // toolbar height + small offset
+                                                        .right(rems(0.875)) // align with switcher button
+                                                        .occlude()
+                                                        .min_w(rems(6.875)) // 110px
+                                                        .bg(AppColors::menu_bg())
+                                                        .border_1()
+                                                        .border_color(AppColors::border())
+                                                        .rounded(px(4.0))
+                                                        .shadow_lg()
+                                                        .py(rems(0.25)) // 4px
                                                         .child(
                                                             div()
-                                                                .flex()
-                                                                .flex_row()
-                                                                .items_center()
-                                                                .gap(rems(0.5))
-                                                                .child(
-                                                                    svg()
-                                                                        .path("icons/table.svg")
-                                                                        .size(rems(0.875))
-                                                                        .text_color(
-                                                                            if current_view_mode == ViewMode::Table {
-                                                                                AppColors::accent()
-                                                                            } else {
-                                                                                AppColors::text_dim()
-                                                                            },
-                                                                        ),
-                                                                )
+                                                                .id("view-table")
+                                                                .px(rems(0.75))
+                                                                .py(rems(0.375))
+                                                                .cursor_pointer()
+                                                                .hover(|s| s.bg(AppColors::menu_hover()))
+                                                                .on_click({
+                                                                    let table_view = table_view.clone();
+                                                                    cx.listener(move |this, _, _, cx| {
+                                                                        table_view.update(cx, |t, cx| {
+                                                                            t.set_view_mode(ViewMode::Table, cx);
+                                                                        });
+                                                                        this.view_dropdown_open = false;
+                                                                        this.current_view_mode = ViewMode::Table;
+                                                                        cx.notify();
+                                                                    })
+                                                                })
                                                                 .child(
                                                                     div()
-                                                                        .text_size(rems(0.75))
-                                                                        .text_color(
-                                                                            if current_view_mode == ViewMode::Table {
-                                                                                AppColors::accent()
-                                                                            } else {
-                                                                                AppColors::text_secondary()
-                                                                            },
+                                                                        .flex()
+                                                                        .flex_row()
+                                                                        .items_center()
+                                                                        .gap(rems(0.5))
+                                                                        .child(
+                                                                            svg()
+                                                                                .path("icons/table.svg")
+                                                                                .size(rems(0.875))
+                                                                                .text_color(
+                                                                                    if current_view_mode == ViewMode::Table {
+                                                                                        AppColors::accent()
+                                                                                    } else {
+                                                                                        AppColors::text_dim()
+                                                                                    },
+                                                                                ),
                                                                         )
-                                                                        .child("Table"),
+                                                                        .child(
+                                                                            div()
+                                                                                .text_size(rems(0.75))
+                                                                                .text_color(
+                                                                                    if current_view_mode == ViewMode::Table {
+                                                                                        AppColors::accent()
+                                                                                    } else {
+                                                                                        AppColors::text_secondary()
+                                                                                    },
+                                                                                )
+                                                                                .child("Table"),
+                                                                        ),
                                                                 ),
-                                                        ),
-                                                )
-                                                .child(
-                                                    div()
-                                                        .id("view-json")
-                                                        .px(rems(0.75))
-                                                        .py(rems(0.375))
-                                                        .cursor_pointer()
-                                                        .hover(|s| s.bg(AppColors::menu_hover()))
-                                                        .on_click({
-                                                            let table_view = table_view.clone();
-                                                            cx.listener(move |this, _, _, cx| {
-                                                                table_view.update(cx, |t, cx| {
-                                                                    t.set_view_mode(ViewMode::Json, cx);
-                                                                });
-                                                                this.view_dropdown_open = false;
-                                                                this.current_view_mode = ViewMode::Json;
-                                                                cx.notify();
-                                                            })
-                                                        })
+                                                        )
                                                         .child(
                                                             div()
-                                                                .flex()
-                                                                .flex_row()
-                                                                .items_center()
-                                                                .gap(rems(0.5))
-                                                                .child(
-                                                                    svg()
-                                                                        .path("icons/code.svg")
-                                                                        .size(rems(0.875))
-                                                                        .text_color(
-                                                                            if current_view_mode == ViewMode::Json {
-                                                                                AppColors::accent()
-                                                                            } else {
-                                                                                AppColors::text_dim()
-                                                                            },
-                                                                        ),
-                                                                )
+                                                                .id("view-json")
+                                                                .px(rems(0.75))
+                                                                .py(rems(0.375))
+                                                                .cursor_pointer()
+                                                                .hover(|s| s.bg(AppColors::menu_hover()))
+                                                                .on_click({
+                                                                    let table_view = table_view.clone();
+                                                                    cx.listener(move |this, _, _, cx| {
+                                                                        table_view.update(cx, |t, cx| {
+                                                                            t.set_view_mode(ViewMode::Json, cx);
+                                                                        });
+                                                                        this.view_dropdown_open = false;
+                                                                        this.current_view_mode = ViewMode::Json;
+                                                                        cx.notify();
+                                                                    })
+                                                                })
                                                                 .child(
                                                                     div()
-                                                                        .text_size(rems(0.75))
-                                                                        .text_color(
-                                                                            if current_view_mode == ViewMode::Json {
-                                                                                AppColors::accent()
-                                                                            } else {
-                                                                                AppColors::text_secondary()
-                                                                            },
+                                                                        .flex()
+                                                                        .flex_row()
+                                                                        .items_center()
+                                                                        .gap(rems(0.5))
+                                                                        .child(
+                                                                            svg()
+                                                                                .path("icons/code.svg")
+                                                                                .size(rems(0.875))
+                                                                                .text_color(
+                                                                                    if current_view_mode == ViewMode::Json {
+                                                                                        AppColors::accent()
+                                                                                    } else {
+                                                                                        AppColors::text_dim()
+                                                                                    },
+                                                                                ),
                                                                         )
-                                                                        .child("JSON"),
+                                                                        .child(
+                                                                            div()
+                                                                                .text_size(rems(0.75))
+                                                                                .text_color(
+                                                                                    if current_view_mode == ViewMode::Json {
+                                                                                        AppColors::accent()
+                                                                                    } else {
+                                                                                        AppColors::text_secondary()
+                                                                                    },
+                                                                                )
+                                                                                .child("JSON"),
+                                                                        ),
                                                                 ),
                                                         ),
                                                 ),
-                                        ),
-                                )
-                            }),
-                    )
-                    // Detail panel
-                    .when(has_detail, |el| {
-                        let content = detail_content.unwrap();
-                        el.child(
-                            div()
-                                .id("detail-panel")
-                                .flex()
-                                .flex_col()
-                                .w(rems(25.0)) // 400px
-                                .h_full()
-                                .bg(AppColors::bg_secondary())
-                                .border_l_1()
-                                .border_color(AppColors::border_subtle())
-                                // Title bar
-                                .child(
+                                        )
+                                    }),
+                            )
+                            // Detail panel
+                            .when(has_detail, |el| {
+                                let content = detail_content.unwrap();
+                                el.child(
                                     div()
+                                        .id("detail-panel")
                                         .flex()
-                                        .flex_row()
-                                        .items_center()
-                                        .justify_between()
-                                        .h(rems(2.0)) // 32px
-                                        .px(rems(0.75)) // 12px
-                                        .bg(AppColors::bg_header())
-                                        .border_b_1()
+                                        .flex_col()
+                                        .w(rems(25.0)) // 400px
+                                        .h_full()
+                                        .bg(AppColors::bg_secondary())
+                                        .border_l_1()
                                         .border_color(AppColors::border_subtle())
+                                        // Title bar
                                         .child(
                                             div()
-                                                .text_size(rems(0.75)) // 12px
-                                                .font_weight(FontWeight::SEMIBOLD)
-                                                .text_color(AppColors::text())
-                                                .child("View"),
+                                                .flex()
+                                                .flex_row()
+                                                .items_center()
+                                                .justify_between()
+                                                .h(rems(2.0)) // 32px
+                                                .px(rems(0.75)) // 12px
+                                                .bg(AppColors::bg_header())
+                                                .border_b_1()
+                                                .border_color(AppColors::border_subtle())
+                                                .child(
+                                                    div()
+                                                        .text_size(rems(0.75)) // 12px
+                                                        .font_weight(FontWeight::SEMIBOLD)
+                                                        .text_color(AppColors::text())
+                                                        .child("View"),
+                                                )
+                                                .child(
+                                                    div()
+                                                        .id("close-detail")
+                                                        .cursor_pointer()
+                                                        .p(rems(0.25)) // 4px
+                                                        .rounded(px(3.0)) // Keep border radius as px
+                                                        .hover(|s| s.bg(AppColors::bg_hover()))
+                                                        .on_click(cx.listener(|this, _, _, cx| {
+                                                            this.close_detail(cx);
+                                                        }))
+                                                        .child(
+                                                            svg()
+                                                                .path("icons/close.svg")
+                                                                .size(rems(0.75)) // 12px
+                                                                .text_color(AppColors::text_muted()),
+                                                        ),
+                                                ),
                                         )
+                                        // Field name
                                         .child(
                                             div()
-                                                .id("close-detail")
-                                                .cursor_pointer()
-                                                .p(rems(0.25)) // 4px
-                                                .rounded(px(3.0)) // Keep border radius as px
-                                                .hover(|s| s.bg(AppColors::bg_hover()))
-                                                .on_click(cx.listener(|this, _, _, cx| {
-                                                    this.close_detail(cx);
-                                                }))
+                                                .flex()
+                                                .flex_row()
+                                                .items_center()
+                                                .h(rems(1.75)) // 28px
+                                                .px(rems(0.75)) // 12px
+                                                .bg(AppColors::bg_secondary())
+                                                .border_b_1()
+                                                .border_color(AppColors::border_subtle())
+                                                .child(
+                                                    div()
+                                                        .text_size(rems(0.6875)) // 11px
+                                                        .text_color(AppColors::text_muted())
+                                                        .child("Field: "),
+                                                )
                                                 .child(
-                                                    svg()
-                                                        .path("icons/close.svg")
-                                                        .size(rems(0.75)) // 12px
-                                                        .text_color(AppColors::text_muted()),
+                                                    div()
+                                                        .text_size(rems(0.6875)) // 11px
+                                                        .font_weight(FontWeight::MEDIUM)
+                                                        .text_color(AppColors::accent())
+                                                        .child(content.column_name.clone()),
                                                 ),
-                                        ),
-                                )
-                                // Field name
-                                .child(
-                                    div()
-                                        .flex()
-                                        .flex_row()
-                                        .items_center()
-                                        .h(rems(1.75)) // 28px
-                                        .px(rems(0.75)) // 12px
-                                        .bg(AppColors::bg_secondary())
-                                        .border_b_1()
-                                        .border_color(AppColors::border_subtle())
-                                        .child(
-                                            div()
-                                                .text_size(rems(0.6875)) // 11px
-                                                .text_color(AppColors::text_muted())
-                                                .child("Field: "),
                                         )
+                                        // Content — selectable text area
                                         .child(
                                             div()
-                                                .text_size(rems(0.6875)) // 11px
-                                                .font_weight(FontWeight::MEDIUM)
-                                                .text_color(AppColors::accent())
-                                                .child(content.column_name.clone()),
+                                                .id("detail-content-scroll")
+                                                .flex_1()
+                                                .p(rems(0.75)) // 12px
+                                                .overflow_y_scroll()
+                                                .overflow_x_scroll()
+                                                .when_some(detail_text_area, |el, text_area| {
+                                                    el.child(text_area)
+                                                }),
                                         ),
                                 )
-                                // Content — selectable text area
-                                .child(
-                                    div()
-                                        .id("detail-content-scroll")
-                                        .flex_1()
-                                        .p(rems(0.75)) // 12px
-                                        .overflow_y_scroll()
-                                        .overflow_x_scroll()
-                                        .when_some(detail_text_area, |el, text_area| {
-                                            el.child(text_area)
-                                        }),
-                                ),
-                        )
-                    })
-                    // Context menu overlay (rendered here, outside overflow_hidden)
-                    .when_some(context_menu, |el, menu| {
-                        if let Some(position) = self.context_menu_position {
-                            el.child(
-                                deferred(
-                                    anchored()
-                                        .position(position)
-                                        .snap_to_window_with_margin(px(8.0))
-                                        .anchor(Corner::TopLeft)
-                                        .child(
-                                            div()
-                                                .occlude()
-                                                .child(menu)
+                            })
+                            // Insert or replace a whole document
+                            .when_some(document_editor, |el, editor| el.child(editor))
+                            // Pending changes diff
+                            .when(show_changes, |el| el.child(self.render_changes_panel(cx)))
+                            // Export options and progress
+                            .when_some(export_panel, |el, panel| el.child(panel))
+                            // Import from a file
+                            .when_some(import_wizard, |el, wizard| el.child(wizard))
+                            // Query plan of the filter
+                            .when_some(explain_panel, |el, panel| el.child(panel))
+                            // Context menu overlay (rendered here, outside overflow_hidden)
+                            .when_some(context_menu, |el, menu| {
+                                if let Some(position) = self.context_menu_position {
+                                    el.child(
+                                        deferred(
+                                            anchored()
+                                                .position(position)
+                                                .snap_to_window_with_margin(px(8.0))
+                                                .anchor(Corner::TopLeft)
+                                                .child(
+                                                    div()
+                                                        .occlude()
+                                                        .child(menu)
+                                                )
                                         )
-                                )
-                                .with_priority(2)
-                            )
-                        } else {
-                            el.child(deferred(div().occlude().child(menu)).with_priority(2))
-                        }
-                    })
+                                        .with_priority(2)
+                                    )
+                                } else {
+                                    el.child(deferred(div().occlude().child(menu)).with_priority(2))
+                                }
+                            }),
+                    )
                     .into_any_element()
             }
-        }
+        };
+        self.render_with_tabs(documents, cx)
     }
 }
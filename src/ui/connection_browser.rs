@@ -2,8 +2,9 @@ use gpui::{prelude::*, rems, *};
 use std::collections::{HashMap, HashSet};
 
 use crate::db::driver::{CollectionInfo, DatabaseInfo};
-use crate::db::{Connection, ConnectionConfig, DatabaseType};
-use crate::db::driver::create_connection;
+use crate::db::Connection;
+use crate::db::registry::registry;
+use crate::db::runtime;
 use crate::ui::tooltip::Tooltip;
 
 /// Maximum number of databases to show initially
@@ -81,6 +82,12 @@ pub struct CollectionContextMenuRequested(pub String, pub String, pub String, pu
 
 impl EventEmitter<CollectionContextMenuRequested> for ConnectionBrowser {}
 
+/// Event emitted when connecting needs a password that isn't saved (connection_id)
+#[derive(Clone)]
+pub struct PasswordRequired(pub String);
+
+impl EventEmitter<PasswordRequired> for ConnectionBrowser {}
+
 /// Loading state for the browser
 #[derive(Clone, PartialEq)]
 pub enum LoadingState {
@@ -221,6 +228,11 @@ impl ConnectionBrowser {
         self.loading_state == LoadingState::NotConnected
     }
 
+    /// Set the password entered for this session (connections that ask on connect)
+    pub fn set_password(&mut self, password: String) {
+        self.connection.password = Some(password);
+    }
+
     /// Get the connection ID
     #[allow(dead_code)] // API method for external access
     pub fn connection_id(&self) -> &str {
@@ -235,17 +247,6 @@ impl ConnectionBrowser {
         hasher.finish()
     }
 
-    fn format_bytes(bytes: u64) -> String {
-        const UNITS: &[&str; 5] = &["B", "KB", "MB", "GB", "TB"];
-        let mut size = bytes as f64;
-        let mut unit_index = 0;
-        while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-            size /= 1024.0;
-            unit_index += 1;
-        }
-        format!("{:.1} {}", size, UNITS[unit_index])
-    }
-
     /// Rebuild the flattened tree cache
     /// Called ONCE when state changes, NOT during render
     fn rebuild_flat_items(&mut self) {
@@ -276,7 +277,7 @@ impl ConnectionBrowser {
             self.flat_items.push(FlatTreeItem::Database {
                 stable_key: SharedString::from(format!("db-{:016x}", Self::hash_name(&db.name))),
                 name: SharedString::from(db.name.clone()),
-                formatted_size: db.size_bytes.map(|s| SharedString::from(Self::format_bytes(s))),
+                formatted_size: db.size_bytes.map(|s| SharedString::from(format_bytes(s))),
                 is_expanded,
                 is_loading,
             });
@@ -329,7 +330,9 @@ impl ConnectionBrowser {
     }
 
     pub fn load_databases(&mut self, cx: &mut Context<Self>) {
-        if self.connection.db_type != DatabaseType::MongoDB {
+        // Ask first; the sidebar retries once the password has been entered
+        if self.connection.needs_password() {
+            cx.emit(PasswordRequired(self.connection.id.clone()));
             return;
         }
 
@@ -339,78 +342,55 @@ impl ConnectionBrowser {
         self.loading_state = LoadingState::LoadingDatabases;
         self.flat_items_dirty = true;
 
-        let conn_string = self.connection.get_connection_string();
-        let config = ConnectionConfig::new(self.connection.db_type, conn_string);
-
-        let (tx, rx) = std::sync::mpsc::channel();
+        let connection_id = self.connection.id.clone();
+        let config = self.connection.config();
 
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            let result = rt.block_on(async {
-                match create_connection(config) {
-                    Ok(conn) => conn.list_databases().await,
-                    Err(e) => Err(e),
-                }
-            });
-            tx.send(result).ok();
+        let task = runtime::spawn(async move {
+            registry().get(&connection_id, config).await?.list_databases().await
         });
 
         cx.spawn(async move |this, cx| {
-            loop {
-                match rx.try_recv() {
-                    Ok(result) => {
-                        this.update(cx, |browser, cx| {
-                            match result {
-                                Ok(databases) => {
-                                    browser.databases = databases;
-                                    browser.loading_state = LoadingState::Idle;
-
-                                    // Only apply default if user has NO saved preferences
-                                    // If has_saved_preferences is true, respect the saved state
-                                    // (even if it results in showing all or showing a specific list)
-                                    if !browser.has_saved_preferences {
-                                        // No saved preferences - use default (first N databases)
-                                        browser.visible_databases = browser.databases
-                                            .iter()
-                                            .take(MAX_DATABASES_SHOWN)
-                                            .map(|db| db.name.clone())
-                                            .collect();
-                                    }
-                                    // If has_saved_preferences is true:
-                                    // - show_all_databases=true means show all
-                                    // - visible_databases contains the specific list to show
-
-                                    // Load collections for any databases that were expanded in preview
-                                    for db_name in &previously_expanded {
-                                        let db_exists = browser.databases.iter().any(|db| &db.name == db_name);
-                                        if db_exists {
-                                            browser.expanded_databases.insert(db_name.clone());
-                                            browser.load_collections(db_name, cx);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    browser.loading_state = LoadingState::Error(e.to_string());
-                                }
+            let result = task.await;
+            this.update(cx, |browser, cx| {
+                match result {
+                    Ok(Ok(databases)) => {
+                        browser.databases = databases;
+                        browser.loading_state = LoadingState::Idle;
+
+                        // Only apply default if user has NO saved preferences
+                        // If has_saved_preferences is true, respect the saved state
+                        // (even if it results in showing all or showing a specific list)
+                        if !browser.has_saved_preferences {
+                            // No saved preferences - use default (first N databases)
+                            browser.visible_databases = browser.databases
+                                .iter()
+                                .take(MAX_DATABASES_SHOWN)
+                                .map(|db| db.name.clone())
+                                .collect();
+                        }
+                        // If has_saved_preferences is true:
+                        // - show_all_databases=true means show all
+                        // - visible_databases contains the specific list to show
+
+                        // Load collections for any databases that were expanded in preview
+                        for db_name in &previously_expanded {
+                            let db_exists = browser.databases.iter().any(|db| &db.name == db_name);
+                            if db_exists {
+                                browser.expanded_databases.insert(db_name.clone());
+                                browser.load_collections(db_name, cx);
                             }
-                            browser.flat_items_dirty = true;
-                            cx.notify();
-                        }).ok();
-                        break;
+                        }
                     }
-                    Err(std::sync::mpsc::TryRecvError::Empty) => {
-                        cx.background_executor().timer(std::time::Duration::from_millis(50)).await;
+                    Ok(Err(e)) => {
+                        browser.loading_state = LoadingState::Error(e.to_string());
                     }
-                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                        this.update(cx, |browser, cx| {
-                            browser.loading_state = LoadingState::Error("Failed to load databases".to_string());
-                            browser.flat_items_dirty = true;
-                            cx.notify();
-                        }).ok();
-                        break;
+                    Err(_) => {
+                        browser.loading_state = LoadingState::Error("Failed to load databases".to_string());
                     }
                 }
-            }
+                browser.flat_items_dirty = true;
+                cx.notify();
+            }).ok();
         }).detach();
 
         cx.notify();
@@ -454,59 +434,30 @@ impl ConnectionBrowser {
             .insert(db_name.clone(), CollectionLoadingState::Loading);
         self.flat_items_dirty = true;
 
-        let conn_string = self.connection.get_connection_string();
-        let config = ConnectionConfig::new(self.connection.db_type, conn_string);
+        let connection_id = self.connection.id.clone();
+        let config = self.connection.config();
         let db_name_clone = db_name.clone();
 
-        let (tx, rx) = std::sync::mpsc::channel();
-
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            let result = rt.block_on(async {
-                match create_connection(config) {
-                    Ok(conn) => conn.list_collections(&db_name_clone).await,
-                    Err(e) => Err(e),
-                }
-            });
-            tx.send(result).ok();
+        let task = runtime::spawn(async move {
+            registry().get(&connection_id, config).await?.list_collections(&db_name_clone).await
         });
 
         let db_name_for_task = db_name.clone();
         cx.spawn(async move |this, cx| {
-            loop {
-                match rx.try_recv() {
-                    Ok(result) => {
-                        this.update(cx, |browser, cx| {
-                            match result {
-                                Ok(collections) => {
-                                    browser.collections.insert(db_name_for_task.clone(), collections);
-                                    browser.collection_loading_states
-                                        .insert(db_name_for_task.clone(), CollectionLoadingState::Loaded);
-                                }
-                                Err(e) => {
-                                    browser.collection_loading_states
-                                        .insert(db_name_for_task.clone(), CollectionLoadingState::Error(e.to_string()));
-                                }
-                            }
-                            browser.flat_items_dirty = true;
-                            cx.notify();
-                        }).ok();
-                        break;
-                    }
-                    Err(std::sync::mpsc::TryRecvError::Empty) => {
-                        cx.background_executor().timer(std::time::Duration::from_millis(50)).await;
+            let result = task.await;
+            this.update(cx, |browser, cx| {
+                let state = match result {
+                    Ok(Ok(collections)) => {
+                        browser.collections.insert(db_name_for_task.clone(), collections);
+                        CollectionLoadingState::Loaded
                     }
-                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                        this.update(cx, |browser, cx| {
-                            browser.collection_loading_states
-                                .insert(db_name_for_task.clone(), CollectionLoadingState::Error("Connection lost".to_string()));
-                            browser.flat_items_dirty = true;
-                            cx.notify();
-                        }).ok();
-                        break;
-                    }
-                }
-            }
+                    Ok(Err(e)) => CollectionLoadingState::Error(e.to_string()),
+                    Err(_) => CollectionLoadingState::Error("Connection lost".to_string()),
+                };
+                browser.collection_loading_states.insert(db_name_for_task.clone(), state);
+                browser.flat_items_dirty = true;
+                cx.notify();
+            }).ok();
         }).detach();
 
         cx.notify();
@@ -526,6 +477,18 @@ impl ConnectionBrowser {
     }
 }
 
+/// Human-readable size, e.g. `12.5 MB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str; 5] = &["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit_index = 0;
+    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_index += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit_index])
+}
+
 impl Render for ConnectionBrowser {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         // Colors - defined once at top
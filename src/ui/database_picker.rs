@@ -366,7 +366,7 @@ where
 }
 
 /// Render a checkbox with tick mark when checked
-fn render_checkbox(is_checked: bool, accent_color: Rgba) -> impl IntoElement {
+pub fn render_checkbox(is_checked: bool, accent_color: Rgba) -> impl IntoElement {
     let checkbox_bg = rgb(0x1f1f1f);
     let border_color = if is_checked {
         accent_color
@@ -0,0 +1,839 @@
+//! Entity-relationship diagram of a SQL database: tables as boxes with their
+//! columns, foreign keys as edges. Drag the background to pan, scroll to zoom,
+//! drag a table to move it and click it to show only its neighbourhood.
+
+use gpui::{prelude::*, rems, *};
+use std::collections::BTreeSet;
+
+use crate::db::diagram::{
+    self, Diagram, DiagramFormat, DiagramTable, EdgeCurve, Layout, Rect, BOX_PADDING, FONT_SIZE,
+    HEADER_HEIGHT, ROW_HEIGHT,
+};
+use crate::db::driver::ConnectionConfig;
+use crate::db::error::ConnectionError;
+use crate::db::registry::registry;
+use crate::db::runtime;
+use crate::db::DatabaseType;
+use crate::ui::theme::AppColors;
+use crate::ui::widgets::render_option;
+
+const MIN_ZOOM: f32 = 0.2;
+const MAX_ZOOM: f32 = 2.0;
+/// Zoom change of one click on the zoom buttons
+const ZOOM_STEP: f32 = 1.25;
+/// Zoom change per pixel scrolled
+const SCROLL_ZOOM_RATE: f32 = 0.002;
+/// Neighbourhood depths offered for the selected table
+const NEIGHBOURHOOD_DEPTHS: [usize; 3] = [1, 2, 3];
+
+enum DiagramState {
+    Loading,
+    Loaded(Diagram),
+    Failed(String),
+}
+
+/// What the mouse is dragging
+enum Drag {
+    Canvas {
+        start: Point<Pixels>,
+        offset: Point<Pixels>,
+    },
+    Table {
+        table: usize,
+        start: Point<Pixels>,
+        origin: (f32, f32),
+        moved: bool,
+    },
+}
+
+pub struct DiagramView {
+    connection_id: String,
+    config: ConnectionConfig,
+    database_name: String,
+    /// PostgreSQL schemas of the database; empty for other drivers
+    schemas: Vec<String>,
+    schema: Option<String>,
+    state: DiagramState,
+    /// Visible tables: all of them, or the neighbourhood of one
+    visible: Vec<usize>,
+    layout: Layout,
+    selected: Option<usize>,
+    /// Table and depth the diagram is narrowed to
+    neighbourhood: Option<(usize, usize)>,
+    zoom: f32,
+    /// Screen position of the diagram's origin within the canvas
+    offset: Point<Pixels>,
+    drag: Option<Drag>,
+    /// Canvas bounds from the last paint, to fit the diagram into
+    viewport: Bounds<Pixels>,
+    /// Fit the diagram once the canvas has been measured
+    fit_pending: bool,
+    /// Outcome of the last export
+    notice: Option<Result<String, String>>,
+}
+
+impl DiagramView {
+    pub fn new(
+        connection_id: String,
+        config: ConnectionConfig,
+        database_name: String,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut view = Self {
+            connection_id,
+            config,
+            database_name,
+            schemas: Vec::new(),
+            schema: None,
+            state: DiagramState::Loading,
+            visible: Vec::new(),
+            layout: Layout::default(),
+            selected: None,
+            neighbourhood: None,
+            zoom: 1.0,
+            offset: Point::default(),
+            drag: None,
+            viewport: Bounds::default(),
+            fit_pending: false,
+            notice: None,
+        };
+        view.load(cx);
+        view
+    }
+
+    /// List the tables the sidebar shows and read their structure. PostgreSQL
+    /// databases are drawn one schema at a time.
+    fn load(&mut self, cx: &mut Context<Self>) {
+        self.state = DiagramState::Loading;
+        self.notice = None;
+        cx.notify();
+
+        let connection_id = self.connection_id.clone();
+        let config = self.config.clone();
+        let database_name = self.database_name.clone();
+        let requested = self.schema.clone();
+        let by_schema = self.config.driver == DatabaseType::PostgreSQL;
+        let task = runtime::spawn(async move {
+            let conn = registry().get(&connection_id, config).await?;
+            let names: Vec<String> = conn
+                .list_collections(&database_name)
+                .await?
+                .into_iter()
+                .map(|collection| collection.name)
+                .collect();
+            if !by_schema {
+                let diagram = diagram::load(conn, &database_name, names, None).await?;
+                return Ok::<_, ConnectionError>((Vec::new(), None, diagram));
+            }
+
+            let schemas: Vec<String> = names
+                .iter()
+                .filter_map(|name| name.split_once('.').map(|(schema, _)| schema.to_string()))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            let schema = requested
+                .filter(|schema| schemas.contains(schema))
+                .or_else(|| schemas.iter().find(|schema| *schema == "public").cloned())
+                .or_else(|| schemas.first().cloned());
+            let tables = match &schema {
+                Some(schema) => {
+                    let prefix = format!("{}.", schema);
+                    names
+                        .into_iter()
+                        .filter(|name| name.starts_with(&prefix))
+                        .collect()
+                }
+                None => Vec::new(),
+            };
+            let diagram = diagram::load(conn, &database_name, tables, schema.as_deref()).await?;
+            Ok((schemas, schema, diagram))
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |view, cx| {
+                match result {
+                    Ok(Ok((schemas, schema, diagram))) => {
+                        view.schemas = schemas;
+                        view.schema = schema;
+                        view.selected = None;
+                        view.neighbourhood = None;
+                        view.visible = diagram.all();
+                        view.state = DiagramState::Loaded(diagram);
+                        view.auto_layout(cx);
+                    }
+                    Ok(Err(e)) => view.state = DiagramState::Failed(e.to_string()),
+                    Err(_) => {
+                        view.state =
+                            DiagramState::Failed("Loading the diagram was interrupted".to_string())
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn set_schema(&mut self, schema: String, cx: &mut Context<Self>) {
+        if self.schema.as_ref() != Some(&schema) {
+            self.schema = Some(schema);
+            self.load(cx);
+        }
+    }
+
+    /// Lay the visible tables out again, dropping any moved by hand, and fit them
+    fn auto_layout(&mut self, cx: &mut Context<Self>) {
+        if let DiagramState::Loaded(diagram) = &self.state {
+            self.layout = diagram.layout(&self.visible);
+        }
+        self.fit_pending = true;
+        self.fit(cx);
+    }
+
+    /// Zoom and pan so the whole diagram fits the canvas, without enlarging it
+    fn fit(&mut self, cx: &mut Context<Self>) {
+        let width: f32 = self.viewport.size.width.into();
+        let height: f32 = self.viewport.size.height.into();
+        if width <= 0.0 || height <= 0.0 || self.layout.width <= 0.0 {
+            return;
+        }
+        self.fit_pending = false;
+        self.zoom = (width / self.layout.width)
+            .min(height / self.layout.height)
+            .clamp(MIN_ZOOM, 1.0);
+        self.offset = point(
+            px((width - self.layout.width * self.zoom) / 2.0),
+            px((height - self.layout.height * self.zoom) / 2.0),
+        );
+        cx.notify();
+    }
+
+    fn set_viewport(&mut self, bounds: Bounds<Pixels>, cx: &mut Context<Self>) {
+        self.viewport = bounds;
+        if self.fit_pending {
+            self.fit(cx);
+        }
+    }
+
+    /// Zoom by `factor`, keeping the point `anchor` of the canvas in place
+    fn zoom_by(&mut self, factor: f32, anchor: Point<Pixels>, cx: &mut Context<Self>) {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let ratio = zoom / self.zoom;
+        self.offset = point(
+            anchor.x - (anchor.x - self.offset.x) * ratio,
+            anchor.y - (anchor.y - self.offset.y) * ratio,
+        );
+        self.zoom = zoom;
+        cx.notify();
+    }
+
+    fn zoom_around_center(&mut self, factor: f32, cx: &mut Context<Self>) {
+        let center = point(
+            self.viewport.size.width / 2.0,
+            self.viewport.size.height / 2.0,
+        );
+        self.zoom_by(factor, center, cx);
+    }
+
+    /// Show only the tables at most `depth` keys away from `table`, or every
+    /// table again
+    fn set_neighbourhood(&mut self, neighbourhood: Option<(usize, usize)>, cx: &mut Context<Self>) {
+        let DiagramState::Loaded(diagram) = &self.state else {
+            return;
+        };
+        self.neighbourhood = neighbourhood;
+        self.visible = match neighbourhood {
+            Some((table, depth)) => diagram.neighbourhood(table, depth),
+            None => diagram.all(),
+        };
+        self.auto_layout(cx);
+    }
+
+    fn on_mouse_down(&mut self, event: &MouseDownEvent, _: &mut Window, _: &mut Context<Self>) {
+        self.drag = Some(Drag::Canvas {
+            start: event.position,
+            offset: self.offset,
+        });
+    }
+
+    fn on_table_mouse_down(
+        &mut self,
+        table: usize,
+        event: &MouseDownEvent,
+        cx: &mut Context<Self>,
+    ) {
+        cx.stop_propagation();
+        if let Some(rect) = self.layout.boxes.get(&table) {
+            self.drag = Some(Drag::Table {
+                table,
+                start: event.position,
+                origin: (rect.x, rect.y),
+                moved: false,
+            });
+        }
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseMoveEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if event.pressed_button != Some(MouseButton::Left) {
+            return;
+        }
+        match &mut self.drag {
+            Some(Drag::Canvas { start, offset }) => {
+                self.offset = *offset + (event.position - *start);
+                cx.notify();
+            }
+            Some(Drag::Table {
+                table,
+                start,
+                origin,
+                moved,
+            }) => {
+                let dx: f32 = (event.position.x - start.x).into();
+                let dy: f32 = (event.position.y - start.y).into();
+                *moved |= dx.abs() > 2.0 || dy.abs() > 2.0;
+                if let Some(rect) = self.layout.boxes.get_mut(table) {
+                    rect.x = origin.0 + dx / self.zoom;
+                    rect.y = origin.1 + dy / self.zoom;
+                }
+                self.layout.fit_bounds();
+                cx.notify();
+            }
+            None => {}
+        }
+    }
+
+    fn on_mouse_up(&mut self, _: &MouseUpEvent, _: &mut Window, cx: &mut Context<Self>) {
+        // A table clicked without being dragged is selected, or unselected
+        if let Some(Drag::Table {
+            table,
+            moved: false,
+            ..
+        }) = self.drag.take()
+        {
+            self.selected = if self.selected == Some(table) {
+                None
+            } else {
+                Some(table)
+            };
+            cx.notify();
+        }
+    }
+
+    fn on_scroll_wheel(
+        &mut self,
+        event: &ScrollWheelEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let delta: f32 = event.delta.pixel_delta(window.line_height()).y.into();
+        if delta != 0.0 {
+            let anchor = event.position - self.viewport.origin;
+            self.zoom_by(1.0 + delta * SCROLL_ZOOM_RATE, anchor, cx);
+        }
+    }
+
+    /// Ask where to save the visible tables, then export them there
+    fn export(&mut self, format: DiagramFormat, cx: &mut Context<Self>) {
+        let DiagramState::Loaded(diagram) = &self.state else {
+            return;
+        };
+        let diagram = diagram.clone();
+        let layout = self.layout.clone();
+        let directory = dirs::download_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_default();
+        let stem = match &self.schema {
+            Some(schema) => format!("{}-{}", self.database_name, schema),
+            None => self.database_name.clone(),
+        };
+        let file_name = format!("{}.{}", stem, format.extension());
+        let path = cx.prompt_for_new_path(&directory, Some(file_name.as_str()));
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+            let task = runtime::spawn({
+                let path = path.clone();
+                async move { diagram.export(&layout, format, &path) }
+            });
+            let notice = match task.await {
+                Ok(Ok(())) => Ok(format!("Saved {}", path.display())),
+                Ok(Err(e)) => Err(format!("{:#}", e)),
+                Err(_) => Err("The export was interrupted".to_string()),
+            };
+            this.update(cx, |view, cx| {
+                view.notice = Some(notice);
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let loaded = matches!(self.state, DiagramState::Loaded(_));
+        let summary = match &self.state {
+            DiagramState::Loaded(diagram) => {
+                let mut summary = format!(
+                    "{} of {} tables · {} foreign keys",
+                    self.visible.len(),
+                    diagram.tables.len(),
+                    diagram.edges.len()
+                );
+                if diagram.external_keys > 0 {
+                    summary.push_str(&format!(" · {} to other schemas", diagram.external_keys));
+                }
+                summary
+            }
+            DiagramState::Loading => "Loading...".to_string(),
+            DiagramState::Failed(_) => String::new(),
+        };
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .gap(rems(0.75)) // 12px
+            .w_full()
+            .h(rems(2.25)) // 36px
+            .px(rems(0.75)) // 12px
+            .bg(AppColors::bg_header())
+            .border_b_1()
+            .border_color(AppColors::border())
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.25)) // 4px
+                    .min_w_0()
+                    .overflow_hidden()
+                    .when(!self.schemas.is_empty(), |el| {
+                        el.child(
+                            div()
+                                .mr(rems(0.25)) // 4px
+                                .text_size(rems(0.6875)) // 11px
+                                .text_color(AppColors::text_muted())
+                                .child("Schema"),
+                        )
+                        .children(self.schemas.iter().map(|schema| {
+                            let value = schema.clone();
+                            render_option(
+                                SharedString::from(format!("diagram-schema-{}", schema)),
+                                schema.clone(),
+                                self.schema.as_ref() == Some(schema),
+                                true,
+                            )
+                            .on_click(cx.listener(
+                                move |this, _, _, cx| {
+                                    this.set_schema(value.clone(), cx);
+                                },
+                            ))
+                        }))
+                    })
+                    .child(
+                        div()
+                            .ml(rems(0.5)) // 8px
+                            .text_size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_muted())
+                            .whitespace_nowrap()
+                            .child(summary),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .flex_none()
+                    .items_center()
+                    .gap(rems(0.125)) // 2px
+                    .when(loaded, |el| {
+                        el.child(
+                            render_option("diagram-zoom-out", "−", false, true).on_click(
+                                cx.listener(|this, _, _, cx| {
+                                    this.zoom_around_center(1.0 / ZOOM_STEP, cx);
+                                }),
+                            ),
+                        )
+                        .child(
+                            div()
+                                .w(rems(2.75))
+                                .text_size(rems(0.6875)) // 11px
+                                .text_color(AppColors::text_muted())
+                                .text_center()
+                                .child(format!("{:.0}%", self.zoom * 100.0)),
+                        )
+                        .child(render_option("diagram-zoom-in", "+", false, true).on_click(
+                            cx.listener(|this, _, _, cx| {
+                                this.zoom_around_center(ZOOM_STEP, cx);
+                            }),
+                        ))
+                        .child(render_option("diagram-fit", "Fit", false, true).on_click(
+                            cx.listener(|this, _, _, cx| {
+                                this.fit(cx);
+                            }),
+                        ))
+                        .child(
+                            render_option("diagram-auto-layout", "Auto-layout", false, true)
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.auto_layout(cx);
+                                })),
+                        )
+                        .child(
+                            div()
+                                .mx(rems(0.375)) // 6px
+                                .w(px(1.0))
+                                .h(rems(1.0))
+                                .bg(AppColors::border()),
+                        )
+                        .children(
+                            [DiagramFormat::Svg, DiagramFormat::Png].map(|format| {
+                                render_option(
+                                    SharedString::from(format!(
+                                        "diagram-export-{}",
+                                        format.extension()
+                                    )),
+                                    format!("Export {}", format.label()),
+                                    false,
+                                    true,
+                                )
+                                .on_click(cx.listener(
+                                    move |this, _, _, cx| {
+                                        this.export(format, cx);
+                                    },
+                                ))
+                            }),
+                        )
+                    })
+                    .child(
+                        div()
+                            .id("refresh-diagram")
+                            .ml(rems(0.25)) // 4px
+                            .p(rems(0.25)) // 4px
+                            .rounded(px(3.0))
+                            .cursor_pointer()
+                            .hover(|s| s.bg(AppColors::bg_hover()))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.load(cx);
+                            }))
+                            .child(
+                                svg()
+                                    .path("icons/refresh.svg")
+                                    .size(rems(0.75)) // 12px
+                                    .text_color(AppColors::text_dim()),
+                            ),
+                    ),
+            )
+    }
+
+    /// Selected table and its neighbourhood filter, over the bottom of the canvas
+    fn render_selection(&self, diagram: &Diagram, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected = self
+            .selected
+            .filter(|table| self.layout.boxes.contains_key(table));
+
+        div()
+            .absolute()
+            .left(rems(0.75))
+            .bottom(rems(0.75))
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap(rems(0.25)) // 4px
+            .px(rems(0.5)) // 8px
+            .py(rems(0.25)) // 4px
+            .rounded(px(4.0))
+            .bg(AppColors::bg_secondary())
+            .border_1()
+            .border_color(AppColors::border())
+            .text_size(rems(0.6875)) // 11px
+            .when_some(selected, |el, table| {
+                el.child(
+                    div()
+                        .mr(rems(0.25)) // 4px
+                        .text_color(AppColors::text())
+                        .child(diagram.tables[table].name.clone()),
+                )
+                .child(
+                    div()
+                        .text_color(AppColors::text_muted())
+                        .child("Neighbours"),
+                )
+                .children(NEIGHBOURHOOD_DEPTHS.map(|depth| {
+                    render_option(
+                        SharedString::from(format!("diagram-depth-{}", depth)),
+                        depth.to_string(),
+                        self.neighbourhood == Some((table, depth)),
+                        true,
+                    )
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.set_neighbourhood(Some((table, depth)), cx);
+                    }))
+                }))
+            })
+            .when(selected.is_none(), |el| {
+                el.child(
+                    div()
+                        .text_color(AppColors::text_dim())
+                        .child("Click a table to show its neighbourhood"),
+                )
+            })
+            .when(self.neighbourhood.is_some(), |el| {
+                el.child(
+                    render_option("diagram-all-tables", "All tables", false, true).on_click(
+                        cx.listener(|this, _, _, cx| {
+                            this.set_neighbourhood(None, cx);
+                        }),
+                    ),
+                )
+            })
+    }
+
+    fn render_table(
+        &self,
+        index: usize,
+        table: &DiagramTable,
+        rect: &Rect,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let zoom = self.zoom;
+        let scaled = |value: f32| px(value * zoom);
+        let selected = self.selected == Some(index);
+
+        div()
+            .id(SharedString::from(format!("diagram-table-{}", index)))
+            .absolute()
+            .left(self.offset.x + scaled(rect.x))
+            .top(self.offset.y + scaled(rect.y))
+            .w(scaled(rect.width))
+            .h(scaled(rect.height))
+            .flex()
+            .flex_col()
+            .overflow_hidden()
+            .rounded(scaled(4.0))
+            .bg(AppColors::bg_secondary())
+            .border_1()
+            .border_color(if selected {
+                AppColors::accent()
+            } else {
+                AppColors::border()
+            })
+            .font_family("Fira Code")
+            .text_size(scaled(FONT_SIZE))
+            .cursor_pointer()
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, event: &MouseDownEvent, _, cx| {
+                    this.on_table_mouse_down(index, event, cx);
+                }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .flex_none()
+                    .h(scaled(HEADER_HEIGHT))
+                    .px(scaled(BOX_PADDING))
+                    .bg(AppColors::bg_header())
+                    .border_b_1()
+                    .border_color(AppColors::border())
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(AppColors::text())
+                    .whitespace_nowrap()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .child(table.label.clone()),
+            )
+            .children(table.columns.iter().map(|column| {
+                let (marker, color) = match (column.primary_key, column.foreign_key) {
+                    (true, _) => ("PK", AppColors::warning()),
+                    (false, true) => ("FK", AppColors::accent()),
+                    _ => ("", AppColors::text_secondary()),
+                };
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .flex_none()
+                    .h(scaled(ROW_HEIGHT))
+                    .px(scaled(BOX_PADDING))
+                    .whitespace_nowrap()
+                    .child(
+                        div()
+                            .flex_none()
+                            .w(scaled(FONT_SIZE * 1.8))
+                            .text_size(scaled(9.0))
+                            .text_color(color)
+                            .child(marker),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .text_color(color)
+                            .child(column.name.clone()),
+                    )
+                    .child(
+                        div()
+                            .flex_none()
+                            .pl(scaled(FONT_SIZE))
+                            .text_color(AppColors::text_dim())
+                            .child(column.data_type.clone()),
+                    )
+            }))
+    }
+
+    fn render_diagram(&self, diagram: &Diagram, cx: &mut Context<Self>) -> AnyElement {
+        // Edges touching the selected table are drawn on top, highlighted
+        let mut edges: Vec<(EdgeCurve, bool)> = diagram
+            .edges
+            .iter()
+            .filter_map(|edge| {
+                let highlighted = self
+                    .selected
+                    .is_some_and(|t| edge.from == t || edge.to == t);
+                Some((diagram.edge_curve(edge, &self.layout)?, highlighted))
+            })
+            .collect();
+        edges.sort_by_key(|(_, highlighted)| *highlighted);
+        let zoom = self.zoom;
+        let offset = self.offset;
+        let view = cx.entity();
+
+        let mut tables: Vec<(usize, Rect)> = self
+            .layout
+            .boxes
+            .iter()
+            .map(|(table, rect)| (*table, *rect))
+            .collect();
+        tables.sort_by_key(|(table, _)| *table);
+
+        div()
+            .id("diagram-canvas")
+            .relative()
+            .size_full()
+            .overflow_hidden()
+            .bg(AppColors::bg_main())
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
+            .on_mouse_move(cx.listener(Self::on_mouse_move))
+            .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_scroll_wheel(cx.listener(Self::on_scroll_wheel))
+            .child(
+                canvas(
+                    move |bounds, _, cx| {
+                        view.update(cx, |view, cx| view.set_viewport(bounds, cx));
+                    },
+                    move |bounds, _, window, _| {
+                        let to_screen = |(x, y): (f32, f32)| {
+                            bounds.origin + offset + point(px(x * zoom), px(y * zoom))
+                        };
+                        for (curve, highlighted) in edges {
+                            let color = if highlighted {
+                                AppColors::accent()
+                            } else {
+                                AppColors::text_dim()
+                            };
+                            let mut line =
+                                PathBuilder::stroke(px(if highlighted { 2.0 } else { 1.25 }));
+                            line.move_to(to_screen(curve.start));
+                            line.cubic_bezier_to(
+                                to_screen(curve.end),
+                                to_screen(curve.control_a),
+                                to_screen(curve.control_b),
+                            );
+                            if let Ok(path) = line.build() {
+                                window.paint_path(path, color);
+                            }
+
+                            let [tip, left, right] = curve.arrow_head();
+                            let mut head = PathBuilder::fill();
+                            head.move_to(to_screen(tip));
+                            head.line_to(to_screen(left));
+                            head.line_to(to_screen(right));
+                            head.close();
+                            if let Ok(path) = head.build() {
+                                window.paint_path(path, color);
+                            }
+                        }
+                    },
+                )
+                .absolute()
+                .size_full(),
+            )
+            .children(
+                tables.iter().map(|(table, rect)| {
+                    self.render_table(*table, &diagram.tables[*table], rect, cx)
+                }),
+            )
+            .child(self.render_selection(diagram, cx))
+            .when_some(self.notice.clone(), |el, notice| {
+                let (text, color) = match notice {
+                    Ok(text) => (text, AppColors::success()),
+                    Err(text) => (text, AppColors::error()),
+                };
+                el.child(
+                    div()
+                        .absolute()
+                        .right(rems(0.75))
+                        .bottom(rems(0.75))
+                        .max_w(rems(30.0))
+                        .px(rems(0.5)) // 8px
+                        .py(rems(0.25)) // 4px
+                        .rounded(px(4.0))
+                        .bg(AppColors::bg_secondary())
+                        .border_1()
+                        .border_color(AppColors::border())
+                        .text_size(rems(0.6875)) // 11px
+                        .text_color(color)
+                        .child(text),
+                )
+            })
+            .into_any_element()
+    }
+}
+
+impl Render for DiagramView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let message = |text: String, color: Hsla| {
+            div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .p(rems(1.0)) // 16px
+                .child(
+                    div()
+                        .text_size(rems(0.8125)) // 13px
+                        .text_color(color)
+                        .child(text),
+                )
+                .into_any_element()
+        };
+        let body = match &self.state {
+            DiagramState::Loading => {
+                message("Loading tables...".to_string(), AppColors::text_muted())
+            }
+            DiagramState::Failed(error) => message(error.clone(), AppColors::error()),
+            DiagramState::Loaded(diagram) if diagram.tables.is_empty() => {
+                message("No tables to draw".to_string(), AppColors::text_muted())
+            }
+            DiagramState::Loaded(diagram) => self.render_diagram(diagram, cx),
+        };
+
+        div()
+            .id("diagram-view")
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(AppColors::bg_main())
+            .child(self.render_toolbar(cx))
+            .child(div().flex_1().min_h_0().w_full().child(body))
+    }
+}
@@ -0,0 +1,409 @@
+//! Side panel for inserting a MongoDB document or replacing a whole one,
+//! written as Extended JSON
+
+use gpui::{prelude::*, rems, *};
+use serde_json::Value;
+
+use crate::db::extended_json::{self, DocumentError, ExtJsonFormat};
+use crate::ui::text_editor::{EditorChanged, TextEditor};
+use crate::ui::theme::AppColors;
+
+// Define actions for the document editor
+actions!(document_editor, [SaveDocument]);
+
+/// Register document editor key bindings
+pub fn register_document_editor_bindings(cx: &mut App) {
+    cx.bind_keys([KeyBinding::new(
+        "ctrl-enter",
+        SaveDocument,
+        Some("DocumentEditor"),
+    )]);
+}
+
+/// Event emitted with a valid document to write. `id` is the `_id` of the
+/// document to replace, or `None` to insert a new one.
+pub struct DocumentSubmitted {
+    pub id: Option<Value>,
+    pub document: Value,
+}
+
+/// Event emitted when the panel is closed without saving
+pub struct DocumentEditorClosed;
+
+impl EventEmitter<DocumentSubmitted> for DocumentEditor {}
+impl EventEmitter<DocumentEditorClosed> for DocumentEditor {}
+
+/// Extended JSON editor for one whole document. The text is validated as it is
+/// typed; syntax errors are shown with their line and column and the line is
+/// highlighted in the editor.
+pub struct DocumentEditor {
+    editor: Entity<TextEditor>,
+    /// `_id` of the document being edited, `None` when inserting
+    id: Option<Value>,
+    format: ExtJsonFormat,
+    /// Why the current text cannot be saved
+    error: Option<DocumentError>,
+    /// The document was submitted and the write has not finished
+    saving: bool,
+    /// Error returned by the server for the last save
+    save_error: Option<String>,
+}
+
+impl DocumentEditor {
+    /// Open the editor on an existing document, or on an empty one to insert
+    pub fn new(document: Option<&Value>, cx: &mut Context<Self>) -> Self {
+        let format = ExtJsonFormat::Relaxed;
+        let text = match document {
+            Some(document) => extended_json::to_text(document, format)
+                .or_else(|_| serde_json::to_string_pretty(document))
+                .unwrap_or_default(),
+            None => "{\n    \n}".to_string(),
+        };
+        let editor = cx.new(|cx| TextEditor::new(cx, "{ }", &text));
+        if document.is_none() {
+            // Inside the braces
+            editor.update(cx, |editor, cx| editor.set_cursor(6, cx));
+        }
+
+        cx.subscribe(&editor, |this, _, _: &EditorChanged, cx| {
+            this.save_error = None;
+            this.validate(cx);
+        })
+        .detach();
+
+        Self {
+            editor,
+            id: document.and_then(|document| document.get("_id")).cloned(),
+            format,
+            error: None,
+            saving: false,
+            save_error: None,
+        }
+    }
+
+    pub fn is_insert(&self) -> bool {
+        self.id.is_none()
+    }
+
+    pub fn focus(&self, window: &mut Window, cx: &App) {
+        window.focus(&self.editor.focus_handle(cx));
+    }
+
+    /// Parse the text, recording (and highlighting) the error if it is invalid
+    fn validate(&mut self, cx: &mut Context<Self>) -> Option<Value> {
+        let result = extended_json::parse_document(&self.editor.read(cx).text());
+        let (document, error) = match result {
+            Ok(document) => match self.changed_id_error(&document) {
+                Some(error) => (None, Some(error)),
+                None => (Some(document), None),
+            },
+            Err(error) => (None, Some(error)),
+        };
+
+        let error_line = error
+            .as_ref()
+            .and_then(|error| error.position)
+            .map(|position| position.line - 1);
+        self.editor
+            .update(cx, |editor, cx| editor.set_error_line(error_line, cx));
+        self.error = error;
+        cx.notify();
+        document
+    }
+
+    /// `replaceOne` keeps the `_id`; reject a document that tries to change it
+    fn changed_id_error(&self, document: &Value) -> Option<DocumentError> {
+        let id = self.id.as_ref()?;
+        let new_id = document.get("_id")?;
+        let canonical =
+            |value: &Value| extended_json::convert(value, ExtJsonFormat::Canonical).ok();
+        (canonical(new_id) != canonical(id)).then(|| DocumentError {
+            message: "_id cannot be changed; insert a copy of the document instead".into(),
+            position: None,
+        })
+    }
+
+    /// Rewrite the text in the other Extended JSON format. Only valid text can be
+    /// converted, so an invalid document keeps its format and shows the error.
+    fn set_format(&mut self, format: ExtJsonFormat, cx: &mut Context<Self>) {
+        if format == self.format {
+            return;
+        }
+        let Some(document) = self.validate(cx) else {
+            return;
+        };
+        match extended_json::to_text(&document, format) {
+            Ok(text) => {
+                self.format = format;
+                self.editor
+                    .update(cx, |editor, cx| editor.set_text(&text, cx));
+            }
+            Err(e) => {
+                self.error = Some(DocumentError {
+                    message: e.to_string(),
+                    position: None,
+                });
+            }
+        }
+        cx.notify();
+    }
+
+    fn save(&mut self, cx: &mut Context<Self>) {
+        if self.saving {
+            return;
+        }
+        let Some(document) = self.validate(cx) else {
+            return;
+        };
+        self.saving = true;
+        self.save_error = None;
+        cx.emit(DocumentSubmitted {
+            id: self.id.clone(),
+            document,
+        });
+        cx.notify();
+    }
+
+    /// Report that the submitted write failed; the text stays for another try
+    pub fn set_save_error(&mut self, error: String, cx: &mut Context<Self>) {
+        self.saving = false;
+        self.save_error = Some(error);
+        cx.notify();
+    }
+
+    /// Put the cursor at the position of the syntax error
+    fn jump_to_error(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(position) = self.error.as_ref().and_then(|error| error.position) else {
+            return;
+        };
+        self.editor
+            .update(cx, |editor, cx| editor.set_cursor(position.offset, cx));
+        self.focus(window, cx);
+    }
+
+    fn render_format_toggle(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let current = self.format;
+        div()
+            .flex()
+            .flex_row()
+            .rounded(px(4.0))
+            .border_1()
+            .border_color(AppColors::border())
+            .overflow_hidden()
+            .children(
+                [ExtJsonFormat::Relaxed, ExtJsonFormat::Canonical].map(|format| {
+                    let selected = format == current;
+                    div()
+                        .id(format.label())
+                        .cursor_pointer()
+                        .px(rems(0.5)) // 8px
+                        .py(rems(0.125)) // 2px
+                        .text_size(rems(0.6875)) // 11px
+                        .when(selected, |el| {
+                            el.bg(AppColors::bg_active())
+                                .text_color(AppColors::accent())
+                        })
+                        .when(!selected, |el| {
+                            el.text_color(AppColors::text_muted())
+                                .hover(|s| s.bg(AppColors::bg_hover()))
+                        })
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.set_format(format, cx);
+                        }))
+                        .child(format.label())
+                }),
+            )
+    }
+
+    fn render_status(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let (text, color, has_position) = match (&self.error, &self.save_error) {
+            (Some(error), _) => match error.position {
+                Some(position) => (
+                    format!(
+                        "Line {}, column {}: {}",
+                        position.line, position.column, error.message
+                    ),
+                    AppColors::error(),
+                    true,
+                ),
+                None => (error.message.clone(), AppColors::error(), false),
+            },
+            (None, Some(error)) => (error.clone(), AppColors::error(), false),
+            (None, None) if self.format == ExtJsonFormat::Relaxed => (
+                "Valid. Plain integers are saved as Int32 when they fit; use Canonical to keep Int64"
+                    .to_string(),
+                AppColors::text_muted(),
+                false,
+            ),
+            (None, None) => (
+                "Valid Extended JSON".to_string(),
+                AppColors::text_muted(),
+                false,
+            ),
+        };
+
+        div()
+            .id("document-editor-status")
+            .px(rems(0.75)) // 12px
+            .py(rems(0.375)) // 6px
+            .border_t_1()
+            .border_color(AppColors::border_subtle())
+            .text_size(rems(0.6875)) // 11px
+            .text_color(color)
+            .when(has_position, |el| {
+                el.cursor_pointer()
+                    .hover(|s| s.bg(AppColors::bg_hover()))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.jump_to_error(window, cx);
+                    }))
+            })
+            .child(text)
+    }
+}
+
+impl Render for DocumentEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_insert = self.is_insert();
+        let can_save = self.error.is_none() && !self.saving;
+        let save_label = match (self.saving, is_insert) {
+            (true, _) => "Saving...",
+            (false, true) => "Insert",
+            (false, false) => "Replace",
+        };
+
+        div()
+            .id("document-editor")
+            .key_context("DocumentEditor")
+            .on_action(cx.listener(|this, _: &SaveDocument, _, cx| {
+                this.save(cx);
+            }))
+            .flex()
+            .flex_col()
+            .w(rems(30.0)) // 480px
+            .h_full()
+            .bg(AppColors::bg_secondary())
+            .border_l_1()
+            .border_color(AppColors::border_subtle())
+            // Title bar
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .h(rems(2.0)) // 32px
+                    .px(rems(0.75)) // 12px
+                    .bg(AppColors::bg_header())
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(AppColors::text())
+                            .child(if is_insert {
+                                "Insert Document"
+                            } else {
+                                "Edit Document"
+                            }),
+                    )
+                    .child(
+                        div()
+                            .id("close-document-editor")
+                            .cursor_pointer()
+                            .p(rems(0.25)) // 4px
+                            .rounded(px(3.0))
+                            .hover(|s| s.bg(AppColors::bg_hover()))
+                            .on_click(cx.listener(|_, _, _, cx| {
+                                cx.emit(DocumentEditorClosed);
+                            }))
+                            .child(
+                                svg()
+                                    .path("icons/close.svg")
+                                    .size(rems(0.75)) // 12px
+                                    .text_color(AppColors::text_muted()),
+                            ),
+                    ),
+            )
+            // Format
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .h(rems(1.75)) // 28px
+                    .px(rems(0.75)) // 12px
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_muted())
+                            .child("Extended JSON"),
+                    )
+                    .child(self.render_format_toggle(cx)),
+            )
+            // Editor
+            .child(
+                div()
+                    .id("document-editor-scroll")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_scroll()
+                    .font_family("Fira Code")
+                    .child(self.editor.clone()),
+            )
+            .child(self.render_status(cx))
+            // Actions
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_end()
+                    .gap(rems(0.5)) // 8px
+                    .h(rems(2.5)) // 40px
+                    .px(rems(0.75)) // 12px
+                    .border_t_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .id("cancel-document")
+                            .cursor_pointer()
+                            .px(rems(0.5)) // 8px
+                            .py(rems(0.125)) // 2px
+                            .rounded(px(3.0))
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_secondary())
+                            .hover(|s| s.bg(AppColors::bg_hover()).text_color(AppColors::text()))
+                            .on_click(cx.listener(|_, _, _, cx| {
+                                cx.emit(DocumentEditorClosed);
+                            }))
+                            .child("Cancel"),
+                    )
+                    .child(
+                        div()
+                            .id("save-document")
+                            .px(rems(0.75)) // 12px
+                            .py(rems(0.25)) // 4px
+                            .rounded(px(4.0))
+                            .text_size(rems(0.75)) // 12px
+                            .when(can_save, |el| {
+                                el.cursor_pointer()
+                                    .bg(AppColors::accent())
+                                    .hover(|s| s.bg(AppColors::accent_hover()))
+                                    .text_color(AppColors::text())
+                            })
+                            .when(!can_save, |el| {
+                                el.bg(AppColors::bg_active())
+                                    .text_color(AppColors::text_dim())
+                            })
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.save(cx);
+                            }))
+                            .child(save_label),
+                    ),
+            )
+    }
+}
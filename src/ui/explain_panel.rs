@@ -0,0 +1,507 @@
+//! Side panel showing the query plan of a collection view's filter or a console
+//! statement as an expandable tree
+
+use gpui::{prelude::*, rems, *};
+use std::collections::HashSet;
+
+use crate::db::driver::ConnectionConfig;
+use crate::db::explain::{format_cost, format_count, format_ms, PlanNode, QueryPlan};
+use crate::db::registry::registry;
+use crate::db::runtime;
+use crate::ui::theme::AppColors;
+
+/// Event emitted when the panel is closed
+pub struct ExplainPanelClosed;
+
+impl EventEmitter<ExplainPanelClosed> for ExplainPanel {}
+
+/// What to explain
+#[derive(Clone)]
+pub enum ExplainTarget {
+    /// The page a collection view loads with this filter and sort
+    Documents {
+        collection: String,
+        limit: u32,
+        skip: u32,
+        filter: Option<String>,
+        sort: Option<String>,
+    },
+    /// A console statement or shell command
+    Query(String),
+}
+
+/// Where and what to explain, gathered by the view that opens the panel
+#[derive(Clone)]
+pub struct ExplainRequest {
+    pub connection_id: String,
+    pub config: ConnectionConfig,
+    pub database: String,
+    pub target: ExplainTarget,
+}
+
+enum ExplainState {
+    Loading,
+    Loaded(Box<QueryPlan>),
+    Failed(String),
+}
+
+/// Path of a node from the root, as child indexes
+type NodePath = Vec<usize>;
+
+pub struct ExplainPanel {
+    request: ExplainRequest,
+    state: ExplainState,
+    /// Nodes whose children are hidden
+    collapsed: HashSet<NodePath>,
+    /// Nodes whose details are shown
+    expanded: HashSet<NodePath>,
+}
+
+impl ExplainPanel {
+    pub fn new(request: ExplainRequest, cx: &mut Context<Self>) -> Self {
+        let mut panel = Self {
+            request,
+            state: ExplainState::Loading,
+            collapsed: HashSet::new(),
+            expanded: HashSet::new(),
+        };
+        panel.run(cx);
+        panel
+    }
+
+    fn run(&mut self, cx: &mut Context<Self>) {
+        self.state = ExplainState::Loading;
+        cx.notify();
+
+        let request = self.request.clone();
+        let task = runtime::spawn(async move {
+            let conn = registry()
+                .get(&request.connection_id, request.config)
+                .await?;
+            match request.target {
+                ExplainTarget::Documents {
+                    collection,
+                    limit,
+                    skip,
+                    filter,
+                    sort,
+                } => {
+                    conn.explain_documents(
+                        &request.database,
+                        &collection,
+                        limit,
+                        skip,
+                        filter.as_deref(),
+                        sort.as_deref(),
+                    )
+                    .await
+                }
+                ExplainTarget::Query(query) => conn.explain_query(&request.database, &query).await,
+            }
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |panel, cx| {
+                panel.state = match result {
+                    Ok(Ok(plan)) => {
+                        panel.collapsed.clear();
+                        panel.expanded.clear();
+                        ExplainState::Loaded(Box::new(plan))
+                    }
+                    Ok(Err(e)) => ExplainState::Failed(e.to_string()),
+                    Err(_) => ExplainState::Failed("Explain task was interrupted".into()),
+                };
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn is_loading(&self) -> bool {
+        matches!(self.state, ExplainState::Loading)
+    }
+
+    fn copy_json(&self, cx: &mut Context<Self>) {
+        if let ExplainState::Loaded(plan) = &self.state {
+            let json = serde_json::to_string_pretty(&plan.raw).unwrap_or_default();
+            cx.write_to_clipboard(ClipboardItem::new_string(json));
+        }
+    }
+
+    fn toggle(set: &mut HashSet<NodePath>, path: &NodePath) {
+        if !set.remove(path) {
+            set.insert(path.clone());
+        }
+    }
+
+    fn render_summary(plan: &QueryPlan) -> impl IntoElement {
+        let full_scan = plan.root.has_full_scan();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(rems(0.25)) // 4px
+            .px(rems(0.75)) // 12px
+            .py(rems(0.5)) // 8px
+            .border_b_1()
+            .border_color(AppColors::border_subtle())
+            .children(plan.summary.iter().map(|(label, value)| {
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap(rems(0.5)) // 8px
+                    .text_size(rems(0.6875)) // 11px
+                    .child(
+                        div()
+                            .w(rems(8.0)) // 128px
+                            .text_color(AppColors::text_muted())
+                            .child(label.clone()),
+                    )
+                    .child(
+                        div()
+                            .text_color(AppColors::text_secondary())
+                            .child(value.clone()),
+                    )
+            }))
+            .when(full_scan, |el| {
+                el.child(
+                    div()
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap(rems(0.375)) // 6px
+                        .mt(rems(0.25)) // 4px
+                        .text_size(rems(0.6875)) // 11px
+                        .text_color(AppColors::warning())
+                        .child(
+                            svg()
+                                .path("icons/warning.svg")
+                                .size(rems(0.75)) // 12px
+                                .text_color(AppColors::warning()),
+                        )
+                        .child("The plan reads a whole table or collection"),
+                )
+            })
+    }
+
+    /// Cost, rows and time of a node, as one muted line
+    fn metrics(node: &PlanNode) -> String {
+        let mut parts = Vec::new();
+        if let Some(cost) = node.cost {
+            parts.push(format!("cost {}", format_cost(cost)));
+        }
+        if let Some(rows) = node.estimated_rows {
+            parts.push(format!("est. {} rows", format_count(rows)));
+        }
+        if let Some(rows) = node.actual_rows {
+            parts.push(format!("{} rows", format_count(rows)));
+        }
+        if let Some(ms) = node.time_ms {
+            parts.push(format_ms(ms));
+        }
+        parts.join(" · ")
+    }
+
+    /// Rows of `node` and, unless collapsed, its children, indented by depth
+    fn render_node(
+        &self,
+        node: &PlanNode,
+        path: NodePath,
+        rows: &mut Vec<AnyElement>,
+        cx: &mut Context<Self>,
+    ) {
+        let depth = path.len();
+        let has_children = !node.children.is_empty();
+        let collapsed = self.collapsed.contains(&path);
+        let expanded = self.expanded.contains(&path);
+        let metrics = Self::metrics(node);
+        let id = path
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("-");
+
+        let chevron = div()
+            .id(SharedString::from(format!("plan-toggle-{}", id)))
+            .flex()
+            .items_center()
+            .justify_center()
+            .flex_none()
+            .size(rems(1.0)) // 16px
+            .rounded(px(3.0))
+            .when(has_children, |el| {
+                el.cursor_pointer()
+                    .hover(|s| s.bg(AppColors::bg_hover()))
+                    .on_click(cx.listener({
+                        let path = path.clone();
+                        move |this, _, _, cx| {
+                            Self::toggle(&mut this.collapsed, &path);
+                            cx.notify();
+                        }
+                    }))
+                    .child(
+                        svg()
+                            .path(if collapsed {
+                                "icons/chevron-right.svg"
+                            } else {
+                                "icons/chevron-down.svg"
+                            })
+                            .size(rems(0.625)) // 10px
+                            .text_color(AppColors::text_dim()),
+                    )
+            });
+
+        let row = div()
+            .flex()
+            .flex_row()
+            .items_start()
+            .gap(rems(0.25)) // 4px
+            .pl(rems(0.5 + depth as f32)) // 8px plus 16px per level
+            .pr(rems(0.75)) // 12px
+            .py(rems(0.25)) // 4px
+            .when(node.full_scan, |el| el.bg(AppColors::error_hover_bg()))
+            .child(chevron)
+            .child(
+                div()
+                    .id(SharedString::from(format!("plan-node-{}", id)))
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .min_w_0()
+                    .gap(rems(0.125)) // 2px
+                    .when(!node.details.is_empty(), |el| {
+                        el.cursor_pointer().on_click(cx.listener({
+                            let path = path.clone();
+                            move |this, _, _, cx| {
+                                Self::toggle(&mut this.expanded, &path);
+                                cx.notify();
+                            }
+                        }))
+                    })
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .flex_wrap()
+                            .items_center()
+                            .gap(rems(0.375)) // 6px
+                            .text_size(rems(0.75)) // 12px
+                            .child(
+                                div()
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .text_color(if node.full_scan {
+                                        AppColors::error()
+                                    } else {
+                                        AppColors::text()
+                                    })
+                                    .child(node.operation.clone()),
+                            )
+                            .when_some(node.target.clone(), |el, target| {
+                                el.child(
+                                    div().text_color(AppColors::text_secondary()).child(target),
+                                )
+                            })
+                            .when_some(node.index.clone(), |el, index| {
+                                el.child(
+                                    div()
+                                        .px(rems(0.3125)) // 5px
+                                        .rounded(px(3.0))
+                                        .bg(AppColors::bg_active())
+                                        .text_size(rems(0.625)) // 10px
+                                        .text_color(AppColors::success())
+                                        .child(format!("index {}", index)),
+                                )
+                            })
+                            .when(node.full_scan, |el| {
+                                el.child(
+                                    div()
+                                        .px(rems(0.3125)) // 5px
+                                        .rounded(px(3.0))
+                                        .bg(AppColors::bg_active())
+                                        .text_size(rems(0.625)) // 10px
+                                        .text_color(AppColors::warning())
+                                        .child("full scan"),
+                                )
+                            }),
+                    )
+                    .when(!metrics.is_empty(), |el| {
+                        el.child(
+                            div()
+                                .text_size(rems(0.6875)) // 11px
+                                .text_color(AppColors::text_muted())
+                                .child(metrics),
+                        )
+                    })
+                    .when(expanded, |el| {
+                        el.children(node.details.iter().map(|(key, value)| {
+                            div()
+                                .flex()
+                                .flex_row()
+                                .gap(rems(0.375)) // 6px
+                                .text_size(rems(0.6875)) // 11px
+                                .child(
+                                    div()
+                                        .flex_none()
+                                        .text_color(AppColors::text_dim())
+                                        .child(format!("{}:", key)),
+                                )
+                                .child(
+                                    div()
+                                        .min_w_0()
+                                        .text_color(AppColors::text_secondary())
+                                        .child(value.clone()),
+                                )
+                        }))
+                    }),
+            );
+        rows.push(row.into_any_element());
+
+        if !collapsed {
+            for (i, child) in node.children.iter().enumerate() {
+                let mut child_path = path.clone();
+                child_path.push(i);
+                self.render_node(child, child_path, rows, cx);
+            }
+        }
+    }
+
+    fn render_body(&self, cx: &mut Context<Self>) -> AnyElement {
+        let status = |text: String, color: Hsla| {
+            div()
+                .px(rems(0.75)) // 12px
+                .py(rems(0.5)) // 8px
+                .text_size(rems(0.6875)) // 11px
+                .text_color(color)
+                .child(text)
+                .into_any_element()
+        };
+
+        match &self.state {
+            ExplainState::Loading => status("Explaining...".to_string(), AppColors::text_muted()),
+            ExplainState::Failed(error) => status(error.clone(), AppColors::error()),
+            ExplainState::Loaded(plan) => {
+                let mut rows = Vec::new();
+                self.render_node(&plan.root, Vec::new(), &mut rows, cx);
+                div()
+                    .flex()
+                    .flex_col()
+                    .child(Self::render_summary(plan))
+                    .child(div().flex().flex_col().py(rems(0.25)).children(rows))
+                    .into_any_element()
+            }
+        }
+    }
+
+    fn render_button(id: &'static str, label: &'static str, enabled: bool) -> Stateful<Div> {
+        div()
+            .id(id)
+            .px(rems(0.75)) // 12px
+            .py(rems(0.25)) // 4px
+            .rounded(px(4.0))
+            .text_size(rems(0.75)) // 12px
+            .when(enabled, |el| {
+                el.cursor_pointer()
+                    .text_color(AppColors::text_secondary())
+                    .hover(|s| s.bg(AppColors::bg_hover()).text_color(AppColors::text()))
+            })
+            .when(!enabled, |el| el.text_color(AppColors::text_dim()))
+            .child(label)
+    }
+}
+
+impl Render for ExplainPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let loading = self.is_loading();
+        let loaded = matches!(self.state, ExplainState::Loaded(_));
+
+        div()
+            .id("explain-panel")
+            .flex()
+            .flex_col()
+            .w(rems(30.0)) // 480px
+            .h_full()
+            .bg(AppColors::bg_secondary())
+            .border_l_1()
+            .border_color(AppColors::border_subtle())
+            // Title bar
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .h(rems(2.0)) // 32px
+                    .px(rems(0.75)) // 12px
+                    .bg(AppColors::bg_header())
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(AppColors::text())
+                            .child("Query Plan"),
+                    )
+                    .child(
+                        div()
+                            .id("close-explain-panel")
+                            .cursor_pointer()
+                            .p(rems(0.25)) // 4px
+                            .rounded(px(3.0))
+                            .hover(|s| s.bg(AppColors::bg_hover()))
+                            .on_click(cx.listener(|_, _, _, cx| {
+                                cx.emit(ExplainPanelClosed);
+                            }))
+                            .child(
+                                svg()
+                                    .path("icons/close.svg")
+                                    .size(rems(0.75)) // 12px
+                                    .text_color(AppColors::text_muted()),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .id("explain-plan")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .child(self.render_body(cx)),
+            )
+            // Actions
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_end()
+                    .gap(rems(0.5)) // 8px
+                    .h(rems(2.5)) // 40px
+                    .px(rems(0.75)) // 12px
+                    .border_t_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        Self::render_button("copy-explain-json", "Copy JSON", loaded).when(
+                            loaded,
+                            |el| {
+                                el.on_click(cx.listener(|this, _, _, cx| {
+                                    this.copy_json(cx);
+                                }))
+                            },
+                        ),
+                    )
+                    .child(
+                        Self::render_button("rerun-explain", "Refresh", !loading).when(
+                            !loading,
+                            |el| {
+                                el.on_click(cx.listener(|this, _, _, cx| {
+                                    this.run(cx);
+                                }))
+                            },
+                        ),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,562 @@
+//! Side panel for exporting rows of a collection view or console result to a file
+
+use gpui::{prelude::*, rems, *};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::db::driver::ConnectionConfig;
+use crate::db::export::{
+    self, CsvOptions, CsvQuoting, ExportFormat, ExportOptions, ExportProgress, ExportSource,
+};
+use crate::db::registry::registry;
+use crate::db::{runtime, DatabaseType};
+use crate::ui::text_input::TextInput;
+use crate::ui::theme::AppColors;
+use crate::ui::widgets::{render_option, render_section};
+
+/// How often the progress of a running export is redrawn
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Delimiters offered for CSV, with their labels
+const CSV_DELIMITERS: [(char, &str); 4] = [(',', ","), (';', ";"), ('\t', "Tab"), ('|', "|")];
+
+/// Event emitted when the panel is closed
+pub struct ExportPanelClosed;
+
+impl EventEmitter<ExportPanelClosed> for ExportPanel {}
+
+/// Every row of the view: rows already in memory, or a query run in batches
+#[derive(Clone)]
+pub enum ExportAll {
+    Rows(Vec<Value>),
+    Query(Box<ExportQuery>),
+}
+
+/// Collection or table to read, with the filter and sort of the view
+#[derive(Clone)]
+pub struct ExportQuery {
+    pub connection_id: String,
+    pub config: ConnectionConfig,
+    pub database: String,
+    pub collection: String,
+    pub filter: Option<String>,
+    pub sort: Option<String>,
+}
+
+/// What the panel can export, gathered by the view that opens it
+pub struct ExportRequest {
+    /// Rows of the page on screen
+    pub page: Vec<Value>,
+    /// Rows selected in the table
+    pub selected: Vec<Value>,
+    pub all: ExportAll,
+    /// Number of rows in `all`, for the progress
+    pub total: usize,
+    pub driver: DatabaseType,
+    /// Table named in `INSERT` statements unless changed in the panel
+    pub table: String,
+    /// Suggested file name, without extension
+    pub file_stem: String,
+}
+
+/// Which rows to export
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ExportScope {
+    Page,
+    Selected,
+    All,
+}
+
+enum ExportState {
+    Idle,
+    Running {
+        progress: Arc<ExportProgress>,
+        total: usize,
+    },
+    Done(String),
+    Failed(String),
+    Cancelled,
+}
+
+/// Export options and the progress of the running export. Dropping the panel
+/// cancels an export that is still running.
+pub struct ExportPanel {
+    request: ExportRequest,
+    scope: ExportScope,
+    format: ExportFormat,
+    csv: CsvOptions,
+    table_input: Entity<TextInput>,
+    state: ExportState,
+}
+
+impl ExportPanel {
+    pub fn new(request: ExportRequest, cx: &mut Context<Self>) -> Self {
+        let table_input = cx.new(|cx| TextInput::new(cx, "Table name", &request.table));
+        let scope = if request.selected.len() > 1 {
+            ExportScope::Selected
+        } else {
+            ExportScope::Page
+        };
+
+        cx.on_release(|panel, _| {
+            if let ExportState::Running { progress, .. } = &panel.state {
+                progress.cancel();
+            }
+        })
+        .detach();
+
+        Self {
+            request,
+            scope,
+            format: ExportFormat::Csv,
+            csv: CsvOptions::default(),
+            table_input,
+            state: ExportState::Idle,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(self.state, ExportState::Running { .. })
+    }
+
+    fn scope_count(&self, scope: ExportScope) -> usize {
+        match scope {
+            ExportScope::Page => self.request.page.len(),
+            ExportScope::Selected => self.request.selected.len(),
+            ExportScope::All => self.request.total,
+        }
+    }
+
+    /// Ask where to save, then export there
+    fn choose_file(&mut self, cx: &mut Context<Self>) {
+        if self.is_running() || self.scope_count(self.scope) == 0 {
+            return;
+        }
+        let directory = dirs::download_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_default();
+        let file_name = format!("{}.{}", self.request.file_stem, self.format.extension());
+        let path = cx.prompt_for_new_path(&directory, Some(file_name.as_str()));
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+            this.update(cx, |panel, cx| panel.start(path, cx)).ok();
+        })
+        .detach();
+    }
+
+    fn start(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        let source = match self.scope {
+            ExportScope::Page => ExportAll::Rows(self.request.page.clone()),
+            ExportScope::Selected => ExportAll::Rows(self.request.selected.clone()),
+            ExportScope::All => self.request.all.clone(),
+        };
+        let table = self.table_input.read(cx).text().trim().to_string();
+        let options = ExportOptions {
+            format: self.format,
+            csv: self.csv.clone(),
+            driver: self.request.driver,
+            table: if table.is_empty() {
+                self.request.table.clone()
+            } else {
+                table
+            },
+        };
+        let progress = Arc::new(ExportProgress::default());
+        self.state = ExportState::Running {
+            progress: progress.clone(),
+            total: self.scope_count(self.scope),
+        };
+        cx.notify();
+
+        let task = runtime::spawn({
+            let path = path.clone();
+            let progress = progress.clone();
+            async move {
+                let source = match source {
+                    ExportAll::Rows(rows) => ExportSource::Rows(rows),
+                    ExportAll::Query(query) => {
+                        let ExportQuery {
+                            connection_id,
+                            config,
+                            database,
+                            collection,
+                            filter,
+                            sort,
+                        } = *query;
+                        ExportSource::Query {
+                            connection: registry().get(&connection_id, config).await?,
+                            database,
+                            collection,
+                            filter,
+                            sort,
+                        }
+                    }
+                };
+                export::export(source, &path, &options, &progress).await
+            }
+        });
+
+        cx.spawn(async move |this, cx| {
+            while !task.is_finished() {
+                cx.background_executor().timer(PROGRESS_INTERVAL).await;
+                if this.update(cx, |_, cx| cx.notify()).is_err() {
+                    return;
+                }
+            }
+            let result = task.await;
+            this.update(cx, |panel, cx| {
+                panel.state = match result {
+                    Ok(Ok(count)) => ExportState::Done(format!(
+                        "Exported {} {} to {}",
+                        count,
+                        if count == 1 { "row" } else { "rows" },
+                        path.display()
+                    )),
+                    Ok(Err(_)) if progress.is_cancelled() => ExportState::Cancelled,
+                    Ok(Err(e)) => ExportState::Failed(format!("{:#}", e)),
+                    Err(_) => ExportState::Failed("Export task was interrupted".into()),
+                };
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn cancel(&mut self, cx: &mut Context<Self>) {
+        if let ExportState::Running { progress, .. } = &self.state {
+            progress.cancel();
+            cx.notify();
+        }
+    }
+
+    fn close(&mut self, cx: &mut Context<Self>) {
+        self.cancel(cx);
+        cx.emit(ExportPanelClosed);
+    }
+
+    fn render_scopes(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let all_label = match &self.request.all {
+            ExportAll::Query(query) if query.filter.is_some() => "All rows matching the filter",
+            _ => "All rows",
+        };
+        let scopes = [
+            (ExportScope::Page, "Current page"),
+            (ExportScope::Selected, "Selected rows"),
+            (ExportScope::All, all_label),
+        ];
+        let running = self.is_running();
+
+        div()
+            .flex()
+            .flex_col()
+            .children(scopes.map(|(scope, label)| {
+                let count = self.scope_count(scope);
+                let enabled = !running && count > 0;
+                render_option(
+                    SharedString::from(format!("export-scope-{}", label)),
+                    format!("{} ({})", label, count),
+                    scope == self.scope,
+                    enabled,
+                )
+                .when(enabled, |el| {
+                    el.on_click(cx.listener(move |this, _, _, cx| {
+                        this.scope = scope;
+                        cx.notify();
+                    }))
+                })
+            }))
+    }
+
+    fn render_formats(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = self.is_running();
+        div()
+            .flex()
+            .flex_row()
+            .flex_wrap()
+            .gap(rems(0.25)) // 4px
+            .children(
+                ExportFormat::available(self.request.driver)
+                    .into_iter()
+                    .map(|format| {
+                        render_option(
+                            format.label(),
+                            format.label(),
+                            format == self.format,
+                            !running,
+                        )
+                        .when(!running, |el| {
+                            el.on_click(cx.listener(move |this, _, _, cx| {
+                                this.format = format;
+                                cx.notify();
+                            }))
+                        })
+                    }),
+            )
+    }
+
+    fn render_csv_options(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = self.is_running();
+        let quotings = [
+            (CsvQuoting::Needed, "As needed"),
+            (CsvQuoting::All, "All fields"),
+        ];
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(rems(0.375)) // 6px
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.25)) // 4px
+                    .child(
+                        div()
+                            .w(rems(4.0)) // 64px
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_dim())
+                            .child("Delimiter"),
+                    )
+                    .children(CSV_DELIMITERS.map(|(delimiter, label)| {
+                        render_option(
+                            SharedString::from(format!("csv-delimiter-{}", label)),
+                            label,
+                            delimiter == self.csv.delimiter,
+                            !running,
+                        )
+                        .when(!running, |el| {
+                            el.on_click(cx.listener(move |this, _, _, cx| {
+                                this.csv.delimiter = delimiter;
+                                cx.notify();
+                            }))
+                        })
+                    })),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.25)) // 4px
+                    .child(
+                        div()
+                            .w(rems(4.0)) // 64px
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_dim())
+                            .child("Quoting"),
+                    )
+                    .children(quotings.map(|(quoting, label)| {
+                        render_option(label, label, quoting == self.csv.quoting, !running).when(
+                            !running,
+                            |el| {
+                                el.on_click(cx.listener(move |this, _, _, cx| {
+                                    this.csv.quoting = quoting;
+                                    cx.notify();
+                                }))
+                            },
+                        )
+                    })),
+            )
+            .child(
+                div()
+                    .text_size(rems(0.6875)) // 11px
+                    .text_color(AppColors::text_dim())
+                    .child("Nested fields become columns named with dotted paths"),
+            )
+    }
+
+    fn render_state(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let status = |text: String, color: Hsla| {
+            div()
+                .px(rems(0.75)) // 12px
+                .py(rems(0.5)) // 8px
+                .text_size(rems(0.6875)) // 11px
+                .text_color(color)
+                .child(text)
+        };
+
+        match &self.state {
+            ExportState::Idle => div(),
+            ExportState::Running { progress, total } => {
+                let written = progress.written();
+                let fraction = if *total == 0 {
+                    0.0
+                } else {
+                    (written as f32 / *total as f32).min(1.0)
+                };
+                let text = if progress.is_cancelled() {
+                    "Cancelling...".to_string()
+                } else {
+                    format!("Exported {} of {} rows", written, total)
+                };
+
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(rems(0.375)) // 6px
+                    .px(rems(0.75)) // 12px
+                    .py(rems(0.5)) // 8px
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .justify_between()
+                            .child(
+                                div()
+                                    .text_size(rems(0.6875)) // 11px
+                                    .text_color(AppColors::text_secondary())
+                                    .child(text),
+                            )
+                            .child(
+                                div()
+                                    .id("cancel-export")
+                                    .cursor_pointer()
+                                    .px(rems(0.5)) // 8px
+                                    .py(rems(0.125)) // 2px
+                                    .rounded(px(3.0))
+                                    .text_size(rems(0.6875)) // 11px
+                                    .text_color(AppColors::text_secondary())
+                                    .hover(|s| {
+                                        s.bg(AppColors::bg_hover()).text_color(AppColors::text())
+                                    })
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.cancel(cx);
+                                    }))
+                                    .child("Cancel"),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .h(px(4.0))
+                            .w_full()
+                            .rounded(px(2.0))
+                            .bg(AppColors::bg_active())
+                            .child(
+                                div()
+                                    .h_full()
+                                    .w(relative(fraction))
+                                    .rounded(px(2.0))
+                                    .bg(AppColors::accent()),
+                            ),
+                    )
+            }
+            ExportState::Done(message) => status(message.clone(), AppColors::success()),
+            ExportState::Failed(error) => status(error.clone(), AppColors::error()),
+            ExportState::Cancelled => {
+                status("Export cancelled".to_string(), AppColors::text_muted())
+            }
+        }
+    }
+}
+
+impl Render for ExportPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let can_export = !self.is_running() && self.scope_count(self.scope) > 0;
+
+        div()
+            .id("export-panel")
+            .flex()
+            .flex_col()
+            .w(rems(25.0)) // 400px
+            .h_full()
+            .bg(AppColors::bg_secondary())
+            .border_l_1()
+            .border_color(AppColors::border_subtle())
+            // Title bar
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .h(rems(2.0)) // 32px
+                    .px(rems(0.75)) // 12px
+                    .bg(AppColors::bg_header())
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(AppColors::text())
+                            .child("Export"),
+                    )
+                    .child(
+                        div()
+                            .id("close-export-panel")
+                            .cursor_pointer()
+                            .p(rems(0.25)) // 4px
+                            .rounded(px(3.0))
+                            .hover(|s| s.bg(AppColors::bg_hover()))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.close(cx);
+                            }))
+                            .child(
+                                svg()
+                                    .path("icons/close.svg")
+                                    .size(rems(0.75)) // 12px
+                                    .text_color(AppColors::text_muted()),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .id("export-options")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .child(render_section("Rows", self.render_scopes(cx)))
+                    .child(render_section("Format", self.render_formats(cx)))
+                    .when(self.format == ExportFormat::Csv, |el| {
+                        el.child(render_section("CSV", self.render_csv_options(cx)))
+                    })
+                    .when(self.format == ExportFormat::SqlInsert, |el| {
+                        el.child(render_section("Table", self.table_input.clone()))
+                    })
+                    .child(self.render_state(cx)),
+            )
+            // Actions
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_end()
+                    .gap(rems(0.5)) // 8px
+                    .h(rems(2.5)) // 40px
+                    .px(rems(0.75)) // 12px
+                    .border_t_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .id("start-export")
+                            .px(rems(0.75)) // 12px
+                            .py(rems(0.25)) // 4px
+                            .rounded(px(4.0))
+                            .text_size(rems(0.75)) // 12px
+                            .when(can_export, |el| {
+                                el.cursor_pointer()
+                                    .bg(AppColors::accent())
+                                    .hover(|s| s.bg(AppColors::accent_hover()))
+                                    .text_color(AppColors::text())
+                            })
+                            .when(!can_export, |el| {
+                                el.bg(AppColors::bg_active())
+                                    .text_color(AppColors::text_dim())
+                            })
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.choose_file(cx);
+                            }))
+                            .child("Export..."),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,902 @@
+//! Side panel for importing a CSV, JSON Lines or JSON array file into the open
+//! collection or table: pick the file, check the preview, map source columns to
+//! target fields and types, then insert in batches with a report of failed rows
+
+use gpui::{prelude::*, rems, *};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::db::driver::ConnectionConfig;
+use crate::db::import::{
+    self, FieldMapping, FieldType, ImportFormat, ImportOptions, ImportProgress, ImportReport,
+    ImportTarget, SourceOptions, SourcePreview,
+};
+use crate::db::registry::registry;
+use crate::db::{runtime, sql_edit, DatabaseType};
+use crate::ui::collection_view::value_to_display_string;
+use crate::ui::text_input::{InputChanged, TextInput};
+use crate::ui::theme::AppColors;
+use crate::ui::widgets::{render_option, render_section};
+
+/// How often the progress of a running import is redrawn
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Preview rows shown above the mapping
+const PREVIEW_SHOWN: usize = 5;
+
+/// Failed rows listed in the report
+const ERRORS_SHOWN: usize = 100;
+
+/// Delimiters offered for CSV, with their labels
+const CSV_DELIMITERS: [(char, &str); 4] = [(',', ","), (';', ";"), ('\t', "Tab"), ('|', "|")];
+
+/// Event emitted when an import inserted rows, so the view can reload
+pub struct ImportFinished;
+
+/// Event emitted when the panel is closed
+pub struct ImportWizardClosed;
+
+impl EventEmitter<ImportFinished> for ImportWizard {}
+impl EventEmitter<ImportWizardClosed> for ImportWizard {}
+
+/// Collection or table the wizard imports into
+pub struct ImportDestination {
+    pub connection_id: String,
+    pub config: ConnectionConfig,
+    pub database: String,
+    pub collection: String,
+    pub driver: DatabaseType,
+    /// Columns of the SQL table; empty for collections
+    pub columns: Vec<String>,
+}
+
+/// A source column and where it goes
+struct MappingRow {
+    source: String,
+    /// Target field or column; empty skips the column
+    target: Entity<TextInput>,
+    field_type: FieldType,
+}
+
+enum ImportState {
+    Idle,
+    Running(Arc<ImportProgress>),
+    Done(ImportReport),
+    Failed { error: String, inserted: usize },
+}
+
+/// Import wizard. Dropping it cancels an import that is still running.
+pub struct ImportWizard {
+    destination: ImportDestination,
+    path: Option<PathBuf>,
+    source: Option<SourceOptions>,
+    preview: Option<SourcePreview>,
+    preview_error: Option<String>,
+    mappings: Vec<MappingRow>,
+    stop_on_error: bool,
+    state: ImportState,
+}
+
+impl ImportWizard {
+    pub fn new(destination: ImportDestination, cx: &mut Context<Self>) -> Self {
+        cx.on_release(|wizard, _| {
+            if let ImportState::Running(progress) = &wizard.state {
+                progress.cancel();
+            }
+        })
+        .detach();
+
+        Self {
+            destination,
+            path: None,
+            source: None,
+            preview: None,
+            preview_error: None,
+            mappings: Vec::new(),
+            stop_on_error: false,
+            state: ImportState::Idle,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(self.state, ImportState::Running(_))
+    }
+
+    fn choose_file(&mut self, cx: &mut Context<Self>) {
+        if self.is_running() {
+            return;
+        }
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            this.update(cx, |wizard, cx| {
+                wizard.source = Some(SourceOptions::detect(&path));
+                wizard.path = Some(path);
+                wizard.state = ImportState::Idle;
+                wizard.load_preview(cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Change how the file is read and read the preview again
+    fn update_source(&mut self, cx: &mut Context<Self>, update: impl FnOnce(&mut SourceOptions)) {
+        if self.is_running() {
+            return;
+        }
+        if let Some(source) = &mut self.source {
+            update(source);
+            self.state = ImportState::Idle;
+            self.load_preview(cx);
+        }
+    }
+
+    fn load_preview(&mut self, cx: &mut Context<Self>) {
+        let (Some(path), Some(source)) = (self.path.clone(), self.source.clone()) else {
+            return;
+        };
+        cx.notify();
+
+        // A JSON array is parsed whole, so even the preview is read off the UI thread
+        let task = runtime::spawn(async move { import::preview(&path, &source) });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |wizard, cx| {
+                match result {
+                    Ok(Ok(preview)) => {
+                        wizard.set_mappings(&preview.columns, cx);
+                        wizard.preview = Some(preview);
+                        wizard.preview_error = None;
+                    }
+                    Ok(Err(e)) => {
+                        wizard.preview = None;
+                        wizard.preview_error = Some(format!("{:#}", e));
+                    }
+                    Err(_) => {
+                        wizard.preview = None;
+                        wizard.preview_error = Some("Reading the file was interrupted".into());
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// One mapping per source column. Mappings of columns still in the file
+    /// are kept; new ones go to the field or column of the same name.
+    fn set_mappings(&mut self, columns: &[String], cx: &mut Context<Self>) {
+        let mut previous = std::mem::take(&mut self.mappings);
+        for source in columns {
+            if let Some(ix) = previous.iter().position(|row| &row.source == source) {
+                self.mappings.push(previous.remove(ix));
+                continue;
+            }
+
+            let target = if self.destination.columns.is_empty() {
+                source.clone()
+            } else {
+                self.destination
+                    .columns
+                    .iter()
+                    .find(|column| column.eq_ignore_ascii_case(source))
+                    .cloned()
+                    .unwrap_or_default()
+            };
+            let target = cx.new(|cx| TextInput::new(cx, "Skip", &target));
+            cx.subscribe(&target, |_, _, _: &InputChanged, cx| cx.notify())
+                .detach();
+            self.mappings.push(MappingRow {
+                source: source.clone(),
+                target,
+                field_type: FieldType::Auto,
+            });
+        }
+    }
+
+    fn field_mappings(&self, cx: &App) -> Vec<FieldMapping> {
+        self.mappings
+            .iter()
+            .map(|row| FieldMapping {
+                source: row.source.clone(),
+                target: row.target.read(cx).text().trim().to_string(),
+                field_type: row.field_type,
+            })
+            .collect()
+    }
+
+    /// Why the import cannot start, if it cannot
+    fn blocker(&self, cx: &App) -> Option<String> {
+        if self.preview.is_none() {
+            return Some("Choose a file to import".into());
+        }
+        import::mapping_error(&self.field_mappings(cx), &self.destination.columns)
+    }
+
+    fn start(&mut self, cx: &mut Context<Self>) {
+        if self.is_running() || self.blocker(cx).is_some() {
+            return;
+        }
+        let (Some(path), Some(source)) = (self.path.clone(), self.source.clone()) else {
+            return;
+        };
+        let destination = &self.destination;
+        let options = ImportOptions {
+            source,
+            mappings: self.field_mappings(cx),
+            driver: destination.driver,
+            table: sql_edit::table_reference(
+                destination.driver,
+                &destination.database,
+                &destination.collection,
+            ),
+            stop_on_error: self.stop_on_error,
+        };
+        let connection_id = destination.connection_id.clone();
+        let config = destination.config.clone();
+        let database = destination.database.clone();
+        let collection = destination.collection.clone();
+
+        let progress = Arc::new(ImportProgress::default());
+        self.state = ImportState::Running(progress.clone());
+        cx.notify();
+
+        let task = runtime::spawn({
+            let progress = progress.clone();
+            async move {
+                let target = ImportTarget {
+                    connection: registry().get(&connection_id, config).await?,
+                    database,
+                    collection,
+                };
+                import::import(&path, target, &options, &progress).await
+            }
+        });
+
+        cx.spawn(async move |this, cx| {
+            while !task.is_finished() {
+                cx.background_executor().timer(PROGRESS_INTERVAL).await;
+                if this.update(cx, |_, cx| cx.notify()).is_err() {
+                    return;
+                }
+            }
+            let result = task.await;
+            this.update(cx, |wizard, cx| {
+                wizard.state = match result {
+                    Ok(Ok(report)) => ImportState::Done(report),
+                    Ok(Err(e)) => ImportState::Failed {
+                        error: format!("{:#}", e),
+                        inserted: progress.inserted(),
+                    },
+                    Err(_) => ImportState::Failed {
+                        error: "Import task was interrupted".into(),
+                        inserted: progress.inserted(),
+                    },
+                };
+                if progress.inserted() > 0 {
+                    cx.emit(ImportFinished);
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn cancel(&mut self, cx: &mut Context<Self>) {
+        if let ImportState::Running(progress) = &self.state {
+            progress.cancel();
+            cx.notify();
+        }
+    }
+
+    fn close(&mut self, cx: &mut Context<Self>) {
+        self.cancel(cx);
+        cx.emit(ImportWizardClosed);
+    }
+
+    fn render_label(text: impl Into<SharedString>) -> impl IntoElement {
+        div()
+            .w(rems(4.5)) // 72px
+            .flex_none()
+            .text_size(rems(0.6875)) // 11px
+            .text_color(AppColors::text_dim())
+            .child(text.into())
+    }
+
+    fn render_file(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = self.is_running();
+        let file_name = self
+            .path
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "No file chosen".to_string());
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(rems(0.375)) // 6px
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.5)) // 8px
+                    .child(
+                        render_option("choose-import-file", "Choose File...", false, !running)
+                            .border_1()
+                            .border_color(AppColors::border())
+                            .when(!running, |el| {
+                                el.on_click(cx.listener(|this, _, _, cx| {
+                                    this.choose_file(cx);
+                                }))
+                            }),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .whitespace_nowrap()
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_secondary())
+                            .child(file_name),
+                    ),
+            )
+            .when_some(self.source.clone(), |el, source| {
+                el.child(
+                    div()
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap(rems(0.25)) // 4px
+                        .child(Self::render_label("Format"))
+                        .children(ImportFormat::ALL.map(|format| {
+                            render_option(
+                                format.label(),
+                                format.label(),
+                                format == source.format,
+                                !running,
+                            )
+                            .when(!running, |el| {
+                                el.on_click(cx.listener(move |this, _, _, cx| {
+                                    this.update_source(cx, |source| source.format = format);
+                                }))
+                            })
+                        })),
+                )
+                .when(source.format == ImportFormat::Csv, |el| {
+                    el.child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap(rems(0.25)) // 4px
+                            .child(Self::render_label("Delimiter"))
+                            .children(CSV_DELIMITERS.map(|(delimiter, label)| {
+                                render_option(
+                                    SharedString::from(format!("import-delimiter-{}", label)),
+                                    label,
+                                    delimiter == source.delimiter,
+                                    !running,
+                                )
+                                .when(!running, |el| {
+                                    el.on_click(cx.listener(move |this, _, _, cx| {
+                                        this.update_source(cx, |source| {
+                                            source.delimiter = delimiter
+                                        });
+                                    }))
+                                })
+                            })),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap(rems(0.25)) // 4px
+                            .child(Self::render_label("First row"))
+                            .children([(true, "Column names"), (false, "Data")].map(
+                                |(has_header, label)| {
+                                    render_option(
+                                        label,
+                                        label,
+                                        has_header == source.has_header,
+                                        !running,
+                                    )
+                                    .when(!running, |el| {
+                                        el.on_click(cx.listener(move |this, _, _, cx| {
+                                            this.update_source(cx, |source| {
+                                                source.has_header = has_header
+                                            });
+                                        }))
+                                    })
+                                },
+                            )),
+                    )
+                })
+            })
+            .when_some(self.preview_error.clone(), |el, error| {
+                el.child(
+                    div()
+                        .text_size(rems(0.6875)) // 11px
+                        .text_color(AppColors::error())
+                        .child(error),
+                )
+            })
+    }
+
+    fn render_preview(&self, preview: &SourcePreview) -> impl IntoElement {
+        let cell = |text: SharedString, header: bool| {
+            div()
+                .w(rems(7.0)) // 112px
+                .flex_none()
+                .px(rems(0.375)) // 6px
+                .py(rems(0.125)) // 2px
+                .overflow_hidden()
+                .text_ellipsis()
+                .whitespace_nowrap()
+                .text_size(rems(0.6875)) // 11px
+                .border_r_1()
+                .border_color(AppColors::border_subtle())
+                .when(header, |el| {
+                    el.font_weight(FontWeight::SEMIBOLD)
+                        .text_color(AppColors::text_secondary())
+                })
+                .when(!header, |el| el.text_color(AppColors::text_muted()))
+                .child(text)
+        };
+        let columns = &preview.columns;
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(rems(0.375)) // 6px
+            .child(
+                div()
+                    .id("import-preview")
+                    .overflow_x_scroll()
+                    .rounded(px(3.0))
+                    .border_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div().flex().flex_row().bg(AppColors::bg_header()).children(
+                            columns
+                                .iter()
+                                .map(|column| cell(column.clone().into(), true)),
+                        ),
+                    )
+                    .children(preview.rows.iter().take(PREVIEW_SHOWN).map(|row| {
+                        div()
+                            .flex()
+                            .flex_row()
+                            .border_t_1()
+                            .border_color(AppColors::border_subtle())
+                            .children(columns.iter().map(|column| {
+                                let text = row
+                                    .get(column)
+                                    .map(value_to_display_string)
+                                    .unwrap_or_default();
+                                cell(text, false)
+                            }))
+                    })),
+            )
+            .children(preview.errors.iter().map(|error| {
+                div()
+                    .text_size(rems(0.6875)) // 11px
+                    .text_color(AppColors::error())
+                    .child(format!("Row {}: {}", error.row, error.message))
+            }))
+            .when(preview.rows.is_empty() && preview.errors.is_empty(), |el| {
+                el.child(
+                    div()
+                        .text_size(rems(0.6875)) // 11px
+                        .text_color(AppColors::text_dim())
+                        .child("The file has no rows"),
+                )
+            })
+    }
+
+    fn render_mappings(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = self.is_running();
+        let types = FieldType::available(self.destination.driver);
+        let sample = self
+            .preview
+            .as_ref()
+            .and_then(|preview| preview.rows.first());
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(rems(0.5)) // 8px
+            .children(self.mappings.iter().enumerate().map(|(ix, row)| {
+                let example = sample
+                    .and_then(|sample| sample.get(&row.source))
+                    .filter(|value| !value.is_null())
+                    .map(value_to_display_string);
+                let current = row.field_type;
+
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(rems(0.25)) // 4px
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap(rems(0.5)) // 8px
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .w(rems(9.0)) // 144px
+                                    .flex_none()
+                                    .overflow_hidden()
+                                    .child(
+                                        div()
+                                            .text_ellipsis()
+                                            .whitespace_nowrap()
+                                            .text_size(rems(0.75)) // 12px
+                                            .text_color(AppColors::text())
+                                            .child(row.source.clone()),
+                                    )
+                                    .when_some(example, |el, example| {
+                                        el.child(
+                                            div()
+                                                .text_ellipsis()
+                                                .whitespace_nowrap()
+                                                .text_size(rems(0.625)) // 10px
+                                                .text_color(AppColors::text_dim())
+                                                .child(example),
+                                        )
+                                    }),
+                            )
+                            .child(
+                                svg()
+                                    .path("icons/chevron-right.svg")
+                                    .size(rems(0.75)) // 12px
+                                    .flex_none()
+                                    .text_color(AppColors::text_dim()),
+                            )
+                            .child(div().flex_1().min_w_0().child(row.target.clone())),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .flex_wrap()
+                            .gap(rems(0.125)) // 2px
+                            .children(types.iter().map(|&field_type| {
+                                render_option(
+                                    SharedString::from(format!(
+                                        "import-type-{}-{}",
+                                        ix,
+                                        field_type.label()
+                                    )),
+                                    field_type.label(),
+                                    field_type == current,
+                                    !running,
+                                )
+                                .when(!running, |el| {
+                                    el.on_click(cx.listener(move |this, _, _, cx| {
+                                        if let Some(row) = this.mappings.get_mut(ix) {
+                                            row.field_type = field_type;
+                                            cx.notify();
+                                        }
+                                    }))
+                                })
+                            })),
+                    )
+            }))
+            .child(
+                div()
+                    .text_size(rems(0.6875)) // 11px
+                    .text_color(AppColors::text_dim())
+                    .child(if self.destination.columns.is_empty() {
+                        "Leave a target empty to skip the column; dotted names write nested fields. Empty values are imported as null."
+                    } else {
+                        "Leave a target empty to skip the column. Empty values are imported as NULL."
+                    }),
+            )
+    }
+
+    fn render_state(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let text = |text: String, color: Hsla| {
+            div()
+                .text_size(rems(0.6875)) // 11px
+                .text_color(color)
+                .child(text)
+        };
+        let noun = |count: usize| if count == 1 { "row" } else { "rows" };
+
+        let content = match &self.state {
+            ImportState::Idle => return div(),
+            ImportState::Running(progress) => div()
+                .flex()
+                .flex_col()
+                .gap(rems(0.375)) // 6px
+                .child(
+                    div()
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .justify_between()
+                        .child(text(
+                            if progress.is_cancelled() {
+                                "Cancelling...".to_string()
+                            } else {
+                                format!(
+                                    "Read {} {}, inserted {}, failed {}",
+                                    progress.rows_read(),
+                                    noun(progress.rows_read()),
+                                    progress.inserted(),
+                                    progress.failed()
+                                )
+                            },
+                            AppColors::text_secondary(),
+                        ))
+                        .child(
+                            div()
+                                .id("cancel-import")
+                                .cursor_pointer()
+                                .px(rems(0.5)) // 8px
+                                .py(rems(0.125)) // 2px
+                                .rounded(px(3.0))
+                                .text_size(rems(0.6875)) // 11px
+                                .text_color(AppColors::text_secondary())
+                                .hover(|s| {
+                                    s.bg(AppColors::bg_hover()).text_color(AppColors::text())
+                                })
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.cancel(cx);
+                                }))
+                                .child("Cancel"),
+                        ),
+                )
+                .child(
+                    div()
+                        .h(px(4.0))
+                        .w_full()
+                        .rounded(px(2.0))
+                        .bg(AppColors::bg_active())
+                        .child(
+                            div()
+                                .h_full()
+                                .w(relative(progress.fraction()))
+                                .rounded(px(2.0))
+                                .bg(AppColors::accent()),
+                        ),
+                ),
+            ImportState::Done(report) => {
+                let summary = match (report.cancelled, report.stopped_at_error) {
+                    (true, _) => format!(
+                        "Cancelled after inserting {} {}",
+                        report.inserted,
+                        noun(report.inserted)
+                    ),
+                    (false, true) => format!(
+                        "Stopped at the first error after inserting {} {}",
+                        report.inserted,
+                        noun(report.inserted)
+                    ),
+                    (false, false) => format!(
+                        "Inserted {} {}, {} failed",
+                        report.inserted,
+                        noun(report.inserted),
+                        report.failed
+                    ),
+                };
+                let color = if report.failed == 0 && !report.cancelled {
+                    AppColors::success()
+                } else {
+                    AppColors::warning()
+                };
+                let hidden = report.failed.saturating_sub(ERRORS_SHOWN);
+
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(rems(0.25)) // 4px
+                    .child(text(summary, color))
+                    .children(report.errors.iter().take(ERRORS_SHOWN).map(|error| {
+                        div()
+                            .flex()
+                            .flex_row()
+                            .gap(rems(0.5)) // 8px
+                            .text_size(rems(0.6875)) // 11px
+                            .child(
+                                div()
+                                    .flex_none()
+                                    .w(rems(4.0)) // 64px
+                                    .text_color(AppColors::text_dim())
+                                    .child(format!("Row {}", error.row)),
+                            )
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .min_w_0()
+                                    .text_color(AppColors::error())
+                                    .child(error.message.clone()),
+                            )
+                    }))
+                    .when(hidden > 0, |el| {
+                        el.child(text(
+                            format!("and {} more failed {}", hidden, noun(hidden)),
+                            AppColors::text_dim(),
+                        ))
+                    })
+            }
+            ImportState::Failed { error, inserted } => div()
+                .flex()
+                .flex_col()
+                .gap(rems(0.25)) // 4px
+                .child(text(error.clone(), AppColors::error()))
+                .child(text(
+                    format!(
+                        "{} {} were inserted before the import failed",
+                        inserted,
+                        noun(*inserted)
+                    ),
+                    AppColors::text_muted(),
+                )),
+        };
+
+        div()
+            .px(rems(0.75)) // 12px
+            .py(rems(0.5)) // 8px
+            .child(content)
+    }
+}
+
+impl Render for ImportWizard {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = self.is_running();
+        let blocker = self.blocker(cx);
+        let can_import = !running && blocker.is_none();
+        let stop_on_error = self.stop_on_error;
+        let preview = self.preview.clone();
+
+        div()
+            .id("import-wizard")
+            .flex()
+            .flex_col()
+            .w(rems(32.0)) // 512px
+            .h_full()
+            .bg(AppColors::bg_secondary())
+            .border_l_1()
+            .border_color(AppColors::border_subtle())
+            // Title bar
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .h(rems(2.0)) // 32px
+                    .px(rems(0.75)) // 12px
+                    .bg(AppColors::bg_header())
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(AppColors::text())
+                            .child(format!("Import into {}", self.destination.collection)),
+                    )
+                    .child(
+                        div()
+                            .id("close-import-wizard")
+                            .cursor_pointer()
+                            .p(rems(0.25)) // 4px
+                            .rounded(px(3.0))
+                            .hover(|s| s.bg(AppColors::bg_hover()))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.close(cx);
+                            }))
+                            .child(
+                                svg()
+                                    .path("icons/close.svg")
+                                    .size(rems(0.75)) // 12px
+                                    .text_color(AppColors::text_muted()),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .id("import-steps")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .child(render_section("1. File", self.render_file(cx)))
+                    .when_some(preview, |el, preview| {
+                        el.child(render_section("2. Preview", self.render_preview(&preview)))
+                            .when(!self.mappings.is_empty(), |el| {
+                                el.child(render_section("3. Columns", self.render_mappings(cx)))
+                            })
+                    })
+                    .child(self.render_state(cx)),
+            )
+            // Actions
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .gap(rems(0.5)) // 8px
+                    .h(rems(2.5)) // 40px
+                    .px(rems(0.75)) // 12px
+                    .border_t_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        render_option(
+                            "stop-on-error",
+                            "Stop on first error",
+                            stop_on_error,
+                            !running,
+                        )
+                        .when(!running, |el| {
+                            el.on_click(cx.listener(|this, _, _, cx| {
+                                this.stop_on_error = !this.stop_on_error;
+                                cx.notify();
+                            }))
+                        }),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap(rems(0.5)) // 8px
+                            .when_some(blocker.filter(|_| !running), |el, blocker| {
+                                el.child(
+                                    div()
+                                        .text_size(rems(0.6875)) // 11px
+                                        .text_color(AppColors::text_dim())
+                                        .child(blocker),
+                                )
+                            })
+                            .child(
+                                div()
+                                    .id("start-import")
+                                    .px(rems(0.75)) // 12px
+                                    .py(rems(0.25)) // 4px
+                                    .rounded(px(4.0))
+                                    .text_size(rems(0.75)) // 12px
+                                    .when(can_import, |el| {
+                                        el.cursor_pointer()
+                                            .bg(AppColors::accent())
+                                            .hover(|s| s.bg(AppColors::accent_hover()))
+                                            .text_color(AppColors::text())
+                                    })
+                                    .when(!can_import, |el| {
+                                        el.bg(AppColors::bg_active())
+                                            .text_color(AppColors::text_dim())
+                                    })
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.start(cx);
+                                    }))
+                                    .child(if running { "Importing..." } else { "Import" }),
+                            ),
+                    ),
+            )
+    }
+}
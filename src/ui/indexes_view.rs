@@ -0,0 +1,938 @@
+//! Indexes of a collection or table: what exists, how big and how used it is,
+//! and a form to create new ones that follows the build's progress
+
+use gpui::{prelude::*, rems, *};
+use std::time::Duration;
+
+use crate::db::driver::{ConnectionConfig, IndexBuildProgress, IndexInfo, IndexOrder, IndexSpec};
+use crate::db::registry::registry;
+use crate::db::{runtime, DatabaseType};
+use crate::ui::connection_browser::format_bytes;
+use crate::ui::text_input::TextInput;
+use crate::ui::theme::AppColors;
+use crate::ui::widgets::{render_badge, render_option, render_section};
+
+/// How often a running build's progress is read
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// MongoDB index types offered besides ascending and descending
+const MONGO_INDEX_TYPES: [&str; 3] = ["text", "hashed", "2dsphere"];
+
+enum IndexesState {
+    Loading,
+    Loaded(Vec<IndexInfo>),
+    Failed(String),
+}
+
+/// One key of the index being created
+struct KeyRow {
+    field: Entity<TextInput>,
+    order: IndexOrder,
+}
+
+/// The create form, open in a side panel
+struct CreateForm {
+    keys: Vec<KeyRow>,
+    name: Entity<TextInput>,
+    unique: bool,
+    partial_filter: Entity<TextInput>,
+    expire_after: Entity<TextInput>,
+    /// A build is running; `progress` is its last reported state
+    building: bool,
+    progress: Option<IndexBuildProgress>,
+    error: Option<String>,
+}
+
+pub struct IndexesView {
+    connection_id: String,
+    config: ConnectionConfig,
+    database_name: String,
+    collection_name: String,
+    db_type: DatabaseType,
+    state: IndexesState,
+    form: Option<CreateForm>,
+    /// Index whose drop is waiting for a second click
+    confirm_drop: Option<String>,
+    /// Outcome of the last create or drop
+    notice: Option<(String, bool)>,
+}
+
+impl IndexesView {
+    pub fn new(
+        connection_id: String,
+        config: ConnectionConfig,
+        database_name: String,
+        collection_name: String,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let db_type = config.driver;
+        let mut view = Self {
+            connection_id,
+            config,
+            database_name,
+            collection_name,
+            db_type,
+            state: IndexesState::Loading,
+            form: None,
+            confirm_drop: None,
+            notice: None,
+        };
+        view.load(cx);
+        view
+    }
+
+    fn load(&mut self, cx: &mut Context<Self>) {
+        self.state = IndexesState::Loading;
+        self.confirm_drop = None;
+        cx.notify();
+
+        let connection_id = self.connection_id.clone();
+        let config = self.config.clone();
+        let database_name = self.database_name.clone();
+        let collection_name = self.collection_name.clone();
+        let task = runtime::spawn(async move {
+            registry()
+                .get(&connection_id, config)
+                .await?
+                .list_indexes(&database_name, &collection_name)
+                .await
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |view, cx| {
+                view.state = match result {
+                    Ok(Ok(indexes)) => IndexesState::Loaded(indexes),
+                    Ok(Err(e)) => IndexesState::Failed(e.to_string()),
+                    Err(_) => IndexesState::Failed("Loading indexes was interrupted".into()),
+                };
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn key_orders(&self) -> Vec<IndexOrder> {
+        let mut orders = vec![IndexOrder::Ascending, IndexOrder::Descending];
+        if self.db_type == DatabaseType::MongoDB {
+            orders.extend(
+                MONGO_INDEX_TYPES
+                    .iter()
+                    .map(|kind| IndexOrder::Special(kind.to_string())),
+            );
+        }
+        orders
+    }
+
+    fn new_key_row(cx: &mut Context<Self>) -> KeyRow {
+        KeyRow {
+            field: cx.new(|cx| TextInput::new(cx, "Field", "")),
+            order: IndexOrder::Ascending,
+        }
+    }
+
+    fn open_form(&mut self, cx: &mut Context<Self>) {
+        if self.form.is_some() {
+            return;
+        }
+        let filter_placeholder = if self.db_type == DatabaseType::MongoDB {
+            "{ \"status\": \"active\" }"
+        } else {
+            "status = 'active'"
+        };
+        self.form = Some(CreateForm {
+            keys: vec![Self::new_key_row(cx)],
+            name: cx.new(|cx| TextInput::new(cx, "Generated from the keys", "")),
+            unique: false,
+            partial_filter: cx.new(|cx| TextInput::new(cx, filter_placeholder, "")),
+            expire_after: cx.new(|cx| TextInput::new(cx, "Seconds", "")),
+            building: false,
+            progress: None,
+            error: None,
+        });
+        cx.notify();
+    }
+
+    fn close_form(&mut self, cx: &mut Context<Self>) {
+        if self.form.as_ref().is_some_and(|form| form.building) {
+            return;
+        }
+        self.form = None;
+        cx.notify();
+    }
+
+    fn update_form(&mut self, cx: &mut Context<Self>, update: impl FnOnce(&mut CreateForm)) {
+        if let Some(form) = self.form.as_mut().filter(|form| !form.building) {
+            update(form);
+            cx.notify();
+        }
+    }
+
+    /// The index described by the form, or why it cannot be created
+    fn form_spec(&self, form: &CreateForm, cx: &App) -> Result<IndexSpec, String> {
+        let keys: Vec<(String, IndexOrder)> = form
+            .keys
+            .iter()
+            .map(|key| {
+                (
+                    key.field.read(cx).text().trim().to_string(),
+                    key.order.clone(),
+                )
+            })
+            .filter(|(field, _)| !field.is_empty())
+            .collect();
+        if keys.is_empty() {
+            return Err("Enter at least one field".into());
+        }
+        let name = form.name.read(cx).text().trim().to_string();
+        let partial_filter = form.partial_filter.read(cx).text().trim().to_string();
+        let expire_after = form.expire_after.read(cx).text();
+        let expire_after_seconds = match expire_after.trim() {
+            "" => None,
+            seconds => Some(
+                seconds
+                    .parse::<i64>()
+                    .map_err(|_| "Expiry must be a whole number of seconds".to_string())?,
+            ),
+        };
+
+        Ok(IndexSpec {
+            name: (!name.is_empty()).then_some(name),
+            keys,
+            unique: form.unique,
+            partial_filter: (!partial_filter.is_empty()).then_some(partial_filter),
+            expire_after_seconds,
+        })
+    }
+
+    fn create(&mut self, cx: &mut Context<Self>) {
+        let Some(form) = self.form.as_ref().filter(|form| !form.building) else {
+            return;
+        };
+        let spec = match self.form_spec(form, cx) {
+            Ok(spec) => spec,
+            Err(error) => {
+                self.update_form(cx, |form| form.error = Some(error));
+                return;
+            }
+        };
+        self.update_form(cx, |form| {
+            form.error = None;
+            form.progress = None;
+            form.building = true;
+        });
+
+        let connection_id = self.connection_id.clone();
+        let config = self.config.clone();
+        let database_name = self.database_name.clone();
+        let collection_name = self.collection_name.clone();
+        let task = runtime::spawn(async move {
+            registry()
+                .get(&connection_id, config)
+                .await?
+                .create_index(&database_name, &collection_name, &spec)
+                .await
+        });
+
+        cx.spawn(async move |this, cx| {
+            while !task.is_finished() {
+                cx.background_executor().timer(PROGRESS_INTERVAL).await;
+                let Ok(poll) = this.update(cx, |view, _| view.poll_progress()) else {
+                    return;
+                };
+                // Progress is best effort; the build's own result is what counts
+                let progress = poll.await.ok().and_then(|result| result.ok()).flatten();
+                if this
+                    .update(cx, |view, cx| {
+                        if let Some(form) = view.form.as_mut().filter(|form| form.building) {
+                            form.progress = progress;
+                            cx.notify();
+                        }
+                    })
+                    .is_err()
+                {
+                    return;
+                }
+            }
+            let result = task.await;
+            this.update(cx, |view, cx| {
+                let error = match result {
+                    Ok(Ok(())) => None,
+                    Ok(Err(e)) => Some(e.to_string()),
+                    Err(_) => Some("Index build was interrupted".to_string()),
+                };
+                match error {
+                    None => {
+                        view.form = None;
+                        view.notice = Some(("Index created".to_string(), false));
+                        view.load(cx);
+                    }
+                    Some(error) => {
+                        if let Some(form) = view.form.as_mut() {
+                            form.building = false;
+                            form.error = Some(error);
+                        }
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn poll_progress(
+        &self,
+    ) -> tokio::task::JoinHandle<crate::db::error::Result<Option<IndexBuildProgress>>> {
+        let connection_id = self.connection_id.clone();
+        let config = self.config.clone();
+        let database_name = self.database_name.clone();
+        let collection_name = self.collection_name.clone();
+        runtime::spawn(async move {
+            registry()
+                .get(&connection_id, config)
+                .await?
+                .index_build_progress(&database_name, &collection_name)
+                .await
+        })
+    }
+
+    /// First click asks for confirmation, the second drops the index
+    fn drop_index(&mut self, name: String, cx: &mut Context<Self>) {
+        if self.confirm_drop.as_deref() != Some(name.as_str()) {
+            self.confirm_drop = Some(name);
+            cx.notify();
+            return;
+        }
+        self.confirm_drop = None;
+
+        let connection_id = self.connection_id.clone();
+        let config = self.config.clone();
+        let database_name = self.database_name.clone();
+        let collection_name = self.collection_name.clone();
+        let task = runtime::spawn({
+            let name = name.clone();
+            async move {
+                registry()
+                    .get(&connection_id, config)
+                    .await?
+                    .drop_index(&database_name, &collection_name, &name)
+                    .await
+            }
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |view, cx| {
+                match result {
+                    Ok(Ok(())) => {
+                        view.notice = Some((format!("Dropped {}", name), false));
+                        view.load(cx);
+                    }
+                    Ok(Err(e)) => view.notice = Some((e.to_string(), true)),
+                    Err(_) => view.notice = Some(("Drop was interrupted".to_string(), true)),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let summary = match &self.state {
+            IndexesState::Loaded(indexes) if indexes.len() == 1 => "1 index".to_string(),
+            IndexesState::Loaded(indexes) => format!("{} indexes", indexes.len()),
+            IndexesState::Loading => "Loading...".to_string(),
+            IndexesState::Failed(_) => String::new(),
+        };
+        let button = |id: &'static str, icon: &'static str, label: &'static str| {
+            div()
+                .id(id)
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap(rems(0.25)) // 4px
+                .px(rems(0.625)) // 10px
+                .py(rems(0.3125)) // 5px
+                .rounded(px(4.0))
+                .cursor_pointer()
+                .hover(|s| s.bg(AppColors::bg_hover()))
+                .child(
+                    svg()
+                        .path(icon)
+                        .size(rems(0.75)) // 12px
+                        .text_color(AppColors::text_dim()),
+                )
+                .child(
+                    div()
+                        .text_size(rems(0.75)) // 12px
+                        .text_color(AppColors::text_secondary())
+                        .child(label),
+                )
+        };
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .w_full()
+            .h(rems(2.25)) // 36px
+            .px(rems(0.75)) // 12px
+            .bg(AppColors::bg_header())
+            .border_b_1()
+            .border_color(AppColors::border())
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.75)) // 12px
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_muted())
+                            .child(summary),
+                    )
+                    .when_some(self.notice.clone(), |el, (notice, is_error)| {
+                        el.child(
+                            div()
+                                .text_size(rems(0.75)) // 12px
+                                .text_color(if is_error {
+                                    AppColors::error()
+                                } else {
+                                    AppColors::success()
+                                })
+                                .child(notice),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.5)) // 8px
+                    .child(
+                        button("refresh-indexes", "icons/refresh.svg", "Refresh").on_click(
+                            cx.listener(|this, _, _, cx| {
+                                this.notice = None;
+                                this.load(cx);
+                            }),
+                        ),
+                    )
+                    .child(
+                        button("create-index", "icons/plus.svg", "Create Index").on_click(
+                            cx.listener(|this, _, _, cx| {
+                                this.open_form(cx);
+                            }),
+                        ),
+                    ),
+            )
+    }
+
+    fn render_header_cell(label: &'static str) -> Div {
+        div()
+            .text_size(rems(0.6875)) // 11px
+            .font_weight(FontWeight::MEDIUM)
+            .text_color(AppColors::text_muted())
+            .child(label)
+    }
+
+    fn render_list(&self, indexes: &[IndexInfo], cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.75)) // 12px
+                    .h(rems(1.75)) // 28px
+                    .px(rems(0.75)) // 12px
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(Self::render_header_cell("Name").w(rems(14.0)))
+                    .child(Self::render_header_cell("Keys").flex_1())
+                    .child(Self::render_header_cell("Size").w(rems(5.0)))
+                    .child(Self::render_header_cell("Used").w(rems(5.0)))
+                    .child(div().w(rems(4.5))),
+            )
+            .children(indexes.iter().map(|index| {
+                let keys = index
+                    .keys
+                    .iter()
+                    .map(|(field, order)| format!("{} {}", field, order.label()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let confirming = self.confirm_drop.as_deref() == Some(index.name.as_str());
+                let name = index.name.clone();
+
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_start()
+                    .gap(rems(0.75)) // 12px
+                    .px(rems(0.75)) // 12px
+                    .py(rems(0.5)) // 8px
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .text_size(rems(0.75)) // 12px
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(rems(0.25)) // 4px
+                            .w(rems(14.0))
+                            .flex_none()
+                            .child(
+                                div()
+                                    .text_color(AppColors::text())
+                                    .overflow_hidden()
+                                    .text_ellipsis()
+                                    .whitespace_nowrap()
+                                    .child(index.name.clone()),
+                            )
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_row()
+                                    .flex_wrap()
+                                    .gap(rems(0.25)) // 4px
+                                    .when(index.primary, |el| {
+                                        el.child(render_badge("primary", AppColors::accent()))
+                                    })
+                                    .when(index.unique && !index.primary, |el| {
+                                        el.child(render_badge("unique", AppColors::accent()))
+                                    })
+                                    .when(index.partial_filter.is_some(), |el| {
+                                        el.child(render_badge(
+                                            "partial",
+                                            AppColors::text_secondary(),
+                                        ))
+                                    })
+                                    .when(index.expire_after_seconds.is_some(), |el| {
+                                        el.child(render_badge("TTL", AppColors::warning()))
+                                    })
+                                    .when_some(index.method.clone(), |el, method| {
+                                        el.child(render_badge(method, AppColors::text_secondary()))
+                                    }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(rems(0.125)) // 2px
+                            .flex_1()
+                            .min_w_0()
+                            .child(div().text_color(AppColors::text_secondary()).child(keys))
+                            .when_some(index.partial_filter.clone(), |el, filter| {
+                                el.child(
+                                    div()
+                                        .text_size(rems(0.6875)) // 11px
+                                        .text_color(AppColors::text_muted())
+                                        .child(format!("where {}", filter)),
+                                )
+                            })
+                            .when_some(index.expire_after_seconds, |el, seconds| {
+                                el.child(
+                                    div()
+                                        .text_size(rems(0.6875)) // 11px
+                                        .text_color(AppColors::text_muted())
+                                        .child(format!("expires after {} s", seconds)),
+                                )
+                            }),
+                    )
+                    .child(
+                        div()
+                            .w(rems(5.0))
+                            .flex_none()
+                            .text_color(AppColors::text_secondary())
+                            .child(index.size_bytes.map(format_bytes).unwrap_or_default()),
+                    )
+                    .child(
+                        div()
+                            .w(rems(5.0))
+                            .flex_none()
+                            .text_color(AppColors::text_secondary())
+                            .child(
+                                index
+                                    .usage_count
+                                    .map(|count| format!("{} ×", count))
+                                    .unwrap_or_default(),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .justify_end()
+                            .w(rems(4.5))
+                            .flex_none()
+                            // The primary key and MongoDB's _id index cannot be dropped here
+                            .when(!index.primary, |el| {
+                                el.child(
+                                    div()
+                                        .id(SharedString::from(format!("drop-index-{}", name)))
+                                        .px(rems(0.375)) // 6px
+                                        .py(rems(0.125)) // 2px
+                                        .rounded(px(3.0))
+                                        .cursor_pointer()
+                                        .text_size(rems(0.6875)) // 11px
+                                        .when(confirming, |el| {
+                                            el.bg(AppColors::error_hover_bg())
+                                                .text_color(AppColors::error())
+                                        })
+                                        .when(!confirming, |el| {
+                                            el.text_color(AppColors::text_muted()).hover(|s| {
+                                                s.bg(AppColors::bg_hover())
+                                                    .text_color(AppColors::error())
+                                            })
+                                        })
+                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                            this.drop_index(name.clone(), cx);
+                                        }))
+                                        .child(if confirming { "Confirm" } else { "Drop" }),
+                                )
+                            }),
+                    )
+            }))
+    }
+
+    fn render_body(&self, cx: &mut Context<Self>) -> AnyElement {
+        let message = |text: String, color: Hsla| {
+            div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .p(rems(1.0)) // 16px
+                .child(
+                    div()
+                        .text_size(rems(0.8125)) // 13px
+                        .text_color(color)
+                        .child(text),
+                )
+                .into_any_element()
+        };
+
+        match &self.state {
+            IndexesState::Loading => {
+                message("Loading indexes...".to_string(), AppColors::text_muted())
+            }
+            IndexesState::Failed(error) => message(error.clone(), AppColors::error()),
+            IndexesState::Loaded(indexes) if indexes.is_empty() => message(
+                "No indexes. Create one to speed up filters and sorts.".to_string(),
+                AppColors::text_dim(),
+            ),
+            IndexesState::Loaded(indexes) => div()
+                .id("index-list")
+                .size_full()
+                .overflow_y_scroll()
+                .child(self.render_list(indexes, cx))
+                .into_any_element(),
+        }
+    }
+
+    fn render_keys(&self, form: &CreateForm, cx: &mut Context<Self>) -> impl IntoElement {
+        let enabled = !form.building;
+        let orders = self.key_orders();
+        let removable = form.keys.len() > 1 && enabled;
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(rems(0.5)) // 8px
+            .children(form.keys.iter().enumerate().map(|(ix, key)| {
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(rems(0.25)) // 4px
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap(rems(0.375)) // 6px
+                            .child(div().flex_1().min_w_0().child(key.field.clone()))
+                            .when(removable, |el| {
+                                el.child(
+                                    div()
+                                        .id(SharedString::from(format!("remove-index-key-{}", ix)))
+                                        .p(rems(0.25)) // 4px
+                                        .rounded(px(3.0))
+                                        .cursor_pointer()
+                                        .hover(|s| s.bg(AppColors::bg_hover()))
+                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                            this.update_form(cx, |form| {
+                                                form.keys.remove(ix);
+                                            });
+                                        }))
+                                        .child(
+                                            svg()
+                                                .path("icons/close.svg")
+                                                .size(rems(0.625)) // 10px
+                                                .text_color(AppColors::text_dim()),
+                                        ),
+                                )
+                            }),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .flex_wrap()
+                            .gap(rems(0.125)) // 2px
+                            .children(orders.iter().map(|order| {
+                                let order = order.clone();
+                                render_option(
+                                    SharedString::from(format!(
+                                        "index-key-{}-{}",
+                                        ix,
+                                        order.label()
+                                    )),
+                                    order.label().to_string(),
+                                    order == key.order,
+                                    enabled,
+                                )
+                                .when(enabled, |el| {
+                                    el.on_click(cx.listener(move |this, _, _, cx| {
+                                        let order = order.clone();
+                                        this.update_form(cx, |form| {
+                                            if let Some(key) = form.keys.get_mut(ix) {
+                                                key.order = order;
+                                            }
+                                        });
+                                    }))
+                                })
+                            })),
+                    )
+            }))
+            .child(
+                render_option("add-index-key", "+ Add field", false, enabled).when(enabled, |el| {
+                    el.on_click(cx.listener(|this, _, _, cx| {
+                        let row = Self::new_key_row(cx);
+                        this.update_form(cx, |form| form.keys.push(row));
+                    }))
+                }),
+            )
+    }
+
+    fn render_build(form: &CreateForm) -> impl IntoElement {
+        let progress = form.progress.as_ref();
+        let fraction = progress
+            .filter(|progress| progress.total > 0)
+            .map(|progress| (progress.done as f32 / progress.total as f32).min(1.0));
+        let text = match progress {
+            Some(IndexBuildProgress {
+                phase: Some(phase), ..
+            }) => phase.clone(),
+            _ => "Building index...".to_string(),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(rems(0.375)) // 6px
+            .px(rems(0.75)) // 12px
+            .py(rems(0.5)) // 8px
+            .child(
+                div()
+                    .text_size(rems(0.6875)) // 11px
+                    .text_color(AppColors::text_secondary())
+                    .child(match fraction {
+                        Some(fraction) => format!("{} ({:.0}%)", text, fraction * 100.0),
+                        None => text,
+                    }),
+            )
+            .when_some(fraction, |el, fraction| {
+                el.child(
+                    div()
+                        .h(px(4.0))
+                        .w_full()
+                        .rounded(px(2.0))
+                        .bg(AppColors::bg_active())
+                        .child(
+                            div()
+                                .h_full()
+                                .w(relative(fraction))
+                                .rounded(px(2.0))
+                                .bg(AppColors::accent()),
+                        ),
+                )
+            })
+    }
+
+    fn render_form(&self, form: &CreateForm, cx: &mut Context<Self>) -> impl IntoElement {
+        let enabled = !form.building;
+        let is_mongo = self.db_type == DatabaseType::MongoDB;
+        // MySQL has no partial indexes
+        let has_partial = self.db_type != DatabaseType::MySQL;
+
+        div()
+            .id("create-index-panel")
+            .flex()
+            .flex_col()
+            .w(rems(22.0)) // 352px
+            .h_full()
+            .bg(AppColors::bg_secondary())
+            .border_l_1()
+            .border_color(AppColors::border_subtle())
+            // Title bar
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .h(rems(2.0)) // 32px
+                    .px(rems(0.75)) // 12px
+                    .bg(AppColors::bg_header())
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(AppColors::text())
+                            .child("Create Index"),
+                    )
+                    .when(enabled, |el| {
+                        el.child(
+                            div()
+                                .id("close-create-index")
+                                .cursor_pointer()
+                                .p(rems(0.25)) // 4px
+                                .rounded(px(3.0))
+                                .hover(|s| s.bg(AppColors::bg_hover()))
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.close_form(cx);
+                                }))
+                                .child(
+                                    svg()
+                                        .path("icons/close.svg")
+                                        .size(rems(0.75)) // 12px
+                                        .text_color(AppColors::text_muted()),
+                                ),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .id("create-index-options")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .child(render_section(
+                        if is_mongo { "Fields" } else { "Columns" },
+                        self.render_keys(form, cx),
+                    ))
+                    .child(render_section("Name", form.name.clone()))
+                    .child(render_section(
+                        "Options",
+                        div().flex().flex_row().child(
+                            render_option("index-unique", "Unique", form.unique, enabled).when(
+                                enabled,
+                                |el| {
+                                    el.on_click(cx.listener(|this, _, _, cx| {
+                                        this.update_form(cx, |form| form.unique = !form.unique);
+                                    }))
+                                },
+                            ),
+                        ),
+                    ))
+                    .when(has_partial, |el| {
+                        el.child(render_section(
+                            "Partial filter",
+                            form.partial_filter.clone(),
+                        ))
+                    })
+                    .when(is_mongo, |el| {
+                        el.child(render_section(
+                            "Expire documents after (TTL)",
+                            form.expire_after.clone(),
+                        ))
+                    })
+                    .when(form.building, |el| el.child(Self::render_build(form)))
+                    .when_some(form.error.clone(), |el, error| {
+                        el.child(
+                            div()
+                                .px(rems(0.75)) // 12px
+                                .py(rems(0.5)) // 8px
+                                .text_size(rems(0.6875)) // 11px
+                                .text_color(AppColors::error())
+                                .child(error),
+                        )
+                    }),
+            )
+            // Actions
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_end()
+                    .gap(rems(0.5)) // 8px
+                    .h(rems(2.5)) // 40px
+                    .px(rems(0.75)) // 12px
+                    .border_t_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .id("start-create-index")
+                            .px(rems(0.75)) // 12px
+                            .py(rems(0.25)) // 4px
+                            .rounded(px(4.0))
+                            .text_size(rems(0.75)) // 12px
+                            .when(enabled, |el| {
+                                el.cursor_pointer()
+                                    .bg(AppColors::accent())
+                                    .hover(|s| s.bg(AppColors::accent_hover()))
+                                    .text_color(AppColors::text())
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.create(cx);
+                                    }))
+                            })
+                            .when(!enabled, |el| {
+                                el.bg(AppColors::bg_active())
+                                    .text_color(AppColors::text_dim())
+                            })
+                            .child(if enabled { "Create" } else { "Building..." }),
+                    ),
+            )
+    }
+}
+
+impl Render for IndexesView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .id("indexes-view")
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(AppColors::bg_main())
+            .child(self.render_toolbar(cx))
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .flex_1()
+                    .min_h_0()
+                    .w_full()
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .h_full()
+                            .child(self.render_body(cx)),
+                    )
+                    .when_some(self.form.as_ref(), |el, form| {
+                        el.child(self.render_form(form, cx))
+                    }),
+            )
+    }
+}
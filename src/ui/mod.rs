@@ -3,15 +3,32 @@ pub mod connection_browser;
 pub mod connection_modal;
 pub mod database_menu;
 pub mod database_picker;
+pub mod diagram_view;
+pub mod document_editor;
+pub mod explain_panel;
+pub mod export_panel;
 pub mod filter_menu;
+pub mod import_wizard;
+pub mod indexes_view;
 pub mod pane;
+pub mod password_prompt;
+pub mod pipeline_builder;
+pub mod properties_view;
+pub mod query_console;
+pub mod redis_console;
+pub mod schema_view;
 pub mod selectable_text;
+pub mod server_status_view;
 pub mod sidebar;
+pub mod structure_view;
 pub mod tab;
 pub mod tab_bar;
 pub mod table_view;
+pub mod text_editor;
 pub mod text_input;
 pub mod theme;
 pub mod title_bar;
 pub mod tooltip;
+pub mod unlock_prompt;
+pub mod widgets;
 pub mod workspace;
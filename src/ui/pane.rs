@@ -1,7 +1,14 @@
 use gpui::{prelude::*, rems, *};
 use std::collections::HashMap;
-
-use crate::ui::collection_view::CollectionView;
+use std::sync::Arc;
+
+use crate::db::{ConnectionConfig, ConnectionStorage, DatabaseType};
+use crate::ui::collection_view::{CollectionView, LoadingStateChanged, OpenKeyRequested};
+use crate::ui::diagram_view::DiagramView;
+use crate::ui::properties_view::PropertiesView;
+use crate::ui::query_console::QueryConsole;
+use crate::ui::redis_console::RedisConsole;
+use crate::ui::server_status_view::ServerStatusView;
 use crate::ui::tab::TabData;
 use crate::ui::tab_bar::TabBar;
 
@@ -31,6 +38,73 @@ impl TabInfo {
         }
     }
 
+    /// Tab for a query console; every console gets its own tab
+    pub fn console(index: usize, database_name: String, connection_string: String) -> Self {
+        Self {
+            id: SharedString::from(format!("console-{}", index)),
+            collection_name: if index == 1 {
+                "Console".to_string()
+            } else {
+                format!("Console {}", index)
+            },
+            database_name,
+            connection_string,
+            is_loading: false,
+        }
+    }
+
+    /// Tab for the diagram of a database; one per database
+    pub fn diagram(database_name: String, connection_string: String) -> Self {
+        Self {
+            id: SharedString::from(format!("diagram-{}", database_name)),
+            collection_name: "Diagram".to_string(),
+            database_name,
+            connection_string,
+            is_loading: false,
+        }
+    }
+
+    /// Tab for the properties of a database or of one of its collections
+    pub fn properties(
+        database_name: String,
+        collection_name: Option<String>,
+        connection_string: String,
+    ) -> Self {
+        let (id, title) = match &collection_name {
+            Some(collection_name) => (
+                format!("properties-{}.{}", database_name, collection_name),
+                format!("{} Properties", collection_name),
+            ),
+            None => (
+                format!("properties-{}", database_name),
+                "Properties".to_string(),
+            ),
+        };
+        Self {
+            id: SharedString::from(id),
+            collection_name: title,
+            database_name,
+            connection_string,
+            is_loading: false,
+        }
+    }
+
+    /// Tab for the live status of a server; one per connection. The
+    /// connection's name stands in for the database as the subtitle.
+    pub fn server_status(
+        connection_id: &str,
+        connection_name: String,
+        connection_string: String,
+    ) -> Self {
+        Self {
+            id: SharedString::from(format!("status-{}", connection_id)),
+            collection_name: "Server Status".to_string(),
+            database_name: connection_name,
+            connection_string,
+            is_loading: false,
+        }
+    }
+
     pub fn title(&self) -> String {
         self.collection_name.clone()
     }
@@ -40,10 +114,15 @@ impl TabInfo {
     }
 
     pub fn to_tab_data(&self, is_active: bool) -> TabData {
-        TabData::new(self.id.clone(), self.title())
-            .subtitle(self.subtitle())
+        let data = TabData::new(self.id.clone(), self.title())
             .active(is_active)
-            .loading(self.is_loading)
+            .loading(self.is_loading);
+        // Consoles opened on a whole connection have no database to show
+        if self.database_name.is_empty() {
+            data
+        } else {
+            data.subtitle(self.subtitle())
+        }
     }
 }
 
@@ -60,6 +139,18 @@ pub struct Pane {
     active_tab_index: Option<usize>,
     /// Collection views keyed by tab ID
     collection_views: HashMap<TabId, Entity<CollectionView>>,
+    /// Query consoles keyed by tab ID
+    query_consoles: HashMap<TabId, Entity<QueryConsole>>,
+    /// Redis command consoles keyed by tab ID
+    redis_consoles: HashMap<TabId, Entity<RedisConsole>>,
+    /// Entity-relationship diagrams keyed by tab ID
+    diagrams: HashMap<TabId, Entity<DiagramView>>,
+    /// Database and collection properties keyed by tab ID
+    properties: HashMap<TabId, Entity<PropertiesView>>,
+    /// Server status dashboards keyed by tab ID
+    server_statuses: HashMap<TabId, Entity<ServerStatusView>>,
+    /// Number of consoles opened so far (for numbering tabs)
+    console_count: usize,
 }
 
 impl Pane {
@@ -69,6 +160,12 @@ impl Pane {
             tabs: Vec::new(),
             active_tab_index: None,
             collection_views: HashMap::new(),
+            query_consoles: HashMap::new(),
+            redis_consoles: HashMap::new(),
+            diagrams: HashMap::new(),
+            properties: HashMap::new(),
+            server_statuses: HashMap::new(),
+            console_count: 0,
         }
     }
 
@@ -81,9 +178,10 @@ impl Pane {
     /// Open a new tab or activate existing one for the given collection
     pub fn open_collection(
         &mut self,
+        connection_id: String,
         collection_name: String,
         database_name: String,
-        connection_string: String,
+        config: ConnectionConfig,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
@@ -100,18 +198,25 @@ impl Pane {
         let tab_info = TabInfo::new(
             collection_name.clone(),
             database_name.clone(),
-            connection_string.clone(),
+            config.connection_string.clone(),
         );
         let tab_id_clone = tab_info.id.clone();
 
         // Create collection view
-        let view =
-            cx.new(|cx| CollectionView::new(collection_name, database_name, connection_string, cx));
+        let view = cx.new(|cx| {
+            CollectionView::new(
+                connection_id.clone(),
+                collection_name,
+                database_name.clone(),
+                config.clone(),
+                cx,
+            )
+        });
 
         // Subscribe to view events to update loading state
         cx.subscribe_in(&view, window, {
             let tab_id = tab_id_clone.clone();
-            move |pane, _, event: &crate::ui::collection_view::LoadingStateChanged, _, cx| {
+            move |pane, _, event: &LoadingStateChanged, _, cx| {
                 if let Some(tab) = pane.tabs.iter_mut().find(|t| t.id == tab_id) {
                     tab.is_loading = event.0;
                     cx.notify();
@@ -120,6 +225,23 @@ impl Pane {
         })
         .detach();
 
+        // Redis keyspace listings open keys in their own tab
+        cx.subscribe_in(
+            &view,
+            window,
+            move |pane, _, event: &OpenKeyRequested, window, cx| {
+                pane.open_collection(
+                    connection_id.clone(),
+                    event.0.clone(),
+                    database_name.clone(),
+                    config.clone(),
+                    window,
+                    cx,
+                );
+            },
+        )
+        .detach();
+
         self.collection_views.insert(tab_id_clone, view);
         self.tabs.push(tab_info);
         self.active_tab_index = Some(self.tabs.len() - 1);
@@ -127,11 +249,151 @@ impl Pane {
         cx.notify();
     }
 
+    /// Open a new query console tab for the given database.
+    /// Redis connections get a command console with per-connection history.
+    pub fn open_query_console(
+        &mut self,
+        connection_id: String,
+        database_name: String,
+        config: ConnectionConfig,
+        storage: Arc<ConnectionStorage>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.console_count += 1;
+        let tab_info = TabInfo::console(
+            self.console_count,
+            database_name.clone(),
+            config.connection_string.clone(),
+        );
+        let tab_id = tab_info.id.clone();
+
+        if config.driver == DatabaseType::Redis {
+            let console =
+                cx.new(|cx| RedisConsole::new(connection_id, database_name, config, storage, cx));
+            self.subscribe_console_loading(&console, tab_id.clone(), cx);
+            console.update(cx, |console, cx| console.focus_input(window, cx));
+            self.redis_consoles.insert(tab_id, console);
+        } else {
+            let console = cx.new(|cx| QueryConsole::new(connection_id, database_name, config, cx));
+            self.subscribe_console_loading(&console, tab_id.clone(), cx);
+            console.update(cx, |console, cx| console.focus_editor(window, cx));
+            self.query_consoles.insert(tab_id, console);
+        }
+
+        self.tabs.push(tab_info);
+        self.active_tab_index = Some(self.tabs.len() - 1);
+
+        cx.notify();
+    }
+
+    /// Open the diagram tab of a database, or activate it if already open
+    pub fn open_diagram(
+        &mut self,
+        connection_id: String,
+        database_name: String,
+        config: ConnectionConfig,
+        cx: &mut Context<Self>,
+    ) {
+        let tab_info = TabInfo::diagram(database_name.clone(), config.connection_string.clone());
+        if let Some(index) = self.tabs.iter().position(|t| t.id == tab_info.id) {
+            self.active_tab_index = Some(index);
+            cx.notify();
+            return;
+        }
+
+        let diagram = cx.new(|cx| DiagramView::new(connection_id, config, database_name, cx));
+        self.diagrams.insert(tab_info.id.clone(), diagram);
+        self.tabs.push(tab_info);
+        self.active_tab_index = Some(self.tabs.len() - 1);
+
+        cx.notify();
+    }
+
+    /// Open the properties tab of a database or collection, or activate it if
+    /// already open
+    pub fn open_properties(
+        &mut self,
+        connection_id: String,
+        database_name: String,
+        collection_name: Option<String>,
+        config: ConnectionConfig,
+        cx: &mut Context<Self>,
+    ) {
+        let tab_info = TabInfo::properties(
+            database_name.clone(),
+            collection_name.clone(),
+            config.connection_string.clone(),
+        );
+        if let Some(index) = self.tabs.iter().position(|t| t.id == tab_info.id) {
+            self.active_tab_index = Some(index);
+            cx.notify();
+            return;
+        }
+
+        let properties = cx.new(|cx| {
+            PropertiesView::new(connection_id, config, database_name, collection_name, cx)
+        });
+        self.properties.insert(tab_info.id.clone(), properties);
+        self.tabs.push(tab_info);
+        self.active_tab_index = Some(self.tabs.len() - 1);
+
+        cx.notify();
+    }
+
+    /// Open the server status tab of a connection, or activate it if already open
+    pub fn open_server_status(
+        &mut self,
+        connection_id: String,
+        connection_name: String,
+        config: ConnectionConfig,
+        cx: &mut Context<Self>,
+    ) {
+        let tab_info = TabInfo::server_status(
+            &connection_id,
+            connection_name,
+            config.connection_string.clone(),
+        );
+        if let Some(index) = self.tabs.iter().position(|t| t.id == tab_info.id) {
+            self.active_tab_index = Some(index);
+            cx.notify();
+            return;
+        }
+
+        let status = cx.new(|cx| ServerStatusView::new(connection_id, config, cx));
+        self.server_statuses.insert(tab_info.id.clone(), status);
+        self.tabs.push(tab_info);
+        self.active_tab_index = Some(self.tabs.len() - 1);
+
+        cx.notify();
+    }
+
+    /// Show a spinner on a console's tab while it runs
+    fn subscribe_console_loading<V: EventEmitter<LoadingStateChanged>>(
+        &mut self,
+        console: &Entity<V>,
+        tab_id: TabId,
+        cx: &mut Context<Self>,
+    ) {
+        cx.subscribe(console, move |pane, _, event: &LoadingStateChanged, cx| {
+            if let Some(tab) = pane.tabs.iter_mut().find(|t| t.id == tab_id) {
+                tab.is_loading = event.0;
+                cx.notify();
+            }
+        })
+        .detach();
+    }
+
     /// Close a tab by ID
     pub fn close_tab(&mut self, tab_id: &TabId, cx: &mut Context<Self>) {
         if let Some(index) = self.tabs.iter().position(|t| &t.id == tab_id) {
             self.tabs.remove(index);
             self.collection_views.remove(tab_id);
+            self.query_consoles.remove(tab_id);
+            self.redis_consoles.remove(tab_id);
+            self.diagrams.remove(tab_id);
+            self.properties.remove(tab_id);
+            self.server_statuses.remove(tab_id);
 
             // Adjust active index
             if self.tabs.is_empty() {
@@ -228,10 +490,36 @@ impl Render for Pane {
         let tabs_data = self.get_tab_data();
 
         // Get active content view
-        let active_view = self
-            .active_tab()
-            .and_then(|tab| self.collection_views.get(&tab.id))
-            .cloned();
+        let active_view: Option<AnyView> = self.active_tab().and_then(|tab| {
+            self.collection_views
+                .get(&tab.id)
+                .map(|view| view.clone().into())
+                .or_else(|| {
+                    self.query_consoles
+                        .get(&tab.id)
+                        .map(|console| console.clone().into())
+                })
+                .or_else(|| {
+                    self.redis_consoles
+                        .get(&tab.id)
+                        .map(|console| console.clone().into())
+                })
+                .or_else(|| {
+                    self.diagrams
+                        .get(&tab.id)
+                        .map(|diagram| diagram.clone().into())
+                })
+                .or_else(|| {
+                    self.properties
+                        .get(&tab.id)
+                        .map(|properties| properties.clone().into())
+                })
+                .or_else(|| {
+                    self.server_statuses
+                        .get(&tab.id)
+                        .map(|status| status.clone().into())
+                })
+        });
 
         // Create entity handle for callbacks
         let entity = cx.entity().downgrade();
@@ -0,0 +1,156 @@
+use gpui::{prelude::*, rems, *};
+
+use crate::ui::text_input::TextInput;
+use crate::ui::title_bar::TitleBar;
+
+// Define actions for the password prompt
+actions!(password_prompt, [Submit]);
+
+/// Register password prompt key bindings
+pub fn register_password_prompt_bindings(cx: &mut App) {
+    cx.bind_keys([KeyBinding::new("enter", Submit, Some("PasswordPrompt"))]);
+}
+
+/// Called with the entered password
+type OnSubmit = Box<dyn FnOnce(String, &mut App)>;
+
+/// Small window asking for the password of a connection that doesn't save it
+pub struct PasswordPrompt {
+    title_bar: Entity<TitleBar>,
+    connection_name: String,
+    username: Option<String>,
+    password_input: Entity<TextInput>,
+    on_submit: Option<OnSubmit>,
+}
+
+impl PasswordPrompt {
+    pub fn new(
+        connection_name: String,
+        username: Option<String>,
+        on_submit: impl FnOnce(String, &mut App) + 'static,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let title_bar = cx.new(|_| TitleBar::modal("Password Required"));
+        let password_input = cx.new(|cx| TextInput::new(cx, "Password", "").password());
+
+        Self {
+            title_bar,
+            connection_name,
+            username,
+            password_input,
+            on_submit: Some(Box::new(on_submit)),
+        }
+    }
+
+    /// Open the prompt in its own window
+    pub fn open(
+        connection_name: String,
+        username: Option<String>,
+        on_submit: impl FnOnce(String, &mut App) + 'static,
+        cx: &mut App,
+    ) {
+        let bounds = Bounds::centered(None, size(px(420.0), px(220.0)), cx);
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_decorations: Some(WindowDecorations::Client),
+                titlebar: Some(TitlebarOptions {
+                    title: Some(SharedString::from("Password Required")),
+                    appears_transparent: true,
+                    ..Default::default()
+                }),
+                focus: true,
+                show: true,
+                is_resizable: false,
+                ..Default::default()
+            },
+            |window, cx| {
+                let prompt = cx.new(|cx| Self::new(connection_name, username, on_submit, cx));
+                prompt.update(cx, |prompt, cx| {
+                    window.focus(&prompt.password_input.focus_handle(cx));
+                });
+                prompt
+            },
+        )
+        .ok();
+    }
+
+    fn submit(&mut self, _: &Submit, window: &mut Window, cx: &mut Context<Self>) {
+        let password = self.password_input.read(cx).text();
+        if password.is_empty() {
+            return;
+        }
+        if let Some(on_submit) = self.on_submit.take() {
+            cx.defer(move |cx| on_submit(password, cx));
+        }
+        window.remove_window();
+    }
+}
+
+impl Render for PasswordPrompt {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let description = match &self.username {
+            Some(user) => format!(
+                "Enter the password for {} on {}",
+                user, self.connection_name
+            ),
+            None => format!("Enter the password for {}", self.connection_name),
+        };
+
+        div()
+            .id("password-prompt")
+            .key_context("PasswordPrompt")
+            .on_action(cx.listener(Self::submit))
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(rgb(0x1a1a1a))
+            .font_family("Fira Code")
+            .child(self.title_bar.clone())
+            .child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .flex_col()
+                    .gap(px(14.0))
+                    .p(px(24.0))
+                    .child(
+                        div()
+                            .text_size(rems(0.8125)) // 13px
+                            .text_color(rgb(0xe0e0e0))
+                            .child(description),
+                    )
+                    .child(self.password_input.clone())
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .justify_between()
+                            .child(
+                                div()
+                                    .text_size(rems(0.6875)) // 11px
+                                    .text_color(rgb(0x606060))
+                                    .child("Kept in memory until Chambers closes"),
+                            )
+                            .child(
+                                div()
+                                    .id("password-connect-btn")
+                                    .px(px(18.0))
+                                    .py(px(8.0))
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .text_size(rems(0.8125)) // 13px
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .text_color(rgb(0xffffff))
+                                    .bg(rgb(0x0078d4))
+                                    .hover(|s| s.bg(rgb(0x1a8cff)))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.submit(&Submit, window, cx);
+                                    }))
+                                    .child("Connect"),
+                            ),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,751 @@
+//! Stage-by-stage aggregation pipeline editor for a MongoDB collection. Each
+//! stage shows a sample of the documents it outputs, and the finished pipeline
+//! can be copied as shell, Python or Rust code.
+
+use gpui::{prelude::*, rems, *};
+use serde_json::Value;
+use std::time::Duration;
+
+use crate::db::aggregation::{self, CodeLanguage, Stage, StageKind, PREVIEW_LIMIT};
+use crate::db::driver::ConnectionConfig;
+use crate::db::extended_json::{self, ExtJsonFormat};
+use crate::db::registry::registry;
+use crate::db::runtime;
+use crate::ui::selectable_text::SelectableTextArea;
+use crate::ui::text_editor::{EditorChanged, TextEditor};
+use crate::ui::theme::AppColors;
+use crate::ui::widgets::render_option;
+
+/// Quiet time after an edit before the previews are run again
+const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(600);
+
+/// Documents output by one stage
+enum StagePreview {
+    /// The stage is switched off, so its input passes through unchanged
+    Disabled,
+    /// This stage or one before it does not parse
+    Blocked,
+    Loading,
+    Documents(Vec<String>),
+    Failed(String),
+}
+
+/// One stage with its editor and the last preview of its output
+struct StageCard {
+    stage: Stage,
+    editor: Entity<TextEditor>,
+    /// The parsed stage, or why its body is invalid
+    parsed: Result<Value, String>,
+    preview: StagePreview,
+}
+
+pub struct PipelineBuilder {
+    connection_id: String,
+    config: ConnectionConfig,
+    database_name: String,
+    collection_name: String,
+    stages: Vec<StageCard>,
+    /// Bumped on every change so previews of an older pipeline are dropped
+    generation: u64,
+    /// Language of the open export panel
+    code_language: Option<CodeLanguage>,
+    code_text_area: Option<Entity<SelectableTextArea>>,
+}
+
+impl PipelineBuilder {
+    pub fn new(
+        connection_id: String,
+        config: ConnectionConfig,
+        database_name: String,
+        collection_name: String,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut builder = Self {
+            connection_id,
+            config,
+            database_name,
+            collection_name,
+            stages: Vec::new(),
+            generation: 0,
+            code_language: None,
+            code_text_area: None,
+        };
+        builder.add_stage(StageKind::Match, cx);
+        builder
+    }
+
+    fn add_stage(&mut self, kind: StageKind, cx: &mut Context<Self>) {
+        let stage = Stage::new(kind);
+        let editor = cx.new(|cx| TextEditor::new(cx, "{ }", &stage.body));
+        cx.subscribe(&editor, |this, editor, _: &EditorChanged, cx| {
+            let text = editor.read(cx).text();
+            if let Some(card) = this.stages.iter_mut().find(|card| card.editor == editor) {
+                card.parsed = aggregation::parse_stage(card.stage.kind, &text);
+                card.stage.body = text;
+            }
+            this.pipeline_changed(cx);
+        })
+        .detach();
+
+        let parsed = aggregation::parse_stage(kind, &stage.body);
+        self.stages.push(StageCard {
+            stage,
+            editor,
+            parsed,
+            preview: StagePreview::Loading,
+        });
+        self.pipeline_changed(cx);
+    }
+
+    fn remove_stage(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index < self.stages.len() {
+            self.stages.remove(index);
+            self.pipeline_changed(cx);
+        }
+    }
+
+    /// Swap a stage with its neighbour above (`up`) or below
+    fn move_stage(&mut self, index: usize, up: bool, cx: &mut Context<Self>) {
+        let other = if up {
+            index.checked_sub(1)
+        } else {
+            Some(index + 1)
+        };
+        if let Some(other) = other.filter(|other| *other < self.stages.len()) {
+            self.stages.swap(index, other);
+            self.pipeline_changed(cx);
+        }
+    }
+
+    fn toggle_stage(&mut self, index: usize, cx: &mut Context<Self>) {
+        if let Some(card) = self.stages.get_mut(index) {
+            card.stage.enabled = !card.stage.enabled;
+            self.pipeline_changed(cx);
+        }
+    }
+
+    /// Enabled stages, or `None` while any of them does not parse
+    fn pipeline(&self) -> Option<Vec<Value>> {
+        self.stages
+            .iter()
+            .filter(|card| card.stage.enabled)
+            .map(|card| card.parsed.clone().ok())
+            .collect()
+    }
+
+    fn pipeline_changed(&mut self, cx: &mut Context<Self>) {
+        self.generation += 1;
+        self.update_code(cx);
+        self.schedule_previews(cx);
+        cx.notify();
+    }
+
+    /// Rerun the previews once the pipeline has not changed for a moment
+    fn schedule_previews(&mut self, cx: &mut Context<Self>) {
+        let generation = self.generation;
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(PREVIEW_DEBOUNCE).await;
+            this.update(cx, |builder, cx| {
+                if builder.generation == generation {
+                    builder.run_previews(cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Run the pipeline up to each enabled stage, sampling its output with `$limit`.
+    /// The stages run concurrently and each preview is shown as it arrives.
+    fn run_previews(&mut self, cx: &mut Context<Self>) {
+        let generation = self.generation;
+        let mut prefix = Vec::new();
+        let mut blocked = false;
+        let mut tasks = Vec::new();
+
+        for (index, card) in self.stages.iter_mut().enumerate() {
+            if !card.stage.enabled {
+                card.preview = StagePreview::Disabled;
+                continue;
+            }
+            match &card.parsed {
+                Ok(stage) if !blocked => prefix.push(stage.clone()),
+                _ => blocked = true,
+            }
+            if blocked {
+                card.preview = StagePreview::Blocked;
+                continue;
+            }
+            card.preview = StagePreview::Loading;
+
+            let pipeline = prefix.clone();
+            let config = self.config.clone();
+            let connection_id = self.connection_id.clone();
+            let db_name = self.database_name.clone();
+            let coll_name = self.collection_name.clone();
+            let task = runtime::spawn(async move {
+                let conn = registry().get(&connection_id, config).await?;
+                conn.aggregate(&db_name, &coll_name, &pipeline, PREVIEW_LIMIT)
+                    .await
+            });
+            tasks.push((index, task));
+        }
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            for (index, task) in tasks {
+                let result = task.await;
+                let updated = this.update(cx, |builder, cx| {
+                    if builder.generation != generation {
+                        return false;
+                    }
+                    if let Some(card) = builder.stages.get_mut(index) {
+                        card.preview = match result {
+                            Ok(Ok(documents)) => StagePreview::Documents(
+                                documents.iter().map(document_text).collect(),
+                            ),
+                            Ok(Err(e)) => StagePreview::Failed(e.to_string()),
+                            Err(_) => StagePreview::Failed("The preview was interrupted".into()),
+                        };
+                    }
+                    cx.notify();
+                    true
+                });
+                if !updated.unwrap_or(false) {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn set_code_language(&mut self, language: Option<CodeLanguage>, cx: &mut Context<Self>) {
+        self.code_language = language;
+        self.update_code(cx);
+        cx.notify();
+    }
+
+    /// Code for the pipeline in the export panel's language, or why there is none
+    fn code(&self) -> Option<Result<String, String>> {
+        let language = self.code_language?;
+        Some(match self.pipeline() {
+            Some(pipeline) => Ok(aggregation::generate_code(
+                language,
+                &self.database_name,
+                &self.collection_name,
+                &pipeline,
+            )),
+            None => Err("Fix the invalid stages to export the pipeline".to_string()),
+        })
+    }
+
+    fn update_code(&mut self, cx: &mut Context<Self>) {
+        let Some(code) = self.code() else {
+            self.code_text_area = None;
+            return;
+        };
+        let code = code.unwrap_or_else(|message| message);
+        match &self.code_text_area {
+            Some(text_area) => {
+                text_area.update(cx, |text_area, cx| text_area.set_content(code, cx));
+            }
+            None => {
+                self.code_text_area = Some(cx.new(|cx| SelectableTextArea::new(cx, code)));
+            }
+        }
+    }
+
+    fn copy_code(&mut self, cx: &mut Context<Self>) {
+        if let Some(Ok(code)) = self.code() {
+            cx.write_to_clipboard(ClipboardItem::new_string(code));
+        }
+    }
+
+    fn render_icon_button(
+        id: impl Into<ElementId>,
+        icon: &'static str,
+        enabled: bool,
+    ) -> Stateful<Div> {
+        div()
+            .id(id)
+            .p(rems(0.25)) // 4px
+            .rounded(px(3.0))
+            .when(enabled, |el| {
+                el.cursor_pointer().hover(|s| s.bg(AppColors::bg_hover()))
+            })
+            .child(
+                svg()
+                    .path(icon)
+                    .size(rems(0.75)) // 12px
+                    .text_color(if enabled {
+                        AppColors::text_muted()
+                    } else {
+                        AppColors::text_dim()
+                    }),
+            )
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let code_open = self.code_language.is_some();
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .h(rems(2.25)) // 36px
+            .px(rems(0.75)) // 12px
+            .bg(AppColors::bg_secondary())
+            .border_b_1()
+            .border_color(AppColors::border_subtle())
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.25)) // 4px
+                    .child(
+                        div()
+                            .mr(rems(0.25)) // 4px
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_muted())
+                            .child("Add stage"),
+                    )
+                    .children(StageKind::ALL.map(|kind| {
+                        render_option(kind.operator(), kind.operator(), false, true).on_click(
+                            cx.listener(move |this, _, _, cx| {
+                                this.add_stage(kind, cx);
+                            }),
+                        )
+                    })),
+            )
+            .child(
+                div()
+                    .id("export-pipeline")
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.375)) // 6px
+                    .cursor_pointer()
+                    .px(rems(0.5)) // 8px
+                    .py(rems(0.1875)) // 3px
+                    .rounded(px(3.0))
+                    .when(code_open, |el| el.bg(AppColors::bg_active()))
+                    .hover(|s| s.bg(AppColors::bg_hover()))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        let language = if code_open {
+                            None
+                        } else {
+                            Some(CodeLanguage::Shell)
+                        };
+                        this.set_code_language(language, cx);
+                    }))
+                    .child(
+                        svg()
+                            .path("icons/code.svg")
+                            .size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_muted()),
+                    )
+                    .child(
+                        div()
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_secondary())
+                            .child("Export Code"),
+                    ),
+            )
+    }
+
+    fn render_stage(
+        &self,
+        index: usize,
+        card: &StageCard,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let enabled = card.stage.enabled;
+        let is_first = index == 0;
+        let is_last = index + 1 == self.stages.len();
+        let error = card.parsed.as_ref().err().cloned();
+
+        div()
+            .flex()
+            .flex_row()
+            .flex_shrink_0()
+            .h(rems(15.0)) // 240px
+            .rounded(px(4.0))
+            .border_1()
+            .border_color(AppColors::border_subtle())
+            .bg(AppColors::bg_secondary())
+            .overflow_hidden()
+            // Stage body
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .w(rems(24.0)) // 384px
+                    .flex_shrink_0()
+                    .border_r_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap(rems(0.25)) // 4px
+                            .h(rems(2.0)) // 32px
+                            .px(rems(0.5)) // 8px
+                            .bg(AppColors::bg_header())
+                            .border_b_1()
+                            .border_color(AppColors::border_subtle())
+                            .child(
+                                div()
+                                    .text_size(rems(0.6875)) // 11px
+                                    .text_color(AppColors::text_dim())
+                                    .child(format!("{}", index + 1)),
+                            )
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .text_size(rems(0.75)) // 12px
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .text_color(if enabled {
+                                        AppColors::accent()
+                                    } else {
+                                        AppColors::text_dim()
+                                    })
+                                    .child(card.stage.kind.operator()),
+                            )
+                            .child(
+                                render_option(
+                                    ("toggle-stage", index),
+                                    if enabled { "On" } else { "Off" },
+                                    enabled,
+                                    true,
+                                )
+                                .on_click(cx.listener(
+                                    move |this, _, _, cx| {
+                                        this.toggle_stage(index, cx);
+                                    },
+                                )),
+                            )
+                            .child(
+                                Self::render_icon_button(
+                                    ("stage-up", index),
+                                    "icons/chevron-up.svg",
+                                    !is_first,
+                                )
+                                .on_click(cx.listener(
+                                    move |this, _, _, cx| {
+                                        this.move_stage(index, true, cx);
+                                    },
+                                )),
+                            )
+                            .child(
+                                Self::render_icon_button(
+                                    ("stage-down", index),
+                                    "icons/chevron-down.svg",
+                                    !is_last,
+                                )
+                                .on_click(cx.listener(
+                                    move |this, _, _, cx| {
+                                        this.move_stage(index, false, cx);
+                                    },
+                                )),
+                            )
+                            .child(
+                                Self::render_icon_button(
+                                    ("remove-stage", index),
+                                    "icons/trash.svg",
+                                    true,
+                                )
+                                .on_click(cx.listener(
+                                    move |this, _, _, cx| {
+                                        this.remove_stage(index, cx);
+                                    },
+                                )),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .id(("stage-editor", index))
+                            .flex_1()
+                            .min_h_0()
+                            .overflow_scroll()
+                            .bg(AppColors::bg_main())
+                            .font_family("Fira Code")
+                            .when(!enabled, |el| el.opacity(0.6))
+                            .child(card.editor.clone()),
+                    )
+                    .when_some(error, |el, error| {
+                        el.child(
+                            div()
+                                .px(rems(0.5)) // 8px
+                                .py(rems(0.25)) // 4px
+                                .border_t_1()
+                                .border_color(AppColors::border_subtle())
+                                .text_size(rems(0.6875)) // 11px
+                                .text_color(AppColors::error())
+                                .child(error),
+                        )
+                    }),
+            )
+            // Output sample
+            .child(self.render_preview(index, &card.preview))
+    }
+
+    fn render_preview(&self, index: usize, preview: &StagePreview) -> impl IntoElement {
+        let status = match preview {
+            StagePreview::Disabled => {
+                "Stage is off; its input passes through unchanged".to_string()
+            }
+            StagePreview::Blocked => "Fix the invalid stages to see the output".to_string(),
+            StagePreview::Loading => "Running...".to_string(),
+            StagePreview::Documents(documents) if documents.is_empty() => {
+                "No documents".to_string()
+            }
+            StagePreview::Documents(documents) if documents.len() as u32 >= PREVIEW_LIMIT => {
+                format!("Sample of the output (first {} documents)", documents.len())
+            }
+            StagePreview::Documents(documents) => match documents.len() {
+                1 => "1 document".to_string(),
+                n => format!("{} documents", n),
+            },
+            StagePreview::Failed(_) => "Stage failed".to_string(),
+        };
+        let (documents, error) = match preview {
+            StagePreview::Documents(documents) => (documents.clone(), None),
+            StagePreview::Failed(error) => (Vec::new(), Some(error.clone())),
+            _ => (Vec::new(), None),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .flex_1()
+            .min_w_0()
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .h(rems(2.0)) // 32px
+                    .px(rems(0.75)) // 12px
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .text_size(rems(0.6875)) // 11px
+                    .text_color(AppColors::text_muted())
+                    .child(status),
+            )
+            .child(
+                div()
+                    .id(("stage-output", index))
+                    .flex()
+                    .flex_row()
+                    .flex_1()
+                    .min_h_0()
+                    .gap(rems(0.5)) // 8px
+                    .p(rems(0.5)) // 8px
+                    .overflow_x_scroll()
+                    .when_some(error, |el, error| {
+                        el.child(
+                            div()
+                                .text_size(rems(0.6875)) // 11px
+                                .text_color(AppColors::error())
+                                .child(error),
+                        )
+                    })
+                    .children(documents.into_iter().map(|text| {
+                        div()
+                            .flex_shrink_0()
+                            .w(rems(15.0)) // 240px
+                            .h_full()
+                            .p(rems(0.5)) // 8px
+                            .rounded(px(3.0))
+                            .border_1()
+                            .border_color(AppColors::border_subtle())
+                            .bg(AppColors::bg_main())
+                            .overflow_hidden()
+                            .font_family("Fira Code")
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_secondary())
+                            .children(
+                                text.lines()
+                                    .map(|line| div().whitespace_nowrap().child(line.to_string())),
+                            )
+                    })),
+            )
+    }
+
+    fn render_code_panel(
+        &self,
+        language: CodeLanguage,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let can_copy = self.pipeline().is_some();
+
+        div()
+            .flex()
+            .flex_col()
+            .w(rems(30.0)) // 480px
+            .h_full()
+            .bg(AppColors::bg_secondary())
+            .border_l_1()
+            .border_color(AppColors::border_subtle())
+            // Title bar
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .h(rems(2.0)) // 32px
+                    .px(rems(0.75)) // 12px
+                    .bg(AppColors::bg_header())
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(AppColors::text())
+                            .child("Export Pipeline"),
+                    )
+                    .child(
+                        Self::render_icon_button("close-pipeline-code", "icons/close.svg", true)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.set_code_language(None, cx);
+                            })),
+                    ),
+            )
+            // Language
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.25)) // 4px
+                    .h(rems(2.0)) // 32px
+                    .px(rems(0.75)) // 12px
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .children(CodeLanguage::ALL.map(|option| {
+                        render_option(option.label(), option.label(), option == language, true)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.set_code_language(Some(option), cx);
+                            }))
+                    })),
+            )
+            // Code
+            .child(
+                div()
+                    .id("pipeline-code-scroll")
+                    .flex_1()
+                    .min_h_0()
+                    .p(rems(0.75)) // 12px
+                    .overflow_y_scroll()
+                    .overflow_x_scroll()
+                    .font_family("Fira Code")
+                    .when_some(self.code_text_area.clone(), |el, text_area| {
+                        el.child(text_area)
+                    }),
+            )
+            // Actions
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_end()
+                    .h(rems(2.5)) // 40px
+                    .px(rems(0.75)) // 12px
+                    .border_t_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .id("copy-pipeline-code")
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap(rems(0.375)) // 6px
+                            .px(rems(0.75)) // 12px
+                            .py(rems(0.25)) // 4px
+                            .rounded(px(4.0))
+                            .text_size(rems(0.75)) // 12px
+                            .when(can_copy, |el| {
+                                el.cursor_pointer()
+                                    .bg(AppColors::accent())
+                                    .hover(|s| s.bg(AppColors::accent_hover()))
+                                    .text_color(AppColors::text())
+                            })
+                            .when(!can_copy, |el| {
+                                el.bg(AppColors::bg_active())
+                                    .text_color(AppColors::text_dim())
+                            })
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.copy_code(cx);
+                            }))
+                            .child(
+                                svg()
+                                    .path("icons/copy.svg")
+                                    .size(rems(0.75)) // 12px
+                                    .text_color(AppColors::text()),
+                            )
+                            .child("Copy"),
+                    ),
+            )
+    }
+}
+
+impl Render for PipelineBuilder {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let stages: Vec<AnyElement> = self
+            .stages
+            .iter()
+            .enumerate()
+            .map(|(index, card)| self.render_stage(index, card, cx).into_any_element())
+            .collect();
+        let is_empty = stages.is_empty();
+
+        div()
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(AppColors::bg_main())
+            .child(self.render_toolbar(cx))
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .flex_1()
+                    .min_h_0()
+                    .child(
+                        div()
+                            .id("pipeline-stages")
+                            .flex()
+                            .flex_col()
+                            .flex_1()
+                            .min_w_0()
+                            .gap(rems(0.75)) // 12px
+                            .p(rems(0.75)) // 12px
+                            .overflow_y_scroll()
+                            .when(is_empty, |el| {
+                                el.items_center().justify_center().child(
+                                    div()
+                                        .text_size(rems(0.8125)) // 13px
+                                        .text_color(AppColors::text_muted())
+                                        .child("Add a stage to start building the pipeline"),
+                                )
+                            })
+                            .children(stages),
+                    )
+                    .when_some(self.code_language, |el, language| {
+                        el.child(self.render_code_panel(language, cx))
+                    }),
+            )
+    }
+}
+
+/// Relaxed Extended JSON of one output document, pretty-printed
+fn document_text(document: &Value) -> String {
+    extended_json::to_text(document, ExtJsonFormat::Relaxed)
+        .or_else(|_| serde_json::to_string_pretty(document))
+        .unwrap_or_default()
+}
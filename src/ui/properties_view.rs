@@ -0,0 +1,357 @@
+//! Properties of a database or of one of its collections: counts, sizes on disk,
+//! index sizes and options such as capped limits or time-series settings
+
+use gpui::{prelude::*, rems, *};
+
+use crate::db::driver::{ConnectionConfig, StorageStats};
+use crate::db::registry::registry;
+use crate::db::runtime;
+use crate::ui::connection_browser::format_bytes;
+use crate::ui::theme::AppColors;
+use crate::ui::widgets::render_titled_section;
+
+enum PropertiesState {
+    Loading,
+    Loaded(StorageStats),
+    Failed(String),
+}
+
+pub struct PropertiesView {
+    connection_id: String,
+    config: ConnectionConfig,
+    database_name: String,
+    /// `None` for the properties of the whole database
+    collection_name: Option<String>,
+    state: PropertiesState,
+}
+
+impl PropertiesView {
+    pub fn new(
+        connection_id: String,
+        config: ConnectionConfig,
+        database_name: String,
+        collection_name: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut view = Self {
+            connection_id,
+            config,
+            database_name,
+            collection_name,
+            state: PropertiesState::Loading,
+        };
+        view.load(cx);
+        view
+    }
+
+    fn load(&mut self, cx: &mut Context<Self>) {
+        self.state = PropertiesState::Loading;
+        cx.notify();
+
+        let connection_id = self.connection_id.clone();
+        let config = self.config.clone();
+        let database_name = self.database_name.clone();
+        let collection_name = self.collection_name.clone();
+        let task = runtime::spawn(async move {
+            let conn = registry().get(&connection_id, config).await?;
+            match collection_name {
+                Some(collection_name) => {
+                    conn.collection_stats(&database_name, &collection_name)
+                        .await
+                }
+                None => conn.database_stats(&database_name).await,
+            }
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |view, cx| {
+                view.state = match result {
+                    Ok(Ok(stats)) => PropertiesState::Loaded(stats),
+                    Ok(Err(e)) => PropertiesState::Failed(e.to_string()),
+                    Err(_) => {
+                        PropertiesState::Failed("Loading the statistics was interrupted".into())
+                    }
+                };
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Names of the objects the statistics count, e.g. `("Documents", "Collections")`
+    fn nouns(&self) -> (&'static str, &'static str) {
+        if self.config.driver.is_sql() {
+            ("Rows", "Tables")
+        } else {
+            ("Documents", "Collections")
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let (kind, name) = match &self.collection_name {
+            Some(collection_name) if self.config.driver.is_sql() => ("Table", collection_name),
+            Some(collection_name) => ("Collection", collection_name),
+            None => ("Database", &self.database_name),
+        };
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .w_full()
+            .h(rems(2.25)) // 36px
+            .px(rems(0.75)) // 12px
+            .bg(AppColors::bg_header())
+            .border_b_1()
+            .border_color(AppColors::border())
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.375)) // 6px
+                    .text_size(rems(0.75)) // 12px
+                    .child(div().text_color(AppColors::text_muted()).child(kind))
+                    .child(
+                        div()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(AppColors::text())
+                            .child(name.clone()),
+                    ),
+            )
+            .child(
+                div()
+                    .id("refresh-properties")
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.25)) // 4px
+                    .px(rems(0.625)) // 10px
+                    .py(rems(0.3125)) // 5px
+                    .rounded(px(4.0))
+                    .cursor_pointer()
+                    .hover(|s| s.bg(AppColors::bg_hover()))
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.load(cx);
+                    }))
+                    .child(
+                        svg()
+                            .path("icons/refresh.svg")
+                            .size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_dim()),
+                    )
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_secondary())
+                            .child("Refresh"),
+                    ),
+            )
+    }
+
+    /// One figure of the overview, e.g. "Data size  12.5 MB"
+    fn render_stat(label: &'static str, value: String) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap(rems(0.125)) // 2px
+            .w(rems(10.0)) // 160px
+            .px(rems(0.75)) // 12px
+            .py(rems(0.5)) // 8px
+            .rounded(px(4.0))
+            .bg(AppColors::bg_secondary())
+            .border_1()
+            .border_color(AppColors::border_subtle())
+            .child(
+                div()
+                    .text_size(rems(0.6875)) // 11px
+                    .text_color(AppColors::text_muted())
+                    .child(label),
+            )
+            .child(
+                div()
+                    .text_size(rems(1.0)) // 16px
+                    .text_color(AppColors::text())
+                    .child(value),
+            )
+    }
+
+    fn render_overview(&self, stats: &StorageStats) -> impl IntoElement {
+        let (rows, collections) = self.nouns();
+        let count = |n: u64| n.to_string();
+        let stats_list: Vec<(&'static str, Option<String>)> = vec![
+            (rows, stats.document_count.map(count)),
+            (collections, stats.collection_count.map(count)),
+            ("Data size", stats.data_size.map(format_bytes)),
+            ("Storage size", stats.storage_size.map(format_bytes)),
+            ("Free space", stats.free_size.map(format_bytes)),
+            ("Avg. object size", stats.avg_object_size.map(format_bytes)),
+            ("Indexes", stats.index_count.map(count)),
+            ("Index size", stats.index_size.map(format_bytes)),
+            ("Total size", stats.total_size.map(format_bytes)),
+            ("Max size", stats.max_size.map(format_bytes)),
+            ("Max documents", stats.max_documents.map(count)),
+        ];
+
+        div()
+            .flex()
+            .flex_row()
+            .flex_wrap()
+            .gap(rems(0.5)) // 8px
+            .px(rems(0.75)) // 12px
+            .children(
+                stats_list
+                    .into_iter()
+                    .filter_map(|(label, value)| Some(Self::render_stat(label, value?))),
+            )
+    }
+
+    /// Index sizes as bars relative to the largest index
+    fn render_index_sizes(stats: &StorageStats) -> impl IntoElement {
+        let largest = stats
+            .index_sizes
+            .iter()
+            .map(|(_, size)| *size)
+            .max()
+            .unwrap_or_default()
+            .max(1);
+
+        div()
+            .flex()
+            .flex_col()
+            .children(stats.index_sizes.iter().map(|(name, size)| {
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.75)) // 12px
+                    .px(rems(0.75)) // 12px
+                    .py(rems(0.25)) // 4px
+                    .text_size(rems(0.75)) // 12px
+                    .hover(|s| s.bg(AppColors::bg_hover()))
+                    .child(
+                        div()
+                            .w(rems(16.0)) // 256px
+                            .flex_none()
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .whitespace_nowrap()
+                            .text_color(AppColors::text())
+                            .child(name.clone()),
+                    )
+                    .child(
+                        div()
+                            .w(rems(5.0)) // 80px
+                            .flex_none()
+                            .text_color(AppColors::text_secondary())
+                            .child(format_bytes(*size)),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .h(rems(0.375)) // 6px
+                            .rounded(px(3.0))
+                            .bg(AppColors::bg_active())
+                            .child(
+                                div()
+                                    .h_full()
+                                    .rounded(px(3.0))
+                                    .bg(AppColors::accent())
+                                    .w(relative(*size as f32 / largest as f32)),
+                            ),
+                    )
+            }))
+    }
+
+    fn render_properties(stats: &StorageStats) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .children(stats.properties.iter().map(|(name, value)| {
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap(rems(0.75)) // 12px
+                    .px(rems(0.75)) // 12px
+                    .py(rems(0.25)) // 4px
+                    .text_size(rems(0.75)) // 12px
+                    .hover(|s| s.bg(AppColors::bg_hover()))
+                    .child(
+                        div()
+                            .w(rems(10.0)) // 160px
+                            .flex_none()
+                            .text_color(AppColors::text_muted())
+                            .child(name.clone()),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .text_color(AppColors::text())
+                            .child(value.clone()),
+                    )
+            }))
+    }
+}
+
+impl Render for PropertiesView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let message = |text: String, color: Hsla| {
+            div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .p(rems(1.0)) // 16px
+                .child(
+                    div()
+                        .text_size(rems(0.8125)) // 13px
+                        .text_color(color)
+                        .child(text),
+                )
+                .into_any_element()
+        };
+        let body = match &self.state {
+            PropertiesState::Loading => {
+                message("Loading statistics...".to_string(), AppColors::text_muted())
+            }
+            PropertiesState::Failed(error) => message(error.clone(), AppColors::error()),
+            PropertiesState::Loaded(stats) => div()
+                .id("properties-scroll")
+                .size_full()
+                .overflow_y_scroll()
+                .child(render_titled_section(
+                    "Overview",
+                    None,
+                    self.render_overview(stats),
+                ))
+                .when(!stats.index_sizes.is_empty(), |el| {
+                    el.child(render_titled_section(
+                        "Index sizes",
+                        None,
+                        Self::render_index_sizes(stats),
+                    ))
+                })
+                .when(!stats.properties.is_empty(), |el| {
+                    el.child(render_titled_section(
+                        "Properties",
+                        None,
+                        Self::render_properties(stats),
+                    ))
+                })
+                .into_any_element(),
+        };
+
+        div()
+            .id("properties-view")
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(AppColors::bg_main())
+            .child(self.render_toolbar(cx))
+            .child(div().flex_1().min_h_0().w_full().child(body))
+    }
+}
@@ -0,0 +1,602 @@
+use gpui::{prelude::*, rems, *};
+use serde_json::Value;
+
+use crate::db::driver::{ConnectionConfig, QueryResult};
+use crate::db::registry::registry;
+use crate::db::{runtime, sql_edit, DatabaseType};
+use crate::ui::collection_view::{value_to_display_string, LoadingStateChanged};
+use crate::ui::explain_panel::{ExplainPanel, ExplainPanelClosed, ExplainRequest, ExplainTarget};
+use crate::ui::export_panel::{ExportAll, ExportPanel, ExportPanelClosed, ExportRequest};
+use crate::ui::table_view::{Column, PageChangeRequested, Row, TableView, ViewMode, PAGE_SIZE};
+use crate::ui::text_editor::TextEditor;
+use crate::ui::theme::AppColors;
+
+// Define actions for the query console
+actions!(query_console, [RunQuery]);
+
+/// Register query console key bindings
+pub fn register_query_console_bindings(cx: &mut App) {
+    cx.bind_keys([KeyBinding::new(
+        "ctrl-enter",
+        RunQuery,
+        Some("QueryConsole"),
+    )]);
+}
+
+impl EventEmitter<LoadingStateChanged> for QueryConsole {}
+
+/// Execution state of the console
+#[derive(Clone, PartialEq)]
+enum ConsoleState {
+    Idle,
+    Running,
+    Done,
+    Error(String),
+}
+
+/// Query console - a multi-line editor whose text is sent to the driver's
+/// `execute_query`, with the last result set shown in a `TableView`
+pub struct QueryConsole {
+    connection_id: String,
+    database_name: String,
+    config: ConnectionConfig,
+    db_type: DatabaseType,
+    editor: Entity<TextEditor>,
+    table_view: Entity<TableView>,
+    state: ConsoleState,
+    /// Result of the last successful run
+    result: Option<QueryResult>,
+    current_page: usize,
+    view_mode: ViewMode,
+    /// Export options and progress for the result rows
+    export_panel: Option<Entity<ExportPanel>>,
+    /// Query plan of the selection or script
+    explain_panel: Option<Entity<ExplainPanel>>,
+}
+
+impl QueryConsole {
+    pub fn new(
+        connection_id: String,
+        database_name: String,
+        config: ConnectionConfig,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let db_type = config.driver;
+        let placeholder = match db_type {
+            DatabaseType::MongoDB => "db.collection.find({}).limit(50)",
+            _ => "Write a query and press Ctrl+Enter",
+        };
+        let editor = cx.new(|cx| TextEditor::new(cx, placeholder, ""));
+
+        let table_view = cx.new(|_| {
+            let mut table = TableView::new();
+            table.hide_toolbar();
+            table
+        });
+
+        cx.subscribe(&table_view, |this, _, event: &PageChangeRequested, cx| {
+            this.current_page = event.page;
+            this.populate_table(cx);
+        })
+        .detach();
+
+        Self {
+            connection_id,
+            database_name,
+            config,
+            db_type,
+            editor,
+            table_view,
+            state: ConsoleState::Idle,
+            result: None,
+            current_page: 0,
+            view_mode: ViewMode::Table,
+            export_panel: None,
+            explain_panel: None,
+        }
+    }
+
+    pub fn focus_editor(&self, window: &mut Window, cx: &mut App) {
+        window.focus(&self.editor.focus_handle(cx));
+    }
+
+    /// Selected text, or the whole editor if nothing is selected
+    fn query_text(&self, cx: &App) -> String {
+        let editor = self.editor.read(cx);
+        editor.selected_text().unwrap_or_else(|| editor.text())
+    }
+
+    /// Run the selected text, or the whole editor if nothing is selected
+    fn run_query(&mut self, cx: &mut Context<Self>) {
+        if self.state == ConsoleState::Running {
+            return;
+        }
+
+        let query = self.query_text(cx);
+        if query.trim().is_empty() {
+            return;
+        }
+
+        self.state = ConsoleState::Running;
+        cx.emit(LoadingStateChanged(true));
+        cx.notify();
+
+        let config = self.config.clone();
+        let db_name = self.database_name.clone();
+
+        let connection_id = self.connection_id.clone();
+
+        let task = runtime::spawn(async move {
+            registry()
+                .get(&connection_id, config)
+                .await?
+                .execute_query(&db_name, &query)
+                .await
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |console, cx| {
+                match result {
+                    Ok(Ok(result)) => {
+                        console.result = Some(result);
+                        console.current_page = 0;
+                        console.state = ConsoleState::Done;
+                        console
+                            .table_view
+                            .update(cx, |table, _| table.clear_row_selection());
+                        console.populate_table(cx);
+                    }
+                    Ok(Err(e)) => {
+                        console.state = ConsoleState::Error(e.to_string());
+                    }
+                    Err(_) => {
+                        console.state = ConsoleState::Error("Query was interrupted".to_string());
+                    }
+                }
+                cx.emit(LoadingStateChanged(false));
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Push the current page of results into the table view
+    fn populate_table(&self, cx: &mut Context<Self>) {
+        let Some(result) = &self.result else {
+            return;
+        };
+
+        let columns: Vec<Column> = result
+            .columns
+            .iter()
+            .map(|name| Column::new(name.clone()))
+            .collect();
+
+        let rows: Vec<Row> = match &result.documents {
+            // Documents may lack some fields; those cells stay empty
+            Some(documents) => documents
+                .iter()
+                .skip(self.current_page * PAGE_SIZE)
+                .take(PAGE_SIZE)
+                .map(|doc| {
+                    Row::new(
+                        result
+                            .columns
+                            .iter()
+                            .map(|name| {
+                                doc.get(name)
+                                    .map(value_to_display_string)
+                                    .unwrap_or_default()
+                            })
+                            .collect(),
+                    )
+                })
+                .collect(),
+            None => result
+                .rows
+                .iter()
+                .skip(self.current_page * PAGE_SIZE)
+                .take(PAGE_SIZE)
+                .map(|row| Row::new(row.iter().map(value_to_display_string).collect()))
+                .collect(),
+        };
+
+        let total = result.row_count();
+        let page = self.current_page;
+        let json = (self.view_mode == ViewMode::Json).then(|| self.result_json());
+
+        self.table_view.update(cx, |table, cx| {
+            table.set_columns(columns, cx);
+            table.set_rows(rows, cx);
+            table.set_total_items(total, cx);
+            table.set_page(page, cx);
+            if let Some(json) = json {
+                table.set_raw_json(json, cx);
+            }
+        });
+    }
+
+    /// Pretty-printed JSON array of the current page, one object per row
+    fn result_json(&self) -> String {
+        let objects = self.result_objects(self.current_page * PAGE_SIZE, PAGE_SIZE);
+        serde_json::to_string_pretty(&objects).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// Result rows from `skip` on as objects: documents as returned, SQL rows
+    /// keyed by column name
+    fn result_objects(&self, skip: usize, take: usize) -> Vec<Value> {
+        let Some(result) = &self.result else {
+            return Vec::new();
+        };
+
+        if let Some(documents) = &result.documents {
+            return documents.iter().skip(skip).take(take).cloned().collect();
+        }
+
+        result
+            .rows
+            .iter()
+            .skip(skip)
+            .take(take)
+            .map(|row| {
+                Value::Object(
+                    result
+                        .columns
+                        .iter()
+                        .cloned()
+                        .zip(row.iter().cloned())
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Open the export panel on the current page, the selected rows, or every
+    /// result row
+    fn open_export_panel(&mut self, cx: &mut Context<Self>) {
+        let Some(total) = self.result.as_ref().map(QueryResult::row_count) else {
+            return;
+        };
+        let page = self.result_objects(self.current_page * PAGE_SIZE, PAGE_SIZE);
+        let selected = self
+            .table_view
+            .read(cx)
+            .selected_rows()
+            .into_iter()
+            .filter_map(|ix| page.get(ix).cloned())
+            .collect();
+        let request = ExportRequest {
+            page,
+            selected,
+            all: ExportAll::Rows(self.result_objects(0, total)),
+            total,
+            driver: self.db_type,
+            table: sql_edit::quote_identifier(self.db_type, "query_results"),
+            file_stem: "query_results".to_string(),
+        };
+
+        let panel = cx.new(|cx| ExportPanel::new(request, cx));
+        cx.subscribe(&panel, |this, _, _: &ExportPanelClosed, cx| {
+            this.export_panel = None;
+            cx.notify();
+        })
+        .detach();
+        self.explain_panel = None;
+        self.export_panel = Some(panel);
+        cx.notify();
+    }
+
+    /// Explain the selected statement, or the whole editor if nothing is selected
+    fn open_explain_panel(&mut self, cx: &mut Context<Self>) {
+        let query = self.query_text(cx);
+        if query.trim().is_empty() {
+            return;
+        }
+        let request = ExplainRequest {
+            connection_id: self.connection_id.clone(),
+            config: self.config.clone(),
+            database: self.database_name.clone(),
+            target: ExplainTarget::Query(query),
+        };
+
+        let panel = cx.new(|cx| ExplainPanel::new(request, cx));
+        cx.subscribe(&panel, |this, _, _: &ExplainPanelClosed, cx| {
+            this.explain_panel = None;
+            cx.notify();
+        })
+        .detach();
+        // An export still running stays open, since closing it would cancel it
+        if !self
+            .export_panel
+            .as_ref()
+            .is_some_and(|panel| panel.read(cx).is_running())
+        {
+            self.export_panel = None;
+        }
+        self.explain_panel = Some(panel);
+        cx.notify();
+    }
+
+    fn set_view_mode(&mut self, mode: ViewMode, cx: &mut Context<Self>) {
+        self.view_mode = mode;
+        self.table_view.update(cx, |table, cx| {
+            table.set_view_mode(mode, cx);
+        });
+        self.populate_table(cx);
+        cx.notify();
+    }
+
+    /// One-line summary of the last run, e.g. "42 rows · 18 ms"
+    fn status_text(&self) -> Option<String> {
+        let result = self.result.as_ref()?;
+        let elapsed = format_elapsed(result.elapsed);
+        Some(match result.affected_rows {
+            Some(1) => format!("1 row affected · {}", elapsed),
+            Some(n) => format!("{} rows affected · {}", n, elapsed),
+            None if result.row_count() == 1 => format!("1 row · {}", elapsed),
+            None => format!("{} rows · {}", result.row_count(), elapsed),
+        })
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_running = self.state == ConsoleState::Running;
+        let status = if is_running {
+            Some("Running...".to_string())
+        } else {
+            self.status_text()
+        };
+        let has_rows = self
+            .result
+            .as_ref()
+            .is_some_and(|r| r.affected_rows.is_none());
+        let view_mode = self.view_mode;
+
+        div()
+            .id("query-console-toolbar")
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .w_full()
+            .h(rems(2.25)) // 36px
+            .px(rems(0.75)) // 12px
+            .bg(AppColors::bg_header())
+            .border_b_1()
+            .border_color(AppColors::border())
+            // Left side: Run button and shortcut hint
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.625)) // 10px
+                    .child(
+                        div()
+                            .id("run-query")
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap(rems(0.375)) // 6px
+                            .px(rems(0.625)) // 10px
+                            .py(rems(0.25)) // 4px
+                            .rounded(px(4.0))
+                            .bg(if is_running {
+                                AppColors::bg_active()
+                            } else {
+                                AppColors::accent()
+                            })
+                            .when(!is_running, |el| {
+                                el.cursor_pointer()
+                                    .hover(|s| s.bg(AppColors::accent_hover()))
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.run_query(cx);
+                                    }))
+                            })
+                            .child(
+                                svg()
+                                    .path("icons/terminal.svg")
+                                    .size(rems(0.75)) // 12px
+                                    .text_color(AppColors::text()),
+                            )
+                            .child(
+                                div()
+                                    .text_size(rems(0.75)) // 12px
+                                    .text_color(AppColors::text())
+                                    .child("Run"),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .id("explain-query")
+                            .px(rems(0.625)) // 10px
+                            .py(rems(0.25)) // 4px
+                            .rounded(px(4.0))
+                            .cursor_pointer()
+                            .text_size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_secondary())
+                            .hover(|s| s.bg(AppColors::bg_hover()).text_color(AppColors::text()))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.open_explain_panel(cx);
+                            }))
+                            .child("Explain"),
+                    )
+                    .child(
+                        div()
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_dim())
+                            .child("Ctrl+Enter runs the selection or the whole script"),
+                    ),
+            )
+            // Right side: status and view toggle
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.75)) // 12px
+                    .when_some(status, |el, status| {
+                        el.child(
+                            div()
+                                .text_size(rems(0.75)) // 12px
+                                .text_color(AppColors::text_muted())
+                                .child(status),
+                        )
+                    })
+                    .when(has_rows, |el| {
+                        el.child(
+                            div()
+                                .id("export-results")
+                                .flex()
+                                .flex_row()
+                                .items_center()
+                                .gap(rems(0.25)) // 4px
+                                .px(rems(0.5)) // 8px
+                                .py(rems(0.125)) // 2px
+                                .rounded(px(4.0))
+                                .cursor_pointer()
+                                .hover(|s| s.bg(AppColors::bg_hover()))
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.open_export_panel(cx);
+                                }))
+                                .child(
+                                    svg()
+                                        .path("icons/document.svg")
+                                        .size(rems(0.75)) // 12px
+                                        .text_color(AppColors::text_dim()),
+                                )
+                                .child(
+                                    div()
+                                        .text_size(rems(0.75)) // 12px
+                                        .text_color(AppColors::text_secondary())
+                                        .child("Export"),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .id("query-view-toggle")
+                                .flex()
+                                .flex_row()
+                                .rounded(px(4.0))
+                                .border_1()
+                                .border_color(AppColors::border())
+                                .overflow_hidden()
+                                .children(
+                                    [(ViewMode::Table, "Table"), (ViewMode::Json, "JSON")].map(
+                                        |(mode, label)| {
+                                            div()
+                                                .id(label)
+                                                .px(rems(0.5)) // 8px
+                                                .py(rems(0.125)) // 2px
+                                                .text_size(rems(0.75)) // 12px
+                                                .cursor_pointer()
+                                                .when(mode == view_mode, |el| {
+                                                    el.bg(AppColors::bg_hover())
+                                                        .text_color(AppColors::text())
+                                                })
+                                                .when(mode != view_mode, |el| {
+                                                    el.text_color(AppColors::text_muted())
+                                                        .hover(|s| s.bg(AppColors::bg_active()))
+                                                })
+                                                .on_click(cx.listener(move |this, _, _, cx| {
+                                                    this.set_view_mode(mode, cx);
+                                                }))
+                                                .child(label)
+                                        },
+                                    ),
+                                ),
+                        )
+                    }),
+            )
+    }
+
+    fn render_results(&self) -> AnyElement {
+        let message = |text: String, color: Hsla| {
+            div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .p(rems(1.0)) // 16px
+                .child(
+                    div()
+                        .text_size(rems(0.8125)) // 13px
+                        .text_color(color)
+                        .child(text),
+                )
+                .into_any_element()
+        };
+
+        match (&self.state, &self.result) {
+            (ConsoleState::Error(err), _) => message(err.clone(), AppColors::error()),
+            (_, Some(result)) if result.affected_rows.is_some() => message(
+                self.status_text().unwrap_or_default(),
+                AppColors::text_muted(),
+            ),
+            (_, Some(_)) => self.table_view.clone().into_any_element(),
+            (_, None) => message(
+                "Run a query to see results".to_string(),
+                AppColors::text_dim(),
+            ),
+        }
+    }
+}
+
+fn format_elapsed(elapsed: std::time::Duration) -> String {
+    let ms = elapsed.as_millis();
+    if ms < 1000 {
+        format!("{} ms", ms)
+    } else {
+        format!("{:.2} s", elapsed.as_secs_f64())
+    }
+}
+
+impl Render for QueryConsole {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .id("query-console")
+            .key_context("QueryConsole")
+            .on_action(cx.listener(|this, _: &RunQuery, _, cx| {
+                this.run_query(cx);
+            }))
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(AppColors::bg_main())
+            .child(self.render_toolbar(cx))
+            // Editor
+            .child(
+                div()
+                    .id("query-editor")
+                    .w_full()
+                    .h(relative(0.35))
+                    .flex_none()
+                    .overflow_scroll()
+                    .bg(AppColors::bg_secondary())
+                    .border_b_1()
+                    .border_color(AppColors::border())
+                    .child(self.editor.clone()),
+            )
+            // Results, with the export or explain panel beside them
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .flex_1()
+                    .min_h_0()
+                    .w_full()
+                    .child(
+                        div()
+                            .id("query-results")
+                            .flex_1()
+                            .min_w_0()
+                            .h_full()
+                            .overflow_hidden()
+                            .child(self.render_results()),
+                    )
+                    .when_some(self.export_panel.clone(), |el, panel| el.child(panel))
+                    .when_some(self.explain_panel.clone(), |el, panel| el.child(panel)),
+            )
+    }
+}
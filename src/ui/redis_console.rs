@@ -0,0 +1,641 @@
+use gpui::{prelude::*, rems, *};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::db::driver::{ConnectionConfig, RespValue};
+use crate::db::registry::registry;
+use crate::db::{runtime, ConnectionStorage};
+use crate::ui::collection_view::LoadingStateChanged;
+use crate::ui::text_input::TextInput;
+use crate::ui::theme::AppColors;
+
+// Define actions for the Redis console
+actions!(
+    redis_console,
+    [RunCommand, HistoryPrevious, HistoryNext, ClearConsole]
+);
+
+/// Register Redis console key bindings
+pub fn register_redis_console_bindings(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("enter", RunCommand, Some("RedisConsole")),
+        KeyBinding::new("up", HistoryPrevious, Some("RedisConsole")),
+        KeyBinding::new("down", HistoryNext, Some("RedisConsole")),
+        KeyBinding::new("ctrl-l", ClearConsole, Some("RedisConsole")),
+    ]);
+}
+
+impl EventEmitter<LoadingStateChanged> for RedisConsole {}
+
+/// A command that was sent and the reply it got
+struct ConsoleEntry {
+    command: String,
+    database_name: String,
+    /// Server reply, or the connection error that prevented one
+    reply: Result<RespValue, String>,
+    elapsed: Duration,
+}
+
+/// Redis command console - a redis-cli style prompt whose replies are shown as
+/// collapsible RESP trees. Command history is kept per connection.
+pub struct RedisConsole {
+    connection_id: String,
+    /// Logical database (`dbN`) commands run against; empty = connection default
+    database_name: String,
+    config: ConnectionConfig,
+    storage: Arc<ConnectionStorage>,
+    input: Entity<TextInput>,
+    entries: Vec<ConsoleEntry>,
+    /// Previously run commands, oldest first
+    history: Vec<String>,
+    /// Position while browsing history with up/down
+    history_index: Option<usize>,
+    /// Input text from before history browsing started
+    draft: String,
+    is_running: bool,
+    /// Paths of collapsed aggregate nodes ("entry/index/...")
+    collapsed: HashSet<String>,
+    scroll_handle: ScrollHandle,
+}
+
+impl RedisConsole {
+    pub fn new(
+        connection_id: String,
+        database_name: String,
+        config: ConnectionConfig,
+        storage: Arc<ConnectionStorage>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let input =
+            cx.new(|cx| TextInput::new(cx, "Enter a command, e.g. HGETALL key", "").borderless());
+        let history = storage
+            .get_command_history(&connection_id)
+            .unwrap_or_default();
+
+        Self {
+            connection_id,
+            database_name,
+            config,
+            storage,
+            input,
+            entries: Vec::new(),
+            history,
+            history_index: None,
+            draft: String::new(),
+            is_running: false,
+            collapsed: HashSet::new(),
+            scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    pub fn focus_input(&self, window: &mut Window, cx: &mut App) {
+        window.focus(&self.input.focus_handle(cx));
+    }
+
+    fn run_command(&mut self, cx: &mut Context<Self>) {
+        if self.is_running {
+            return;
+        }
+
+        let command = self.input.read(cx).text().trim().to_string();
+        if command.is_empty() {
+            return;
+        }
+
+        // Remember the command, skipping immediate repeats
+        if self.history.last() != Some(&command) {
+            self.history.push(command.clone());
+            self.storage
+                .add_command_history(&self.connection_id, &command)
+                .ok();
+        }
+        self.history_index = None;
+        self.draft.clear();
+        self.set_input(String::new(), cx);
+
+        self.is_running = true;
+        cx.emit(LoadingStateChanged(true));
+        cx.notify();
+
+        let config = self.config.clone();
+        let db_name = self.database_name.clone();
+        let start = Instant::now();
+
+        let connection_id = self.connection_id.clone();
+
+        let task = runtime::spawn({
+            let command = command.clone();
+            async move {
+                registry()
+                    .get(&connection_id, config)
+                    .await?
+                    .execute_command(&db_name, &command)
+                    .await
+            }
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = match task.await {
+                Ok(result) => result.map_err(|e| e.to_string()),
+                Err(_) => Err("Command was interrupted".to_string()),
+            };
+            this.update(cx, |console, cx| {
+                console.finish_command(command, result, start.elapsed(), cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn finish_command(
+        &mut self,
+        command: String,
+        reply: Result<RespValue, String>,
+        elapsed: Duration,
+        cx: &mut Context<Self>,
+    ) {
+        let database_name = self.database_name.clone();
+
+        // Each command runs on a fresh connection, so a successful SELECT
+        // switches the database used for the following commands
+        if let Ok(RespValue::Status(status)) = &reply {
+            let mut parts = command.split_whitespace();
+            let is_select = parts
+                .next()
+                .is_some_and(|name| name.eq_ignore_ascii_case("select"));
+            if let (true, "OK", Some(index)) = (is_select, status.as_str(), parts.next()) {
+                if index.parse::<u32>().is_ok() {
+                    self.database_name = format!("db{}", index);
+                }
+            }
+        }
+
+        self.entries.push(ConsoleEntry {
+            command,
+            database_name,
+            reply,
+            elapsed,
+        });
+        self.is_running = false;
+        self.scroll_handle.scroll_to_bottom();
+        cx.emit(LoadingStateChanged(false));
+        cx.notify();
+    }
+
+    fn set_input(&self, text: String, cx: &mut Context<Self>) {
+        self.input.update(cx, |input, cx| {
+            input.set_text(&text);
+            cx.notify();
+        });
+    }
+
+    fn history_previous(&mut self, cx: &mut Context<Self>) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match self.history_index {
+            None => {
+                self.draft = self.input.read(cx).text();
+                self.history.len() - 1
+            }
+            Some(index) => index.saturating_sub(1),
+        };
+        self.history_index = Some(index);
+        self.set_input(self.history[index].clone(), cx);
+    }
+
+    fn history_next(&mut self, cx: &mut Context<Self>) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+        if index + 1 < self.history.len() {
+            self.history_index = Some(index + 1);
+            self.set_input(self.history[index + 1].clone(), cx);
+        } else {
+            self.history_index = None;
+            let draft = std::mem::take(&mut self.draft);
+            self.set_input(draft, cx);
+        }
+    }
+
+    fn clear(&mut self, cx: &mut Context<Self>) {
+        self.entries.clear();
+        self.collapsed.clear();
+        cx.notify();
+    }
+
+    fn toggle_collapsed(&mut self, path: String, cx: &mut Context<Self>) {
+        if !self.collapsed.remove(&path) {
+            self.collapsed.insert(path);
+        }
+        cx.notify();
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let prompt = prompt_label(&self.database_name);
+
+        div()
+            .id("redis-console-toolbar")
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .w_full()
+            .h(rems(2.25)) // 36px
+            .px(rems(0.75)) // 12px
+            .bg(AppColors::bg_header())
+            .border_b_1()
+            .border_color(AppColors::border())
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.625)) // 10px
+                    .child(
+                        svg()
+                            .path("icons/redis.svg")
+                            .size(rems(0.875)) // 14px
+                            .text_color(AppColors::text_muted()),
+                    )
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_secondary())
+                            .child(prompt),
+                    )
+                    .child(
+                        div()
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_dim())
+                            .child("Enter runs · Up/Down browse history · Ctrl+L clears"),
+                    ),
+            )
+            .child(
+                div()
+                    .id("redis-console-clear")
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.375)) // 6px
+                    .px(rems(0.5)) // 8px
+                    .py(rems(0.25)) // 4px
+                    .rounded(px(4.0))
+                    .cursor_pointer()
+                    .hover(|s| s.bg(AppColors::bg_hover()))
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.clear(cx);
+                    }))
+                    .child(
+                        svg()
+                            .path("icons/trash.svg")
+                            .size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_muted()),
+                    )
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_muted())
+                            .child("Clear"),
+                    ),
+            )
+    }
+
+    fn render_entry(
+        &self,
+        index: usize,
+        entry: &ConsoleEntry,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let mut rows = Vec::new();
+        match &entry.reply {
+            Ok(value) => self.push_reply_rows(&mut rows, value, None, index.to_string(), 0, cx),
+            Err(error) => rows.push(
+                div()
+                    .text_color(AppColors::error())
+                    .child(error.clone())
+                    .into_any_element(),
+            ),
+        }
+
+        div()
+            .flex()
+            .flex_col()
+            .w_full()
+            .py(rems(0.375)) // 6px
+            .border_b_1()
+            .border_color(AppColors::border_subtle())
+            // Command line
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .gap(rems(0.5)) // 8px
+                            .child(
+                                div()
+                                    .text_color(AppColors::accent())
+                                    .child(prompt_label(&entry.database_name)),
+                            )
+                            .child(
+                                div()
+                                    .text_color(AppColors::text())
+                                    .child(entry.command.clone()),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_dim())
+                            .child(format!("{} ms", entry.elapsed.as_millis())),
+                    ),
+            )
+            .children(rows)
+            .into_any_element()
+    }
+
+    /// Flatten a reply tree into indented rows; aggregates can be collapsed
+    fn push_reply_rows(
+        &self,
+        rows: &mut Vec<AnyElement>,
+        value: &RespValue,
+        label: Option<String>,
+        path: String,
+        depth: usize,
+        cx: &mut Context<Self>,
+    ) {
+        let children: Vec<(String, &RespValue)> = match value {
+            RespValue::Array(items) | RespValue::Set(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| (format!("{})", i + 1), item))
+                .collect(),
+            RespValue::Map(entries) => entries
+                .iter()
+                .map(|(key, item)| (format!("{} =>", scalar_text(key)), item))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let is_aggregate = matches!(
+            value,
+            RespValue::Array(_) | RespValue::Set(_) | RespValue::Map(_)
+        );
+        let is_collapsed = self.collapsed.contains(&path);
+        let copy_text = copy_text(value);
+        let group = SharedString::from(format!("resp-{}", path));
+
+        rows.push(
+            div()
+                .id(group.clone())
+                .group(group.clone())
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap(rems(0.375)) // 6px
+                .pl(rems(depth as f32 * 1.25))
+                .rounded(px(3.0))
+                .hover(|s| s.bg(AppColors::bg_hover()))
+                .when(is_aggregate && !children.is_empty(), |el| {
+                    let path = path.clone();
+                    el.cursor_pointer()
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.toggle_collapsed(path.clone(), cx);
+                        }))
+                        .child(
+                            svg()
+                                .path(if is_collapsed {
+                                    "icons/chevron-right.svg"
+                                } else {
+                                    "icons/chevron-down.svg"
+                                })
+                                .size(rems(0.625)) // 10px
+                                .text_color(AppColors::text_muted()),
+                        )
+                })
+                .when_some(label, |el, label| {
+                    el.child(div().text_color(AppColors::text_dim()).child(label))
+                })
+                .child(div().text_color(value_color(value)).child(if is_aggregate {
+                    aggregate_summary(value)
+                } else {
+                    scalar_text(value)
+                }))
+                .child(
+                    div()
+                        .id(SharedString::from(format!("copy-{}", path)))
+                        .invisible()
+                        .group_hover(group, |s| s.visible())
+                        .p(rems(0.125)) // 2px
+                        .rounded(px(3.0))
+                        .cursor_pointer()
+                        .hover(|s| s.bg(AppColors::bg_active()))
+                        .on_click(move |_, _, cx| {
+                            cx.stop_propagation();
+                            cx.write_to_clipboard(ClipboardItem::new_string(copy_text.clone()));
+                        })
+                        .child(
+                            svg()
+                                .path("icons/copy.svg")
+                                .size(rems(0.6875)) // 11px
+                                .text_color(AppColors::text_muted()),
+                        ),
+                )
+                .into_any_element(),
+        );
+
+        if !is_collapsed {
+            for (i, (label, child)) in children.into_iter().enumerate() {
+                self.push_reply_rows(
+                    rows,
+                    child,
+                    Some(label),
+                    format!("{}/{}", path, i),
+                    depth + 1,
+                    cx,
+                );
+            }
+        }
+    }
+}
+
+/// redis-cli style prompt, e.g. "db2>"
+fn prompt_label(database_name: &str) -> String {
+    if database_name.is_empty() {
+        "redis>".to_string()
+    } else {
+        format!("{}>", database_name)
+    }
+}
+
+/// redis-cli style rendering of a scalar reply, e.g. `(integer) 5` or `"value"`
+fn scalar_text(value: &RespValue) -> String {
+    match value {
+        RespValue::Nil => "(nil)".to_string(),
+        RespValue::Integer(n) => format!("(integer) {}", n),
+        RespValue::Double(n) => format!("(double) {}", n),
+        RespValue::Boolean(b) => format!("({})", b),
+        RespValue::Status(s) => s.clone(),
+        RespValue::BulkString(s) => format!("{:?}", s),
+        RespValue::Error(e) => format!("(error) {}", e),
+        RespValue::Array(_) | RespValue::Set(_) | RespValue::Map(_) => aggregate_summary(value),
+    }
+}
+
+/// Header for an array/set/map node, e.g. "(array) 3 items"
+fn aggregate_summary(value: &RespValue) -> String {
+    let (kind, len) = match value {
+        RespValue::Array(items) => ("array", items.len()),
+        RespValue::Set(items) => ("set", items.len()),
+        RespValue::Map(entries) => ("map", entries.len()),
+        _ => return String::new(),
+    };
+    match len {
+        0 => format!("(empty {})", kind),
+        1 => format!("({}) 1 item", kind),
+        n => format!("({}) {} items", kind, n),
+    }
+}
+
+fn value_color(value: &RespValue) -> Hsla {
+    match value {
+        RespValue::BulkString(_) => AppColors::success(),
+        RespValue::Integer(_) | RespValue::Double(_) | RespValue::Boolean(_) => {
+            AppColors::warning()
+        }
+        RespValue::Error(_) => AppColors::error(),
+        RespValue::Nil | RespValue::Array(_) | RespValue::Set(_) | RespValue::Map(_) => {
+            AppColors::text_muted()
+        }
+        RespValue::Status(_) => AppColors::text(),
+    }
+}
+
+/// Text put on the clipboard for a node: the raw value for scalars, the
+/// redis-cli rendering for arrays, sets and maps
+fn copy_text(value: &RespValue) -> String {
+    match value {
+        RespValue::Nil => String::new(),
+        RespValue::Integer(n) => n.to_string(),
+        RespValue::Double(n) => n.to_string(),
+        RespValue::Boolean(b) => b.to_string(),
+        RespValue::Status(s) | RespValue::BulkString(s) | RespValue::Error(s) => s.clone(),
+        RespValue::Array(_) | RespValue::Set(_) | RespValue::Map(_) => cli_text(value, 0),
+    }
+}
+
+/// Multi-line redis-cli rendering; `indent` is the column nested lines start at
+fn cli_text(value: &RespValue, indent: usize) -> String {
+    let items: Vec<(String, &RespValue)> = match value {
+        RespValue::Array(items) | RespValue::Set(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (format!("{})", i + 1), item))
+            .collect(),
+        RespValue::Map(entries) => entries
+            .iter()
+            .enumerate()
+            .map(|(i, (key, item))| (format!("{}# {} =>", i + 1, scalar_text(key)), item))
+            .collect(),
+        _ => return scalar_text(value),
+    };
+    if items.is_empty() {
+        return aggregate_summary(value);
+    }
+
+    let mut out = String::new();
+    for (i, (prefix, item)) in items.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+            out.push_str(&" ".repeat(indent));
+        }
+        out.push_str(prefix);
+        out.push(' ');
+        out.push_str(&cli_text(item, indent + prefix.chars().count() + 1));
+    }
+    out
+}
+
+impl Render for RedisConsole {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entries: Vec<AnyElement> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| self.render_entry(index, entry, cx))
+            .collect();
+        let is_empty = entries.is_empty();
+
+        div()
+            .id("redis-console")
+            .key_context("RedisConsole")
+            .on_action(cx.listener(|this, _: &RunCommand, _, cx| {
+                this.run_command(cx);
+            }))
+            .on_action(cx.listener(|this, _: &HistoryPrevious, _, cx| {
+                this.history_previous(cx);
+            }))
+            .on_action(cx.listener(|this, _: &HistoryNext, _, cx| {
+                this.history_next(cx);
+            }))
+            .on_action(cx.listener(|this, _: &ClearConsole, _, cx| {
+                this.clear(cx);
+            }))
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(AppColors::bg_main())
+            .child(self.render_toolbar(cx))
+            // Transcript of commands and replies
+            .child(
+                div()
+                    .id("redis-console-output")
+                    .flex_1()
+                    .min_h_0()
+                    .w_full()
+                    .overflow_y_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .px(rems(0.75)) // 12px
+                    .text_size(rems(0.8125)) // 13px
+                    .when(is_empty, |el| {
+                        el.flex().items_center().justify_center().child(
+                            div()
+                                .text_color(AppColors::text_dim())
+                                .child("Replies will appear here"),
+                        )
+                    })
+                    .children(entries),
+            )
+            // Prompt
+            .child(
+                div()
+                    .id("redis-console-prompt")
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.5)) // 8px
+                    .w_full()
+                    .h(rems(2.25)) // 36px
+                    .px(rems(0.75)) // 12px
+                    .bg(AppColors::bg_secondary())
+                    .border_t_1()
+                    .border_color(AppColors::border())
+                    .child(
+                        div()
+                            .text_size(rems(0.8125)) // 13px
+                            .text_color(if self.is_running {
+                                AppColors::text_dim()
+                            } else {
+                                AppColors::accent()
+                            })
+                            .child(prompt_label(&self.database_name)),
+                    )
+                    .child(div().flex_1().child(self.input.clone())),
+            )
+    }
+}
@@ -0,0 +1,457 @@
+//! Schema of a MongoDB collection sampled with `$sample`: every field path with
+//! its types, how often it is present and a few example values
+
+use gpui::{prelude::*, rems, *};
+
+use crate::db::driver::ConnectionConfig;
+use crate::db::error::ConnectionError;
+use crate::db::registry::registry;
+use crate::db::runtime;
+use crate::db::schema::{self, FieldSchema, SchemaAnalysis, DEFAULT_SAMPLE_SIZE, SAMPLE_SIZES};
+use crate::ui::collection_view::value_to_display_string;
+use crate::ui::theme::AppColors;
+use crate::ui::widgets::{render_badge, render_option};
+
+/// Emitted with the sampled top-level fields, `_id` first, after each sample
+pub struct SchemaSampled(pub Vec<String>);
+
+enum SchemaState {
+    Loading,
+    Loaded(SchemaAnalysis),
+    Failed(String),
+}
+
+/// Which fields the list shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldFilter {
+    All,
+    Conflicts,
+    Rare,
+}
+
+impl FieldFilter {
+    const ALL: [FieldFilter; 3] = [FieldFilter::All, FieldFilter::Conflicts, FieldFilter::Rare];
+
+    fn label(&self) -> &'static str {
+        match self {
+            FieldFilter::All => "All fields",
+            FieldFilter::Conflicts => "Type conflicts",
+            FieldFilter::Rare => "Rare",
+        }
+    }
+}
+
+pub struct SchemaView {
+    connection_id: String,
+    config: ConnectionConfig,
+    database_name: String,
+    collection_name: String,
+    sample_size: u32,
+    state: SchemaState,
+    filter: FieldFilter,
+}
+
+impl EventEmitter<SchemaSampled> for SchemaView {}
+
+impl SchemaView {
+    pub fn new(
+        connection_id: String,
+        config: ConnectionConfig,
+        database_name: String,
+        collection_name: String,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut view = Self {
+            connection_id,
+            config,
+            database_name,
+            collection_name,
+            sample_size: DEFAULT_SAMPLE_SIZE,
+            state: SchemaState::Loading,
+            filter: FieldFilter::All,
+        };
+        view.sample(cx);
+        view
+    }
+
+    fn sample(&mut self, cx: &mut Context<Self>) {
+        self.state = SchemaState::Loading;
+        cx.notify();
+
+        let connection_id = self.connection_id.clone();
+        let config = self.config.clone();
+        let database_name = self.database_name.clone();
+        let collection_name = self.collection_name.clone();
+        let size = self.sample_size;
+        let task = runtime::spawn(async move {
+            let documents = registry()
+                .get(&connection_id, config)
+                .await?
+                .aggregate(
+                    &database_name,
+                    &collection_name,
+                    &schema::sample_pipeline(size),
+                    size,
+                )
+                .await?;
+            Ok::<_, ConnectionError>(SchemaAnalysis::analyze(&documents))
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |view, cx| {
+                // A sample of another size was asked for meanwhile
+                if view.sample_size != size {
+                    return;
+                }
+                match result {
+                    Ok(Ok(analysis)) => {
+                        cx.emit(SchemaSampled(analysis.columns()));
+                        view.state = SchemaState::Loaded(analysis);
+                    }
+                    Ok(Err(e)) => view.state = SchemaState::Failed(e.to_string()),
+                    Err(_) => {
+                        view.state = SchemaState::Failed("Sampling was interrupted".to_string())
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn set_sample_size(&mut self, size: u32, cx: &mut Context<Self>) {
+        if self.sample_size != size {
+            self.sample_size = size;
+            self.sample(cx);
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let loading = matches!(self.state, SchemaState::Loading);
+        let summary = match &self.state {
+            SchemaState::Loaded(analysis) => format!(
+                "{} documents sampled · {} fields · {} type conflicts · {} rare",
+                analysis.sample_size,
+                analysis.fields.len(),
+                analysis.conflict_count(),
+                analysis.rare_count()
+            ),
+            SchemaState::Loading => "Sampling...".to_string(),
+            SchemaState::Failed(_) => String::new(),
+        };
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .w_full()
+            .h(rems(2.25)) // 36px
+            .px(rems(0.75)) // 12px
+            .bg(AppColors::bg_header())
+            .border_b_1()
+            .border_color(AppColors::border())
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.25)) // 4px
+                    .child(
+                        div()
+                            .mr(rems(0.25)) // 4px
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_muted())
+                            .child("Sample"),
+                    )
+                    .children(SAMPLE_SIZES.iter().map(|&size| {
+                        render_option(
+                            SharedString::from(format!("schema-sample-{}", size)),
+                            size.to_string(),
+                            size == self.sample_size,
+                            true,
+                        )
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.set_sample_size(size, cx);
+                        }))
+                    }))
+                    .child(
+                        div()
+                            .id("resample-schema")
+                            .ml(rems(0.25)) // 4px
+                            .p(rems(0.25)) // 4px
+                            .rounded(px(3.0))
+                            .when(!loading, |el| {
+                                el.cursor_pointer()
+                                    .hover(|s| s.bg(AppColors::bg_hover()))
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.sample(cx);
+                                    }))
+                            })
+                            .child(
+                                svg()
+                                    .path("icons/refresh.svg")
+                                    .size(rems(0.75)) // 12px
+                                    .text_color(AppColors::text_dim()),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .ml(rems(0.5)) // 8px
+                            .text_size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_muted())
+                            .child(summary),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.125)) // 2px
+                    .children(FieldFilter::ALL.iter().map(|&filter| {
+                        render_option(
+                            SharedString::from(format!("schema-filter-{}", filter.label())),
+                            filter.label(),
+                            filter == self.filter,
+                            true,
+                        )
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.filter = filter;
+                            cx.notify();
+                        }))
+                    })),
+            )
+    }
+
+    fn render_header_cell(label: &'static str) -> Div {
+        div()
+            .text_size(rems(0.6875)) // 11px
+            .font_weight(FontWeight::MEDIUM)
+            .text_color(AppColors::text_muted())
+            .child(label)
+    }
+
+    fn render_field(analysis: &SchemaAnalysis, field: &FieldSchema) -> impl IntoElement {
+        let presence = analysis.presence(field);
+        let rare = analysis.is_rare(field);
+        let conflict = field.has_type_conflict();
+        // The parent path is dimmed so nested fields still read as a tree
+        let (parent, name) = match field.path.rsplit_once('.') {
+            Some((parent, name)) if field.depth > 0 => (format!("{}.", parent), name.to_string()),
+            _ => (String::new(), field.path.clone()),
+        };
+        let examples = field
+            .examples
+            .iter()
+            .map(|value| value_to_display_string(value).to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap(rems(0.75)) // 12px
+            .px(rems(0.75)) // 12px
+            .py(rems(0.375)) // 6px
+            .border_b_1()
+            .border_color(AppColors::border_subtle())
+            .text_size(rems(0.75)) // 12px
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .w(rems(16.0))
+                    .flex_none()
+                    .overflow_hidden()
+                    .pl(rems(field.depth as f32 * 0.75))
+                    .child(
+                        div()
+                            .flex_none()
+                            .text_color(AppColors::text_dim())
+                            .child(parent),
+                    )
+                    .child(
+                        div()
+                            .text_color(AppColors::text())
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .whitespace_nowrap()
+                            .child(name),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .flex_wrap()
+                    .items_center()
+                    .gap(rems(0.25)) // 4px
+                    .w(rems(16.0))
+                    .flex_none()
+                    .children(field.types.iter().map(|(name, count)| {
+                        let share = *count as f64 / field.count.max(1) as f64;
+                        let label = if field.types.len() > 1 {
+                            format!("{} {:.0}%", name, share * 100.0)
+                        } else {
+                            name.to_string()
+                        };
+                        render_badge(
+                            label,
+                            if conflict && *name != "Null" {
+                                AppColors::warning()
+                            } else {
+                                AppColors::text_secondary()
+                            },
+                        )
+                    }))
+                    .when(conflict, |el| {
+                        el.child(render_badge("conflict", AppColors::warning()))
+                    }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.5)) // 8px
+                    .w(rems(9.0))
+                    .flex_none()
+                    .child(
+                        div()
+                            .w(rems(4.0))
+                            .h(px(4.0))
+                            .rounded(px(2.0))
+                            .bg(AppColors::bg_active())
+                            .child(
+                                div()
+                                    .h_full()
+                                    .w(relative(presence as f32))
+                                    .rounded(px(2.0))
+                                    .bg(if rare {
+                                        AppColors::warning()
+                                    } else {
+                                        AppColors::accent()
+                                    }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .text_color(if rare {
+                                AppColors::warning()
+                            } else {
+                                AppColors::text_secondary()
+                            })
+                            .child(format!("{:.0}%", presence * 100.0)),
+                    )
+                    .when(rare, |el| {
+                        el.child(render_badge("rare", AppColors::warning()))
+                    }),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .text_color(AppColors::text_muted())
+                    .child(examples),
+            )
+    }
+
+    fn render_body(&self) -> AnyElement {
+        let message = |text: String, color: Hsla| {
+            div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .p(rems(1.0)) // 16px
+                .child(
+                    div()
+                        .text_size(rems(0.8125)) // 13px
+                        .text_color(color)
+                        .child(text),
+                )
+                .into_any_element()
+        };
+
+        let analysis = match &self.state {
+            SchemaState::Loading => {
+                return message(
+                    format!("Sampling {} documents...", self.sample_size),
+                    AppColors::text_muted(),
+                )
+            }
+            SchemaState::Failed(error) => return message(error.clone(), AppColors::error()),
+            SchemaState::Loaded(analysis) => analysis,
+        };
+        if analysis.fields.is_empty() {
+            return message("The collection is empty".to_string(), AppColors::text_dim());
+        }
+
+        let fields: Vec<&FieldSchema> = analysis
+            .fields
+            .iter()
+            .filter(|field| match self.filter {
+                FieldFilter::All => true,
+                FieldFilter::Conflicts => field.has_type_conflict(),
+                FieldFilter::Rare => analysis.is_rare(field),
+            })
+            .collect();
+        if fields.is_empty() {
+            return message(
+                match self.filter {
+                    FieldFilter::Conflicts => "Every field has a single type",
+                    _ => "Every field is present in most documents",
+                }
+                .to_string(),
+                AppColors::text_dim(),
+            );
+        }
+
+        div()
+            .id("schema-fields")
+            .size_full()
+            .overflow_y_scroll()
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.75)) // 12px
+                    .h(rems(1.75)) // 28px
+                    .px(rems(0.75)) // 12px
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(Self::render_header_cell("Field").w(rems(16.0)))
+                    .child(Self::render_header_cell("Types").w(rems(16.0)))
+                    .child(Self::render_header_cell("Present").w(rems(9.0)))
+                    .child(Self::render_header_cell("Examples").flex_1()),
+            )
+            .children(
+                fields
+                    .into_iter()
+                    .map(|field| Self::render_field(analysis, field)),
+            )
+            .into_any_element()
+    }
+}
+
+impl Render for SchemaView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .id("schema-view")
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(AppColors::bg_main())
+            .child(self.render_toolbar(cx))
+            .child(div().flex_1().min_h_0().w_full().child(self.render_body()))
+    }
+}
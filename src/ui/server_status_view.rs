@@ -0,0 +1,623 @@
+//! Live activity of a server: its status is polled on an interval and the last
+//! readings are plotted as rolling charts of throughput, connections, cache hit
+//! ratio, memory and replication lag
+
+use gpui::{prelude::*, rems, *};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::db::driver::{ConnectionConfig, ServerStatus};
+use crate::db::registry::registry;
+use crate::db::runtime;
+use crate::ui::connection_browser::format_bytes;
+use crate::ui::theme::AppColors;
+use crate::ui::widgets::render_option;
+
+/// Polling intervals offered in the toolbar, in seconds
+const INTERVALS: [u64; 4] = [1, 2, 5, 10];
+
+/// History windows offered in the toolbar, in seconds
+const WINDOWS: [u64; 4] = [60, 300, 900, 3600];
+
+const DEFAULT_INTERVAL: u64 = 2;
+const DEFAULT_WINDOW: u64 = 300;
+
+/// One reading of the server's status
+struct Sample {
+    at: Instant,
+    status: ServerStatus,
+}
+
+/// A metric plotted over the history window
+struct Chart {
+    title: &'static str,
+    points: Vec<(Instant, f64)>,
+    format: fn(f64) -> String,
+    /// Fixed top of the scale, e.g. 100 for percentages
+    ceiling: Option<f64>,
+    /// Shown in place of the plot when the server does not report the metric
+    missing: &'static str,
+}
+
+pub struct ServerStatusView {
+    connection_id: String,
+    config: ConnectionConfig,
+    /// Readings within the history window, oldest first
+    samples: VecDeque<Sample>,
+    /// Error of the last poll; the charts keep what was read before
+    error: Option<String>,
+    interval: Duration,
+    window: Duration,
+    paused: bool,
+    /// Bumped to stop the running poll loop
+    generation: u64,
+}
+
+impl ServerStatusView {
+    pub fn new(connection_id: String, config: ConnectionConfig, cx: &mut Context<Self>) -> Self {
+        let mut view = Self {
+            connection_id,
+            config,
+            samples: VecDeque::new(),
+            error: None,
+            interval: Duration::from_secs(DEFAULT_INTERVAL),
+            window: Duration::from_secs(DEFAULT_WINDOW),
+            paused: false,
+            generation: 0,
+        };
+        view.start_polling(cx);
+        view
+    }
+
+    /// Poll the server every `interval` until the generation changes or the
+    /// view is closed
+    fn start_polling(&mut self, cx: &mut Context<Self>) {
+        self.generation += 1;
+        let generation = self.generation;
+        let interval = self.interval;
+
+        cx.spawn(async move |this, cx| loop {
+            let Ok(task) = this.update(cx, |view, _| view.poll()) else {
+                return;
+            };
+            let result = task.await;
+            let polling = this
+                .update(cx, |view, cx| {
+                    if view.generation != generation {
+                        return false;
+                    }
+                    match result {
+                        Ok(Ok(status)) => view.record(status),
+                        Ok(Err(e)) => view.error = Some(e.to_string()),
+                        Err(_) => view.error = Some("Polling the server was interrupted".into()),
+                    }
+                    cx.notify();
+                    true
+                })
+                .unwrap_or(false);
+            if !polling {
+                return;
+            }
+
+            cx.background_executor().timer(interval).await;
+            if !this
+                .update(cx, |view, _| view.generation == generation)
+                .unwrap_or(false)
+            {
+                return;
+            }
+        })
+        .detach();
+    }
+
+    fn stop_polling(&mut self) {
+        self.generation += 1;
+    }
+
+    fn poll(&self) -> tokio::task::JoinHandle<crate::db::error::Result<ServerStatus>> {
+        let connection_id = self.connection_id.clone();
+        let config = self.config.clone();
+        runtime::spawn(async move {
+            registry()
+                .get(&connection_id, config)
+                .await?
+                .server_status()
+                .await
+        })
+    }
+
+    fn record(&mut self, status: ServerStatus) {
+        let now = Instant::now();
+        self.error = None;
+        self.samples.push_back(Sample { at: now, status });
+        self.trim(now);
+    }
+
+    /// Drop readings older than the history window
+    fn trim(&mut self, now: Instant) {
+        while self
+            .samples
+            .front()
+            .is_some_and(|sample| now.duration_since(sample.at) > self.window)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    fn set_interval(&mut self, seconds: u64, cx: &mut Context<Self>) {
+        self.interval = Duration::from_secs(seconds);
+        if !self.paused {
+            self.start_polling(cx);
+        }
+        cx.notify();
+    }
+
+    /// A shorter window forgets the older readings at once
+    fn set_window(&mut self, seconds: u64, cx: &mut Context<Self>) {
+        self.window = Duration::from_secs(seconds);
+        self.trim(Instant::now());
+        cx.notify();
+    }
+
+    fn toggle_paused(&mut self, cx: &mut Context<Self>) {
+        self.paused = !self.paused;
+        if self.paused {
+            self.stop_polling();
+        } else {
+            self.start_polling(cx);
+        }
+        cx.notify();
+    }
+
+    fn charts(&self) -> Vec<Chart> {
+        let samples = &self.samples;
+        vec![
+            Chart {
+                title: "Operations / s",
+                points: rate(samples, |status| status.operations),
+                format: format_count,
+                ceiling: None,
+                missing: "Not reported by this server",
+            },
+            Chart {
+                title: "Connections",
+                points: gauge(samples, |status| status.connections.map(|n| n as f64)),
+                format: format_count,
+                ceiling: None,
+                missing: "Not reported by this server",
+            },
+            Chart {
+                title: "Cache hit ratio",
+                points: hit_ratio(samples),
+                format: |ratio| format!("{:.1}%", ratio),
+                ceiling: Some(100.0),
+                missing: "No reads since the last sample",
+            },
+            Chart {
+                title: "Memory",
+                points: gauge(samples, |status| status.memory_bytes.map(|n| n as f64)),
+                format: |bytes| format_bytes(bytes as u64),
+                ceiling: None,
+                missing: "Not reported by this server",
+            },
+            Chart {
+                title: "Replication lag",
+                points: gauge(samples, |status| status.replication_lag),
+                format: |seconds| format!("{:.1} s", seconds),
+                ceiling: None,
+                missing: "Only reported by replicas",
+            },
+        ]
+    }
+
+    fn render_label(text: &'static str) -> impl IntoElement {
+        div()
+            .ml(rems(0.5)) // 8px
+            .mr(rems(0.25)) // 4px
+            .text_size(rems(0.6875)) // 11px
+            .text_color(AppColors::text_muted())
+            .child(text)
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let latest = self.samples.back().map(|sample| &sample.status);
+        let version = latest
+            .and_then(|status| status.properties.iter().find(|(name, _)| name == "Version"))
+            .map(|(_, version)| version.clone());
+        let summary = match (version, latest.and_then(|status| status.uptime)) {
+            (Some(version), Some(uptime)) => format!("{} · up {}", version, format_uptime(uptime)),
+            (Some(version), None) => version,
+            (None, _) if self.samples.is_empty() => "Connecting...".to_string(),
+            (None, _) => String::new(),
+        };
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .w_full()
+            .h(rems(2.25)) // 36px
+            .px(rems(0.75)) // 12px
+            .bg(AppColors::bg_header())
+            .border_b_1()
+            .border_color(AppColors::border())
+            .child(
+                div()
+                    .text_size(rems(0.75)) // 12px
+                    .text_color(AppColors::text_muted())
+                    .child(summary),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.25)) // 4px
+                    .child(Self::render_label("Every"))
+                    .children(INTERVALS.iter().map(|&seconds| {
+                        render_option(
+                            SharedString::from(format!("status-interval-{}", seconds)),
+                            format!("{}s", seconds),
+                            Duration::from_secs(seconds) == self.interval,
+                            true,
+                        )
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.set_interval(seconds, cx);
+                        }))
+                    }))
+                    .child(Self::render_label("History"))
+                    .children(WINDOWS.iter().map(|&seconds| {
+                        render_option(
+                            SharedString::from(format!("status-window-{}", seconds)),
+                            format_window(seconds),
+                            Duration::from_secs(seconds) == self.window,
+                            true,
+                        )
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.set_window(seconds, cx);
+                        }))
+                    }))
+                    .child(
+                        div()
+                            .id("toggle-status-polling")
+                            .ml(rems(0.5)) // 8px
+                            .px(rems(0.625)) // 10px
+                            .py(rems(0.3125)) // 5px
+                            .rounded(px(4.0))
+                            .cursor_pointer()
+                            .hover(|s| s.bg(AppColors::bg_hover()))
+                            .text_size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_secondary())
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.toggle_paused(cx);
+                            }))
+                            .child(if self.paused { "Resume" } else { "Pause" }),
+                    ),
+            )
+    }
+
+    fn render_chart(&self, chart: Chart) -> impl IntoElement {
+        let end = self
+            .samples
+            .back()
+            .map(|sample| sample.at)
+            .unwrap_or_else(Instant::now);
+        let window = self.window.as_secs_f64();
+        let latest = chart
+            .points
+            .last()
+            .map(|(_, value)| (chart.format)(*value))
+            .unwrap_or_else(|| "–".to_string());
+        let top = chart.ceiling.unwrap_or_else(|| {
+            let max = chart
+                .points
+                .iter()
+                .map(|(_, value)| *value)
+                .fold(0.0, f64::max);
+            if max > 0.0 {
+                max * 1.25
+            } else {
+                1.0
+            }
+        });
+        // Fractions of the plot's width and height, from the bottom left
+        let points: Vec<(f32, f32)> = chart
+            .points
+            .iter()
+            .map(|(at, value)| {
+                let age = end.duration_since(*at).as_secs_f64();
+                (
+                    (1.0 - age / window).clamp(0.0, 1.0) as f32,
+                    (value / top).clamp(0.0, 1.0) as f32,
+                )
+            })
+            .collect();
+        let empty = if self.samples.len() < 2 {
+            "Waiting for data..."
+        } else {
+            chart.missing
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .flex_1()
+            .min_w(rems(22.0)) // 352px
+            .rounded(px(4.0))
+            .bg(AppColors::bg_secondary())
+            .border_1()
+            .border_color(AppColors::border_subtle())
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .px(rems(0.75)) // 12px
+                    .pt(rems(0.5)) // 8px
+                    .child(
+                        div()
+                            .text_size(rems(0.6875)) // 11px
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(AppColors::text_secondary())
+                            .child(chart.title),
+                    )
+                    .child(
+                        div()
+                            .text_size(rems(1.0)) // 16px
+                            .text_color(AppColors::text())
+                            .child(latest),
+                    ),
+            )
+            .child(
+                div()
+                    .relative()
+                    .h(rems(8.0)) // 128px
+                    .mx(rems(0.75)) // 12px
+                    .my(rems(0.5)) // 8px
+                    .when(points.is_empty(), |el| {
+                        el.flex()
+                            .items_center()
+                            .justify_center()
+                            .text_size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_dim())
+                            .child(empty)
+                    })
+                    .when(!points.is_empty(), |el| {
+                        el.child(
+                            canvas(
+                                |_, _, _| {},
+                                move |bounds, _, window, _| {
+                                    paint_chart(bounds, &points, window);
+                                },
+                            )
+                            .absolute()
+                            .size_full(),
+                        )
+                        .child(
+                            div()
+                                .absolute()
+                                .top_0()
+                                .left_0()
+                                .text_size(rems(0.625)) // 10px
+                                .text_color(AppColors::text_dim())
+                                .child((chart.format)(top)),
+                        )
+                    }),
+            )
+    }
+
+    fn render_properties(&self) -> impl IntoElement {
+        let properties = self
+            .samples
+            .back()
+            .map(|sample| sample.status.properties.clone())
+            .unwrap_or_default();
+
+        div()
+            .flex()
+            .flex_col()
+            .py(rems(0.5)) // 8px
+            .children(properties.into_iter().map(|(name, value)| {
+                div()
+                    .flex()
+                    .flex_row()
+                    .gap(rems(0.75)) // 12px
+                    .px(rems(0.75)) // 12px
+                    .py(rems(0.25)) // 4px
+                    .text_size(rems(0.75)) // 12px
+                    .hover(|s| s.bg(AppColors::bg_hover()))
+                    .child(
+                        div()
+                            .w(rems(14.0)) // 224px
+                            .flex_none()
+                            .text_color(AppColors::text_muted())
+                            .child(name),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .text_color(AppColors::text())
+                            .child(value),
+                    )
+            }))
+    }
+}
+
+impl Render for ServerStatusView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let charts = self.charts();
+
+        div()
+            .id("server-status-view")
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(AppColors::bg_main())
+            .child(self.render_toolbar(cx))
+            .when_some(self.error.clone(), |el, error| {
+                el.child(
+                    div()
+                        .px(rems(0.75)) // 12px
+                        .py(rems(0.375)) // 6px
+                        .bg(AppColors::bg_secondary())
+                        .border_b_1()
+                        .border_color(AppColors::border_subtle())
+                        .text_size(rems(0.75)) // 12px
+                        .text_color(AppColors::error())
+                        .child(error),
+                )
+            })
+            .child(
+                div()
+                    .id("server-status-scroll")
+                    .flex_1()
+                    .min_h_0()
+                    .w_full()
+                    .overflow_y_scroll()
+                    .child(
+                        div()
+                            .flex()
+                            .flex_row()
+                            .flex_wrap()
+                            .gap(rems(0.75)) // 12px
+                            .p(rems(0.75)) // 12px
+                            .children(charts.into_iter().map(|chart| self.render_chart(chart))),
+                    )
+                    .child(
+                        div()
+                            .border_t_1()
+                            .border_color(AppColors::border_subtle())
+                            .child(self.render_properties()),
+                    ),
+            )
+    }
+}
+
+/// Grid lines, then the series as a line over a shaded area
+fn paint_chart(bounds: Bounds<Pixels>, points: &[(f32, f32)], window: &mut Window) {
+    let width: f32 = bounds.size.width.into();
+    let height: f32 = bounds.size.height.into();
+    let to_screen =
+        |(x, y): (f32, f32)| bounds.origin + point(px(x * width), px((1.0 - y) * height));
+
+    for level in [0.0, 0.5, 1.0] {
+        let mut grid = PathBuilder::stroke(px(1.0));
+        grid.move_to(to_screen((0.0, level)));
+        grid.line_to(to_screen((1.0, level)));
+        if let Ok(path) = grid.build() {
+            window.paint_path(path, AppColors::border_subtle());
+        }
+    }
+
+    // A single reading is drawn as a flat line up to the right edge
+    let mut series = points.to_vec();
+    if let [(x, y)] = series[..] {
+        series = vec![(x.min(0.99), y), (1.0, y)];
+    }
+    let (Some(first), Some(last)) = (series.first().copied(), series.last().copied()) else {
+        return;
+    };
+
+    let mut area = PathBuilder::fill();
+    area.move_to(to_screen((first.0, 0.0)));
+    for point in &series {
+        area.line_to(to_screen(*point));
+    }
+    area.line_to(to_screen((last.0, 0.0)));
+    area.close();
+    if let Ok(path) = area.build() {
+        window.paint_path(path, AppColors::accent().opacity(0.15));
+    }
+
+    let mut line = PathBuilder::stroke(px(1.5));
+    line.move_to(to_screen(first));
+    for point in &series[1..] {
+        line.line_to(to_screen(*point));
+    }
+    if let Ok(path) = line.build() {
+        window.paint_path(path, AppColors::accent());
+    }
+}
+
+/// Per-second rate of a counter between consecutive readings. A counter that
+/// went down means the server restarted, and that interval is left out.
+fn rate(
+    samples: &VecDeque<Sample>,
+    counter: fn(&ServerStatus) -> Option<u64>,
+) -> Vec<(Instant, f64)> {
+    samples
+        .iter()
+        .zip(samples.iter().skip(1))
+        .filter_map(|(before, after)| {
+            let (from, to) = (counter(&before.status)?, counter(&after.status)?);
+            let seconds = after.at.duration_since(before.at).as_secs_f64();
+            (to >= from && seconds > 0.0).then(|| (after.at, (to - from) as f64 / seconds))
+        })
+        .collect()
+}
+
+/// Share of the reads between consecutive readings served from the cache, in percent
+fn hit_ratio(samples: &VecDeque<Sample>) -> Vec<(Instant, f64)> {
+    samples
+        .iter()
+        .zip(samples.iter().skip(1))
+        .filter_map(|(before, after)| {
+            let hits = after
+                .status
+                .cache_hits?
+                .checked_sub(before.status.cache_hits?)?;
+            let misses = after
+                .status
+                .cache_misses?
+                .checked_sub(before.status.cache_misses?)?;
+            let reads = hits + misses;
+            (reads > 0).then(|| (after.at, hits as f64 * 100.0 / reads as f64))
+        })
+        .collect()
+}
+
+fn gauge(
+    samples: &VecDeque<Sample>,
+    value: fn(&ServerStatus) -> Option<f64>,
+) -> Vec<(Instant, f64)> {
+    samples
+        .iter()
+        .filter_map(|sample| Some((sample.at, value(&sample.status)?)))
+        .collect()
+}
+
+/// Short count, e.g. `950`, `12.5`, `3.2k` or `1.1M`
+fn format_count(value: f64) -> String {
+    if value >= 1_000_000.0 {
+        format!("{:.1}M", value / 1_000_000.0)
+    } else if value >= 1_000.0 {
+        format!("{:.1}k", value / 1_000.0)
+    } else if value.fract() == 0.0 {
+        format!("{}", value)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+/// Uptime in its two largest units, e.g. `3d 4h` or `12m 5s`
+fn format_uptime(seconds: u64) -> String {
+    let (days, hours, minutes) = (seconds / 86_400, seconds / 3_600 % 24, seconds / 60 % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m {}s", minutes, seconds % 60)
+    }
+}
+
+fn format_window(seconds: u64) -> String {
+    if seconds >= 3_600 {
+        format!("{}h", seconds / 3_600)
+    } else {
+        format!("{}m", seconds / 60)
+    }
+}
@@ -2,14 +2,16 @@ use gpui::{prelude::*, rems, *};
 use gpui_component::menu::{PopupMenu, PopupMenuItem};
 use std::sync::Arc;
 
-use crate::db::{create_connection, Connection, ConnectionConfig, ConnectionStorage, DatabaseType};
+use crate::db::registry::registry;
+use crate::db::{runtime, Connection, ConnectionConfig, ConnectionStorage, DatabaseType};
 use crate::ui::connection_browser::{
     CollectionContextMenuRequested, CollectionSelected, ConnectionBrowser,
-    DatabaseContextMenuRequested, LoadingState,
+    DatabaseContextMenuRequested, LoadingState, PasswordRequired,
 };
 use crate::ui::database_menu::{DatabaseMenu, DatabaseSelected};
 use crate::ui::database_picker::{DatabasePicker, DatabaseVisibilityChanged};
 use crate::ui::filter_menu::{FilterChanged, FilterMenu};
+use crate::ui::password_prompt::PasswordPrompt;
 use crate::ui::tooltip::Tooltip;
 
 /// Size of the resize handle in pixels
@@ -36,16 +38,59 @@ pub struct EditConnectionRequested(pub Connection);
 
 impl EventEmitter<EditConnectionRequested> for Sidebar {}
 
-/// Event emitted when a collection is selected (database_name, collection_name, connection_string)
+/// Event emitted when a collection is selected (connection_id, database_name, collection_name, config)
 #[derive(Clone)]
 pub struct OpenCollectionRequested {
+    pub connection_id: String,
     pub database_name: String,
     pub collection_name: String,
-    pub connection_string: String,
+    pub config: ConnectionConfig,
 }
 
 impl EventEmitter<OpenCollectionRequested> for Sidebar {}
 
+/// Event emitted when a query console is requested (empty database_name = connection default)
+#[derive(Clone)]
+pub struct OpenQueryConsoleRequested {
+    pub connection_id: String,
+    pub database_name: String,
+    pub config: ConnectionConfig,
+}
+
+impl EventEmitter<OpenQueryConsoleRequested> for Sidebar {}
+
+/// Event emitted when the ER diagram of a SQL database is requested
+#[derive(Clone)]
+pub struct OpenDiagramRequested {
+    pub connection_id: String,
+    pub database_name: String,
+    pub config: ConnectionConfig,
+}
+
+impl EventEmitter<OpenDiagramRequested> for Sidebar {}
+
+/// Event emitted when the properties of a database or collection are requested
+#[derive(Clone)]
+pub struct OpenPropertiesRequested {
+    pub connection_id: String,
+    pub database_name: String,
+    /// `None` for the properties of the whole database
+    pub collection_name: Option<String>,
+    pub config: ConnectionConfig,
+}
+
+impl EventEmitter<OpenPropertiesRequested> for Sidebar {}
+
+/// Event emitted when the live status of a connection's server is requested
+#[derive(Clone)]
+pub struct OpenServerStatusRequested {
+    pub connection_id: String,
+    pub connection_name: String,
+    pub config: ConnectionConfig,
+}
+
+impl EventEmitter<OpenServerStatusRequested> for Sidebar {}
+
 /// Drag payload for sidebar resize
 #[derive(Clone)]
 pub struct DraggedSidebar;
@@ -61,7 +106,7 @@ impl Render for DraggedSidebar {
 #[allow(dead_code)] // Fields used for future context-aware behavior
 enum ContextMenuTarget {
     /// Right-clicked on a saved connection row
-    Connection(Connection),
+    Connection(Box<Connection>),
     /// Right-clicked on a database row (connection_id, database_name)
     Database(String, String),
     /// Right-clicked on a collection row (connection_id, database_name, collection_name)
@@ -85,6 +130,8 @@ pub struct Sidebar {
     connections: Vec<Connection>,
     expanded_connections: std::collections::HashSet<String>,
     connection_browsers: std::collections::HashMap<String, Entity<ConnectionBrowser>>,
+    /// Passwords entered this session for connections that ask on connect
+    session_passwords: std::collections::HashMap<String, String>,
     /// Whether we are currently refreshing connections
     is_refreshing: bool,
     /// Filter by database types (empty = show all)
@@ -118,29 +165,32 @@ impl Sidebar {
                 .unwrap_or(false);
             let has_show_all = conn.show_all_databases.unwrap_or(false);
 
-            if (has_saved_dbs || has_show_all) && conn.db_type == DatabaseType::MongoDB {
+            if has_saved_dbs || has_show_all {
                 // Auto-expand this connection
                 expanded_connections.insert(conn.id.clone());
 
                 // Create browser with saved databases as preview (no connection)
                 let browser = cx.new(|_cx| ConnectionBrowser::new(conn.clone()));
-                let connection_string = conn.get_connection_string();
 
                 // Subscribe to collection selection events
                 cx.subscribe(&browser, {
-                    let connection_string = connection_string.clone();
-                    move |_this, _, event: &CollectionSelected, cx| {
+                    let connection_id = conn.id.clone();
+                    move |this, _, event: &CollectionSelected, cx| {
                         println!("Collection selected: {}.{}", event.0, event.1);
-                        cx.emit(OpenCollectionRequested {
-                            database_name: event.0.clone(),
-                            collection_name: event.1.clone(),
-                            connection_string: connection_string.clone(),
-                        });
-                        cx.notify();
+                        this.open_collection(&connection_id, &event.0, &event.1, cx);
                     }
                 })
                 .detach();
 
+                // Ask for the password of connections that don't save it
+                cx.subscribe(&browser, |this, _, event: &PasswordRequired, cx| {
+                    let conn_id = event.0.clone();
+                    this.with_password(&event.0, cx, move |this, cx| {
+                        this.retry_connection(&conn_id, cx);
+                    });
+                })
+                .detach();
+
                 // Subscribe to database context menu events
                 cx.subscribe(&browser, |this, _, event: &DatabaseContextMenuRequested, cx| {
                     // We need window access but subscribe doesn't provide it
@@ -188,6 +238,7 @@ impl Sidebar {
             connections,
             expanded_connections,
             connection_browsers,
+            session_passwords: std::collections::HashMap::new(),
             is_refreshing: false,
             type_filter: std::collections::HashSet::new(),
             context_menu: None,
@@ -200,7 +251,157 @@ impl Sidebar {
     }
 
     pub fn refresh_connections(&mut self, _cx: &mut Context<Self>) {
-        self.connections = self.storage.get_all().unwrap_or_default();
+        self.connections = self.load_connections();
+    }
+
+    /// Saved connections, with any passwords entered this session filled in
+    fn load_connections(&self) -> Vec<Connection> {
+        let mut connections = self.storage.get_all().unwrap_or_default();
+        for conn in &mut connections {
+            if conn.needs_password() {
+                conn.password = self.session_passwords.get(&conn.id).cloned();
+            }
+        }
+        connections
+    }
+
+    /// Run `then` once the connection has a password, asking for it first if the
+    /// connection doesn't save it and none was entered this session
+    fn with_password(
+        &mut self,
+        conn_id: &str,
+        cx: &mut Context<Self>,
+        then: impl FnOnce(&mut Self, &mut Context<Self>) + 'static,
+    ) {
+        let Some(conn) = self.connections.iter().find(|c| c.id == conn_id) else {
+            return;
+        };
+        if !conn.needs_password() {
+            then(self, cx);
+            return;
+        }
+
+        let sidebar = cx.entity().downgrade();
+        let conn_id = conn_id.to_string();
+        PasswordPrompt::open(
+            conn.name.clone(),
+            conn.username.clone(),
+            move |password, cx| {
+                sidebar
+                    .update(cx, |this, cx| {
+                        this.set_session_password(&conn_id, password, cx);
+                        then(this, cx);
+                    })
+                    .ok();
+            },
+            cx,
+        );
+    }
+
+    /// Remember a password entered on connect until the app closes
+    fn set_session_password(&mut self, conn_id: &str, password: String, cx: &mut Context<Self>) {
+        if let Some(conn) = self.connections.iter_mut().find(|c| c.id == conn_id) {
+            conn.password = Some(password.clone());
+        }
+        if let Some(browser) = self.connection_browsers.get(conn_id) {
+            browser.update(cx, |browser, _| browser.set_password(password.clone()));
+        }
+        self.session_passwords.insert(conn_id.to_string(), password);
+    }
+
+    fn open_collection(
+        &mut self,
+        conn_id: &str,
+        database_name: &str,
+        collection_name: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let connection_id = conn_id.to_string();
+        let database_name = database_name.to_string();
+        let collection_name = collection_name.to_string();
+        self.with_password(conn_id, cx, move |this, cx| {
+            let Some(conn) = this.connections.iter().find(|c| c.id == connection_id) else {
+                return;
+            };
+            cx.emit(OpenCollectionRequested {
+                connection_id: connection_id.clone(),
+                database_name,
+                collection_name,
+                config: conn.config(),
+            });
+            cx.notify();
+        });
+    }
+
+    /// Open a query console (empty database_name = connection default)
+    fn open_query_console(&mut self, conn_id: &str, database_name: &str, cx: &mut Context<Self>) {
+        let connection_id = conn_id.to_string();
+        let database_name = database_name.to_string();
+        self.with_password(conn_id, cx, move |this, cx| {
+            let Some(conn) = this.connections.iter().find(|c| c.id == connection_id) else {
+                return;
+            };
+            cx.emit(OpenQueryConsoleRequested {
+                connection_id: connection_id.clone(),
+                database_name,
+                config: conn.config(),
+            });
+        });
+    }
+
+    /// Open the ER diagram of a SQL database
+    fn open_diagram(&mut self, conn_id: &str, database_name: &str, cx: &mut Context<Self>) {
+        let connection_id = conn_id.to_string();
+        let database_name = database_name.to_string();
+        self.with_password(conn_id, cx, move |this, cx| {
+            let Some(conn) = this.connections.iter().find(|c| c.id == connection_id) else {
+                return;
+            };
+            cx.emit(OpenDiagramRequested {
+                connection_id: connection_id.clone(),
+                database_name,
+                config: conn.config(),
+            });
+        });
+    }
+
+    /// Open the properties of a database, or of a collection when one is given
+    fn open_properties(
+        &mut self,
+        conn_id: &str,
+        database_name: &str,
+        collection_name: Option<&str>,
+        cx: &mut Context<Self>,
+    ) {
+        let connection_id = conn_id.to_string();
+        let database_name = database_name.to_string();
+        let collection_name = collection_name.map(|name| name.to_string());
+        self.with_password(conn_id, cx, move |this, cx| {
+            let Some(conn) = this.connections.iter().find(|c| c.id == connection_id) else {
+                return;
+            };
+            cx.emit(OpenPropertiesRequested {
+                connection_id: connection_id.clone(),
+                database_name,
+                collection_name,
+                config: conn.config(),
+            });
+        });
+    }
+
+    /// Open the live status dashboard of a connection's server
+    fn open_server_status(&mut self, conn_id: &str, cx: &mut Context<Self>) {
+        let connection_id = conn_id.to_string();
+        self.with_password(conn_id, cx, move |this, cx| {
+            let Some(conn) = this.connections.iter().find(|c| c.id == connection_id) else {
+                return;
+            };
+            cx.emit(OpenServerStatusRequested {
+                connection_id: connection_id.clone(),
+                connection_name: conn.name.clone(),
+                config: conn.config(),
+            });
+        });
     }
 
     pub fn set_width(&mut self, width: Pixels) {
@@ -305,7 +506,7 @@ impl Sidebar {
         cx.notify();
 
         // Refresh connections from storage
-        self.connections = self.storage.get_all().unwrap_or_default();
+        self.connections = self.load_connections();
 
         // Also refresh all expanded connection browsers
         let expanded_ids: Vec<String> = self.expanded_connections.iter().cloned().collect();
@@ -348,6 +549,20 @@ impl Sidebar {
         }
     }
 
+    /// Close the live connection and collapse its tree; expanding it again reconnects
+    fn disconnect_connection(&mut self, conn_id: &str, cx: &mut Context<Self>) {
+        registry().disconnect(conn_id);
+        self.expanded_connections.remove(conn_id);
+        self.connection_browsers.remove(conn_id);
+
+        if self.database_picker_connection_id.as_deref() == Some(conn_id) {
+            self.database_picker = None;
+            self.database_picker_connection_id = None;
+        }
+
+        cx.notify();
+    }
+
     /// Remove a connection from storage and UI state
     fn remove_connection(&mut self, conn_id: &str, cx: &mut Context<Self>) {
         // Delete from SQLite storage
@@ -360,6 +575,7 @@ impl Sidebar {
         self.connections.retain(|c| c.id != conn_id);
         self.expanded_connections.remove(conn_id);
         self.connection_browsers.remove(conn_id);
+        registry().disconnect(conn_id);
 
         // Close picker if open for this connection
         if self.database_picker_connection_id.as_deref() == Some(conn_id) {
@@ -488,12 +704,14 @@ impl Sidebar {
         let conn_name = conn.name.clone();
         let conn_id = conn.id.clone();
         let conn_for_edit = conn.clone();
+        let is_connected = registry().is_connected(&conn_id);
+        let is_sqlite = conn.db_type == DatabaseType::SQLite;
 
         // Capture sidebar entity for use in menu item callbacks
         let sidebar_entity = cx.entity().clone();
 
         let menu = PopupMenu::build(window, cx, move |menu, _window, _cx| {
-            menu.item(
+            let mut menu = menu.item(
                 PopupMenuItem::new("Properties")
                     .icon(gpui_component::IconName::Settings)
                     .on_click({
@@ -508,9 +726,36 @@ impl Sidebar {
             )
             .item(
                 PopupMenuItem::new("Query Console")
-                    .icon(gpui_component::IconName::SquareTerminal),
-            )
-            .item(
+                    .icon(gpui_component::IconName::SquareTerminal)
+                    .on_click({
+                        let conn_id = conn_id.clone();
+                        let entity = sidebar_entity.clone();
+                        move |_, _, cx| {
+                            entity.update(cx, |sidebar, cx| {
+                                sidebar.open_query_console(&conn_id, "", cx);
+                            });
+                        }
+                    }),
+            );
+
+            // SQLite files have no server to watch
+            if !is_sqlite {
+                menu = menu.item(
+                    PopupMenuItem::new("Server Status")
+                        .icon(gpui_component::IconName::ChartPie)
+                        .on_click({
+                            let conn_id = conn_id.clone();
+                            let entity = sidebar_entity.clone();
+                            move |_, _, cx| {
+                                entity.update(cx, |sidebar, cx| {
+                                    sidebar.open_server_status(&conn_id, cx);
+                                });
+                            }
+                        }),
+                );
+            }
+
+            menu = menu.item(
                 PopupMenuItem::new("Copy")
                     .icon(gpui_component::IconName::Copy)
                     .on_click({
@@ -532,8 +777,26 @@ impl Sidebar {
                             });
                         }
                     }),
-            )
-            .separator()
+            );
+
+            // Only offer Disconnect while a live connection exists
+            if is_connected {
+                menu = menu.item(
+                    PopupMenuItem::new("Disconnect")
+                        .icon(gpui_component::IconName::Close)
+                        .on_click({
+                            let entity = sidebar_entity.clone();
+                            let conn_id = conn_id.clone();
+                            move |_, _, cx| {
+                                entity.update(cx, |sidebar, cx| {
+                                    sidebar.disconnect_connection(&conn_id, cx);
+                                });
+                            }
+                        }),
+                );
+            }
+
+            menu.separator()
             .item(
                 PopupMenuItem::new("Remove Connection")
                     .icon(gpui_component::IconName::Delete)
@@ -564,7 +827,7 @@ impl Sidebar {
         self.context_menu = Some(menu);
         self.context_menu_position = Some(position);
         self._context_menu_subscription = Some(subscription);
-        self.context_menu_target = Some(ContextMenuTarget::Connection(conn));
+        self.context_menu_target = Some(ContextMenuTarget::Connection(Box::new(conn)));
         cx.notify();
     }
 
@@ -590,7 +853,7 @@ impl Sidebar {
             .connections
             .iter()
             .find(|c| c.id == conn_id)
-            .map(|c| (c.get_connection_string(), c.db_type));
+            .map(|c| c.config());
 
         // Capture sidebar entity for use in menu item callbacks
         let sidebar_entity = cx.entity().clone();
@@ -601,10 +864,45 @@ impl Sidebar {
 
         let menu = PopupMenu::build(window, cx, move |menu, _window, _cx| {
             let mut menu = menu
-                .item(
-                    PopupMenuItem::new("Query Console")
-                        .icon(gpui_component::IconName::SquareTerminal),
-                )
+                .item(query_console_item(
+                    conn_id.clone(),
+                    db_name.clone(),
+                    sidebar_entity.clone(),
+                ));
+
+            // Foreign keys only exist between SQL tables
+            if conn_info.as_ref().is_some_and(|config| config.driver.is_sql()) {
+                let entity = sidebar_entity.clone();
+                let conn_id = conn_id.clone();
+                let db_name = db_name.clone();
+                menu = menu.item(
+                    PopupMenuItem::new("ER Diagram")
+                        .icon(gpui_component::IconName::LayoutDashboard)
+                        .on_click(move |_, _, cx| {
+                            entity.update(cx, |sidebar, cx| {
+                                sidebar.open_diagram(&conn_id, &db_name, cx);
+                            });
+                        }),
+                );
+            }
+
+            // Redis has no statistics per database
+            if conn_info.as_ref().is_some_and(|config| config.driver != DatabaseType::Redis) {
+                let entity = sidebar_entity.clone();
+                let conn_id = conn_id.clone();
+                let db_name = db_name.clone();
+                menu = menu.item(
+                    PopupMenuItem::new("Properties")
+                        .icon(gpui_component::IconName::Info)
+                        .on_click(move |_, _, cx| {
+                            entity.update(cx, |sidebar, cx| {
+                                sidebar.open_properties(&conn_id, &db_name, None, cx);
+                            });
+                        }),
+                );
+            }
+
+            menu = menu
                 .item(
                     PopupMenuItem::new("Copy")
                         .icon(gpui_component::IconName::Copy)
@@ -632,7 +930,7 @@ impl Sidebar {
                 .separator();
 
             // Add Drop action if we have connection info
-            if let Some((connection_string, db_type)) = conn_info.clone() {
+            if let Some(config) = conn_info.clone() {
                 let db_name_for_drop = db_name.clone();
                 let entity = sidebar_entity.clone();
                 let conn_id_for_refresh = conn_id.clone();
@@ -640,23 +938,28 @@ impl Sidebar {
                     PopupMenuItem::new("Drop Database")
                         .icon(gpui_component::IconName::TriangleAlert)
                         .on_click(move |_, _, cx| {
-                            let connection_string = connection_string.clone();
-                            let db_type = db_type;
+                            let config = config.clone();
                             let db_name = db_name_for_drop.clone();
                             let entity = entity.clone();
                             let conn_id = conn_id_for_refresh.clone();
+                            let task = runtime::spawn({
+                                let conn_id = conn_id.clone();
+                                let db_name = db_name.clone();
+                                async move {
+                                    registry().get(&conn_id, config).await?.drop_database(&db_name).await
+                                }
+                            });
                             cx.spawn(async move |cx| {
-                                let config = ConnectionConfig::new(db_type, connection_string);
-                                if let Ok(conn) = create_connection(config) {
-                                    if let Err(e) = conn.drop_database(&db_name).await {
-                                        eprintln!("Failed to drop database {}: {}", db_name, e);
-                                    } else {
+                                match task.await {
+                                    Ok(Ok(())) => {
                                         eprintln!("Dropped database: {}", db_name);
                                         // Refresh the sidebar to reflect the change
                                         entity.update(cx, |sidebar, cx| {
                                             sidebar.refresh_single_connection(&conn_id, cx);
                                         }).ok();
                                     }
+                                    Ok(Err(e)) => eprintln!("Failed to drop database {}: {}", db_name, e),
+                                    Err(e) => eprintln!("Failed to drop database {}: {}", db_name, e),
                                 }
                             })
                             .detach();
@@ -714,7 +1017,7 @@ impl Sidebar {
             .connections
             .iter()
             .find(|c| c.id == conn_id)
-            .map(|c| (c.get_connection_string(), c.db_type));
+            .map(|c| c.config());
 
         // Capture sidebar entity for use in menu item callbacks
         let sidebar_entity = cx.entity().clone();
@@ -726,10 +1029,30 @@ impl Sidebar {
 
         let menu = PopupMenu::build(window, cx, move |menu, _window, _cx| {
             let mut menu = menu
-                .item(
-                    PopupMenuItem::new("Query Console")
-                        .icon(gpui_component::IconName::SquareTerminal),
-                )
+                .item(query_console_item(
+                    conn_id.clone(),
+                    db_name.clone(),
+                    sidebar_entity.clone(),
+                ));
+
+            // Redis key groups have no statistics of their own
+            if conn_info.as_ref().is_some_and(|config| config.driver != DatabaseType::Redis) {
+                let entity = sidebar_entity.clone();
+                let conn_id = conn_id.clone();
+                let db_name = db_name.clone();
+                let coll_name = coll_name.clone();
+                menu = menu.item(
+                    PopupMenuItem::new("Properties")
+                        .icon(gpui_component::IconName::Info)
+                        .on_click(move |_, _, cx| {
+                            entity.update(cx, |sidebar, cx| {
+                                sidebar.open_properties(&conn_id, &db_name, Some(&coll_name), cx);
+                            });
+                        }),
+                );
+            }
+
+            menu = menu
                 .item(
                     PopupMenuItem::new("Copy")
                         .icon(gpui_component::IconName::Copy)
@@ -757,7 +1080,7 @@ impl Sidebar {
                 .separator();
 
             // Add Drop action if we have connection info
-            if let Some((connection_string, db_type)) = conn_info.clone() {
+            if let Some(config) = conn_info.clone() {
                 let db_name_for_drop = db_name.clone();
                 let coll_name_for_drop = coll_name.clone();
                 let entity = sidebar_entity.clone();
@@ -766,24 +1089,30 @@ impl Sidebar {
                     PopupMenuItem::new("Drop Collection")
                         .icon(gpui_component::IconName::TriangleAlert)
                         .on_click(move |_, _, cx| {
-                            let connection_string = connection_string.clone();
-                            let db_type = db_type;
+                            let config = config.clone();
                             let db_name = db_name_for_drop.clone();
                             let coll_name = coll_name_for_drop.clone();
                             let entity = entity.clone();
                             let conn_id = conn_id_for_refresh.clone();
+                            let task = runtime::spawn({
+                                let conn_id = conn_id.clone();
+                                let db_name = db_name.clone();
+                                let coll_name = coll_name.clone();
+                                async move {
+                                    registry().get(&conn_id, config).await?.drop_collection(&db_name, &coll_name).await
+                                }
+                            });
                             cx.spawn(async move |cx| {
-                                let config = ConnectionConfig::new(db_type, connection_string);
-                                if let Ok(conn) = create_connection(config) {
-                                    if let Err(e) = conn.drop_collection(&db_name, &coll_name).await {
-                                        eprintln!("Failed to drop collection {}.{}: {}", db_name, coll_name, e);
-                                    } else {
+                                match task.await {
+                                    Ok(Ok(())) => {
                                         eprintln!("Dropped collection: {}.{}", db_name, coll_name);
                                         // Refresh the sidebar to reflect the change
                                         entity.update(cx, |sidebar, cx| {
                                             sidebar.refresh_single_connection(&conn_id, cx);
                                         }).ok();
                                     }
+                                    Ok(Err(e)) => eprintln!("Failed to drop collection {}.{}: {}", db_name, coll_name, e),
+                                    Err(e) => eprintln!("Failed to drop collection {}.{}: {}", db_name, coll_name, e),
                                 }
                             })
                             .detach();
@@ -967,27 +1296,30 @@ impl Sidebar {
             // Create or get the connection browser for this connection
             if !self.connection_browsers.contains_key(&id) {
                 let browser = cx.new(|_cx| ConnectionBrowser::new(conn.clone()));
-                let connection_string = conn.get_connection_string();
 
                 // Get saved visible databases and show_all state
                 let saved_visible_dbs = conn.visible_databases.clone();
                 let saved_show_all = conn.show_all_databases.unwrap_or(false);
 
                 cx.subscribe(&browser, {
-                    let connection_string = connection_string.clone();
-                    move |_this, _, event: &CollectionSelected, cx| {
+                    let connection_id = conn.id.clone();
+                    move |this, _, event: &CollectionSelected, cx| {
                         println!("Collection selected: {}.{}", event.0, event.1);
                         // Emit event to workspace
-                        cx.emit(OpenCollectionRequested {
-                            database_name: event.0.clone(),
-                            collection_name: event.1.clone(),
-                            connection_string: connection_string.clone(),
-                        });
-                        cx.notify();
+                        this.open_collection(&connection_id, &event.0, &event.1, cx);
                     }
                 })
                 .detach();
 
+                // Ask for the password of connections that don't save it
+                cx.subscribe(&browser, |this, _, event: &PasswordRequired, cx| {
+                    let conn_id = event.0.clone();
+                    this.with_password(&event.0, cx, move |this, cx| {
+                        this.retry_connection(&conn_id, cx);
+                    });
+                })
+                .detach();
+
                 // Subscribe to database context menu events
                 cx.subscribe(&browser, |this, _, event: &DatabaseContextMenuRequested, cx| {
                     this.pending_db_context_menu = Some((event.0.clone(), event.1.clone(), event.2));
@@ -1004,18 +1336,15 @@ impl Sidebar {
 
                 self.connection_browsers.insert(id.clone(), browser.clone());
 
-                // Load databases for MongoDB connections
-                if conn.db_type == DatabaseType::MongoDB {
-                    // Set the initial visible databases before loading (will be applied after load completes)
-                    browser.update(cx, |browser, _cx| {
-                        browser.set_initial_visible_databases(saved_visible_dbs, saved_show_all);
-                    });
+                // Set the initial visible databases before loading (will be applied after load completes)
+                browser.update(cx, |browser, _cx| {
+                    browser.set_initial_visible_databases(saved_visible_dbs, saved_show_all);
+                });
 
-                    browser.update(cx, |browser, cx| {
-                        browser.load_databases(cx);
-                    });
-                }
-            } else if conn.db_type == DatabaseType::MongoDB {
+                browser.update(cx, |browser, cx| {
+                    browser.load_databases(cx);
+                });
+            } else {
                 // Browser already exists - if it's in NotConnected state (preview only),
                 // trigger the actual connection
                 let browser = self.connection_browsers.get(&id).unwrap().clone();
@@ -1260,28 +1589,16 @@ impl Sidebar {
                                 )
                             }),
                     )
-                    // Expanded content - show connection browser for MongoDB
+                    // Expanded content - show connection browser
                     .when(is_expanded, |el| {
-                        if db_type == DatabaseType::MongoDB {
-                            if let Some(browser) = self.connection_browsers.get(&conn_id) {
-                                el.child(
-                                    div()
-                                        .pl(rems(1.75)) // 28px
-                                        .pr(rems(0.25)) // 4px
-                                        .py(rems(0.25)) // 4px
-                                        .child(browser.clone()),
-                                )
-                            } else {
-                                el.child(
-                                    div()
-                                        .pl(rems(2.25)) // 36px
-                                        .pr(rems(0.5)) // 8px
-                                        .py(rems(0.25)) // 4px
-                                        .text_size(rems(0.75))
-                                        .text_color(text_muted)
-                                        .child("Loading..."),
-                                )
-                            }
+                        if let Some(browser) = self.connection_browsers.get(&conn_id) {
+                            el.child(
+                                div()
+                                    .pl(rems(1.75)) // 28px
+                                    .pr(rems(0.25)) // 4px
+                                    .py(rems(0.25)) // 4px
+                                    .child(browser.clone()),
+                            )
                         } else {
                             el.child(
                                 div()
@@ -1290,7 +1607,7 @@ impl Sidebar {
                                     .py(rems(0.25)) // 4px
                                     .text_size(rems(0.75))
                                     .text_color(text_muted)
-                                    .child("Connect to browse..."),
+                                    .child("Loading..."),
                             )
                         }
                     })
@@ -1347,6 +1664,21 @@ impl Sidebar {
     }
 }
 
+/// "Query Console" context menu item for a database or collection row
+fn query_console_item(
+    connection_id: String,
+    database_name: String,
+    sidebar: Entity<Sidebar>,
+) -> PopupMenuItem {
+    PopupMenuItem::new("Query Console")
+        .icon(gpui_component::IconName::SquareTerminal)
+        .on_click(move |_, _, cx| {
+            sidebar.update(cx, |sidebar, cx| {
+                sidebar.open_query_console(&connection_id, &database_name, cx);
+            });
+        })
+}
+
 impl Render for Sidebar {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         // Process pending database context menu request (deferred from subscribe which lacks window)
@@ -0,0 +1,593 @@
+//! Structure of a SQL table as the catalog describes it: columns, keys,
+//! constraints and triggers, next to the DDL that recreates the table
+
+use gpui::{prelude::*, rems, *};
+
+use crate::db::driver::{ConnectionConfig, KeyConstraint, TableStructure};
+use crate::db::error::ConnectionError;
+use crate::db::registry::registry;
+use crate::db::runtime;
+use crate::ui::selectable_text::SelectableTextArea;
+use crate::ui::theme::AppColors;
+use crate::ui::widgets::{render_badge, render_titled_section};
+
+enum StructureState {
+    Loading,
+    Loaded(TableStructure),
+    Failed(String),
+}
+
+pub struct StructureView {
+    connection_id: String,
+    config: ConnectionConfig,
+    database_name: String,
+    collection_name: String,
+    state: StructureState,
+    ddl: Option<String>,
+    ddl_text_area: Option<Entity<SelectableTextArea>>,
+    /// The DDL was copied; cleared on the next load
+    copied: bool,
+}
+
+impl StructureView {
+    pub fn new(
+        connection_id: String,
+        config: ConnectionConfig,
+        database_name: String,
+        collection_name: String,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut view = Self {
+            connection_id,
+            config,
+            database_name,
+            collection_name,
+            state: StructureState::Loading,
+            ddl: None,
+            ddl_text_area: None,
+            copied: false,
+        };
+        view.load(cx);
+        view
+    }
+
+    fn load(&mut self, cx: &mut Context<Self>) {
+        self.state = StructureState::Loading;
+        self.copied = false;
+        cx.notify();
+
+        let connection_id = self.connection_id.clone();
+        let config = self.config.clone();
+        let database_name = self.database_name.clone();
+        let collection_name = self.collection_name.clone();
+        let task = runtime::spawn(async move {
+            let conn = registry().get(&connection_id, config).await?;
+            let structure = conn
+                .table_structure(&database_name, &collection_name)
+                .await?;
+            let ddl = conn.table_ddl(&database_name, &collection_name).await?;
+            Ok::<_, ConnectionError>((structure, ddl))
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |view, cx| {
+                match result {
+                    Ok(Ok((structure, ddl))) => {
+                        view.state = StructureState::Loaded(structure);
+                        match &view.ddl_text_area {
+                            Some(text_area) => {
+                                text_area.update(cx, |text_area, cx| {
+                                    text_area.set_content(ddl.clone(), cx)
+                                });
+                            }
+                            None => {
+                                view.ddl_text_area =
+                                    Some(cx.new(|cx| SelectableTextArea::new(cx, ddl.clone())));
+                            }
+                        }
+                        view.ddl = Some(ddl);
+                    }
+                    Ok(Err(e)) => view.state = StructureState::Failed(e.to_string()),
+                    Err(_) => {
+                        view.state =
+                            StructureState::Failed("Loading the structure was interrupted".into())
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn copy_ddl(&mut self, cx: &mut Context<Self>) {
+        if let Some(ddl) = &self.ddl {
+            cx.write_to_clipboard(ClipboardItem::new_string(ddl.clone()));
+            self.copied = true;
+            cx.notify();
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let summary = match &self.state {
+            StructureState::Loaded(structure) => format!(
+                "{} columns · {} foreign keys · {} triggers",
+                structure.columns.len(),
+                structure.foreign_keys.len(),
+                structure.triggers.len()
+            ),
+            StructureState::Loading => "Loading...".to_string(),
+            StructureState::Failed(_) => String::new(),
+        };
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .justify_between()
+            .w_full()
+            .h(rems(2.25)) // 36px
+            .px(rems(0.75)) // 12px
+            .bg(AppColors::bg_header())
+            .border_b_1()
+            .border_color(AppColors::border())
+            .child(
+                div()
+                    .text_size(rems(0.75)) // 12px
+                    .text_color(AppColors::text_muted())
+                    .child(summary),
+            )
+            .child(
+                div()
+                    .id("refresh-structure")
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.25)) // 4px
+                    .px(rems(0.625)) // 10px
+                    .py(rems(0.3125)) // 5px
+                    .rounded(px(4.0))
+                    .cursor_pointer()
+                    .hover(|s| s.bg(AppColors::bg_hover()))
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.load(cx);
+                    }))
+                    .child(
+                        svg()
+                            .path("icons/refresh.svg")
+                            .size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_dim()),
+                    )
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .text_color(AppColors::text_secondary())
+                            .child("Refresh"),
+                    ),
+            )
+    }
+
+    /// Row of cells with fixed widths; `None` widths take the remaining space
+    fn render_row(cells: Vec<(Option<f32>, AnyElement)>, header: bool) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap(rems(0.75)) // 12px
+            .px(rems(0.75)) // 12px
+            .py(rems(0.25)) // 4px
+            .text_size(if header { rems(0.6875) } else { rems(0.75) }) // 11px / 12px
+            .text_color(if header {
+                AppColors::text_muted()
+            } else {
+                AppColors::text()
+            })
+            .when(!header, |el| el.hover(|s| s.bg(AppColors::bg_hover())))
+            .children(cells.into_iter().map(|(width, content)| {
+                div()
+                    .min_w_0()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .map(|el| match width {
+                        Some(width) => el.w(rems(width)).flex_none(),
+                        None => el.flex_1(),
+                    })
+                    .child(content)
+            }))
+    }
+
+    fn text(text: impl Into<SharedString>, color: Hsla) -> AnyElement {
+        div()
+            .text_color(color)
+            .child(text.into())
+            .into_any_element()
+    }
+
+    fn none() -> impl IntoElement {
+        div()
+            .px(rems(0.75)) // 12px
+            .text_size(rems(0.75)) // 12px
+            .text_color(AppColors::text_dim())
+            .child("None")
+    }
+
+    fn render_columns(structure: &TableStructure) -> impl IntoElement {
+        let header = |label: &'static str| Self::text(label, AppColors::text_muted());
+        let key_columns = structure
+            .primary_key
+            .as_ref()
+            .map(|key| key.columns.as_slice())
+            .unwrap_or_default();
+
+        div()
+            .flex()
+            .flex_col()
+            .child(Self::render_row(
+                vec![
+                    (Some(12.0), header("Name")),
+                    (Some(10.0), header("Type")),
+                    (Some(4.0), header("Null")),
+                    (Some(12.0), header("Default")),
+                    (Some(7.0), header("Collation")),
+                    (None, header("Comment")),
+                ],
+                true,
+            ))
+            .children(structure.columns.iter().map(|column| {
+                let name = div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(rems(0.375)) // 6px
+                    .child(column.name.clone())
+                    .when(key_columns.contains(&column.name), |el| {
+                        el.child(render_badge("PK", AppColors::accent()))
+                    })
+                    .into_any_element();
+                let default = match (&column.generated, &column.default) {
+                    (Some(generated), _) => Self::text(generated.clone(), AppColors::warning()),
+                    (None, Some(default)) => {
+                        Self::text(default.clone(), AppColors::text_secondary())
+                    }
+                    (None, None) => Self::text("", AppColors::text_dim()),
+                };
+                Self::render_row(
+                    vec![
+                        (Some(12.0), name),
+                        (
+                            Some(10.0),
+                            Self::text(column.data_type.clone(), AppColors::accent()),
+                        ),
+                        (
+                            Some(4.0),
+                            Self::text(
+                                if column.nullable { "YES" } else { "NO" },
+                                AppColors::text_secondary(),
+                            ),
+                        ),
+                        (Some(12.0), default),
+                        (
+                            Some(7.0),
+                            Self::text(
+                                column.collation.clone().unwrap_or_default(),
+                                AppColors::text_secondary(),
+                            ),
+                        ),
+                        (
+                            None,
+                            Self::text(
+                                column.comment.clone().unwrap_or_default(),
+                                AppColors::text_muted(),
+                            ),
+                        ),
+                    ],
+                    false,
+                )
+            }))
+    }
+
+    fn render_keys(structure: &TableStructure) -> AnyElement {
+        let keys: Vec<(&'static str, &KeyConstraint)> = structure
+            .primary_key
+            .iter()
+            .map(|key| ("PRIMARY KEY", key))
+            .chain(
+                structure
+                    .unique_constraints
+                    .iter()
+                    .map(|key| ("UNIQUE", key)),
+            )
+            .collect();
+        if keys.is_empty() {
+            return Self::none().into_any_element();
+        }
+
+        div()
+            .flex()
+            .flex_col()
+            .children(keys.into_iter().map(|(kind, key)| {
+                Self::render_row(
+                    vec![
+                        (Some(8.0), Self::text(kind, AppColors::accent())),
+                        (
+                            Some(16.0),
+                            Self::text(key.name.clone().unwrap_or_default(), AppColors::text()),
+                        ),
+                        (
+                            None,
+                            Self::text(key.columns.join(", "), AppColors::text_secondary()),
+                        ),
+                    ],
+                    false,
+                )
+            }))
+            .into_any_element()
+    }
+
+    fn render_checks(structure: &TableStructure) -> AnyElement {
+        if structure.check_constraints.is_empty() {
+            return Self::none().into_any_element();
+        }
+
+        div()
+            .flex()
+            .flex_col()
+            .children(structure.check_constraints.iter().map(|check| {
+                Self::render_row(
+                    vec![
+                        (
+                            Some(16.0),
+                            Self::text(check.name.clone().unwrap_or_default(), AppColors::text()),
+                        ),
+                        (
+                            None,
+                            Self::text(check.expression.clone(), AppColors::text_secondary()),
+                        ),
+                    ],
+                    false,
+                )
+            }))
+            .into_any_element()
+    }
+
+    fn render_foreign_keys(structure: &TableStructure) -> AnyElement {
+        if structure.foreign_keys.is_empty() {
+            return Self::none().into_any_element();
+        }
+
+        div()
+            .flex()
+            .flex_col()
+            .children(structure.foreign_keys.iter().map(|key| {
+                let target = if key.referenced_columns.is_empty() {
+                    key.referenced_table.clone()
+                } else {
+                    format!(
+                        "{}({})",
+                        key.referenced_table,
+                        key.referenced_columns.join(", ")
+                    )
+                };
+                let actions = [("ON UPDATE", &key.on_update), ("ON DELETE", &key.on_delete)]
+                    .into_iter()
+                    .filter_map(|(clause, action)| {
+                        action
+                            .as_ref()
+                            .map(|action| format!("{} {}", clause, action))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Self::render_row(
+                    vec![
+                        (
+                            Some(16.0),
+                            Self::text(key.name.clone().unwrap_or_default(), AppColors::text()),
+                        ),
+                        (
+                            Some(16.0),
+                            Self::text(
+                                format!("{} → {}", key.columns.join(", "), target),
+                                AppColors::text_secondary(),
+                            ),
+                        ),
+                        (None, Self::text(actions, AppColors::text_muted())),
+                    ],
+                    false,
+                )
+            }))
+            .into_any_element()
+    }
+
+    fn render_triggers(structure: &TableStructure) -> AnyElement {
+        if structure.triggers.is_empty() {
+            return Self::none().into_any_element();
+        }
+
+        div()
+            .flex()
+            .flex_col()
+            .children(structure.triggers.iter().map(|trigger| {
+                Self::render_row(
+                    vec![
+                        (
+                            Some(12.0),
+                            Self::text(trigger.name.clone(), AppColors::text()),
+                        ),
+                        (
+                            Some(12.0),
+                            Self::text(
+                                format!("{} {}", trigger.timing, trigger.events),
+                                AppColors::accent(),
+                            ),
+                        ),
+                        (
+                            None,
+                            Self::text(
+                                trigger.definition.replace('\n', " "),
+                                AppColors::text_muted(),
+                            ),
+                        ),
+                    ],
+                    false,
+                )
+            }))
+            .into_any_element()
+    }
+
+    fn render_structure(structure: &TableStructure) -> impl IntoElement {
+        div()
+            .id("table-structure")
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .overflow_y_scroll()
+            .child(render_titled_section(
+                "Columns",
+                Some(structure.columns.len()),
+                Self::render_columns(structure),
+            ))
+            .child(render_titled_section(
+                "Keys",
+                Some(structure.primary_key.iter().count() + structure.unique_constraints.len()),
+                Self::render_keys(structure),
+            ))
+            .child(render_titled_section(
+                "Check constraints",
+                Some(structure.check_constraints.len()),
+                Self::render_checks(structure),
+            ))
+            .child(render_titled_section(
+                "Foreign keys",
+                Some(structure.foreign_keys.len()),
+                Self::render_foreign_keys(structure),
+            ))
+            .child(render_titled_section(
+                "Triggers",
+                Some(structure.triggers.len()),
+                Self::render_triggers(structure),
+            ))
+    }
+
+    fn render_ddl(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .w(rems(30.0)) // 480px
+            .h_full()
+            .bg(AppColors::bg_secondary())
+            .border_l_1()
+            .border_color(AppColors::border_subtle())
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .h(rems(2.0)) // 32px
+                    .px(rems(0.75)) // 12px
+                    .bg(AppColors::bg_header())
+                    .border_b_1()
+                    .border_color(AppColors::border_subtle())
+                    .child(
+                        div()
+                            .text_size(rems(0.75)) // 12px
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(AppColors::text())
+                            .child("DDL"),
+                    )
+                    .child(
+                        div()
+                            .id("copy-ddl")
+                            .flex()
+                            .flex_row()
+                            .items_center()
+                            .gap(rems(0.25)) // 4px
+                            .px(rems(0.5)) // 8px
+                            .py(rems(0.1875)) // 3px
+                            .rounded(px(3.0))
+                            .cursor_pointer()
+                            .hover(|s| s.bg(AppColors::bg_hover()))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.copy_ddl(cx);
+                            }))
+                            .child(
+                                svg()
+                                    .path(if self.copied {
+                                        "icons/check.svg"
+                                    } else {
+                                        "icons/copy.svg"
+                                    })
+                                    .size(rems(0.75)) // 12px
+                                    .text_color(if self.copied {
+                                        AppColors::success()
+                                    } else {
+                                        AppColors::text_dim()
+                                    }),
+                            )
+                            .child(
+                                div()
+                                    .text_size(rems(0.6875)) // 11px
+                                    .text_color(AppColors::text_secondary())
+                                    .child(if self.copied { "Copied" } else { "Copy" }),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .id("ddl-scroll")
+                    .flex_1()
+                    .min_h_0()
+                    .p(rems(0.75)) // 12px
+                    .overflow_y_scroll()
+                    .overflow_x_scroll()
+                    .font_family("Fira Code")
+                    .when_some(self.ddl_text_area.clone(), |el, text_area| {
+                        el.child(text_area)
+                    }),
+            )
+    }
+}
+
+impl Render for StructureView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let message = |text: String, color: Hsla| {
+            div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .p(rems(1.0)) // 16px
+                .child(
+                    div()
+                        .text_size(rems(0.8125)) // 13px
+                        .text_color(color)
+                        .child(text),
+                )
+                .into_any_element()
+        };
+        let body = match &self.state {
+            StructureState::Loading => {
+                message("Loading structure...".to_string(), AppColors::text_muted())
+            }
+            StructureState::Failed(error) => message(error.clone(), AppColors::error()),
+            StructureState::Loaded(structure) => div()
+                .flex()
+                .flex_row()
+                .size_full()
+                .child(Self::render_structure(structure))
+                .child(self.render_ddl(cx))
+                .into_any_element(),
+        };
+
+        div()
+            .id("structure-view")
+            .flex()
+            .flex_col()
+            .size_full()
+            .bg(AppColors::bg_main())
+            .child(self.render_toolbar(cx))
+            .child(div().flex_1().min_h_0().w_full().child(body))
+    }
+}
@@ -4,6 +4,7 @@
 //! the same public interface as the original custom implementation.
 
 use std::cell::RefCell;
+use std::collections::{BTreeSet, HashSet};
 use std::rc::Rc;
 
 use gpui::{prelude::*, rems, *};
@@ -16,6 +17,17 @@ use crate::ui::selectable_text::SelectableTextArea;
 use crate::ui::text_input::TextInput;
 use crate::ui::theme::AppColors;
 
+// Define actions for the in-cell editor
+actions!(table_view, [ConfirmCellEdit, CancelCellEdit]);
+
+/// Register cell editor key bindings
+pub fn register_table_view_bindings(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("enter", ConfirmCellEdit, Some("CellEditor")),
+        KeyBinding::new("escape", CancelCellEdit, Some("CellEditor")),
+    ]);
+}
+
 /// Items per page
 pub const PAGE_SIZE: usize = 20;
 
@@ -105,6 +117,27 @@ impl Row {
     }
 }
 
+/// Pending-change highlighting, by page-local row index
+#[derive(Clone, Default)]
+pub struct CellMarks {
+    /// Cells holding an edited value (row_ix, col_ix)
+    pub modified: HashSet<(usize, usize)>,
+    /// Rows that will be deleted
+    pub deleted_rows: HashSet<usize>,
+    /// Rows that will be inserted
+    pub inserted_rows: HashSet<usize>,
+}
+
+/// Text input shown in place of a cell while it is being edited
+#[derive(Clone)]
+struct CellEditor {
+    row_ix: usize,
+    col_ix: usize,
+    input: Entity<TextInput>,
+    /// Focus the input on the next render (needs window access)
+    focus_pending: bool,
+}
+
 // ── Events ──────────────────────────────────────────────────────────────
 
 /// Event emitted when a row is selected
@@ -112,12 +145,10 @@ impl Row {
 #[allow(dead_code)]
 pub struct RowSelected(pub usize);
 
-/// Event emitted when a cell is double-clicked (for copy)
+/// Event emitted when a cell is double-clicked (to copy or edit it)
 #[derive(Clone)]
 pub struct CellDoubleClicked {
-    #[allow(dead_code)]
     pub row_index: usize,
-    #[allow(dead_code)]
     pub col_index: usize,
     pub value: SharedString,
 }
@@ -174,6 +205,32 @@ pub struct SortQuerySubmitted {
     pub query: String,
 }
 
+/// Event: the toolbar's add button ("Add Row", "Insert Document") clicked
+#[derive(Clone)]
+pub struct AddRowRequested;
+
+/// Event: "Export" clicked in the toolbar
+#[derive(Clone)]
+pub struct ExportRequested;
+
+/// Event: "Import" clicked in the toolbar
+#[derive(Clone)]
+pub struct ImportRequested;
+
+/// Event: "Explain" clicked next to the filter, with the filter as typed
+#[derive(Clone)]
+pub struct ExplainRequested {
+    pub query: String,
+}
+
+/// Event: Enter pressed in the cell editor
+#[derive(Clone)]
+pub struct CellEditSubmitted {
+    pub row_index: usize,
+    pub col_name: String,
+    pub text: String,
+}
+
 // ── Table Delegate ──────────────────────────────────────────────────────
 
 /// Shared state for tracking cell interactions between delegate and TableView
@@ -189,6 +246,31 @@ pub struct CellInteractionState {
     pub pending_header_context_menu: Option<(usize, Point<Pixels>)>,
     /// Flag to indicate a right-click just happened (for context menu tracking)
     pub right_click_pending: bool,
+    /// Rows picked for row actions such as export: a click picks one row,
+    /// Ctrl-click toggles a row and Shift-click extends from the last picked row
+    pub selected_rows: BTreeSet<usize>,
+    /// Row that Shift-click extends the selection from
+    pub selection_anchor: Option<usize>,
+}
+
+impl CellInteractionState {
+    fn select_row(&mut self, row_ix: usize, modifiers: Modifiers) {
+        match self.selection_anchor {
+            Some(anchor) if modifiers.shift => {
+                self.selected_rows = (anchor.min(row_ix)..=anchor.max(row_ix)).collect();
+            }
+            _ if modifiers.secondary() => {
+                if !self.selected_rows.remove(&row_ix) {
+                    self.selected_rows.insert(row_ix);
+                }
+                self.selection_anchor = Some(row_ix);
+            }
+            _ => {
+                self.selected_rows = BTreeSet::from([row_ix]);
+                self.selection_anchor = Some(row_ix);
+            }
+        }
+    }
 }
 
 /// Delegate for the gpui-component Table
@@ -205,6 +287,10 @@ pub struct TableViewDelegate {
     sort_direction: Option<SortDirection>,
     /// Shared interaction state
     interaction_state: Rc<RefCell<CellInteractionState>>,
+    /// Cell currently being edited (row_ix, col_ix, input)
+    editor: Option<(usize, usize, Entity<TextInput>)>,
+    /// Pending-change highlighting
+    marks: CellMarks,
 }
 
 impl TableViewDelegate {
@@ -216,6 +302,8 @@ impl TableViewDelegate {
             sort_field: None,
             sort_direction: None,
             interaction_state,
+            editor: None,
+            marks: CellMarks::default(),
         }
     }
 
@@ -301,12 +389,36 @@ impl TableDelegate for TableViewDelegate {
             .map(|v| v.len() > MAX_CELL_DISPLAY_LENGTH)
             .unwrap_or(false);
 
-        let text_color = if is_truncated {
+        let is_deleted = self.marks.deleted_rows.contains(&row_ix);
+        let is_modified = self.marks.modified.contains(&(row_ix, col_ix));
+        let is_inserted = self.marks.inserted_rows.contains(&row_ix);
+
+        let text_color = if is_deleted {
+            AppColors::error()
+        } else if is_truncated {
             AppColors::truncated_text()
         } else {
             cx.theme().foreground
         };
 
+        // The cell being edited renders its input instead of the value
+        let editor = self
+            .editor
+            .as_ref()
+            .filter(|(r, c, _)| *r == row_ix && *c == col_ix)
+            .map(|(_, _, input)| input.clone());
+        if let Some(input) = editor {
+            return div()
+                .id(SharedString::from(format!("cell-{}-{}", row_ix, col_ix)))
+                .size_full()
+                .flex()
+                .items_center()
+                .key_context("CellEditor")
+                .text_size(rems(0.75)) // 12px
+                .child(input)
+                .into_any_element();
+        }
+
         // Check if this cell is selected
         let is_selected = self
             .interaction_state
@@ -314,6 +426,11 @@ impl TableDelegate for TableViewDelegate {
             .selected_cell
             .map(|(r, c)| r == row_ix && c == col_ix)
             .unwrap_or(false);
+        // Rows of a multi-row selection are tinted; a single row shows its cell
+        let in_selected_rows = {
+            let state = self.interaction_state.borrow();
+            state.selected_rows.len() > 1 && state.selected_rows.contains(&row_ix)
+        };
 
         let interaction_state = self.interaction_state.clone();
         let interaction_state_for_right_click = self.interaction_state.clone();
@@ -326,10 +443,11 @@ impl TableDelegate for TableViewDelegate {
             .flex()
             .items_center()
             // Track which cell was clicked (for CellClicked event) and select it
-            .on_mouse_down(MouseButton::Left, move |_, _, _| {
+            .on_mouse_down(MouseButton::Left, move |event: &MouseDownEvent, _, _| {
                 let mut state = interaction_state.borrow_mut();
                 state.last_clicked_cell = Some((row_ix, col_ix));
                 state.selected_cell = Some((row_ix, col_ix));
+                state.select_row(row_ix, event.modifiers);
             })
             // Right-click for context menu - capture window position using canvas bounds
             .on_mouse_down(MouseButton::Right, move |_event, window, _| {
@@ -338,6 +456,35 @@ impl TableDelegate for TableViewDelegate {
                 state.pending_context_menu = Some((row_ix, col_ix, window_position));
                 state.right_click_pending = true;
             })
+            // Pending-change tint, under the selection highlight
+            .when(is_modified || is_deleted || is_inserted, |this| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top(px(-4.0))
+                        .left(px(-8.0))
+                        .right(px(-8.0))
+                        .bottom(px(-4.0))
+                        .bg(if is_deleted {
+                            AppColors::bg_cell_deleted()
+                        } else if is_inserted {
+                            AppColors::bg_cell_inserted()
+                        } else {
+                            AppColors::bg_cell_modified()
+                        }),
+                )
+            })
+            .when(in_selected_rows && !is_selected, |this| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top(px(-4.0))
+                        .left(px(-8.0))
+                        .right(px(-8.0))
+                        .bottom(px(-4.0))
+                        .bg(AppColors::bg_cell_selected()),
+                )
+            })
             // Cell selection highlight - absolute overlay extending to cell boundaries
             // Uses negative insets to extend beyond content padding to cell edges
             // Default cell padding is: top/bottom=4px, left/right=8px
@@ -363,8 +510,10 @@ impl TableDelegate for TableViewDelegate {
                     .whitespace_nowrap()
                     .text_color(text_color)
                     .text_size(rems(0.75)) // 12px
+                    .when(is_deleted, |el| el.line_through())
                     .child(value),
             )
+            .into_any_element()
     }
 
     fn render_th(
@@ -423,6 +572,8 @@ pub struct TableView {
     rows: Vec<Row>,
     current_page: usize,
     total_items: usize,
+    /// `total_items` is a lower bound (shown as "≥ N")
+    total_at_least: bool,
     sort_field: Option<String>,
     sort_direction: Option<SortDirection>,
     filter_query: String,
@@ -435,6 +586,22 @@ pub struct TableView {
     filter_input: Option<Entity<TextInput>>,
     /// Sort input field  
     sort_input: Option<Entity<TextInput>>,
+    /// Placeholder for the filter input when it does not take a JSON query (e.g. SQL WHERE)
+    filter_placeholder: Option<&'static str>,
+    /// Whether to render the filter/sort toolbar (hidden when the owner provides its own)
+    show_toolbar: bool,
+    /// In-cell editor, when the owner started editing a cell
+    cell_editor: Option<CellEditor>,
+    /// Pending-change highlighting set by the owner
+    cell_marks: CellMarks,
+    /// Label of the toolbar's add button, hidden when `None`
+    add_row_label: Option<&'static str>,
+    /// Whether the toolbar shows an "Export" button
+    show_export: bool,
+    /// Whether the toolbar shows an "Import" button
+    show_import: bool,
+    /// Whether the filter has an "Explain" button
+    show_explain: bool,
 }
 
 impl EventEmitter<RowSelected> for TableView {}
@@ -447,6 +614,11 @@ impl EventEmitter<ViewDropdownToggled> for TableView {}
 impl EventEmitter<CellContextMenuRequested> for TableView {}
 impl EventEmitter<FilterQuerySubmitted> for TableView {}
 impl EventEmitter<SortQuerySubmitted> for TableView {}
+impl EventEmitter<CellEditSubmitted> for TableView {}
+impl EventEmitter<AddRowRequested> for TableView {}
+impl EventEmitter<ExportRequested> for TableView {}
+impl EventEmitter<ImportRequested> for TableView {}
+impl EventEmitter<ExplainRequested> for TableView {}
 
 impl TableView {
     pub fn new() -> Self {
@@ -456,6 +628,7 @@ impl TableView {
             rows: Vec::new(),
             current_page: 0,
             total_items: 0,
+            total_at_least: false,
             sort_field: None,
             sort_direction: None,
             filter_query: String::new(),
@@ -465,12 +638,57 @@ impl TableView {
             interaction_state: Rc::new(RefCell::new(CellInteractionState::default())),
             filter_input: None,
             sort_input: None,
+            filter_placeholder: None,
+            show_toolbar: true,
+            cell_editor: None,
+            cell_marks: CellMarks::default(),
+            add_row_label: None,
+            show_export: false,
+            show_import: false,
+            show_explain: false,
         }
     }
 
+    /// Use a plain-text filter (starts empty) with the given placeholder instead of a JSON query
+    pub fn set_filter_placeholder(&mut self, placeholder: &'static str) {
+        self.filter_placeholder = Some(placeholder);
+    }
+
+    /// Hide the filter/sort toolbar, e.g. for query results that cannot be re-filtered
+    pub fn hide_toolbar(&mut self) {
+        self.show_toolbar = false;
+    }
+
+    /// Show an add button with this label that emits `AddRowRequested`, or hide it
+    pub fn set_add_row_label(&mut self, label: Option<&'static str>, cx: &mut Context<Self>) {
+        self.add_row_label = label;
+        cx.notify();
+    }
+
+    /// Show an "Export" button that emits `ExportRequested`
+    pub fn set_export_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.show_export = enabled;
+        cx.notify();
+    }
+
+    /// Show an "Import" button that emits `ImportRequested`
+    pub fn set_import_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.show_import = enabled;
+        cx.notify();
+    }
+
+    /// Show an "Explain" button next to the filter that emits `ExplainRequested`
+    pub fn set_explain_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        self.show_explain = enabled;
+        cx.notify();
+    }
+
     fn ensure_inputs(&mut self, cx: &mut Context<Self>) {
         if self.filter_input.is_none() {
-            let input = cx.new(|cx| TextInput::new(cx, "{}", "{}").borderless());
+            let input = match self.filter_placeholder {
+                Some(placeholder) => cx.new(|cx| TextInput::new(cx, placeholder, "").borderless()),
+                None => cx.new(|cx| TextInput::new(cx, "{}", "{}").borderless()),
+            };
             self.filter_input = Some(input);
         }
         if self.sort_input.is_none() {
@@ -561,6 +779,15 @@ impl TableView {
                         .map(|(_, c)| c)
                         .unwrap_or(0);
 
+                    // Double-clicking inside the editor selects text, not the cell
+                    let in_editor = this
+                        .cell_editor
+                        .as_ref()
+                        .is_some_and(|e| e.row_ix == *row_ix && e.col_ix == col_ix);
+                    if in_editor {
+                        return;
+                    }
+
                     if let Some(row) = this.rows.get(*row_ix) {
                         if let Some(value) = row.full_values.get(col_ix) {
                             cx.emit(CellDoubleClicked {
@@ -585,12 +812,19 @@ impl TableView {
             let rows = self.rows.clone();
             let sort_field = self.sort_field.clone();
             let sort_direction = self.sort_direction;
+            let editor = self
+                .cell_editor
+                .as_ref()
+                .map(|e| (e.row_ix, e.col_ix, e.input.clone()));
+            let marks = self.cell_marks.clone();
 
             table_state.update(cx, |state, cx| {
                 let delegate = state.delegate_mut();
                 delegate.set_columns(columns);
                 delegate.rows = rows;
                 delegate.set_sort(sort_field, sort_direction);
+                delegate.editor = editor;
+                delegate.marks = marks;
                 state.refresh(cx);
             });
         }
@@ -604,6 +838,66 @@ impl TableView {
 
     pub fn set_rows(&mut self, rows: Vec<Row>, cx: &mut Context<Self>) {
         self.rows = rows;
+        // Row indices no longer refer to the same data
+        self.cell_editor = None;
+        let row_count = self.rows.len();
+        self.interaction_state
+            .borrow_mut()
+            .selected_rows
+            .retain(|row_ix| *row_ix < row_count);
+        self.update_delegate(cx);
+        cx.notify();
+    }
+
+    /// Highlight cells with pending changes
+    pub fn set_cell_marks(&mut self, marks: CellMarks, cx: &mut Context<Self>) {
+        self.cell_marks = marks;
+        self.update_delegate(cx);
+        cx.notify();
+    }
+
+    /// Replace a cell with a text input holding `text`. Enter emits
+    /// `CellEditSubmitted`, Escape cancels.
+    pub fn start_cell_edit(
+        &mut self,
+        row_index: usize,
+        col_index: usize,
+        text: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let input = cx.new(|cx| TextInput::new(cx, "", text).borderless());
+        self.cell_editor = Some(CellEditor {
+            // Rows past the page size (e.g. pending inserts) still count from the page start
+            row_ix: row_index.saturating_sub(self.current_page * PAGE_SIZE),
+            col_ix: col_index,
+            input,
+            focus_pending: true,
+        });
+        self.update_delegate(cx);
+        cx.notify();
+    }
+
+    pub fn cancel_cell_edit(&mut self, cx: &mut Context<Self>) {
+        if self.cell_editor.take().is_some() {
+            self.update_delegate(cx);
+            cx.notify();
+        }
+    }
+
+    fn submit_cell_edit(&mut self, cx: &mut Context<Self>) {
+        let Some(editor) = self.cell_editor.take() else {
+            return;
+        };
+        let col_name = self
+            .columns
+            .get(editor.col_ix)
+            .map(|c| c.name.to_string())
+            .unwrap_or_default();
+        cx.emit(CellEditSubmitted {
+            row_index: self.current_page * PAGE_SIZE + editor.row_ix,
+            col_name,
+            text: editor.input.read(cx).text(),
+        });
         self.update_delegate(cx);
         cx.notify();
     }
@@ -613,7 +907,15 @@ impl TableView {
         cx.notify();
     }
 
+    pub fn set_total_at_least(&mut self, at_least: bool, cx: &mut Context<Self>) {
+        self.total_at_least = at_least;
+        cx.notify();
+    }
+
     pub fn set_page(&mut self, page: usize, cx: &mut Context<Self>) {
+        if page != self.current_page {
+            self.clear_row_selection();
+        }
         self.current_page = page;
         cx.notify();
     }
@@ -694,6 +996,24 @@ impl TableView {
         }
     }
 
+    /// Forget the picked rows, e.g. when other rows were loaded
+    pub fn clear_row_selection(&mut self) {
+        let mut state = self.interaction_state.borrow_mut();
+        state.selected_rows.clear();
+        state.selection_anchor = None;
+    }
+
+    /// Rows picked with click, Ctrl-click and Shift-click, as indices into the
+    /// current page's rows
+    pub fn selected_rows(&self) -> Vec<usize> {
+        self.interaction_state
+            .borrow()
+            .selected_rows
+            .iter()
+            .copied()
+            .collect()
+    }
+
     #[allow(dead_code)]
     pub fn selected_row(&self) -> Option<usize> {
         self.table_state.as_ref().and({
@@ -779,6 +1099,10 @@ impl TableView {
         let sort_input = self.sort_input.clone().unwrap();
         let current_view = self.view_mode;
         let has_sort = self.sort_field.is_some();
+        let add_row_label = self.add_row_label;
+        let show_export = self.show_export;
+        let show_import = self.show_import;
+        let show_explain = self.show_explain;
 
         div()
             .id("table-toolbar")
@@ -829,7 +1153,32 @@ impl TableView {
                                     .flex_none()
                                     .child("Filter:"),
                             )
-                            .child(div().flex_1().min_w_0().child(filter_input)),
+                            .child(div().flex_1().min_w_0().child(filter_input.clone()))
+                            .when(show_explain, |el| {
+                                el.child(
+                                    div()
+                                        .id("explain-filter")
+                                        .px(rems(0.375)) // 6px
+                                        .py(rems(0.125)) // 2px
+                                        .rounded(px(3.0))
+                                        .cursor_pointer()
+                                        .flex_none()
+                                        .text_size(rems(0.6875)) // 11px
+                                        .text_color(AppColors::text_muted())
+                                        .hover(|s| {
+                                            s.bg(AppColors::bg_hover())
+                                                .text_color(AppColors::text())
+                                        })
+                                        .on_click(cx.listener({
+                                            let filter_input = filter_input.clone();
+                                            move |_, _, _, cx| {
+                                                let query = filter_input.read(cx).text();
+                                                cx.emit(ExplainRequested { query });
+                                            }
+                                        }))
+                                        .child("Explain"),
+                                )
+                            }),
                     )
                     // Sort
                     .child(
@@ -902,6 +1251,103 @@ impl TableView {
                             }),
                     ),
             )
+            // Add Row, when the owner can stage new rows
+            .when_some(add_row_label, |el, label| {
+                el.child(
+                    div()
+                        .id("add-row")
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap(rems(0.25)) // 4px
+                        .px(rems(0.625)) // 10px
+                        .py(rems(0.3125)) // 5px
+                        .mr(rems(0.5)) // 8px
+                        .rounded(px(4.0))
+                        .cursor_pointer()
+                        .flex_none()
+                        .hover(|s| s.bg(AppColors::bg_hover()))
+                        .on_click(cx.listener(|_, _, _, cx| {
+                            cx.emit(AddRowRequested);
+                        }))
+                        .child(
+                            svg()
+                                .path("icons/plus.svg")
+                                .size(rems(0.75)) // 12px
+                                .text_color(AppColors::text_dim()),
+                        )
+                        .child(
+                            div()
+                                .text_size(rems(0.75)) // 12px
+                                .text_color(AppColors::text_secondary())
+                                .child(label),
+                        ),
+                )
+            })
+            .when(show_import, |el| {
+                el.child(
+                    div()
+                        .id("import-rows")
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap(rems(0.25)) // 4px
+                        .px(rems(0.625)) // 10px
+                        .py(rems(0.3125)) // 5px
+                        .mr(rems(0.5)) // 8px
+                        .rounded(px(4.0))
+                        .cursor_pointer()
+                        .flex_none()
+                        .hover(|s| s.bg(AppColors::bg_hover()))
+                        .on_click(cx.listener(|_, _, _, cx| {
+                            cx.emit(ImportRequested);
+                        }))
+                        .child(
+                            svg()
+                                .path("icons/table.svg")
+                                .size(rems(0.75)) // 12px
+                                .text_color(AppColors::text_dim()),
+                        )
+                        .child(
+                            div()
+                                .text_size(rems(0.75)) // 12px
+                                .text_color(AppColors::text_secondary())
+                                .child("Import"),
+                        ),
+                )
+            })
+            .when(show_export, |el| {
+                el.child(
+                    div()
+                        .id("export-rows")
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .gap(rems(0.25)) // 4px
+                        .px(rems(0.625)) // 10px
+                        .py(rems(0.3125)) // 5px
+                        .mr(rems(0.5)) // 8px
+                        .rounded(px(4.0))
+                        .cursor_pointer()
+                        .flex_none()
+                        .hover(|s| s.bg(AppColors::bg_hover()))
+                        .on_click(cx.listener(|_, _, _, cx| {
+                            cx.emit(ExportRequested);
+                        }))
+                        .child(
+                            svg()
+                                .path("icons/document.svg")
+                                .size(rems(0.75)) // 12px
+                                .text_color(AppColors::text_dim()),
+                        )
+                        .child(
+                            div()
+                                .text_size(rems(0.75)) // 12px
+                                .text_color(AppColors::text_secondary())
+                                .child("Export"),
+                        ),
+                )
+            })
             // Right side: View dropdown trigger
             .child(
                 div()
@@ -1036,7 +1482,11 @@ impl TableView {
                                 div()
                                     .text_size(rems(0.75)) // 12px
                                     .text_color(AppColors::accent())
-                                    .child(format!("{}", total_items)),
+                                    .child(if self.total_at_least {
+                                        format!("≥ {}", total_items)
+                                    } else {
+                                        format!("{}", total_items)
+                                    }),
                             ),
                     )
                     .child(
@@ -1106,6 +1556,12 @@ impl Render for TableView {
         // Check for pending context menu requests and emit events
         self.process_pending_interactions(cx);
 
+        // Focus a freshly opened cell editor
+        if let Some(editor) = self.cell_editor.as_mut().filter(|e| e.focus_pending) {
+            editor.focus_pending = false;
+            editor.input.read(cx).focus_handle(cx).focus(window);
+        }
+
         div()
             .id("table-view")
             .size_full()
@@ -1114,7 +1570,7 @@ impl Render for TableView {
             .flex_col()
             .overflow_hidden()
             // Toolbar
-            .child(self.render_toolbar(cx))
+            .when(self.show_toolbar, |el| el.child(self.render_toolbar(cx)))
             // Table view
             .when(view_mode == ViewMode::Table, |el| {
                 if let Some(table_state) = &self.table_state {
@@ -1125,6 +1581,12 @@ impl Render for TableView {
                             .min_h_0()
                             .overflow_hidden()
                             .bg(AppColors::bg_main())
+                            .on_action(cx.listener(|this, _: &ConfirmCellEdit, _, cx| {
+                                this.submit_cell_edit(cx);
+                            }))
+                            .on_action(cx.listener(|this, _: &CancelCellEdit, _, cx| {
+                                this.cancel_cell_edit(cx);
+                            }))
                             .child(Table::new(table_state).stripe(true).bordered(true)),
                     )
                 } else {
@@ -0,0 +1,1013 @@
+use std::ops::Range;
+
+use gpui::{
+    div, fill, hsla, point, prelude::*, px, relative, rems, rgb, rgba, size, App, Bounds,
+    ClipboardItem, Context, CursorStyle, ElementId, ElementInputHandler, Entity,
+    EntityInputHandler, EventEmitter, FocusHandle, Focusable, GlobalElementId, KeyBinding,
+    LayoutId, MouseButton, PaintQuad, Pixels, Point, ShapedLine, SharedString, Style, TextRun,
+    UTF16Selection, UnderlineStyle, Window,
+};
+use unicode_segmentation::*;
+
+/// Spaces inserted by the Tab key
+const INDENT: &str = "    ";
+
+// Define actions for the multi-line text editor
+gpui::actions!(
+    text_editor,
+    [
+        Backspace,
+        Delete,
+        BackspaceWord,
+        DeleteWord,
+        Left,
+        Right,
+        Up,
+        Down,
+        WordLeft,
+        WordRight,
+        SelectLeft,
+        SelectRight,
+        SelectUp,
+        SelectDown,
+        SelectWordLeft,
+        SelectWordRight,
+        SelectAll,
+        Home,
+        End,
+        SelectToHome,
+        SelectToEnd,
+        Newline,
+        Indent,
+        Paste,
+        Cut,
+        Copy,
+    ]
+);
+
+/// Register key bindings for the multi-line text editor
+pub fn register_text_editor_bindings(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("backspace", Backspace, Some("TextEditor")),
+        KeyBinding::new("delete", Delete, Some("TextEditor")),
+        KeyBinding::new("ctrl-backspace", BackspaceWord, Some("TextEditor")),
+        KeyBinding::new("ctrl-delete", DeleteWord, Some("TextEditor")),
+        KeyBinding::new("left", Left, Some("TextEditor")),
+        KeyBinding::new("right", Right, Some("TextEditor")),
+        KeyBinding::new("up", Up, Some("TextEditor")),
+        KeyBinding::new("down", Down, Some("TextEditor")),
+        KeyBinding::new("ctrl-left", WordLeft, Some("TextEditor")),
+        KeyBinding::new("ctrl-right", WordRight, Some("TextEditor")),
+        KeyBinding::new("shift-left", SelectLeft, Some("TextEditor")),
+        KeyBinding::new("shift-right", SelectRight, Some("TextEditor")),
+        KeyBinding::new("shift-up", SelectUp, Some("TextEditor")),
+        KeyBinding::new("shift-down", SelectDown, Some("TextEditor")),
+        KeyBinding::new("ctrl-shift-left", SelectWordLeft, Some("TextEditor")),
+        KeyBinding::new("ctrl-shift-right", SelectWordRight, Some("TextEditor")),
+        KeyBinding::new("ctrl-a", SelectAll, Some("TextEditor")),
+        KeyBinding::new("ctrl-v", Paste, Some("TextEditor")),
+        KeyBinding::new("ctrl-c", Copy, Some("TextEditor")),
+        KeyBinding::new("ctrl-x", Cut, Some("TextEditor")),
+        KeyBinding::new("home", Home, Some("TextEditor")),
+        KeyBinding::new("end", End, Some("TextEditor")),
+        KeyBinding::new("shift-home", SelectToHome, Some("TextEditor")),
+        KeyBinding::new("shift-end", SelectToEnd, Some("TextEditor")),
+        KeyBinding::new("enter", Newline, Some("TextEditor")),
+        KeyBinding::new("tab", Indent, Some("TextEditor")),
+    ]);
+}
+
+/// Event emitted when the text is changed by typing, pasting or deleting
+pub struct EditorChanged;
+
+impl EventEmitter<EditorChanged> for TextEditor {}
+
+/// Layout of one line from the last paint
+#[derive(Clone)]
+struct EditorLine {
+    shaped: ShapedLine,
+    byte_start: usize,
+    byte_end: usize,
+}
+
+/// A multi-line text editor with cursor, selection and keyboard handling.
+/// Used for query text, where the single-line `TextInput` is not enough.
+pub struct TextEditor {
+    focus_handle: FocusHandle,
+    content: String,
+    placeholder: SharedString,
+    selected_range: Range<usize>,
+    selection_reversed: bool,
+    marked_range: Option<Range<usize>>,
+    last_lines: Vec<EditorLine>,
+    last_bounds: Option<Bounds<Pixels>>,
+    last_line_height: Pixels,
+    is_selecting: bool,
+    /// Column (in pixels) to aim for when moving up and down across shorter lines
+    preferred_x: Option<Pixels>,
+    /// Line (0-based) highlighted as containing an error
+    error_line: Option<usize>,
+}
+
+impl TextEditor {
+    pub fn new(cx: &mut Context<Self>, placeholder: &'static str, initial_value: &str) -> Self {
+        let len = initial_value.len();
+        Self {
+            focus_handle: cx.focus_handle(),
+            content: initial_value.to_string(),
+            placeholder: placeholder.into(),
+            selected_range: len..len,
+            selection_reversed: false,
+            marked_range: None,
+            last_lines: Vec::new(),
+            last_bounds: None,
+            last_line_height: px(20.),
+            is_selecting: false,
+            preferred_x: None,
+            error_line: None,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.content.clone()
+    }
+
+    pub fn set_text(&mut self, text: &str, cx: &mut Context<Self>) {
+        self.content = text.to_string();
+        let len = self.content.len();
+        self.selected_range = len..len;
+        self.preferred_x = None;
+        cx.notify();
+    }
+
+    /// Place the cursor at a byte offset into the text
+    pub fn set_cursor(&mut self, offset: usize, cx: &mut Context<Self>) {
+        let mut offset = offset.min(self.content.len());
+        while !self.content.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        self.move_to(offset, cx);
+    }
+
+    /// Highlight a line (0-based) as containing an error, or clear the highlight
+    pub fn set_error_line(&mut self, line: Option<usize>, cx: &mut Context<Self>) {
+        if self.error_line != line {
+            self.error_line = line;
+            cx.notify();
+        }
+    }
+
+    /// The selected text, or `None` when the selection is empty
+    pub fn selected_text(&self) -> Option<String> {
+        (!self.selected_range.is_empty())
+            .then(|| self.content[self.selected_range.clone()].to_string())
+    }
+
+    fn left(&mut self, _: &Left, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.move_to(self.previous_boundary(self.cursor_offset()), cx);
+        } else {
+            self.move_to(self.selected_range.start, cx)
+        }
+    }
+
+    fn right(&mut self, _: &Right, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.move_to(self.next_boundary(self.selected_range.end), cx);
+        } else {
+            self.move_to(self.selected_range.end, cx)
+        }
+    }
+
+    fn up(&mut self, _: &Up, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.vertical_offset(-1);
+        self.move_to_keeping_column(offset, cx);
+    }
+
+    fn down(&mut self, _: &Down, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.vertical_offset(1);
+        self.move_to_keeping_column(offset, cx);
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.previous_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.next_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_up(&mut self, _: &SelectUp, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.vertical_offset(-1);
+        self.select_to_keeping_column(offset, cx);
+    }
+
+    fn select_down(&mut self, _: &SelectDown, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.vertical_offset(1);
+        self.select_to_keeping_column(offset, cx);
+    }
+
+    fn select_all(&mut self, _: &SelectAll, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(0, cx);
+        self.select_to(self.content.len(), cx)
+    }
+
+    fn home(&mut self, _: &Home, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.line_start(self.cursor_offset()), cx);
+    }
+
+    fn end(&mut self, _: &End, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.line_end(self.cursor_offset()), cx);
+    }
+
+    fn select_to_home(&mut self, _: &SelectToHome, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.line_start(self.cursor_offset()), cx);
+    }
+
+    fn select_to_end(&mut self, _: &SelectToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.line_end(self.cursor_offset()), cx);
+    }
+
+    fn word_left(&mut self, _: &WordLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.previous_word_boundary(self.cursor_offset()), cx);
+    }
+
+    fn word_right(&mut self, _: &WordRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.next_word_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_word_left(&mut self, _: &SelectWordLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.previous_word_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_word_right(&mut self, _: &SelectWordRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.next_word_boundary(self.cursor_offset()), cx);
+    }
+
+    fn backspace(&mut self, _: &Backspace, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.previous_boundary(self.cursor_offset()), cx)
+        }
+        self.replace_text_in_range(None, "", window, cx)
+    }
+
+    fn backspace_word(&mut self, _: &BackspaceWord, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.previous_word_boundary(self.cursor_offset()), cx)
+        }
+        self.replace_text_in_range(None, "", window, cx)
+    }
+
+    fn delete(&mut self, _: &Delete, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.next_boundary(self.cursor_offset()), cx)
+        }
+        self.replace_text_in_range(None, "", window, cx)
+    }
+
+    fn delete_word(&mut self, _: &DeleteWord, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.next_word_boundary(self.cursor_offset()), cx)
+        }
+        self.replace_text_in_range(None, "", window, cx)
+    }
+
+    fn newline(&mut self, _: &Newline, window: &mut Window, cx: &mut Context<Self>) {
+        // Keep the indentation of the current line
+        let line_start = self.line_start(self.selected_range.start);
+        let indent: String = self.content[line_start..self.selected_range.start]
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+        self.replace_text_in_range(None, &format!("\n{}", indent), window, cx)
+    }
+
+    fn indent(&mut self, _: &Indent, window: &mut Window, cx: &mut Context<Self>) {
+        self.replace_text_in_range(None, INDENT, window, cx)
+    }
+
+    fn on_mouse_down(
+        &mut self,
+        event: &gpui::MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.focus_handle);
+        self.is_selecting = true;
+        if event.modifiers.shift {
+            self.select_to(self.index_for_mouse_position(event.position), cx);
+        } else {
+            self.move_to(self.index_for_mouse_position(event.position), cx)
+        }
+    }
+
+    fn on_mouse_up(&mut self, _: &gpui::MouseUpEvent, _window: &mut Window, _: &mut Context<Self>) {
+        self.is_selecting = false;
+    }
+
+    fn on_mouse_move(
+        &mut self,
+        event: &gpui::MouseMoveEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.is_selecting {
+            self.select_to(self.index_for_mouse_position(event.position), cx);
+        }
+    }
+
+    fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+            self.replace_text_in_range(None, &text.replace("\r\n", "\n"), window, cx);
+        }
+    }
+
+    fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(text) = self.selected_text() {
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+        }
+    }
+
+    fn cut(&mut self, _: &Cut, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(text) = self.selected_text() {
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+            self.replace_text_in_range(None, "", window, cx)
+        }
+    }
+
+    fn move_to(&mut self, offset: usize, cx: &mut Context<Self>) {
+        self.selected_range = offset..offset;
+        self.preferred_x = None;
+        cx.notify()
+    }
+
+    fn move_to_keeping_column(
+        &mut self,
+        (offset, x): (usize, Option<Pixels>),
+        cx: &mut Context<Self>,
+    ) {
+        self.selected_range = offset..offset;
+        self.preferred_x = x;
+        cx.notify()
+    }
+
+    fn cursor_offset(&self) -> usize {
+        if self.selection_reversed {
+            self.selected_range.start
+        } else {
+            self.selected_range.end
+        }
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        self.content[..offset]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or(0)
+    }
+
+    fn line_end(&self, offset: usize) -> usize {
+        self.content[offset..]
+            .find('\n')
+            .map(|i| offset + i)
+            .unwrap_or(self.content.len())
+    }
+
+    /// Offset one line above (`-1`) or below (`1`) the cursor, keeping the horizontal
+    /// position. Also returns the column to remember for the next vertical move.
+    fn vertical_offset(&self, direction: isize) -> (usize, Option<Pixels>) {
+        let cursor = self.cursor_offset();
+        let Some(row) = self
+            .last_lines
+            .iter()
+            .position(|line| cursor >= line.byte_start && cursor <= line.byte_end)
+        else {
+            return (cursor, None);
+        };
+
+        let line = &self.last_lines[row];
+        let x = self
+            .preferred_x
+            .unwrap_or_else(|| line.shaped.x_for_index(cursor - line.byte_start));
+
+        let target_row = row as isize + direction;
+        if target_row < 0 {
+            return (0, None);
+        }
+        let Some(target) = self.last_lines.get(target_row as usize) else {
+            return (self.content.len(), None);
+        };
+
+        let index = target.shaped.closest_index_for_x(x);
+        ((target.byte_start + index).min(target.byte_end), Some(x))
+    }
+
+    fn index_for_mouse_position(&self, position: Point<Pixels>) -> usize {
+        if self.content.is_empty() {
+            return 0;
+        }
+        let Some(bounds) = self.last_bounds.as_ref() else {
+            return 0;
+        };
+        if position.y < bounds.top() {
+            return 0;
+        }
+        let row = ((position.y - bounds.top()) / self.last_line_height).floor() as usize;
+        let Some(line) = self.last_lines.get(row) else {
+            return self.content.len();
+        };
+        let index = line.shaped.closest_index_for_x(position.x - bounds.left());
+        (line.byte_start + index).min(line.byte_end)
+    }
+
+    fn select_to(&mut self, offset: usize, cx: &mut Context<Self>) {
+        if self.selection_reversed {
+            self.selected_range.start = offset
+        } else {
+            self.selected_range.end = offset
+        };
+        if self.selected_range.end < self.selected_range.start {
+            self.selection_reversed = !self.selection_reversed;
+            self.selected_range = self.selected_range.end..self.selected_range.start;
+        }
+        self.preferred_x = None;
+        cx.notify()
+    }
+
+    fn select_to_keeping_column(
+        &mut self,
+        (offset, x): (usize, Option<Pixels>),
+        cx: &mut Context<Self>,
+    ) {
+        self.select_to(offset, cx);
+        self.preferred_x = x;
+    }
+
+    fn offset_from_utf16(&self, offset: usize) -> usize {
+        let mut utf8_offset = 0;
+        let mut utf16_count = 0;
+        for ch in self.content.chars() {
+            if utf16_count >= offset {
+                break;
+            }
+            utf16_count += ch.len_utf16();
+            utf8_offset += ch.len_utf8();
+        }
+        utf8_offset
+    }
+
+    fn offset_to_utf16(&self, offset: usize) -> usize {
+        let mut utf16_offset = 0;
+        let mut utf8_count = 0;
+        for ch in self.content.chars() {
+            if utf8_count >= offset {
+                break;
+            }
+            utf8_count += ch.len_utf8();
+            utf16_offset += ch.len_utf16();
+        }
+        utf16_offset
+    }
+
+    fn range_to_utf16(&self, range: &Range<usize>) -> Range<usize> {
+        self.offset_to_utf16(range.start)..self.offset_to_utf16(range.end)
+    }
+
+    fn range_from_utf16(&self, range_utf16: &Range<usize>) -> Range<usize> {
+        self.offset_from_utf16(range_utf16.start)..self.offset_from_utf16(range_utf16.end)
+    }
+
+    fn previous_boundary(&self, offset: usize) -> usize {
+        self.content
+            .grapheme_indices(true)
+            .rev()
+            .find_map(|(idx, _)| (idx < offset).then_some(idx))
+            .unwrap_or(0)
+    }
+
+    fn next_boundary(&self, offset: usize) -> usize {
+        self.content
+            .grapheme_indices(true)
+            .find_map(|(idx, _)| (idx > offset).then_some(idx))
+            .unwrap_or(self.content.len())
+    }
+
+    fn previous_word_boundary(&self, offset: usize) -> usize {
+        let mut boundary = 0;
+        let mut found_word = false;
+
+        for (idx, grapheme) in self.content.grapheme_indices(true) {
+            if idx >= offset {
+                break;
+            }
+            let is_word_char = grapheme
+                .chars()
+                .next()
+                .map(|c| c.is_alphanumeric() || c == '_')
+                .unwrap_or(false);
+
+            if is_word_char {
+                if !found_word {
+                    boundary = idx;
+                }
+                found_word = true;
+            } else {
+                found_word = false;
+            }
+        }
+        boundary
+    }
+
+    fn next_word_boundary(&self, offset: usize) -> usize {
+        let mut in_word = false;
+
+        for (idx, grapheme) in self.content.grapheme_indices(true) {
+            if idx <= offset {
+                continue;
+            }
+            let is_word_char = grapheme
+                .chars()
+                .next()
+                .map(|c| c.is_alphanumeric() || c == '_')
+                .unwrap_or(false);
+
+            if is_word_char {
+                in_word = true;
+            } else if in_word {
+                return idx;
+            }
+        }
+        self.content.len()
+    }
+
+    /// Bounds of the text at `offset` on screen, using the last layout
+    fn bounds_for_offset(&self, offset: usize, bounds: Bounds<Pixels>) -> Option<(Pixels, Pixels)> {
+        let row = self
+            .last_lines
+            .iter()
+            .position(|line| offset >= line.byte_start && offset <= line.byte_end)?;
+        let line = &self.last_lines[row];
+        let x = bounds.left() + line.shaped.x_for_index(offset - line.byte_start);
+        let y = bounds.top() + self.last_line_height * row as f32;
+        Some((x, y))
+    }
+}
+
+impl EntityInputHandler for TextEditor {
+    fn text_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        actual_range: &mut Option<Range<usize>>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<String> {
+        let range = self.range_from_utf16(&range_utf16);
+        actual_range.replace(self.range_to_utf16(&range));
+        Some(self.content[range].to_string())
+    }
+
+    fn selected_text_range(
+        &mut self,
+        _ignore_disabled_input: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<UTF16Selection> {
+        Some(UTF16Selection {
+            range: self.range_to_utf16(&self.selected_range),
+            reversed: self.selection_reversed,
+        })
+    }
+
+    fn marked_text_range(
+        &self,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Range<usize>> {
+        self.marked_range
+            .as_ref()
+            .map(|range| self.range_to_utf16(range))
+    }
+
+    fn unmark_text(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
+        self.marked_range = None;
+    }
+
+    fn replace_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let range = range_utf16
+            .as_ref()
+            .map(|range_utf16| self.range_from_utf16(range_utf16))
+            .or(self.marked_range.clone())
+            .unwrap_or(self.selected_range.clone());
+
+        self.content.replace_range(range.clone(), new_text);
+        self.selected_range = range.start + new_text.len()..range.start + new_text.len();
+        self.marked_range.take();
+        self.preferred_x = None;
+        cx.emit(EditorChanged);
+        cx.notify();
+    }
+
+    fn replace_and_mark_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        new_selected_range_utf16: Option<Range<usize>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let range = range_utf16
+            .as_ref()
+            .map(|range_utf16| self.range_from_utf16(range_utf16))
+            .or(self.marked_range.clone())
+            .unwrap_or(self.selected_range.clone());
+
+        self.content.replace_range(range.clone(), new_text);
+        if !new_text.is_empty() {
+            self.marked_range = Some(range.start..range.start + new_text.len());
+        } else {
+            self.marked_range = None;
+        }
+        self.selected_range = new_selected_range_utf16
+            .as_ref()
+            .map(|range_utf16| self.range_from_utf16(range_utf16))
+            .map(|new_range| new_range.start + range.start..new_range.end + range.end)
+            .unwrap_or_else(|| range.start + new_text.len()..range.start + new_text.len());
+
+        cx.emit(EditorChanged);
+        cx.notify();
+    }
+
+    fn bounds_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        bounds: Bounds<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Bounds<Pixels>> {
+        let range = self.range_from_utf16(&range_utf16);
+        let (start_x, top) = self.bounds_for_offset(range.start, bounds)?;
+        let (end_x, _) = self.bounds_for_offset(range.end, bounds)?;
+        Some(Bounds::from_corners(
+            point(start_x, top),
+            point(end_x.max(start_x), top + self.last_line_height),
+        ))
+    }
+
+    fn character_index_for_point(
+        &mut self,
+        point: gpui::Point<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        let local = self.last_bounds?.localize(&point)?;
+        let row = (local.y / self.last_line_height).floor() as usize;
+        let line = self.last_lines.get(row)?;
+        let index = line.shaped.index_for_x(local.x)?;
+        Some(self.offset_to_utf16((line.byte_start + index).min(line.byte_end)))
+    }
+}
+
+impl Focusable for TextEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+struct EditorElement {
+    editor: Entity<TextEditor>,
+}
+
+struct EditorLayoutState {
+    lines: Vec<EditorLine>,
+    line_height: Pixels,
+}
+
+struct EditorPrepaintState {
+    cursor: Option<PaintQuad>,
+    selections: Vec<PaintQuad>,
+    error_line: Option<PaintQuad>,
+}
+
+impl IntoElement for EditorElement {
+    type Element = Self;
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl gpui::Element for EditorElement {
+    type RequestLayoutState = EditorLayoutState;
+    type PrepaintState = EditorPrepaintState;
+
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
+
+    fn source_location(&self) -> Option<&'static core::panic::Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&gpui::InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let editor = self.editor.read(cx);
+        let style = window.text_style();
+        let font_size = style.font_size.to_pixels(window.rem_size());
+        let line_height = window.line_height();
+
+        // Show the placeholder dimmed while there is no content
+        let (text, text_color): (&str, _) = if editor.content.is_empty() {
+            (editor.placeholder.as_ref(), hsla(0., 0., 1., 0.4))
+        } else {
+            (editor.content.as_str(), style.color)
+        };
+
+        let mut lines = Vec::new();
+        let mut byte_offset = 0usize;
+        let mut max_width = px(0.);
+
+        for line_text in text.split('\n') {
+            let line_len = line_text.len();
+            let line_range = byte_offset..byte_offset + line_len;
+
+            // Empty lines still need a glyph so the cursor has somewhere to sit
+            let display_text: SharedString = if line_text.is_empty() {
+                " ".into()
+            } else {
+                line_text.to_string().into()
+            };
+
+            let run = TextRun {
+                len: display_text.len(),
+                font: style.font(),
+                color: text_color,
+                background_color: None,
+                underline: None,
+                strikethrough: None,
+            };
+
+            // Underline the part of an IME composition that falls on this line
+            let runs = match editor.marked_range.as_ref() {
+                Some(marked)
+                    if !line_text.is_empty()
+                        && marked.start < line_range.end
+                        && marked.end > line_range.start =>
+                {
+                    let start = marked.start.max(line_range.start) - line_range.start;
+                    let end = marked.end.min(line_range.end) - line_range.start;
+                    vec![
+                        TextRun {
+                            len: start,
+                            ..run.clone()
+                        },
+                        TextRun {
+                            len: end - start,
+                            underline: Some(UnderlineStyle {
+                                color: Some(run.color),
+                                thickness: px(1.0),
+                                wavy: false,
+                            }),
+                            ..run.clone()
+                        },
+                        TextRun {
+                            len: line_len - end,
+                            ..run
+                        },
+                    ]
+                    .into_iter()
+                    .filter(|run| run.len > 0)
+                    .collect()
+                }
+                _ => vec![run],
+            };
+
+            let shaped = window
+                .text_system()
+                .shape_line(display_text, font_size, &runs, None);
+            max_width = max_width.max(shaped.width);
+
+            lines.push(EditorLine {
+                shaped,
+                byte_start: line_range.start,
+                byte_end: line_range.end,
+            });
+
+            byte_offset += line_len + 1; // +1 for newline
+        }
+
+        let mut layout_style = Style::default();
+        layout_style.size.width = relative(1.).into();
+        // Grow past the container for long lines so the parent can scroll horizontally
+        layout_style.min_size.width = (max_width + px(2.)).into();
+        layout_style.size.height = (line_height * lines.len() as f32).into();
+
+        (
+            window.request_layout(layout_style, [], cx),
+            EditorLayoutState { lines, line_height },
+        )
+    }
+
+    fn prepaint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&gpui::InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        layout: &mut Self::RequestLayoutState,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Self::PrepaintState {
+        let editor = self.editor.read(cx);
+        let selected_range = editor.selected_range.clone();
+        let cursor = editor.cursor_offset();
+        let line_height = layout.line_height;
+
+        // Placeholder lines don't map to content offsets
+        if editor.content.is_empty() {
+            return EditorPrepaintState {
+                cursor: Some(fill(
+                    Bounds::new(bounds.origin, size(px(2.), line_height)),
+                    rgb(0x0078d4),
+                )),
+                selections: Vec::new(),
+                error_line: None,
+            };
+        }
+
+        let mut cursor_quad = None;
+        let mut selections = Vec::new();
+
+        for (row, line) in layout.lines.iter().enumerate() {
+            let top = bounds.top() + line_height * row as f32;
+
+            if selected_range.is_empty() {
+                if cursor >= line.byte_start && cursor <= line.byte_end && cursor_quad.is_none() {
+                    let x = line.shaped.x_for_index(cursor - line.byte_start);
+                    cursor_quad = Some(fill(
+                        Bounds::new(point(bounds.left() + x, top), size(px(2.), line_height)),
+                        rgb(0x0078d4),
+                    ));
+                }
+            } else if selected_range.start <= line.byte_end && selected_range.end >= line.byte_start
+            {
+                let start = selected_range.start.max(line.byte_start) - line.byte_start;
+                let end = selected_range.end.min(line.byte_end) - line.byte_start;
+                let start_x = if start == 0 {
+                    px(0.)
+                } else {
+                    line.shaped.x_for_index(start)
+                };
+                let mut end_x = if end == 0 {
+                    px(0.)
+                } else {
+                    line.shaped.x_for_index(end)
+                };
+                // Show the selected newline as a small block past the end of the line
+                if selected_range.end > line.byte_end {
+                    end_x += px(6.);
+                }
+                selections.push(fill(
+                    Bounds::from_corners(
+                        point(bounds.left() + start_x, top),
+                        point(bounds.left() + end_x, top + line_height),
+                    ),
+                    rgba(0x0078d440),
+                ));
+            }
+        }
+
+        // Tint the whole row of the error line
+        let error_line = editor
+            .error_line
+            .filter(|row| *row < layout.lines.len())
+            .map(|row| {
+                let top = bounds.top() + line_height * row as f32;
+                fill(
+                    Bounds::new(
+                        point(bounds.left(), top),
+                        size(bounds.size.width, line_height),
+                    ),
+                    rgba(0xf4433626),
+                )
+            });
+
+        EditorPrepaintState {
+            cursor: cursor_quad,
+            selections,
+            error_line,
+        }
+    }
+
+    fn paint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&gpui::InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        layout: &mut Self::RequestLayoutState,
+        prepaint: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let focus_handle = self.editor.read(cx).focus_handle.clone();
+        window.handle_input(
+            &focus_handle,
+            ElementInputHandler::new(bounds, self.editor.clone()),
+            cx,
+        );
+
+        if let Some(error_line) = prepaint.error_line.take() {
+            window.paint_quad(error_line)
+        }
+
+        for selection in prepaint.selections.drain(..) {
+            window.paint_quad(selection)
+        }
+
+        for (row, line) in layout.lines.iter().enumerate() {
+            let origin = point(
+                bounds.left(),
+                bounds.top() + layout.line_height * row as f32,
+            );
+            line.shaped
+                .paint(origin, layout.line_height, window, cx)
+                .ok();
+        }
+
+        if focus_handle.is_focused(window) {
+            if let Some(cursor) = prepaint.cursor.take() {
+                window.paint_quad(cursor);
+            }
+        }
+
+        let lines = std::mem::take(&mut layout.lines);
+        let line_height = layout.line_height;
+        self.editor.update(cx, |editor, _cx| {
+            // Keep the layout only when it describes the content (not the placeholder)
+            editor.last_lines = if editor.content.is_empty() {
+                Vec::new()
+            } else {
+                lines
+            };
+            editor.last_bounds = Some(bounds);
+            editor.last_line_height = line_height;
+        });
+    }
+}
+
+impl Render for TextEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .key_context("TextEditor")
+            .track_focus(&self.focus_handle(cx))
+            .cursor(CursorStyle::IBeam)
+            .on_action(cx.listener(Self::backspace))
+            .on_action(cx.listener(Self::backspace_word))
+            .on_action(cx.listener(Self::delete))
+            .on_action(cx.listener(Self::delete_word))
+            .on_action(cx.listener(Self::left))
+            .on_action(cx.listener(Self::right))
+            .on_action(cx.listener(Self::up))
+            .on_action(cx.listener(Self::down))
+            .on_action(cx.listener(Self::word_left))
+            .on_action(cx.listener(Self::word_right))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_up))
+            .on_action(cx.listener(Self::select_down))
+            .on_action(cx.listener(Self::select_word_left))
+            .on_action(cx.listener(Self::select_word_right))
+            .on_action(cx.listener(Self::select_all))
+            .on_action(cx.listener(Self::home))
+            .on_action(cx.listener(Self::end))
+            .on_action(cx.listener(Self::select_to_home))
+            .on_action(cx.listener(Self::select_to_end))
+            .on_action(cx.listener(Self::newline))
+            .on_action(cx.listener(Self::indent))
+            .on_action(cx.listener(Self::paste))
+            .on_action(cx.listener(Self::cut))
+            .on_action(cx.listener(Self::copy))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
+            .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_mouse_move(cx.listener(Self::on_mouse_move))
+            .w_full()
+            .min_h(relative(1.))
+            .px(rems(0.75)) // 12px
+            .py(rems(0.5)) // 8px
+            .text_size(rems(0.8125)) // 13px
+            .text_color(rgb(0xe0e0e0))
+            .line_height(rems(1.25)) // 20px
+            .child(EditorElement {
+                editor: cx.entity().clone(),
+            })
+    }
+}
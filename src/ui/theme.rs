@@ -42,6 +42,21 @@ impl AppColors {
         rgba(0x0078d430).into()
     }
 
+    /// Background of a cell with an unsaved edit (amber tint)
+    pub fn bg_cell_modified() -> Hsla {
+        rgba(0xff980026).into()
+    }
+
+    /// Background of a row marked for deletion (red tint)
+    pub fn bg_cell_deleted() -> Hsla {
+        rgba(0xf4433620).into()
+    }
+
+    /// Background of a row that will be inserted (green tint)
+    pub fn bg_cell_inserted() -> Hsla {
+        rgba(0x4caf5020).into()
+    }
+
     // ── Borders ──────────────────────────────────────────────────────────
 
     /// Default border color
@@ -0,0 +1,538 @@
+use gpui::{prelude::*, rems, *};
+use std::sync::Arc;
+
+use crate::db::vault::VaultState;
+use crate::db::ConnectionStorage;
+use crate::ui::text_input::TextInput;
+use crate::ui::title_bar::TitleBar;
+
+// Define actions for the unlock prompt
+actions!(unlock_prompt, [Submit, FocusNextField]);
+
+/// Register unlock prompt key bindings
+pub fn register_unlock_prompt_bindings(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("enter", Submit, Some("UnlockPrompt")),
+        KeyBinding::new("tab", FocusNextField, Some("UnlockPrompt")),
+    ]);
+}
+
+/// Shortest master passphrase accepted when creating the credential store
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Called with the unlocked storage once the prompt succeeds
+type OnUnlocked = Box<dyn FnOnce(Arc<ConnectionStorage>, &mut App)>;
+
+#[derive(Clone, Copy, PartialEq)]
+enum PromptMode {
+    /// First run: choose a master passphrase (and encrypt any plaintext secrets)
+    Create {
+        plaintext_count: usize,
+    },
+    Unlock,
+    /// Asking before the stored secrets are thrown away
+    ConfirmReset,
+    /// Asking for the passphrase to move the stored secrets into the desktop keyring
+    MoveToKeyring,
+    /// The keyring holding the secrets could not be opened at startup
+    KeyringUnavailable,
+}
+
+/// Startup window asking for the master passphrase that protects saved
+/// passwords and connection strings
+pub struct UnlockPrompt {
+    title_bar: Entity<TitleBar>,
+    storage: Arc<ConnectionStorage>,
+    mode: PromptMode,
+    passphrase_input: Entity<TextInput>,
+    confirm_input: Entity<TextInput>,
+    error: Option<String>,
+    /// Key derivation is running in the background
+    is_working: bool,
+    on_unlocked: Option<OnUnlocked>,
+}
+
+impl UnlockPrompt {
+    /// `keyring_error` is why the keyring chosen earlier could not be opened; the
+    /// prompt then offers to retry before falling back to a passphrase
+    pub fn new(
+        storage: Arc<ConnectionStorage>,
+        keyring_error: Option<String>,
+        on_unlocked: impl FnOnce(Arc<ConnectionStorage>, &mut App) + 'static,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mode = if keyring_error.is_some() {
+            PromptMode::KeyringUnavailable
+        } else {
+            Self::passphrase_mode(&storage)
+        };
+
+        let title_bar = cx.new(|_| TitleBar::modal("Unlock Chambers"));
+        let passphrase_input = cx.new(|cx| TextInput::new(cx, "Master passphrase", "").password());
+        let confirm_input = cx.new(|cx| TextInput::new(cx, "Repeat passphrase", "").password());
+
+        // Closing the prompt without unlocking leaves nothing to show
+        cx.on_release(|this, cx| {
+            if this.on_unlocked.is_some() {
+                cx.quit();
+            }
+        })
+        .detach();
+
+        Self {
+            title_bar,
+            storage,
+            mode,
+            passphrase_input,
+            confirm_input,
+            error: keyring_error,
+            is_working: false,
+            on_unlocked: Some(Box::new(on_unlocked)),
+        }
+    }
+
+    /// Create the credential store on first run, otherwise unlock it
+    fn passphrase_mode(storage: &ConnectionStorage) -> PromptMode {
+        match storage.vault_state() {
+            VaultState::Uninitialized => PromptMode::Create {
+                plaintext_count: storage.plaintext_secret_count().unwrap_or(0),
+            },
+            _ => PromptMode::Unlock,
+        }
+    }
+
+    pub fn focus_input(&self, window: &mut Window, cx: &mut App) {
+        window.focus(&self.passphrase_input.focus_handle(cx));
+    }
+
+    fn submit(&mut self, _: &Submit, window: &mut Window, cx: &mut Context<Self>) {
+        if self.is_working {
+            return;
+        }
+
+        let mode = self.mode;
+        let passphrase = self.passphrase_input.read(cx).text();
+        match mode {
+            PromptMode::Create { .. } => {
+                if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+                    self.error = Some(format!("Use at least {} characters", MIN_PASSPHRASE_LEN));
+                    cx.notify();
+                    return;
+                }
+                if passphrase != self.confirm_input.read(cx).text() {
+                    self.error = Some("Passphrases do not match".to_string());
+                    cx.notify();
+                    return;
+                }
+            }
+            PromptMode::Unlock | PromptMode::MoveToKeyring => {
+                if passphrase.is_empty() {
+                    return;
+                }
+            }
+            PromptMode::KeyringUnavailable => return self.use_keyring(window, cx),
+            PromptMode::ConfirmReset => return,
+        }
+
+        self.is_working = true;
+        self.error = None;
+        cx.notify();
+
+        // Argon2 takes a few hundred milliseconds; keep it off the UI thread
+        let storage = self.storage.clone();
+        let task = cx.background_spawn(async move {
+            match mode {
+                PromptMode::Create { .. } => storage.create_vault(&passphrase),
+                PromptMode::MoveToKeyring => storage.move_vault_to_keyring(&passphrase),
+                _ => storage.unlock_vault(&passphrase),
+            }
+        });
+        self.finish_when_done(task, window, cx);
+    }
+
+    /// Keep secrets in the desktop keyring instead of behind a passphrase
+    fn use_keyring(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.is_working {
+            return;
+        }
+        self.is_working = true;
+        self.error = None;
+        cx.notify();
+
+        // Talks to D-Bus and may wait on the keyring's own unlock dialog
+        let storage = self.storage.clone();
+        let task = cx.background_spawn(async move {
+            storage
+                .use_keyring()
+                .map_err(|e| anyhow::anyhow!("System keyring unavailable: {}", e))
+        });
+        self.finish_when_done(task, window, cx);
+    }
+
+    /// Close the prompt once `task` succeeds, or show its error
+    fn finish_when_done(
+        &mut self,
+        task: Task<anyhow::Result<()>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            let result = task.await;
+            this.update_in(cx, |prompt, window, cx| {
+                prompt.is_working = false;
+                match result {
+                    Ok(()) => prompt.finish(window, cx),
+                    Err(e) => {
+                        prompt.error = Some(e.to_string());
+                        prompt
+                            .passphrase_input
+                            .update(cx, |input, _| input.set_text(""));
+                        prompt.focus_input(window, cx);
+                        cx.notify();
+                    }
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Hand the unlocked storage over and close the prompt
+    fn finish(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(on_unlocked) = self.on_unlocked.take() {
+            let storage = self.storage.clone();
+            cx.defer(move |cx| on_unlocked(storage, cx));
+        }
+        window.remove_window();
+    }
+
+    fn focus_next_field(
+        &mut self,
+        _: &FocusNextField,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(self.mode, PromptMode::Create { .. }) {
+            return;
+        }
+        let next = if self.passphrase_input.focus_handle(cx).is_focused(window) {
+            &self.confirm_input
+        } else {
+            &self.passphrase_input
+        };
+        window.focus(&next.focus_handle(cx));
+    }
+
+    fn set_mode(&mut self, mode: PromptMode, window: &mut Window, cx: &mut Context<Self>) {
+        self.mode = mode;
+        self.error = None;
+        self.passphrase_input
+            .update(cx, |input, _| input.set_text(""));
+        self.confirm_input.update(cx, |input, _| input.set_text(""));
+        self.focus_input(window, cx);
+        cx.notify();
+    }
+
+    fn reset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        match self.storage.reset_vault() {
+            Ok(()) => self.set_mode(PromptMode::Create { plaintext_count: 0 }, window, cx),
+            Err(e) => {
+                self.error = Some(e.to_string());
+                cx.notify();
+            }
+        }
+    }
+
+    fn render_field(label: &str, input: Entity<TextInput>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(6.0))
+            .child(
+                div()
+                    .text_size(rems(0.75)) // 12px
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(rgb(0x909090))
+                    .child(label.to_string()),
+            )
+            .child(input)
+    }
+
+    fn render_button(
+        id: &'static str,
+        label: &'static str,
+        bg: Rgba,
+        hover_bg: Rgba,
+        disabled: bool,
+    ) -> Stateful<Div> {
+        div()
+            .id(id)
+            .px(px(18.0))
+            .py(px(8.0))
+            .rounded_md()
+            .text_size(rems(0.8125)) // 13px
+            .font_weight(FontWeight::MEDIUM)
+            .text_color(rgb(0xffffff))
+            .bg(bg)
+            .when(disabled, |el| el.opacity(0.5))
+            .when(!disabled, |el| {
+                el.cursor_pointer().hover(move |s| s.bg(hover_bg))
+            })
+            .child(label)
+    }
+
+    /// Secondary action shown as text next to the main button
+    fn render_link(id: &'static str, label: &'static str) -> Stateful<Div> {
+        div()
+            .id(id)
+            .cursor_pointer()
+            .text_size(rems(0.75)) // 12px
+            .text_color(rgb(0x808080))
+            .hover(|s| s.text_color(rgb(0xe0e0e0)))
+            .child(label)
+    }
+
+    fn render_body(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let muted = rgb(0x808080);
+        let is_working = self.is_working;
+
+        let (heading, description): (&str, String) = match self.mode {
+            PromptMode::Create { plaintext_count } => (
+                "Set a master passphrase",
+                if plaintext_count > 0 {
+                    format!(
+                        "Saved passwords and connection strings will be encrypted with it. \
+                         {} saved connection{} will be moved out of plain text.",
+                        plaintext_count,
+                        if plaintext_count == 1 { "" } else { "s" }
+                    )
+                } else {
+                    "Saved passwords and connection strings will be encrypted with it.".to_string()
+                },
+            ),
+            PromptMode::Unlock => (
+                "Enter your master passphrase",
+                "It unlocks the saved passwords and connection strings.".to_string(),
+            ),
+            PromptMode::ConfirmReset => (
+                "Reset the credential store?",
+                "Every saved password and connection string will be deleted. \
+                 Connections are kept, but you will need to enter their credentials again."
+                    .to_string(),
+            ),
+            PromptMode::MoveToKeyring => (
+                "Move to the system keyring",
+                "Saved passwords and connection strings will be moved into the system keyring, \
+                 and no passphrase will be asked for at startup."
+                    .to_string(),
+            ),
+            PromptMode::KeyringUnavailable => (
+                "System keyring unavailable",
+                "Saved passwords and connection strings are kept in the system keyring. \
+                 Unlock it and retry, or keep them in an encrypted file from now on; \
+                 those in the keyring would then have to be entered again."
+                    .to_string(),
+            ),
+        };
+
+        let mut body = div()
+            .flex()
+            .flex_col()
+            .gap(px(16.0))
+            .w(px(360.0))
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap(px(10.0))
+                    .child(
+                        svg()
+                            .path("icons/lock.svg")
+                            .size(rems(1.25)) // 20px
+                            .text_color(rgb(0x0078d4)),
+                    )
+                    .child(
+                        div()
+                            .text_size(rems(0.9375)) // 15px
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(rgb(0xe0e0e0))
+                            .child(heading),
+                    ),
+            )
+            .child(
+                div()
+                    .text_size(rems(0.75)) // 12px
+                    .text_color(muted)
+                    .child(description),
+            );
+
+        match self.mode {
+            PromptMode::Create { .. } => {
+                body = body
+                    .child(Self::render_field(
+                        "Passphrase",
+                        self.passphrase_input.clone(),
+                    ))
+                    .child(Self::render_field(
+                        "Confirm passphrase",
+                        self.confirm_input.clone(),
+                    ));
+            }
+            PromptMode::Unlock | PromptMode::MoveToKeyring => {
+                body = body.child(Self::render_field(
+                    "Passphrase",
+                    self.passphrase_input.clone(),
+                ));
+            }
+            PromptMode::ConfirmReset | PromptMode::KeyringUnavailable => {}
+        }
+
+        if let Some(error) = self.error.clone() {
+            body = body.child(
+                div()
+                    .text_size(rems(0.75)) // 12px
+                    .text_color(rgb(0xf14c4c))
+                    .child(error),
+            );
+        }
+
+        let buttons = match self.mode {
+            PromptMode::ConfirmReset => div()
+                .flex()
+                .flex_row()
+                .justify_end()
+                .gap(px(10.0))
+                .child(
+                    Self::render_button(
+                        "reset-cancel-btn",
+                        "Cancel",
+                        rgb(0x333333),
+                        rgb(0x404040),
+                        false,
+                    )
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.set_mode(PromptMode::Unlock, window, cx);
+                    })),
+                )
+                .child(
+                    Self::render_button(
+                        "reset-confirm-btn",
+                        "Reset",
+                        rgb(0xa1260d),
+                        rgb(0xc42b1c),
+                        false,
+                    )
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.reset(window, cx);
+                    })),
+                ),
+            mode => {
+                let label = match (mode, is_working) {
+                    (PromptMode::KeyringUnavailable, true) => "Connecting...",
+                    (PromptMode::KeyringUnavailable, false) => "Retry",
+                    (_, true) => "Deriving key...",
+                    (PromptMode::Unlock, false) => "Unlock",
+                    (PromptMode::MoveToKeyring, false) => "Move",
+                    _ => "Create",
+                };
+                let keyring_supported = ConnectionStorage::keyring_supported();
+                let links = match mode {
+                    PromptMode::Unlock => div()
+                        .flex()
+                        .flex_col()
+                        .gap(px(4.0))
+                        .child(
+                            Self::render_link("forgot-passphrase", "Forgot passphrase?").on_click(
+                                cx.listener(|this, _, window, cx| {
+                                    if !this.is_working {
+                                        this.set_mode(PromptMode::ConfirmReset, window, cx);
+                                    }
+                                }),
+                            ),
+                        )
+                        .when(keyring_supported, |el| {
+                            el.child(
+                                Self::render_link("move-to-keyring", "Move to system keyring")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        if !this.is_working {
+                                            this.set_mode(PromptMode::MoveToKeyring, window, cx);
+                                        }
+                                    })),
+                            )
+                        }),
+                    PromptMode::MoveToKeyring => {
+                        div().child(Self::render_link("move-back", "Back").on_click(cx.listener(
+                            |this, _, window, cx| {
+                                if !this.is_working {
+                                    this.set_mode(PromptMode::Unlock, window, cx);
+                                }
+                            },
+                        )))
+                    }
+                    PromptMode::KeyringUnavailable => div().child(
+                        Self::render_link("use-encrypted-file", "Use encrypted file instead")
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                if !this.is_working {
+                                    let mode = Self::passphrase_mode(&this.storage);
+                                    this.set_mode(mode, window, cx);
+                                }
+                            })),
+                    ),
+                    _ if keyring_supported => div().child(
+                        Self::render_link("use-keyring", "Use system keyring instead").on_click(
+                            cx.listener(|this, _, window, cx| {
+                                this.use_keyring(window, cx);
+                            }),
+                        ),
+                    ),
+                    _ => div(),
+                };
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .justify_between()
+                    .child(links)
+                    .child(
+                        Self::render_button(
+                            "unlock-btn",
+                            label,
+                            rgb(0x0078d4),
+                            rgb(0x1a8cff),
+                            is_working,
+                        )
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.submit(&Submit, window, cx);
+                        })),
+                    )
+            }
+        };
+
+        body.child(buttons)
+    }
+}
+
+impl Render for UnlockPrompt {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .id("unlock-prompt")
+            .key_context("UnlockPrompt")
+            .on_action(cx.listener(Self::submit))
+            .on_action(cx.listener(Self::focus_next_field))
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(rgb(0x1a1a1a))
+            .font_family("Fira Code")
+            .child(self.title_bar.clone())
+            .child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .child(self.render_body(cx)),
+            )
+    }
+}
@@ -0,0 +1,103 @@
+//! Small elements shared by the panels and tabs: option groups, form sections
+//! and badges
+
+use gpui::{prelude::*, rems, *};
+
+use crate::ui::theme::AppColors;
+
+/// A choice in a group of options; disabled options are dimmed and ignore clicks
+pub fn render_option(
+    id: impl Into<ElementId>,
+    label: impl Into<SharedString>,
+    selected: bool,
+    enabled: bool,
+) -> Stateful<Div> {
+    div()
+        .id(id)
+        .px(rems(0.5)) // 8px
+        .py(rems(0.1875)) // 3px
+        .rounded(px(3.0))
+        .text_size(rems(0.6875)) // 11px
+        .when(enabled, |el| el.cursor_pointer())
+        .when(selected, |el| {
+            el.bg(AppColors::bg_active())
+                .text_color(AppColors::accent())
+        })
+        .when(!selected && enabled, |el| {
+            el.text_color(AppColors::text_secondary())
+                .hover(|s| s.bg(AppColors::bg_hover()))
+        })
+        .when(!selected && !enabled, |el| {
+            el.text_color(AppColors::text_dim())
+        })
+        .child(label.into())
+}
+
+/// A labelled group of form controls
+pub fn render_section(label: &'static str, content: impl IntoElement) -> impl IntoElement {
+    div()
+        .flex()
+        .flex_col()
+        .gap(rems(0.375)) // 6px
+        .px(rems(0.75)) // 12px
+        .py(rems(0.5)) // 8px
+        .border_b_1()
+        .border_color(AppColors::border_subtle())
+        .child(
+            div()
+                .text_size(rems(0.6875)) // 11px
+                .text_color(AppColors::text_muted())
+                .child(label),
+        )
+        .child(content)
+}
+
+/// A block of a details page under a heading, optionally with the number of items
+pub fn render_titled_section(
+    label: &'static str,
+    count: Option<usize>,
+    content: impl IntoElement,
+) -> impl IntoElement {
+    div()
+        .flex()
+        .flex_col()
+        .border_b_1()
+        .border_color(AppColors::border_subtle())
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap(rems(0.375)) // 6px
+                .px(rems(0.75)) // 12px
+                .pt(rems(0.625)) // 10px
+                .pb(rems(0.375)) // 6px
+                .child(
+                    div()
+                        .text_size(rems(0.6875)) // 11px
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .text_color(AppColors::text_secondary())
+                        .child(label),
+                )
+                .when_some(count, |el, count| {
+                    el.child(
+                        div()
+                            .text_size(rems(0.6875)) // 11px
+                            .text_color(AppColors::text_dim())
+                            .child(count.to_string()),
+                    )
+                }),
+        )
+        .child(div().pb(rems(0.5)).child(content)) // 8px
+}
+
+/// Short tag next to a name, e.g. "unique" or "PK"
+pub fn render_badge(label: impl Into<SharedString>, color: Hsla) -> impl IntoElement {
+    div()
+        .px(rems(0.3125)) // 5px
+        .rounded(px(3.0))
+        .bg(AppColors::bg_active())
+        .text_size(rems(0.625)) // 10px
+        .text_color(color)
+        .child(label.into())
+}
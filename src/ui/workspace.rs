@@ -1,12 +1,13 @@
 use gpui::{prelude::*, *};
 use std::sync::Arc;
 
-use crate::db::{Connection, ConnectionStorage, DatabaseType};
+use crate::db::{Connection, ConnectionConfig, ConnectionStorage, DatabaseType};
 use crate::ui::connection_modal::ConnectionModal;
 use crate::ui::pane::Pane;
 use crate::ui::sidebar::{
     AddConnectionRequested, DraggedSidebar, EditConnectionRequested, OpenCollectionRequested,
-    Sidebar,
+    OpenDiagramRequested, OpenPropertiesRequested, OpenQueryConsoleRequested,
+    OpenServerStatusRequested, Sidebar,
 };
 use crate::ui::title_bar::TitleBar;
 
@@ -31,9 +32,17 @@ pub fn register_workspace_bindings(cx: &mut App) {
 
 /// Pending collection to open (deferred to render)
 struct PendingCollection {
+    connection_id: String,
     collection_name: String,
     database_name: String,
-    connection_string: String,
+    config: ConnectionConfig,
+}
+
+/// Pending query console to open (deferred to render)
+struct PendingQueryConsole {
+    connection_id: String,
+    database_name: String,
+    config: ConnectionConfig,
 }
 
 pub struct ChambersWorkspace {
@@ -45,15 +54,13 @@ pub struct ChambersWorkspace {
     storage: Arc<ConnectionStorage>,
     pending_db_type: Option<DatabaseType>,
     pending_collection: Option<PendingCollection>,
+    pending_query_console: Option<PendingQueryConsole>,
     pending_edit_connection: Option<Connection>,
     needs_initial_focus: bool,
 }
 
 impl ChambersWorkspace {
-    pub fn new(cx: &mut Context<Self>) -> Self {
-        let storage =
-            Arc::new(ConnectionStorage::new().expect("Failed to initialize connection storage"));
-
+    pub fn new(storage: Arc<ConnectionStorage>, cx: &mut Context<Self>) -> Self {
         let title_bar = cx.new(|_| TitleBar::new());
         let sidebar = cx.new(|cx| Sidebar::new(storage.clone(), cx));
         let pane = cx.new(|_| Pane::new());
@@ -75,15 +82,80 @@ impl ChambersWorkspace {
             |this, _sidebar, event: &OpenCollectionRequested, cx| {
                 // Store the collection info - we'll open in render where we have window
                 this.pending_collection = Some(PendingCollection {
+                    connection_id: event.connection_id.clone(),
                     collection_name: event.collection_name.clone(),
                     database_name: event.database_name.clone(),
-                    connection_string: event.connection_string.clone(),
+                    config: event.config.clone(),
+                });
+                cx.notify();
+            },
+        )
+        .detach();
+
+        // Subscribe to sidebar events - open query console
+        cx.subscribe(
+            &sidebar,
+            |this, _sidebar, event: &OpenQueryConsoleRequested, cx| {
+                this.pending_query_console = Some(PendingQueryConsole {
+                    connection_id: event.connection_id.clone(),
+                    database_name: event.database_name.clone(),
+                    config: event.config.clone(),
                 });
                 cx.notify();
             },
         )
         .detach();
 
+        // Subscribe to sidebar events - open ER diagram (needs no window, so
+        // it opens right away)
+        cx.subscribe(
+            &sidebar,
+            |this, _sidebar, event: &OpenDiagramRequested, cx| {
+                this.pane.update(cx, |pane, cx| {
+                    pane.open_diagram(
+                        event.connection_id.clone(),
+                        event.database_name.clone(),
+                        event.config.clone(),
+                        cx,
+                    );
+                });
+            },
+        )
+        .detach();
+
+        // Subscribe to sidebar events - database or collection properties
+        cx.subscribe(
+            &sidebar,
+            |this, _sidebar, event: &OpenPropertiesRequested, cx| {
+                this.pane.update(cx, |pane, cx| {
+                    pane.open_properties(
+                        event.connection_id.clone(),
+                        event.database_name.clone(),
+                        event.collection_name.clone(),
+                        event.config.clone(),
+                        cx,
+                    );
+                });
+            },
+        )
+        .detach();
+
+        // Subscribe to sidebar events - server status dashboard
+        cx.subscribe(
+            &sidebar,
+            |this, _sidebar, event: &OpenServerStatusRequested, cx| {
+                this.pane.update(cx, |pane, cx| {
+                    pane.open_server_status(
+                        event.connection_id.clone(),
+                        event.connection_name.clone(),
+                        event.config.clone(),
+                        cx,
+                    );
+                });
+            },
+        )
+        .detach();
+
         // Subscribe to sidebar events - edit connection (Properties context menu)
         cx.subscribe(
             &sidebar,
@@ -103,6 +175,7 @@ impl ChambersWorkspace {
             storage,
             pending_db_type: None,
             pending_collection: None,
+            pending_query_console: None,
             pending_edit_connection: None,
             needs_initial_focus: true,
         }
@@ -217,9 +290,25 @@ impl Render for ChambersWorkspace {
         if let Some(pending) = self.pending_collection.take() {
             self.pane.update(cx, |pane, cx| {
                 pane.open_collection(
+                    pending.connection_id,
                     pending.collection_name,
                     pending.database_name,
-                    pending.connection_string,
+                    pending.config,
+                    window,
+                    cx,
+                );
+            });
+        }
+
+        // Handle pending query console open
+        if let Some(pending) = self.pending_query_console.take() {
+            let storage = self.storage.clone();
+            self.pane.update(cx, |pane, cx| {
+                pane.open_query_console(
+                    pending.connection_id,
+                    pending.database_name,
+                    pending.config,
+                    storage,
                     window,
                     cx,
                 );